@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use zbus::message::{Factory, Message};
+
+const PATH: &str = "/org/zbus/Meter";
+const INTERFACE: &str = "org.zbus.Meter";
+const MEMBER: &str = "Level";
+
+fn signal_builder_vs_factory(c: &mut Criterion) {
+    let body = ("level", 42u32, 0.5f64);
+
+    c.bench_function("signal_builder", |b| {
+        b.iter(|| {
+            Message::signal(black_box(PATH), black_box(INTERFACE), black_box(MEMBER))
+                .unwrap()
+                .destination(":1.42")
+                .unwrap()
+                .build(black_box(&body))
+                .unwrap()
+        })
+    });
+
+    let builder = Message::signal(PATH, INTERFACE, MEMBER)
+        .unwrap()
+        .destination(":1.42")
+        .unwrap();
+    let factory = Factory::new(builder, "sud").unwrap();
+    c.bench_function("signal_factory", |b| {
+        b.iter(|| factory.build(black_box(&body)).unwrap())
+    });
+}
+
+criterion_group!(benches, signal_builder_vs_factory);
+criterion_main!(benches);
@@ -8,7 +8,8 @@ mod async_drop;
 pub(crate) mod async_lock;
 pub use async_drop::*;
 pub(crate) mod file;
+pub(crate) mod timeout;
 
-// Not macOS-specific itself but only used on macOS.
-#[cfg(target_os = "macos")]
+// Used for launchd on macOS and autolaunch on other Unix systems.
+#[cfg(any(target_os = "macos", all(unix, feature = "autolaunch")))]
 pub(crate) mod process;
@@ -0,0 +1,23 @@
+use std::{future::Future, time::Duration};
+
+/// Await `future` for at most `duration`.
+///
+/// Returns `None` if `future` didn't complete in time, in which case it's dropped.
+#[cfg(not(feature = "tokio"))]
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    use futures_util::future::{select, Either};
+
+    let future = std::pin::pin!(future);
+    match select(future, async_io::Timer::after(duration)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+/// Await `future` for at most `duration`.
+///
+/// Returns `None` if `future` didn't complete in time, in which case it's dropped.
+#[cfg(feature = "tokio")]
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    tokio::time::timeout(duration, future).await.ok()
+}
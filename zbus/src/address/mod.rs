@@ -14,7 +14,12 @@ pub mod transport;
 use crate::{Error, Guid, OwnedGuid, Result};
 #[cfg(all(unix, not(target_os = "macos")))]
 use nix::unistd::Uid;
-use std::{collections::HashMap, env, str::FromStr};
+use std::{
+    collections::HashMap,
+    env,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 use std::fmt::{Display, Formatter};
 
@@ -54,7 +59,10 @@ impl Address {
         &self.transport
     }
 
-    #[cfg_attr(any(target_os = "macos", windows), async_recursion::async_recursion)]
+    #[cfg_attr(
+        any(target_os = "macos", windows, feature = "autolaunch"),
+        async_recursion::async_recursion
+    )]
     pub(crate) async fn connect(self) -> Result<Stream> {
         self.transport.connect().await
     }
@@ -62,7 +70,13 @@ impl Address {
     /// Get the address for session socket respecting the DBUS_SESSION_BUS_ADDRESS environment
     /// variable. If we don't recognize the value (or it's not set) we fall back to
     /// $XDG_RUNTIME_DIR/bus
+    ///
+    /// If a resolver has been set through [`set_resolver`], it's consulted first.
     pub fn session() -> Result<Self> {
+        if let Some(address) = resolve(BusType::Session) {
+            return address;
+        }
+
         match env::var("DBUS_SESSION_BUS_ADDRESS") {
             Ok(val) => Self::from_str(&val),
             _ => {
@@ -87,7 +101,13 @@ impl Address {
     /// Get the address for system bus respecting the DBUS_SYSTEM_BUS_ADDRESS environment
     /// variable. If we don't recognize the value (or it's not set) we fall back to
     /// /var/run/dbus/system_bus_socket
+    ///
+    /// If a resolver has been set through [`set_resolver`], it's consulted first.
     pub fn system() -> Result<Self> {
+        if let Some(address) = resolve(BusType::System) {
+            return address;
+        }
+
         match env::var("DBUS_SYSTEM_BUS_ADDRESS") {
             Ok(val) => Self::from_str(&val),
             _ => {
@@ -109,6 +129,64 @@ impl Address {
     }
 }
 
+/// The well-known message buses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BusType {
+    /// The session/user message bus.
+    Session,
+    /// The system-wide message bus.
+    System,
+}
+
+type Resolver = dyn Fn(BusType) -> Option<Result<Address>> + Send + Sync;
+
+static RESOLVER: RwLock<Option<Arc<Resolver>>> = RwLock::new(None);
+
+/// Set a process-wide resolver for the addresses of the well-known message buses.
+///
+/// The resolver is consulted by [`Address::session`] and [`Address::system`], and hence by all
+/// APIs connecting to these buses, before looking up the address the usual way. If it returns
+/// `None`, the usual lookup is performed. This allows redirecting all connections, for example to a
+/// private bus in integration tests or to a proxy in sandboxed runtimes.
+///
+/// Any previously set resolver is replaced.
+///
+/// # Example
+///
+/// ```
+/// use zbus::address::{self, Address, BusType};
+///
+/// address::set_resolver(|bus_type| match bus_type {
+///     BusType::Session => Some("unix:path=/tmp/test-session-bus".try_into()),
+///     _ => None,
+/// });
+/// assert_eq!(
+///     Address::session()?.to_string(),
+///     "unix:path=/tmp/test-session-bus",
+/// );
+/// address::reset_resolver();
+/// # Ok::<(), zbus::Error>(())
+/// ```
+pub fn set_resolver<F>(resolver: F)
+where
+    F: Fn(BusType) -> Option<Result<Address>> + Send + Sync + 'static,
+{
+    *RESOLVER.write().expect("poisoned lock") = Some(Arc::new(resolver));
+}
+
+/// Remove the resolver set through [`set_resolver`], if any.
+pub fn reset_resolver() {
+    *RESOLVER.write().expect("poisoned lock") = None;
+}
+
+fn resolve(bus_type: BusType) -> Option<Result<Address>> {
+    // Not holding the lock while calling the resolver, so it can set or reset the resolver.
+    let resolver = RESOLVER.read().expect("poisoned lock").clone();
+
+    resolver.and_then(|resolver| resolver(bus_type))
+}
+
 impl Display for Address {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         self.transport.fmt(f)?;
@@ -182,13 +260,17 @@ mod tests {
     };
     #[cfg(target_os = "macos")]
     use crate::address::transport::Launchd;
-    #[cfg(windows)]
+    #[cfg(not(target_os = "macos"))]
     use crate::address::transport::{Autolaunch, AutolaunchScope};
     use crate::{
         address::transport::{Unix, UnixSocket},
         Error,
     };
-    use std::str::FromStr;
+    use ntest::timeout;
+    use std::{
+        str::FromStr,
+        sync::atomic::{AtomicBool, Ordering},
+    };
     use test_log::test;
 
     #[test]
@@ -285,12 +367,12 @@ mod tests {
                     .set_nonce_file(Some(b"/a/file/path to file 1234".to_vec()))
             ).into()
         );
-        #[cfg(windows)]
+        #[cfg(not(target_os = "macos"))]
         assert_eq!(
             Address::from_str("autolaunch:").unwrap(),
             Transport::Autolaunch(Autolaunch::new()).into(),
         );
-        #[cfg(windows)]
+        #[cfg(not(target_os = "macos"))]
         assert_eq!(
             Address::from_str("autolaunch:scope=*my_cool_scope*").unwrap(),
             Transport::Autolaunch(
@@ -380,12 +462,12 @@ mod tests {
             .to_string(),
             "nonce-tcp:noncefile=/a/file/path%20to%20file%201234,host=localhost,port=4142,family=ipv6"
         );
-        #[cfg(windows)]
+        #[cfg(not(target_os = "macos"))]
         assert_eq!(
             Address::from(Transport::Autolaunch(Autolaunch::new())).to_string(),
             "autolaunch:"
         );
-        #[cfg(windows)]
+        #[cfg(not(target_os = "macos"))]
         assert_eq!(
             Address::from(Transport::Autolaunch(Autolaunch::new().set_scope(Some(
                 AutolaunchScope::Other("*my_cool_scope*".to_string())
@@ -473,5 +555,34 @@ mod tests {
             saw_cookie,
             "nonce file content has been received, but was invalid"
         );
+
+        // A nonce file with the wrong size is rejected before anything is sent.
+        cookie.as_file_mut().write_all(b"TOO LONG").unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let addr = Address::from_str(&format!(
+            "nonce-tcp:host=localhost,port={port},noncefile={encoded_path}"
+        ))
+        .unwrap();
+        assert!(matches!(
+            crate::utils::block_on(addr.connect()),
+            Err(crate::Error::Address(_))
+        ));
+    }
+    #[test]
+    #[timeout(15000)]
+    fn resolver_replacing_itself() {
+        static CALLED: AtomicBool = AtomicBool::new(false);
+
+        // The resolver isn't called with the lock held, so it can reset itself without a deadlock.
+        super::set_resolver(|_| {
+            CALLED.store(true, Ordering::SeqCst);
+            super::reset_resolver();
+
+            None
+        });
+        let _ = Address::system();
+        assert!(CALLED.load(Ordering::SeqCst));
+        assert!(super::RESOLVER.read().unwrap().is_none());
     }
 }
@@ -1,3 +1,5 @@
+#[cfg(all(unix, feature = "autolaunch"))]
+use crate::{process::run, Address};
 use crate::{Error, Result};
 use std::collections::HashMap;
 
@@ -81,3 +83,101 @@ impl std::fmt::Display for AutolaunchScope {
         }
     }
 }
+
+/// Get the address of the session bus through `dbus-launch`.
+///
+/// Just like the reference implementation, we let `dbus-launch` look up the address of the bus
+/// associated with the X11 display of the current session, and start a new bus if there is none.
+#[cfg(all(unix, feature = "autolaunch"))]
+pub(crate) async fn autolaunch_bus_address() -> Result<Address> {
+    let machine_id = machine_id()?;
+    let output = run(
+        "dbus-launch",
+        [
+            "--autolaunch",
+            &machine_id,
+            "--binary-syntax",
+            "--close-stderr",
+        ],
+    )
+    .await?;
+
+    if !output.status.success() {
+        return Err(Error::Address(format!(
+            "dbus-launch terminated with code: {}",
+            output.status
+        )));
+    }
+
+    parse_dbus_launch_output(&output.stdout)
+}
+
+/// Parse the output of `dbus-launch --binary-syntax`.
+///
+/// With the binary syntax, the address is the first nul-terminated string of the output. It's
+/// followed by the PID of the bus and the X11 window ID, in binary form.
+#[cfg(all(unix, feature = "autolaunch"))]
+fn parse_dbus_launch_output(stdout: &[u8]) -> Result<Address> {
+    let addr = stdout
+        .split(|b| *b == b'\0')
+        .next()
+        .filter(|addr| !addr.is_empty())
+        .ok_or_else(|| Error::Address("dbus-launch returned no address".to_owned()))?;
+    let addr = std::str::from_utf8(addr)
+        .map_err(|e| Error::Address(format!("Unable to parse address as UTF-8: {}", e)))?;
+
+    addr.parse()
+}
+
+#[cfg(all(unix, feature = "autolaunch"))]
+fn machine_id() -> Result<String> {
+    let id = std::fs::read_to_string("/var/lib/dbus/machine-id")
+        .or_else(|_| std::fs::read_to_string("/etc/machine-id"))
+        .map_err(|e| {
+            Error::Address(format!(
+                "Failed to read from /var/lib/dbus/machine-id or /etc/machine-id: {e}"
+            ))
+        })?;
+
+    Ok(id.trim_end().to_owned())
+}
+
+#[cfg(all(unix, feature = "autolaunch"))]
+#[cfg(test)]
+mod tests {
+    use super::parse_dbus_launch_output;
+    use crate::{address::Transport, Error};
+    use test_log::test;
+
+    #[test]
+    fn dbus_launch_output() {
+        let mut output =
+            b"unix:abstract=/tmp/dbus-Xe3f8bMq3H,guid=0123456789abcdef0123456789abcdef\0".to_vec();
+        // The PID and window ID that follow the address.
+        output.extend_from_slice(&1234u32.to_ne_bytes());
+        output.extend_from_slice(&[0xff; 8]);
+
+        let addr = parse_dbus_launch_output(&output).unwrap();
+        assert!(matches!(addr.transport(), Transport::Unix(_)));
+        assert_eq!(
+            addr.guid().unwrap().as_str(),
+            "0123456789abcdef0123456789abcdef"
+        );
+
+        // Only the address itself needs to be valid UTF-8.
+        let output = b"unix:path=/tmp/bus\0\xff\xfe";
+        let addr = parse_dbus_launch_output(output).unwrap();
+        assert_eq!(addr.to_string(), "unix:path=/tmp/bus");
+
+        for output in [
+            &b""[..],
+            b"\0unix:path=/tmp/bus",
+            b"\xffunix:path=/tmp/bus\0",
+        ] {
+            assert!(matches!(
+                parse_dbus_launch_output(output),
+                Err(Error::Address(_))
+            ));
+        }
+    }
+}
@@ -7,6 +7,8 @@ use crate::win32::autolaunch_bus_address;
 use crate::{Error, Result};
 #[cfg(not(feature = "tokio"))]
 use async_io::Async;
+#[cfg(all(unix, not(target_os = "macos"), feature = "autolaunch"))]
+use autolaunch::autolaunch_bus_address;
 use std::collections::HashMap;
 #[cfg(not(feature = "tokio"))]
 use std::net::TcpStream;
@@ -30,9 +32,9 @@ mod unix;
 pub use unix::{Unix, UnixSocket};
 mod tcp;
 pub use tcp::{Tcp, TcpTransportFamily};
-#[cfg(windows)]
+#[cfg(not(target_os = "macos"))]
 mod autolaunch;
-#[cfg(windows)]
+#[cfg(not(target_os = "macos"))]
 pub use autolaunch::{Autolaunch, AutolaunchScope};
 #[cfg(target_os = "macos")]
 mod launchd;
@@ -62,7 +64,7 @@ pub enum Transport {
     /// TCP address details
     Tcp(Tcp),
     /// autolaunch D-Bus address.
-    #[cfg(windows)]
+    #[cfg(not(target_os = "macos"))]
     Autolaunch(Autolaunch),
     /// launchd D-Bus address.
     #[cfg(target_os = "macos")]
@@ -80,7 +82,10 @@ pub enum Transport {
 }
 
 impl Transport {
-    #[cfg_attr(any(target_os = "macos", windows), async_recursion::async_recursion)]
+    #[cfg_attr(
+        any(target_os = "macos", windows, feature = "autolaunch"),
+        async_recursion::async_recursion
+    )]
     pub(super) async fn connect(self) -> Result<Stream> {
         match self {
             Transport::Unix(unix) => {
@@ -166,20 +171,29 @@ impl Transport {
 
                     #[cfg(not(feature = "tokio"))]
                     {
-                        let nonce = std::fs::read(nonce_file)?;
+                        let nonce = read_nonce(std::fs::read(nonce_file)?)?;
                         let mut nonce = &nonce[..];
 
                         while !nonce.is_empty() {
                             let len = stream
                                 .write_with(|mut s| std::io::Write::write(&mut s, nonce))
                                 .await?;
+                            if len == 0 {
+                                return Err(Error::InputOutput(
+                                    std::io::Error::new(
+                                        std::io::ErrorKind::WriteZero,
+                                        "failed to send the nonce",
+                                    )
+                                    .into(),
+                                ));
+                            }
                             nonce = &nonce[len..];
                         }
                     }
 
                     #[cfg(feature = "tokio")]
                     {
-                        let nonce = tokio::fs::read(nonce_file).await?;
+                        let nonce = read_nonce(tokio::fs::read(nonce_file).await?)?;
                         tokio::io::AsyncWriteExt::write_all(&mut stream, &nonce).await?;
                     }
 
@@ -188,15 +202,23 @@ impl Transport {
                 None => addr.connect().await.map(Stream::Tcp),
             },
 
-            #[cfg(windows)]
+            #[cfg(not(target_os = "macos"))]
             Transport::Autolaunch(Autolaunch { scope }) => match scope {
                 Some(_) => Err(Error::Address(
                     "Autolaunch scopes are currently unsupported".to_owned(),
                 )),
+                #[cfg(any(windows, feature = "autolaunch"))]
                 None => {
+                    #[cfg(windows)]
                     let addr = autolaunch_bus_address()?;
+                    #[cfg(unix)]
+                    let addr = autolaunch_bus_address().await?;
                     addr.connect().await
                 }
+                #[cfg(all(unix, not(feature = "autolaunch")))]
+                None => Err(Error::Address(
+                    "`autolaunch:` addresses require the `autolaunch` feature".to_owned(),
+                )),
             },
 
             #[cfg(target_os = "macos")]
@@ -218,7 +240,7 @@ impl Transport {
                 feature = "tokio-vsock"
             ))]
             "vsock" => Vsock::from_options(options).map(Self::Vsock),
-            #[cfg(windows)]
+            #[cfg(not(target_os = "macos"))]
             "autolaunch" => Autolaunch::from_options(options).map(Self::Autolaunch),
             #[cfg(target_os = "macos")]
             "launchd" => Launchd::from_options(options).map(Self::Launchd),
@@ -249,6 +271,21 @@ pub(crate) enum Stream {
     Vsock(VsockStream),
 }
 
+/// Validate the contents of a `nonce-tcp:` nonce file.
+///
+/// The nonce is always 16 bytes long. Anything else means we're reading the wrong file (or one
+/// that is still being written), and the server would reject us anyway.
+fn read_nonce(nonce: Vec<u8>) -> Result<Vec<u8>> {
+    if nonce.len() != 16 {
+        return Err(Error::Address(format!(
+            "nonce file must contain 16 bytes, found {}",
+            nonce.len()
+        )));
+    }
+
+    Ok(nonce)
+}
+
 fn decode_hex(c: char) -> Result<u8> {
     match c {
         '0'..='9' => Ok(c as u8 - b'0'),
@@ -339,7 +376,7 @@ impl Display for Transport {
                 feature = "tokio-vsock"
             ))]
             Self::Vsock(vsock) => write!(f, "{}", vsock)?,
-            #[cfg(windows)]
+            #[cfg(not(target_os = "macos"))]
             Self::Autolaunch(autolaunch) => write!(f, "{}", autolaunch)?,
             #[cfg(target_os = "macos")]
             Self::Launchd(launchd) => write!(f, "{}", launchd)?,
@@ -3,6 +3,7 @@ use static_assertions::assert_impl_all;
 use std::net::TcpStream;
 #[cfg(all(unix, not(feature = "tokio")))]
 use std::os::unix::net::UnixStream;
+use std::time::Duration;
 #[cfg(feature = "tokio")]
 use tokio::net::TcpStream;
 #[cfg(all(unix, feature = "tokio"))]
@@ -10,14 +11,19 @@ use tokio::net::UnixStream;
 #[cfg(all(windows, not(feature = "tokio")))]
 use uds_windows::UnixStream;
 
-use zvariant::{ObjectPath, Str};
+use zvariant::{Endian, ObjectPath, Str};
 
-#[cfg(feature = "p2p")]
-use crate::Guid;
 use crate::{
-    address::Address, blocking::Connection, connection::socket::BoxedSplit, names::WellKnownName,
-    object_server::Interface, utils::block_on, AuthMechanism, Error, Result,
+    address::Address,
+    blocking::Connection,
+    connection::socket::BoxedSplit,
+    names::WellKnownName,
+    object_server::{Interface, ObjectTree},
+    utils::block_on,
+    AuthMechanism, Error, Result,
 };
+#[cfg(feature = "p2p")]
+use crate::{fdo::ConnectionCredentials, Guid};
 
 /// A builder for [`zbus::blocking::Connection`].
 #[derive(Debug)]
@@ -102,6 +108,28 @@ impl<'a> Builder<'a> {
         Self(self.0.auth_mechanisms(auth_mechanisms))
     }
 
+    /// Specify the mechanisms offered to clients during authentication.
+    ///
+    /// See [`zbus::connection::Builder::offered_auth_mechanisms`] for details.
+    #[cfg(feature = "p2p")]
+    pub fn offered_auth_mechanisms<I>(self, auth_mechanisms: I) -> Self
+    where
+        I: IntoIterator<Item = AuthMechanism>,
+    {
+        Self(self.0.offered_auth_mechanisms(auth_mechanisms))
+    }
+
+    /// Decide whether to accept a client, once it successfully authenticated.
+    ///
+    /// See [`zbus::connection::Builder::auth_policy`] for details.
+    #[cfg(feature = "p2p")]
+    pub fn auth_policy<F>(self, policy: F) -> Self
+    where
+        F: Fn(&ConnectionCredentials, AuthMechanism) -> bool + Send + Sync + 'static,
+    {
+        Self(self.0.auth_policy(policy))
+    }
+
     /// The cookie context to use during authentication.
     ///
     /// This is only used when the `cookie` authentication mechanism is enabled and only valid for
@@ -129,6 +157,25 @@ impl<'a> Builder<'a> {
         Self(self.0.cookie_id(id))
     }
 
+    /// Only allow a peer running as one of the given users.
+    ///
+    /// See [`zbus::connection::Builder::allowed_peer_uids`] for details.
+    #[cfg(unix)]
+    pub fn allowed_peer_uids<I>(self, uids: I) -> Self
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        Self(self.0.allowed_peer_uids(uids))
+    }
+
+    /// Only allow a peer running as `root` or as the system bus user (`messagebus` or `dbus`).
+    ///
+    /// See [`zbus::connection::Builder::trusted_system_bus_peer`] for details.
+    #[cfg(unix)]
+    pub fn trusted_system_bus_peer(self) -> Self {
+        Self(self.0.trusted_system_bus_peer())
+    }
+
     /// The to-be-created connection will be a peer-to-peer connection.
     ///
     /// This method is only available when the `p2p` feature is enabled.
@@ -179,6 +226,43 @@ impl<'a> Builder<'a> {
         Self(self.0.max_queued(max))
     }
 
+    /// Set the capacity of the message journal.
+    ///
+    /// By default, the journal is disabled. See [`Connection::set_journal_capacity`] for details.
+    pub fn journal_capacity(self, capacity: usize) -> Self {
+        Self(self.0.journal_capacity(capacity))
+    }
+
+    /// Enable or disable strict sender validation of replies and signals.
+    ///
+    /// It's disabled by default. See [`zbus::Connection::set_strict_sender_validation`] for
+    /// details.
+    pub fn strict_sender_validation(self, enabled: bool) -> Self {
+        Self(self.0.strict_sender_validation(enabled))
+    }
+
+    /// Set the default timeout of method calls.
+    ///
+    /// There is no timeout by default. See [`zbus::Connection::set_method_timeout`] for details.
+    pub fn method_timeout(self, timeout: Duration) -> Self {
+        Self(self.0.method_timeout(timeout))
+    }
+
+    /// Set the endianness of the method calls and signals built by the connection.
+    ///
+    /// The default is the native endianness. See [`zbus::Connection::set_endian`] for details.
+    pub fn endian(self, endian: Endian) -> Self {
+        Self(self.0.endian(endian))
+    }
+
+    /// Set the maximum size of the messages received on the connection.
+    ///
+    /// The default, and maximum, is 128 MiB as per the D-Bus specification. See
+    /// [`zbus::Connection::max_message_size`] for details.
+    pub fn max_message_size(self, max: usize) -> Self {
+        Self(self.0.max_message_size(max))
+    }
+
     /// Register a D-Bus [`Interface`] to be served at a given path.
     ///
     /// This is similar to [`zbus::blocking::ObjectServer::at`], except that it allows you to have
@@ -194,6 +278,18 @@ impl<'a> Builder<'a> {
         self.0.serve_at(path, iface).map(Self)
     }
 
+    /// Register all the interfaces of an [`ObjectTree`] to be served, with `prefix` as its root.
+    ///
+    /// This is similar to [`zbus::blocking::ObjectServer::mount`], except that the interfaces are
+    /// available immediately after the connection is established.
+    pub fn mount<P>(self, prefix: P, tree: ObjectTree) -> Result<Self>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<Error>,
+    {
+        self.0.mount(prefix, tree).map(Self)
+    }
+
     /// Register a well-known name for this connection on the bus.
     ///
     /// This is similar to [`zbus::blocking::Connection::request_name`], except the name is
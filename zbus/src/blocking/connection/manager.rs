@@ -0,0 +1,98 @@
+use static_assertions::assert_impl_all;
+use std::sync::OnceLock;
+
+use crate::{blocking::Connection, utils::block_on, Result};
+
+/// A blocking wrapper of [`zbus::connection::BusManager`].
+///
+/// Most of the API is very similar to [`zbus::connection::BusManager`], except it's blocking.
+///
+/// # Example
+///
+/// ```no_run
+/// use zbus::blocking::connection::BusManager;
+///
+/// let session = BusManager::global().session()?;
+/// // Same connection, wherever it's requested from.
+/// let same = BusManager::global().session()?;
+/// assert_eq!(session.unique_name(), same.unique_name());
+/// # Ok::<(), zbus::Error>(())
+/// ```
+///
+/// [`zbus::connection::BusManager`]: crate::connection::BusManager
+#[derive(Debug, Default, Clone)]
+pub struct BusManager {
+    inner: crate::connection::BusManager,
+}
+
+assert_impl_all!(BusManager: Send, Sync, Unpin);
+
+static GLOBAL: OnceLock<BusManager> = OnceLock::new();
+
+impl BusManager {
+    /// Create a new manager, without any connection yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide manager.
+    ///
+    /// It shares its connections with [`zbus::connection::BusManager::global`].
+    ///
+    /// [`zbus::connection::BusManager::global`]: crate::connection::BusManager::global
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(|| crate::connection::BusManager::global().clone().into())
+    }
+
+    /// A connection to the session bus.
+    ///
+    /// The connection is created on the first call, or if the previous one got disconnected.
+    pub fn session(&self) -> Result<Connection> {
+        block_on(self.inner.session()).map(Into::into)
+    }
+
+    /// A connection to the system bus.
+    ///
+    /// The connection is created on the first call, or if the previous one got disconnected.
+    pub fn system(&self) -> Result<Connection> {
+        block_on(self.inner.system()).map(Into::into)
+    }
+
+    /// Get a reference to the underlying async `BusManager`.
+    pub fn inner(&self) -> &crate::connection::BusManager {
+        &self.inner
+    }
+
+    /// Get the underlying async `BusManager`, consuming `self`.
+    pub fn into_inner(self) -> crate::connection::BusManager {
+        self.inner
+    }
+}
+
+impl From<crate::connection::BusManager> for BusManager {
+    fn from(manager: crate::connection::BusManager) -> Self {
+        Self { inner: manager }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::BusManager;
+
+    #[test]
+    #[timeout(15000)]
+    fn shared_connection() {
+        let manager = BusManager::new();
+        let conn = manager.session().unwrap();
+        let name = conn.unique_name().unwrap().to_owned();
+        assert_eq!(manager.session().unwrap().unique_name(), Some(&name));
+
+        // Connections are shared with the async manager.
+        let conn = manager.inner().session();
+        let conn = crate::utils::block_on(conn).unwrap();
+        assert_eq!(conn.unique_name(), Some(&name));
+    }
+}
@@ -3,12 +3,13 @@
 use enumflags2::BitFlags;
 use event_listener::EventListener;
 use static_assertions::assert_impl_all;
-use std::{io, ops::Deref};
+use std::{io, ops::Deref, time::Duration};
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, WellKnownName};
-use zvariant::ObjectPath;
+use zvariant::{Endian, ObjectPath, OwnedValue, Value};
 
 use crate::{
     blocking::ObjectServer,
+    connection::State,
     fdo::{ConnectionCredentials, RequestNameFlags, RequestNameReply},
     message::Message,
     utils::block_on,
@@ -18,6 +19,9 @@ use crate::{
 mod builder;
 pub use builder::Builder;
 
+mod manager;
+pub use manager::BusManager;
+
 /// A blocking wrapper of [`zbus::Connection`].
 ///
 /// Most of the API is very similar to [`zbus::Connection`], except it's blocking.
@@ -40,6 +44,20 @@ impl Connection {
         block_on(crate::Connection::system()).map(Self::from)
     }
 
+    /// The process-wide shared connection to the session/user message bus.
+    ///
+    /// See [`crate::Connection::session_shared`].
+    pub fn session_shared() -> Result<Self> {
+        block_on(crate::Connection::session_shared()).map(Self::from)
+    }
+
+    /// The process-wide shared connection to the system-wide message bus.
+    ///
+    /// See [`crate::Connection::system_shared`].
+    pub fn system_shared() -> Result<Self> {
+        block_on(crate::Connection::system_shared()).map(Self::from)
+    }
+
     /// The capacity of the main (unfiltered) queue.
     pub fn max_queued(&self) -> usize {
         self.inner.max_queued()
@@ -50,6 +68,76 @@ impl Connection {
         self.inner.set_max_queued(max)
     }
 
+    /// The capacity of the message journal.
+    ///
+    /// See [`zbus::Connection::journal_capacity`] for details.
+    pub fn journal_capacity(&self) -> usize {
+        self.inner.journal_capacity()
+    }
+
+    /// Set the capacity of the message journal.
+    ///
+    /// See [`zbus::Connection::set_journal_capacity`] for details.
+    pub fn set_journal_capacity(&self, capacity: usize) {
+        self.inner.set_journal_capacity(capacity)
+    }
+
+    /// Whether strict sender validation is enabled.
+    ///
+    /// See [`zbus::Connection::strict_sender_validation`] for details.
+    pub fn strict_sender_validation(&self) -> bool {
+        self.inner.strict_sender_validation()
+    }
+
+    /// Enable or disable strict sender validation.
+    ///
+    /// See [`zbus::Connection::set_strict_sender_validation`] for details.
+    pub fn set_strict_sender_validation(&self, enabled: bool) {
+        self.inner.set_strict_sender_validation(enabled)
+    }
+
+    /// The default timeout of method calls, if any.
+    ///
+    /// See [`zbus::Connection::method_timeout`] for details.
+    pub fn method_timeout(&self) -> Option<Duration> {
+        self.inner.method_timeout()
+    }
+
+    /// Set the default timeout of method calls.
+    ///
+    /// See [`zbus::Connection::set_method_timeout`] for details.
+    pub fn set_method_timeout(&self, timeout: Option<Duration>) {
+        self.inner.set_method_timeout(timeout)
+    }
+
+    /// The maximum size of the messages received on the connection.
+    ///
+    /// See [`zbus::Connection::max_message_size`] for details.
+    pub fn max_message_size(&self) -> usize {
+        self.inner.max_message_size()
+    }
+
+    /// The endianness of the method calls and signals built by the connection.
+    ///
+    /// See [`zbus::Connection::endian`] for details.
+    pub fn endian(&self) -> Endian {
+        self.inner.endian()
+    }
+
+    /// Set the endianness of the method calls and signals built by the connection.
+    ///
+    /// See [`zbus::Connection::set_endian`] for details.
+    pub fn set_endian(&self, endian: Endian) {
+        self.inner.set_endian(endian)
+    }
+
+    /// The entries of the message journal, oldest first.
+    ///
+    /// See [`zbus::Connection::journal`] for details.
+    pub fn journal(&self) -> Vec<crate::connection::journal::Entry> {
+        self.inner.journal()
+    }
+
     /// The server's GUID.
     pub fn server_guid(&self) -> &str {
         self.inner.server_guid()
@@ -65,6 +153,17 @@ impl Connection {
         block_on(self.inner.send(msg))
     }
 
+    /// Send a raw, already encoded message to the peer.
+    ///
+    /// See [`zbus::Connection::send_raw`] for details.
+    pub fn send_raw(&self, bytes: Vec<u8>, #[cfg(unix)] fds: Vec<zvariant::OwnedFd>) -> Result<()> {
+        block_on(self.inner.send_raw(
+            bytes,
+            #[cfg(unix)]
+            fds,
+        ))
+    }
+
     /// Send a method call.
     ///
     /// Create a method-call message, send it over the connection, then wait for the reply. Incoming
@@ -101,6 +200,119 @@ impl Connection {
         )
     }
 
+    /// Send a method call and wait for the reply for at most `timeout`.
+    ///
+    /// See [`zbus::Connection::call_method_with_timeout`] for details.
+    pub fn call_method_with_timeout<'d, 'p, 'i, 'm, D, P, I, M, B>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        iface: Option<I>,
+        method_name: M,
+        body: &B,
+        timeout: Duration,
+    ) -> Result<Message>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+    {
+        block_on(self.inner.call_method_with_timeout(
+            destination,
+            path,
+            iface,
+            method_name,
+            body,
+            timeout,
+        ))
+    }
+
+    /// Send a method call without expecting a reply.
+    ///
+    /// See [`zbus::Connection::call_method_noreply`] for details.
+    pub fn call_method_noreply<'d, 'p, 'i, 'm, D, P, I, M, B>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        iface: Option<I>,
+        method_name: M,
+        body: &B,
+    ) -> Result<()>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+    {
+        block_on(
+            self.inner
+                .call_method_noreply(destination, path, iface, method_name, body),
+        )
+    }
+
+    /// Get the value of a property.
+    ///
+    /// See [`zbus::Connection::get_property`] for details.
+    pub fn get_property<'d, 'p, 'i, D, P, I, T>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        iface: I,
+        property_name: &str,
+    ) -> Result<T>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<Error>,
+    {
+        block_on(
+            self.inner
+                .get_property(destination, path, iface, property_name),
+        )
+    }
+
+    /// Set the value of a property.
+    ///
+    /// See [`zbus::Connection::set_property`] for details.
+    pub fn set_property<'d, 'p, 'i, 't, D, P, I, T>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        iface: I,
+        property_name: &str,
+        value: T,
+    ) -> Result<()>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        T: 't + Into<Value<'t>>,
+    {
+        block_on(
+            self.inner
+                .set_property(destination, path, iface, property_name, value),
+        )
+    }
+
     /// Emit a signal.
     ///
     /// Create a signal message, and send it over the connection.
@@ -262,6 +474,21 @@ impl Connection {
     pub fn close(self) -> Result<()> {
         block_on(self.inner.close())
     }
+
+    /// The state of the connection.
+    ///
+    /// See [`zbus::Connection::state`] for details.
+    pub fn state(&self) -> State {
+        self.inner.state()
+    }
+
+    /// Wait for the connection to be terminated.
+    ///
+    /// Returns the error that terminated the connection. See [`zbus::Connection::state`] for
+    /// details.
+    pub fn disconnected(&self) -> Error {
+        block_on(self.inner.disconnected())
+    }
 }
 
 impl From<crate::Connection> for Connection {
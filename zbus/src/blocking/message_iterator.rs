@@ -100,6 +100,27 @@ impl MessageIterator {
         .map(|s| Self { azync: s })
     }
 
+    /// Create a message iterator for the given match rule, that replays the last signals.
+    ///
+    /// This is a wrapper around [`crate::MessageStream::for_sticky_match_rule`].
+    pub fn for_sticky_match_rule<R>(
+        rule: R,
+        conn: &Connection,
+        max_queued: Option<usize>,
+    ) -> Result<Self>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<crate::Error>,
+    {
+        block_on(crate::MessageStream::for_sticky_match_rule(
+            rule,
+            conn.inner(),
+            max_queued,
+        ))
+        .map(Some)
+        .map(|s| Self { azync: s })
+    }
+
     /// The associated match rule, if any.
     pub fn match_rule(&self) -> Option<MatchRule<'_>> {
         self.azync
@@ -107,6 +128,46 @@ impl MessageIterator {
             .expect("Inner stream is `None`")
             .match_rule()
     }
+
+    /// A view of this iterator, only yielding the messages that also match `rule`.
+    ///
+    /// This is a wrapper around [`crate::MessageStream::filter_rule`].
+    pub fn filter_rule<R>(&self, rule: R) -> Result<Self>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<crate::Error>,
+    {
+        self.inner()
+            .filter_rule(rule)
+            .map(|s| Self { azync: Some(s) })
+    }
+
+    /// A view of this iterator, only yielding method calls.
+    ///
+    /// This is a wrapper around [`crate::MessageStream::method_calls`].
+    pub fn method_calls(&self) -> Self {
+        Self {
+            azync: Some(self.inner().method_calls()),
+        }
+    }
+
+    /// A view of this iterator, only yielding signals.
+    ///
+    /// This is a wrapper around [`crate::MessageStream::signals`].
+    pub fn signals(&self) -> Self {
+        Self {
+            azync: Some(self.inner().signals()),
+        }
+    }
+
+    /// A view of this iterator, only yielding error replies.
+    ///
+    /// This is a wrapper around [`crate::MessageStream::errors`].
+    pub fn errors(&self) -> Self {
+        Self {
+            azync: Some(self.inner().errors()),
+        }
+    }
 }
 
 impl Iterator for MessageIterator {
@@ -4,7 +4,7 @@ use static_assertions::assert_impl_all;
 use zvariant::ObjectPath;
 
 use crate::{
-    object_server::{Interface, InterfaceDeref, InterfaceDerefMut, SignalContext},
+    object_server::{Interface, InterfaceDeref, InterfaceDerefMut, ObjectTree, SignalContext},
     utils::block_on,
     Error, Result,
 };
@@ -157,6 +157,17 @@ impl ObjectServer {
         block_on(self.azync.at(path, iface))
     }
 
+    /// Register all the interfaces of an [`ObjectTree`], with `prefix` as its root.
+    ///
+    /// See [`crate::ObjectServer::mount`] for details.
+    pub fn mount<'p, P>(&self, prefix: P, tree: ObjectTree) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.mount(prefix, tree))
+    }
+
     /// Unregister a D-Bus [`Interface`] at a given path.
     ///
     /// If there are no more interfaces left at that path, destroys the object as well.
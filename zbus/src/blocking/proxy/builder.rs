@@ -1,8 +1,11 @@
+use enumflags2::BitFlags;
 use static_assertions::assert_impl_all;
 use zbus_names::{BusName, InterfaceName};
 use zvariant::ObjectPath;
 
-use crate::{blocking::Connection, proxy::CacheProperties, utils::block_on, Error, Result};
+use crate::{
+    blocking::Connection, message::Coercion, proxy::CacheProperties, utils::block_on, Error, Result,
+};
 
 pub use crate::proxy::ProxyDefault;
 
@@ -52,6 +55,30 @@ impl<'a, T> Builder<'a, T> {
         Self(self.0.uncached_properties(properties))
     }
 
+    /// Set the coercions to attempt on replies with unexpected signatures.
+    ///
+    /// See [`zbus::proxy::Builder::reply_coercions`] for details.
+    #[must_use]
+    pub fn reply_coercions(self, coercions: BitFlags<Coercion>) -> Self {
+        Self(self.0.reply_coercions(coercions))
+    }
+
+    /// Whether method calls should activate the destination service if needed.
+    ///
+    /// See [`zbus::proxy::Builder::auto_start`] for details.
+    #[must_use]
+    pub fn auto_start(self, auto_start: bool) -> Self {
+        Self(self.0.auto_start(auto_start))
+    }
+
+    /// Whether method calls may trigger interactive authorization.
+    ///
+    /// See [`zbus::proxy::Builder::allow_interactive_auth`] for details.
+    #[must_use]
+    pub fn allow_interactive_auth(self, allow: bool) -> Self {
+        Self(self.0.allow_interactive_auth(allow))
+    }
+
     /// Build a proxy from the builder.
     ///
     /// # Panics
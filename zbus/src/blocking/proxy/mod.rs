@@ -1,15 +1,30 @@
 //! The client-side proxy API.
 
 use enumflags2::BitFlags;
-use futures_util::StreamExt;
+use event_listener::Event;
+use futures_util::{
+    future::{select, Either},
+    StreamExt,
+};
 use static_assertions::assert_impl_all;
-use std::{fmt, ops::Deref};
-use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
+use std::{
+    fmt,
+    ops::Deref,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+use tracing::{debug, warn};
+use zbus_names::{BusName, InterfaceName, MemberName, OwnedUniqueName, UniqueName};
 use zvariant::{ObjectPath, OwnedValue, Value};
 
 use crate::{
     blocking::Connection,
-    message::Message,
+    message::{Coercion, Message},
     proxy::{MethodFlags, ProxyDefault},
     utils::block_on,
     Error, Result,
@@ -157,6 +172,32 @@ impl<'a> Proxy<'a> {
         self.inner().interface()
     }
 
+    /// The coercions attempted on replies by [`Proxy::call_coerced`].
+    pub fn reply_coercions(&self) -> BitFlags<Coercion> {
+        self.inner().reply_coercions()
+    }
+
+    /// Whether method calls through this proxy can activate the destination service.
+    ///
+    /// See [`Builder::auto_start`] for details.
+    pub fn auto_start(&self) -> bool {
+        self.inner().auto_start()
+    }
+
+    /// Whether method calls through this proxy may trigger interactive authorization.
+    ///
+    /// See [`Builder::allow_interactive_auth`] for details.
+    pub fn allow_interactive_auth(&self) -> bool {
+        self.inner().allow_interactive_auth()
+    }
+
+    /// The current owner of the destination name.
+    ///
+    /// See [`zbus::Proxy::owner`] for details.
+    pub fn owner(&self) -> Result<Option<OwnedUniqueName>> {
+        block_on(self.inner().owner())
+    }
+
     /// Introspect the associated object, and return the XML description.
     ///
     /// See the [xml](xml/index.html) module for parsing the result.
@@ -242,6 +283,19 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().call(method_name, body))
     }
 
+    /// Call a method and return the reply body, coercing it to the expected type if needed.
+    ///
+    /// See [`zbus::Proxy::call_coerced`] for details.
+    pub fn call_coerced<'m, M, B, R>(&self, method_name: M, body: &B) -> Result<R>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: serde::de::DeserializeOwned + zvariant::Type,
+    {
+        block_on(self.inner().call_coerced(method_name, body))
+    }
+
     /// Call a method and return the reply body, optionally supplying a set of
     /// method flags to control the way the method call message is sent and handled.
     ///
@@ -278,6 +332,19 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().call_noreply(method_name, body))
     }
 
+    /// Call several methods back-to-back and return their replies, in the same order.
+    ///
+    /// See [`crate::Proxy::call_pipelined`] for details.
+    pub fn call_pipelined<'m, 'b, C, M, B>(&self, calls: C) -> Result<Vec<Message>>
+    where
+        C: IntoIterator<Item = (M, &'b B)>,
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType + 'b,
+    {
+        block_on(self.inner().call_pipelined(calls))
+    }
+
     /// Create a stream for signal named `signal_name`.
     ///
     /// # Errors
@@ -328,6 +395,41 @@ impl<'a> Proxy<'a> {
             .map(SignalIterator)
     }
 
+    /// Run `handler` for each signal named `signal_name`, on a dedicated thread.
+    ///
+    /// Unlike iterating over [`Proxy::receive_signal`], this doesn't tie the handling of signals
+    /// to any of your threads, so a slow handler doesn't keep the rest of your application, e.g
+    /// the main loop of a GUI, from making progress. A panic in `handler` is caught and logged,
+    /// and the handler keeps being called for the following signals.
+    ///
+    /// The handler is disconnected when the returned [`SignalHandler`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Proxy::receive_signal`], or an I/O error if the thread couldn't be spawned.
+    pub fn connect_signal<M, F>(&self, signal_name: M, handler: F) -> Result<SignalHandler>
+    where
+        M: TryInto<MemberName<'static>>,
+        M::Error: Into<Error>,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let stream = block_on(self.inner().receive_signal(signal_name))?;
+
+        SignalHandler::spawn(stream, handler)
+    }
+
+    /// Run `handler` for each signal emitted by this service, on a dedicated thread.
+    ///
+    /// See [`Proxy::connect_signal`] for details.
+    pub fn connect_all_signals<F>(&self, handler: F) -> Result<SignalHandler>
+    where
+        F: FnMut(Message) + Send + 'static,
+    {
+        let stream = block_on(self.inner().receive_all_signals())?;
+
+        SignalHandler::spawn(stream, handler)
+    }
+
     /// Get an iterator to receive owner changed events.
     ///
     /// If the proxy destination is a unique name, the stream will be notified of the peer
@@ -346,6 +448,18 @@ impl<'a> Proxy<'a> {
         PropertyIterator(block_on(self.inner().receive_property_changed(name)))
     }
 
+    /// Wait until the value of a property satisfies `condition`.
+    ///
+    /// See [`crate::Proxy::wait_for_property`] for details.
+    pub fn wait_for_property<T, F>(&self, name: &str, condition: F, timeout: Duration) -> Result<T>
+    where
+        T: TryFrom<OwnedValue> + Unpin,
+        T::Error: Into<Error>,
+        F: FnMut(&T) -> bool,
+    {
+        block_on(self.inner().wait_for_property(name, condition, timeout))
+    }
+
     /// Get an iterator to receive property changed events.
     ///
     /// Note that zbus doesn't queue the updates. If the listener is slower than the receiver, it
@@ -405,6 +519,16 @@ impl<'a> SignalIterator<'a> {
     pub fn name(&self) -> Option<&MemberName<'a>> {
         self.0.as_ref().expect("`SignalStream` is `None`").name()
     }
+
+    /// Wait for the next signal, for at most `timeout`.
+    ///
+    /// Returns [`Error::Timeout`] if no signal is received in time, and `None` if the stream has
+    /// ended, like [`Iterator::next`] would.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<Message>> {
+        let stream = self.0.as_mut().expect("`SignalStream` is `None`");
+
+        block_on(crate::timeout::timeout(timeout, stream.next())).ok_or(Error::Timeout(timeout))
+    }
 }
 
 assert_impl_all!(SignalIterator<'_>: Send, Sync, Unpin);
@@ -427,6 +551,66 @@ impl std::ops::Drop for SignalIterator<'_> {
     }
 }
 
+/// A signal handler running on a dedicated thread.
+///
+/// Use [`Proxy::connect_signal`] to create an instance of this type. The handler is disconnected
+/// when this is dropped, after it returns if it's currently running.
+#[derive(Debug)]
+pub struct SignalHandler {
+    stopped: Arc<AtomicBool>,
+    stop_event: Arc<Event>,
+}
+
+assert_impl_all!(SignalHandler: Send, Sync, Unpin);
+
+impl SignalHandler {
+    fn spawn<F>(mut stream: crate::proxy::SignalStream<'static>, mut handler: F) -> Result<Self>
+    where
+        F: FnMut(Message) + Send + 'static,
+    {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop_event = Arc::new(Event::new());
+        let name = match stream.name() {
+            Some(name) => format!("zbus `{name}` handler"),
+            None => "zbus signal handler".to_string(),
+        };
+
+        let thread_stopped = stopped.clone();
+        let thread_stop_event = stop_event.clone();
+        thread::Builder::new().name(name).spawn(move || {
+            loop {
+                let stop_listener = thread_stop_event.listen();
+                if thread_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let msg = match block_on(select(stream.next(), stop_listener)) {
+                    Either::Left((Some(msg), _)) => msg,
+                    Either::Left((None, _)) | Either::Right(_) => break,
+                };
+                if catch_unwind(AssertUnwindSafe(|| handler(msg))).is_err() {
+                    warn!("Signal handler panicked");
+                }
+            }
+            debug!("Signal handler stopped");
+
+            block_on(crate::AsyncDrop::async_drop(stream));
+        })?;
+
+        Ok(Self {
+            stopped,
+            stop_event,
+        })
+    }
+}
+
+impl std::ops::Drop for SignalHandler {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.stop_event.notify(usize::MAX);
+    }
+}
+
 /// An [`std::iter::Iterator`] implementation that yields property change notifications.
 ///
 /// Use [`Proxy::receive_property_changed`] to create an instance of this type.
@@ -559,4 +743,76 @@ mod tests {
         // one we subscribed to.
         assert!(signal.args().unwrap().name() == well_known);
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn signal_handler() {
+        let conn = Connection::session().unwrap();
+        let proxy = blocking::fdo::DBusProxy::new(&conn).unwrap();
+        let panicking = "org.freedesktop.zbus.SignalHandlerTest.Panic";
+        let well_known = "org.freedesktop.zbus.SignalHandlerTest";
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handler = proxy
+            .inner()
+            .connect_signal("NameAcquired", move |msg: Message| {
+                let name: zbus_names::OwnedBusName = msg.body().deserialize().unwrap();
+                // The panic must not stop the following signals from being handled.
+                assert_ne!(name.as_str(), panicking);
+                tx.send(name).unwrap();
+            })
+            .unwrap();
+
+        for name in [panicking, well_known] {
+            proxy
+                .request_name(name.try_into().unwrap(), Default::default())
+                .unwrap();
+        }
+        loop {
+            let name = rx.recv().unwrap();
+            if name.as_str() == well_known {
+                break;
+            }
+        }
+
+        drop(handler);
+        // The handler, and hence the sender, is dropped once its thread is done.
+        while rx.recv().is_ok() {}
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn signal_timeout() {
+        let conn = Connection::session().unwrap();
+        let proxy = blocking::fdo::DBusProxy::new(&conn).unwrap();
+        let well_known = "org.freedesktop.zbus.SignalTimeoutTest";
+        let mut owner_changed = proxy
+            .inner()
+            .receive_signal_with_args("NameOwnerChanged", &[(0, well_known)])
+            .unwrap();
+        let mut name_acquired = proxy
+            .receive_name_acquired_with_args(&[(0, well_known)])
+            .unwrap();
+
+        let timeout = Duration::from_millis(10);
+        assert!(matches!(
+            owner_changed.next_timeout(timeout),
+            Err(Error::Timeout(t)) if t == timeout
+        ));
+        assert!(matches!(
+            name_acquired.next_timeout(timeout),
+            Err(Error::Timeout(t)) if t == timeout
+        ));
+
+        proxy
+            .request_name(well_known.try_into().unwrap(), Default::default())
+            .unwrap();
+
+        let timeout = Duration::from_secs(10);
+        let msg = owner_changed.next_timeout(timeout).unwrap().unwrap();
+        let (name, _, _): (String, String, String) = msg.body().deserialize().unwrap();
+        assert_eq!(name, well_known);
+        let signal = name_acquired.next_timeout(timeout).unwrap().unwrap();
+        assert_eq!(signal.args().unwrap().name(), well_known);
+    }
 }
@@ -0,0 +1,671 @@
+//! Filtering proxy between peer-to-peer clients and a message bus.
+//!
+//! This module provides [`BusProxy`], a building block for sandboxing D-Bus clients in the spirit
+//! of [xdg-dbus-proxy]: clients connect to the proxy through peer-to-peer connections and the
+//! proxy forwards their traffic to a real bus connection, as long as it's allowed by the
+//! configured [`Policy`].
+//!
+//! [xdg-dbus-proxy]: https://github.com/flatpak/xdg-dbus-proxy
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+    sync::Mutex,
+};
+
+use futures_util::{future::select, StreamExt};
+use static_assertions::assert_impl_all;
+use tracing::{debug, trace};
+use zbus_names::{BusName, InterfaceName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName};
+use zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::{
+    fdo,
+    message::{Flags, Header, Message, Type},
+    Connection, Error, MatchRule, MessageStream, Result,
+};
+
+const BUS_NAME: &str = "org.freedesktop.DBus";
+
+/// The policy enforced by a [`BusProxy`].
+///
+/// Everything is denied unless allowed explicitly:
+///
+/// * A method call from the client is only forwarded to the bus if its destination is one of the
+///   allowed names and, if any paths or interfaces are allowed, its path and interface are too.
+/// * Signals and method calls, in either direction, that are not covered by the previous rule are
+///   only forwarded if their path and interface are allowed, which requires allowing at least one
+///   path or interface. Of the signals from the bus itself, `NameAcquired` and `NameLost` are
+///   always forwarded to the client, while `NameOwnerChanged` is only forwarded for the allowed
+///   names.
+/// * Of the methods of the bus itself (`org.freedesktop.DBus`), only the few clients can't
+///   function without are allowed: `GetId`, `GetNameOwner`, `ListActivatableNames`, `ListNames`,
+///   `NameHasOwner`, `AddMatch` (without eavesdropping) and `RemoveMatch`, along with the methods
+///   of the `org.freedesktop.DBus.Peer` interface and introspection. In particular, clients can't
+///   own names nor become monitors.
+///
+/// Replies are not subject to the policy: the [`BusProxy`] only forwards the ones to method calls
+/// it forwarded itself.
+///
+/// # Example
+///
+/// ```
+/// use zbus::bus_proxy::Policy;
+///
+/// let _policy = Policy::new()
+///     .allow_name("org.freedesktop.portal.Desktop")?
+///     .allow_path("/org/freedesktop/portal/desktop")?
+///     .allow_interface("org.freedesktop.portal.FileChooser")?;
+/// # Ok::<_, zbus::Error>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Policy {
+    names: HashSet<OwnedBusName>,
+    paths: Vec<OwnedObjectPath>,
+    interfaces: HashSet<OwnedInterfaceName>,
+}
+
+assert_impl_all!(Policy: Send, Sync, Unpin);
+
+impl Policy {
+    /// Create a policy that denies everything, except for the essential calls to the bus itself.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow method calls destined to `name`.
+    pub fn allow_name<'n, N>(mut self, name: N) -> Result<Self>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+        self.names.insert(name.into());
+
+        Ok(self)
+    }
+
+    /// Allow messages on `path` and all the objects under it.
+    pub fn allow_path<'p, P>(mut self, path: P) -> Result<Self>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        self.paths.push(path.into());
+
+        Ok(self)
+    }
+
+    /// Allow messages on `interface`.
+    pub fn allow_interface<'i, I>(mut self, interface: I) -> Result<Self>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+        self.interfaces.insert(interface.into());
+
+        Ok(self)
+    }
+
+    /// Check if a method call or signal from the client is allowed to go to the bus.
+    fn allows_outgoing(&self, msg: &Message) -> bool {
+        let hdr = msg.header();
+        match hdr.message_type() {
+            Type::MethodCall => match hdr.destination() {
+                Some(dest) if dest.as_str() == BUS_NAME => allows_bus_call(msg, &hdr),
+                Some(dest) => {
+                    self.names.iter().any(|name| name.as_str() == dest.as_str())
+                        && self.allows_member(&hdr)
+                }
+                None => false,
+            },
+            Type::Signal => self.allows_member_explicitly(&hdr),
+            Type::MethodReturn | Type::Error => false,
+        }
+    }
+
+    /// Check if a method call or signal from the bus is allowed to go to the client.
+    fn allows_incoming(&self, msg: &Message) -> bool {
+        let hdr = msg.header();
+        match hdr.message_type() {
+            Type::MethodCall => self.allows_member_explicitly(&hdr),
+            Type::Signal if hdr.sender().map(|s| s.as_str()) == Some(BUS_NAME) => {
+                self.allows_bus_signal(msg, &hdr) || self.allows_member_explicitly(&hdr)
+            }
+            Type::Signal => self.allows_member_explicitly(&hdr),
+            Type::MethodReturn | Type::Error => false,
+        }
+    }
+
+    fn allows_bus_signal(&self, msg: &Message, hdr: &Header<'_>) -> bool {
+        if hdr.interface().map(|i| i.as_str()) != Some(BUS_NAME) {
+            return false;
+        }
+
+        match hdr.member().map(|m| m.as_str()) {
+            // These are only sent to the bus connection about its own names.
+            Some("NameAcquired" | "NameLost") => true,
+            Some("NameOwnerChanged") => msg
+                .body()
+                .deserialize::<(&str, &str, &str)>()
+                .is_ok_and(|(name, _, _)| self.names.iter().any(|n| n.as_str() == name)),
+            _ => false,
+        }
+    }
+
+    fn allows_member_explicitly(&self, hdr: &Header<'_>) -> bool {
+        (!self.paths.is_empty() || !self.interfaces.is_empty()) && self.allows_member(hdr)
+    }
+
+    fn allows_member(&self, hdr: &Header<'_>) -> bool {
+        let path_allowed = self.paths.is_empty()
+            || hdr.path().is_some_and(|path| {
+                self.paths.iter().any(|allowed| {
+                    let allowed = allowed.as_str();
+                    let path = path.as_str();
+
+                    allowed == "/"
+                        || path == allowed
+                        || path
+                            .strip_prefix(allowed)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            });
+        let interface_allowed = self.interfaces.is_empty()
+            || hdr
+                .interface()
+                .is_some_and(|iface| self.interfaces.iter().any(|i| i.as_str() == iface.as_str()));
+
+        path_allowed && interface_allowed
+    }
+}
+
+/// A filtering proxy between a peer-to-peer client connection and a bus connection.
+///
+/// Messages are forwarded in their encoded form, without being decoded and re-encoded. Only their
+/// serial numbers are rewritten, as the client and the bus connection allocate them independently.
+/// Since the client believes it's talking to a bus, the proxy replies to its `Hello` call itself,
+/// with the unique name of the bus connection. Hence a separate bus connection should be used for
+/// each client.
+///
+/// Method calls, in either direction, that are not allowed by the [`Policy`] get an
+/// `org.freedesktop.DBus.Error.AccessDenied` error reply, while any other disallowed message is
+/// silently dropped. Replies are only forwarded if they answer a method call the proxy forwarded.
+///
+/// This type is only available when the `p2p` feature is enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// use zbus::{
+///     bus_proxy::{BusProxy, Policy},
+///     connection::Builder,
+///     Connection, Guid,
+/// };
+/// # #[cfg(unix)]
+/// use std::os::unix::net::UnixListener;
+///
+/// # #[cfg(unix)]
+/// # {
+/// let policy = Policy::new().allow_name("org.freedesktop.Notifications")?;
+/// let proxy = BusProxy::new(policy);
+///
+/// let listener = UnixListener::bind("/tmp/sandboxed-bus").unwrap();
+/// let (stream, _) = listener.accept().unwrap();
+/// let client = Builder::unix_stream(stream)
+///     .server(Guid::generate())?
+///     .p2p()
+///     .build()
+///     .await?;
+/// let bus = Connection::session().await?;
+/// proxy.serve(&client, &bus).await?;
+/// # }
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BusProxy {
+    policy: Policy,
+}
+
+assert_impl_all!(BusProxy: Send, Sync, Unpin);
+
+impl BusProxy {
+    /// Create a new `BusProxy` enforcing `policy`.
+    pub fn new(policy: Policy) -> Self {
+        Self { policy }
+    }
+
+    /// The policy enforced by this proxy.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Forward messages between `client` and `bus` until either of them is disconnected.
+    pub async fn serve(&self, client: &Connection, bus: &Connection) -> Result<()> {
+        let pending = Pending::default();
+        let upstream = Box::pin(self.forward_upstream(client, bus, &pending));
+        let downstream = Box::pin(self.forward_downstream(bus, client, &pending));
+
+        let (res, _) = select(upstream, downstream).await.factor_first();
+
+        res
+    }
+
+    async fn forward_upstream(
+        &self,
+        client: &Connection,
+        bus: &Connection,
+        pending: &Pending,
+    ) -> Result<()> {
+        let mut stream = MessageStream::from(client);
+        while let Some(msg) = stream.next().await {
+            let msg = msg?;
+            let hdr = msg.header();
+
+            if is_hello(&hdr) {
+                let unique_name = bus.unique_name().ok_or(Error::MissingField)?;
+                reply(client, &msg, unique_name.as_str()).await?;
+
+                continue;
+            }
+
+            match hdr.message_type() {
+                Type::MethodReturn | Type::Error => {
+                    // Only replies to calls we forwarded to the client can go through, to the
+                    // peer that made the call and with the serial it knows.
+                    let call = hdr
+                        .reply_serial()
+                        .and_then(|serial| pending.take_incoming(serial));
+                    match call {
+                        Some((serial, caller))
+                            if hdr.destination().map(|d| d.as_str()) == Some(caller.as_str()) =>
+                        {
+                            bus.send(&msg.with_new_serial(Some(serial))?).await?;
+                        }
+                        _ => trace!("Dropping unexpected reply from client: {}", msg),
+                    }
+                }
+                _ if self.policy.allows_outgoing(&msg) => {
+                    let forwarded = msg.with_new_serial(None)?;
+                    if expects_reply(&hdr) {
+                        pending.add_outgoing(forwarded.primary_header().serial_num(), &hdr);
+                    }
+                    bus.send(&forwarded).await?;
+                }
+                Type::MethodCall => {
+                    debug!("Denying method call from client: {}", msg);
+                    if expects_reply(&hdr) {
+                        let err =
+                            fdo::Error::AccessDenied(format!("`{msg}` denied by bus proxy policy"));
+                        client.reply_dbus_error(&hdr, err).await?;
+                    }
+                }
+                _ => trace!("Dropping message from client: {}", msg),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn forward_downstream(
+        &self,
+        bus: &Connection,
+        client: &Connection,
+        pending: &Pending,
+    ) -> Result<()> {
+        let mut stream = MessageStream::from(bus);
+        while let Some(msg) = stream.next().await {
+            let msg = msg?;
+            let hdr = msg.header();
+
+            match hdr.message_type() {
+                Type::MethodReturn | Type::Error => {
+                    // Replies to the bus connection's own calls are none of the client's business.
+                    match hdr
+                        .reply_serial()
+                        .and_then(|serial| pending.take_outgoing(serial))
+                    {
+                        Some(serial) => client.send(&msg.with_new_serial(Some(serial))?).await?,
+                        None => trace!("Dropping reply from bus: {}", msg),
+                    }
+                }
+                _ if self.policy.allows_incoming(&msg) => {
+                    if hdr.message_type() == Type::MethodCall {
+                        let forwarded = msg.with_new_serial(None)?;
+                        if expects_reply(&hdr) {
+                            pending.add_incoming(forwarded.primary_header().serial_num(), &hdr);
+                        }
+                        client.send(&forwarded).await?;
+                    } else {
+                        client.send(&msg).await?;
+                    }
+                }
+                Type::MethodCall => {
+                    debug!("Denying method call to client: {}", msg);
+                    if expects_reply(&hdr) {
+                        let err =
+                            fdo::Error::AccessDenied(format!("`{msg}` denied by bus proxy policy"));
+                        bus.reply_dbus_error(&hdr, err).await?;
+                    }
+                }
+                _ => trace!("Dropping message from bus: {}", msg),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The method calls forwarded by a [`BusProxy`] that still await a reply.
+///
+/// Each call is keyed by the serial it was forwarded with, and maps to the serial it was sent with
+/// originally, so replies can be matched to calls and get their reply serial translated back.
+#[derive(Debug, Default)]
+struct Pending {
+    // Calls from the client to the bus.
+    outgoing: Mutex<HashMap<NonZeroU32, NonZeroU32>>,
+    // Calls from the bus to the client, along with the caller.
+    incoming: Mutex<HashMap<NonZeroU32, (NonZeroU32, OwnedUniqueName)>>,
+}
+
+impl Pending {
+    fn add_outgoing(&self, forwarded: NonZeroU32, call: &Header<'_>) {
+        let serial = call.primary().serial_num();
+        self.outgoing
+            .lock()
+            .expect("poisoned lock")
+            .insert(forwarded, serial);
+    }
+
+    fn take_outgoing(&self, forwarded: NonZeroU32) -> Option<NonZeroU32> {
+        self.outgoing
+            .lock()
+            .expect("poisoned lock")
+            .remove(&forwarded)
+    }
+
+    fn add_incoming(&self, forwarded: NonZeroU32, call: &Header<'_>) {
+        // The bus always sets the sender, the check is only for the type system.
+        if let Some(sender) = call.sender() {
+            let serial = call.primary().serial_num();
+            self.incoming
+                .lock()
+                .expect("poisoned lock")
+                .insert(forwarded, (serial, sender.to_owned().into()));
+        }
+    }
+
+    fn take_incoming(&self, forwarded: NonZeroU32) -> Option<(NonZeroU32, OwnedUniqueName)> {
+        self.incoming
+            .lock()
+            .expect("poisoned lock")
+            .remove(&forwarded)
+    }
+}
+
+/// The methods of the bus that clients are allowed to call.
+const BUS_METHODS: &[&str] = &[
+    "GetId",
+    "GetNameOwner",
+    "ListActivatableNames",
+    "ListNames",
+    "NameHasOwner",
+    "RemoveMatch",
+];
+
+fn allows_bus_call(msg: &Message, hdr: &Header<'_>) -> bool {
+    let interface = hdr.interface().map(|i| i.as_str());
+    let Some(member) = hdr.member().map(|m| m.as_str()) else {
+        return false;
+    };
+
+    match interface {
+        Some("org.freedesktop.DBus.Peer") => true,
+        Some("org.freedesktop.DBus.Introspectable") => member == "Introspect",
+        // `MatchRule` doesn't support the `eavesdrop` key, so rules with it fail to parse, just like
+        // malformed ones.
+        Some(BUS_NAME) | None if member == "AddMatch" => msg
+            .body()
+            .deserialize::<&str>()
+            .is_ok_and(|rule| MatchRule::try_from(rule).is_ok()),
+        Some(BUS_NAME) | None => BUS_METHODS.contains(&member),
+        Some(_) => false,
+    }
+}
+
+fn expects_reply(hdr: &Header<'_>) -> bool {
+    !hdr.primary().flags().contains(Flags::NoReplyExpected)
+}
+
+fn is_hello(hdr: &Header<'_>) -> bool {
+    hdr.message_type() == Type::MethodCall
+        && hdr.destination().map(|d| d.as_str()) == Some(BUS_NAME)
+        && hdr.member().map(|m| m.as_str()) == Some("Hello")
+}
+
+async fn reply(conn: &Connection, call: &Message, body: &str) -> Result<()> {
+    let msg = Message::method_reply(call)?
+        .sender(BUS_NAME)?
+        .build(&body)?;
+
+    conn.send(&msg).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BusProxy, Policy};
+    use crate::{fdo, message::Message, Connection, Result};
+    use futures_util::future::{select, Either};
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    fn policy() {
+        let policy = Policy::new()
+            .allow_name("org.zbus.Allowed")
+            .unwrap()
+            .allow_path("/org/zbus/allowed")
+            .unwrap();
+
+        let call = |dest: &str, path: &str| {
+            Message::method(path, "Do")
+                .unwrap()
+                .destination(dest)
+                .unwrap()
+                .interface("org.zbus.Iface")
+                .unwrap()
+                .build(&())
+                .unwrap()
+        };
+        let allowed = |msg: Message| policy.allows_outgoing(&msg);
+
+        assert!(allowed(call("org.zbus.Allowed", "/org/zbus/allowed")));
+        assert!(allowed(call("org.zbus.Allowed", "/org/zbus/allowed/child")));
+        assert!(!allowed(call("org.zbus.Allowed", "/org/zbus/allowed_not")));
+        assert!(!allowed(call("org.zbus.Denied", "/org/zbus/allowed")));
+
+        let policy = policy.allow_interface("org.zbus.Other").unwrap();
+        let allowed = |msg: Message| policy.allows_outgoing(&msg);
+        assert!(!allowed(call("org.zbus.Allowed", "/org/zbus/allowed")));
+    }
+
+    #[test]
+    fn policy_denies_by_default() {
+        let policy = Policy::new();
+        let bus_call = |iface: &str, member: &str, arg: &str| {
+            Message::method("/org/freedesktop/DBus", member)
+                .unwrap()
+                .destination("org.freedesktop.DBus")
+                .unwrap()
+                .interface(iface)
+                .unwrap()
+                .build(&arg)
+                .unwrap()
+        };
+        let allowed = |msg: Message| policy.allows_outgoing(&msg);
+
+        let bus = "org.freedesktop.DBus";
+        assert!(allowed(bus_call(bus, "GetNameOwner", "org.zbus.Name")));
+        assert!(allowed(bus_call(bus, "AddMatch", "type='signal'")));
+        assert!(!allowed(bus_call(
+            bus,
+            "AddMatch",
+            "type='signal',eavesdrop='true'"
+        )));
+        assert!(allowed(bus_call(bus, "AddMatch", "arg0='eavesdrop'")));
+        assert!(!allowed(bus_call(bus, "AddMatch", "type=signal")));
+        assert!(!allowed(bus_call(bus, "RequestName", "org.zbus.Name")));
+        assert!(!allowed(bus_call(bus, "BecomeMonitor", "")));
+        assert!(!allowed(bus_call(
+            "org.freedesktop.DBus.Monitoring",
+            "BecomeMonitor",
+            ""
+        )));
+        assert!(allowed(bus_call("org.freedesktop.DBus.Peer", "Ping", "")));
+
+        // Signals need an explicitly allowed path or interface.
+        let signal = || {
+            Message::signal("/org/zbus/allowed", "org.zbus.Iface", "Changed")
+                .unwrap()
+                .build(&())
+                .unwrap()
+        };
+        assert!(!policy.allows_outgoing(&signal()));
+        let policy = policy.allow_path("/org/zbus/allowed").unwrap();
+        assert!(policy.allows_outgoing(&signal()));
+
+        // Replies only go through for calls that were forwarded, which the policy knows nothing of.
+        let call = bus_call(bus, "Hello", "");
+        let reply = Message::method_reply(&call).unwrap().build(&()).unwrap();
+        assert!(!policy.allows_outgoing(&reply));
+    }
+
+    #[test]
+    fn policy_bus_signals() {
+        let policy = Policy::new().allow_name("org.zbus.Allowed").unwrap();
+        let bus_signal = |member: &str, name: &str| {
+            let body = (name, "", ":1.42");
+            let builder = Message::signal("/org/freedesktop/DBus", "org.freedesktop.DBus", member)
+                .unwrap()
+                .sender("org.freedesktop.DBus")
+                .unwrap();
+            if member == "NameOwnerChanged" {
+                builder.build(&body).unwrap()
+            } else {
+                builder.build(&name).unwrap()
+            }
+        };
+        let allowed = |msg: Message| policy.allows_incoming(&msg);
+
+        assert!(allowed(bus_signal("NameOwnerChanged", "org.zbus.Allowed")));
+        assert!(!allowed(bus_signal("NameOwnerChanged", "org.zbus.Denied")));
+        assert!(!allowed(bus_signal("NameOwnerChanged", ":1.42")));
+        assert!(allowed(bus_signal("NameAcquired", ":1.42")));
+        assert!(allowed(bus_signal("NameLost", "org.zbus.Denied")));
+        assert!(!allowed(bus_signal("ActivatableServicesChanged", "")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn serve() {
+        crate::utils::block_on(test_serve()).unwrap();
+    }
+
+    #[cfg(unix)]
+    async fn test_serve() -> Result<()> {
+        #[cfg(not(feature = "tokio"))]
+        use std::os::unix::net::UnixStream;
+        #[cfg(feature = "tokio")]
+        use tokio::net::UnixStream;
+
+        struct Service;
+
+        #[crate::interface(name = "org.zbus.BusProxyTest")]
+        impl Service {
+            fn echo(&self, value: u32) -> u32 {
+                value
+            }
+        }
+
+        let service_name = "org.zbus.BusProxyTest";
+        let _service = crate::connection::Builder::session()?
+            .name(service_name)?
+            .serve_at("/org/zbus/allowed", Service)?
+            .serve_at("/org/zbus/denied", Service)?
+            .build()
+            .await?;
+
+        let bus = Connection::session().await?;
+        let policy = Policy::new()
+            .allow_name(service_name)?
+            .allow_path("/org/zbus/allowed")?;
+        let proxy = BusProxy::new(policy);
+
+        let (p0, p1) = UnixStream::pair().unwrap();
+        let (server, client) = futures_util::try_join!(
+            crate::connection::Builder::unix_stream(p0)
+                .server(crate::Guid::generate())?
+                .p2p()
+                .build(),
+            crate::connection::Builder::unix_stream(p1).p2p().build(),
+        )?;
+        // `select` polls the proxy first so it's listening before the client sends anything.
+        let serve = Box::pin(proxy.serve(&server, &bus));
+        let test = Box::pin(async {
+            // The client believes it's talking to a bus.
+            let dbus = fdo::DBusProxy::new(&client).await?;
+            assert_eq!(dbus.hello().await?, *bus.unique_name().unwrap());
+
+            // Replies make it back to the client, with the serial of its call.
+            for value in [1u32, 2] {
+                let reply = client
+                    .call_method(
+                        Some(service_name),
+                        "/org/zbus/allowed",
+                        Some(service_name),
+                        "Echo",
+                        &value,
+                    )
+                    .await?;
+                assert_eq!(reply.body().deserialize::<u32>()?, value);
+            }
+
+            let denied = client
+                .call_method(
+                    Some(service_name),
+                    "/org/zbus/denied",
+                    Some(service_name),
+                    "Echo",
+                    &1u32,
+                )
+                .await;
+            assert!(matches!(
+                denied,
+                Err(crate::Error::MethodError(name, _, _))
+                    if name == "org.freedesktop.DBus.Error.AccessDenied"
+            ));
+
+            assert!(dbus.name_has_owner(service_name.try_into()?).await?);
+            let denied = dbus
+                .request_name(
+                    "org.zbus.BusProxyTest.Client".try_into()?,
+                    Default::default(),
+                )
+                .await;
+            assert!(matches!(denied, Err(fdo::Error::AccessDenied(_))));
+
+            Ok(())
+        });
+
+        let res = match select(serve, test).await {
+            Either::Left((res, _)) => panic!("proxy stopped serving: {res:?}"),
+            Either::Right((res, _)) => res,
+        };
+
+        res
+    }
+}
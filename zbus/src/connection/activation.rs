@@ -0,0 +1,276 @@
+//! Support for systemd socket activation of peer-to-peer servers.
+
+#[cfg(not(feature = "tokio"))]
+use async_io::Async;
+use nix::sys::socket::{getsockname, getsockopt, sockopt::AcceptConn, UnixAddr};
+use static_assertions::assert_impl_all;
+use std::{
+    env,
+    os::{
+        fd::{BorrowedFd, FromRawFd, RawFd},
+        unix::net,
+    },
+    sync::Mutex,
+};
+#[cfg(feature = "tokio")]
+use tokio::net::UnixListener;
+use tracing::debug;
+
+use crate::{connection::Builder, Connection, Error, Guid, OwnedGuid, Result};
+
+// See `sd_listen_fds(3)`.
+const LISTEN_FDS_START: RawFd = 3;
+
+// The fds already taken ownership of, since the environment variables are left untouched.
+static TAKEN_FDS: Mutex<Vec<RawFd>> = Mutex::new(Vec::new());
+
+/// A listener for peer-to-peer connections on the sockets passed through [systemd socket
+/// activation][sa].
+///
+/// systemd services exposing a private D-Bus socket get the listening sockets through the
+/// `LISTEN_FDS` and `LISTEN_PID` environment variables. This type picks them up and performs the
+/// server-side authentication handshake on each client accepted on them.
+///
+/// # Example
+///
+/// ```no_run
+/// use zbus::connection::ActivationListener;
+///
+/// # zbus::block_on(async {
+/// let listener = ActivationListener::from_env()?;
+/// let conn = listener.accept().await?;
+///
+/// // Serve `conn`..
+/// # drop(conn);
+/// # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+///
+/// This type is only available on Unix and when the `p2p` feature is enabled.
+///
+/// [sa]: https://www.freedesktop.org/software/systemd/man/latest/sd_listen_fds.html
+#[derive(Debug)]
+pub struct ActivationListener {
+    #[cfg(not(feature = "tokio"))]
+    listeners: Vec<Async<net::UnixListener>>,
+    #[cfg(feature = "tokio")]
+    listeners: Vec<UnixListener>,
+    guid: OwnedGuid,
+}
+
+assert_impl_all!(ActivationListener: Send, Sync, Unpin);
+
+impl ActivationListener {
+    /// Create a listener for the sockets passed by systemd.
+    ///
+    /// The passed fds that are not listening Unix sockets, such as FIFOs, are left alone. Since
+    /// modifying the environment isn't thread-safe, the `LISTEN_FDS`, `LISTEN_PID` and
+    /// `LISTEN_FDNAMES` environment variables are not unset. Child processes ignore them anyway,
+    /// as `LISTEN_PID` doesn't match their PID. Each socket can only be taken once though, so
+    /// this method fails if called again. A new GUID is generated for the server connections.
+    ///
+    /// With the `tokio` feature enabled, this method must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Address`] if no listening Unix sockets were passed to this process, or if they were
+    /// already taken, and [`Error::InputOutput`] if setting them up failed.
+    pub fn from_env() -> Result<Self> {
+        Self::from_listen_fds(|_| true)
+    }
+
+    /// Create a listener for the sockets passed by systemd with the given `name`.
+    ///
+    /// The name of a socket is set with the `FileDescriptorName=` setting of its systemd socket
+    /// unit. This is useful when a service is passed several sockets, for different purposes.
+    /// Otherwise, this behaves like [`ActivationListener::from_env`].
+    pub fn from_env_named(name: &str) -> Result<Self> {
+        Self::from_listen_fds(|fd_name| fd_name == Some(name))
+    }
+
+    fn from_listen_fds<F>(filter: F) -> Result<Self>
+    where
+        F: Fn(Option<&str>) -> bool,
+    {
+        let fds = parse_listen_fds(
+            env::var("LISTEN_PID").ok().as_deref(),
+            env::var("LISTEN_FDS").ok().as_deref(),
+            env::var("LISTEN_FDNAMES").ok().as_deref(),
+            std::process::id(),
+        )?;
+
+        let mut taken_fds = TAKEN_FDS.lock().expect("poisoned lock");
+        let mut listeners = vec![];
+        for (fd, name) in fds {
+            if !filter(name.as_deref()) || taken_fds.contains(&fd) {
+                continue;
+            }
+            if !is_listening_unix_socket(fd) {
+                debug!("Ignoring fd {fd} from systemd, it's not a listening Unix socket");
+
+                continue;
+            }
+
+            // SAFETY: systemd passes us the ownership of the fds, which we make sure to only take
+            // once, and we checked it's a socket.
+            let listener = unsafe { net::UnixListener::from_raw_fd(fd) };
+            taken_fds.push(fd);
+            listener.set_nonblocking(true)?;
+
+            #[cfg(not(feature = "tokio"))]
+            listeners.push(Async::new(listener)?);
+            #[cfg(feature = "tokio")]
+            listeners.push(UnixListener::from_std(listener)?);
+        }
+        if listeners.is_empty() {
+            return Err(Error::Address(
+                "no listening Unix socket was passed by systemd, or they were already taken"
+                    .to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            listeners,
+            guid: Guid::generate().into(),
+        })
+    }
+
+    /// The GUID of the server connections.
+    pub fn guid(&self) -> &OwnedGuid {
+        &self.guid
+    }
+
+    /// Accept a client on any of the sockets and perform the server handshake with it.
+    ///
+    /// Use [`ActivationListener::accept_with`] to customize the connection before it's built.
+    pub async fn accept(&self) -> Result<Connection> {
+        self.accept_with(|builder| builder).await
+    }
+
+    /// Accept a client on any of the sockets, and build the server connection with it using the
+    /// given `customize` function on the [`Builder`].
+    ///
+    /// This allows you to serve interfaces on the connection from the start, for example.
+    pub async fn accept_with<F>(&self, customize: F) -> Result<Connection>
+    where
+        F: FnOnce(Builder<'static>) -> Builder<'static>,
+    {
+        let accepts = self
+            .listeners
+            .iter()
+            .map(|listener| Box::pin(listener.accept()));
+        let (accepted, _, _) = futures_util::future::select_all(accepts).await;
+        let (stream, _) = accepted?;
+        debug!("Accepted a client on an activated socket");
+        #[cfg(not(feature = "tokio"))]
+        let stream = stream.into_inner()?;
+
+        let builder = Builder::unix_stream(stream)
+            .server(self.guid.to_owned())?
+            .p2p();
+
+        customize(builder).build().await
+    }
+}
+
+/// Parse the values of the `LISTEN_PID`, `LISTEN_FDS` and `LISTEN_FDNAMES` environment variables
+/// into the fds passed to the process with the given `own_pid`, along with their name, if any.
+fn parse_listen_fds(
+    pid: Option<&str>,
+    fds: Option<&str>,
+    names: Option<&str>,
+    own_pid: u32,
+) -> Result<Vec<(RawFd, Option<String>)>> {
+    let pid = pid
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .ok_or_else(|| Error::Address("`LISTEN_PID` is not set or invalid".to_owned()))?;
+    if pid != own_pid {
+        return Err(Error::Address(
+            "`LISTEN_PID` is set for another process".to_owned(),
+        ));
+    }
+    let fds = fds
+        .and_then(|fds| fds.parse::<RawFd>().ok())
+        .filter(|fds| *fds > 0 && fds.checked_add(LISTEN_FDS_START).is_some())
+        .ok_or_else(|| Error::Address("`LISTEN_FDS` is not set or invalid".to_owned()))?;
+    debug!("Got {fds} fd(s) from systemd");
+
+    // Names are optional, but if set, there must be one per fd.
+    let names = match names {
+        Some(names) => {
+            let names: Vec<_> = names.split(':').map(|n| Some(n.to_owned())).collect();
+            if names.len() != fds as usize {
+                return Err(Error::Address(
+                    "`LISTEN_FDNAMES` doesn't match `LISTEN_FDS`".to_owned(),
+                ));
+            }
+
+            names
+        }
+        None => vec![None; fds as usize],
+    };
+
+    Ok((LISTEN_FDS_START..LISTEN_FDS_START + fds)
+        .zip(names)
+        .collect())
+}
+
+fn is_listening_unix_socket(fd: RawFd) -> bool {
+    // SAFETY: the fd was passed to us and isn't closed before the borrow ends.
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+
+    getsockname::<UnixAddr>(fd).is_ok() && getsockopt(&borrowed, AcceptConn).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn listen_fds() {
+        let fds = parse_listen_fds(Some("42"), Some("2"), None, 42).unwrap();
+        assert_eq!(fds, [(3, None), (4, None)]);
+
+        let fds = parse_listen_fds(Some("42"), Some("2"), Some("dbus:varlink"), 42).unwrap();
+        assert_eq!(
+            fds,
+            [
+                (3, Some("dbus".to_owned())),
+                (4, Some("varlink".to_owned()))
+            ]
+        );
+
+        for (pid, fds, names) in [
+            (None, Some("1"), None),
+            (Some("43"), Some("1"), None),
+            (Some("nope"), Some("1"), None),
+            (Some("42"), None, None),
+            (Some("42"), Some("0"), None),
+            (Some("42"), Some("-1"), None),
+            (Some("42"), Some("2"), Some("dbus")),
+        ] {
+            assert!(
+                matches!(
+                    parse_listen_fds(pid, fds, names, 42),
+                    Err(Error::Address(_))
+                ),
+                "{pid:?} {fds:?} {names:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn listening_unix_socket() {
+        use std::os::fd::AsRawFd;
+
+        let dir = tempfile::tempdir().unwrap();
+        let listener = net::UnixListener::bind(dir.path().join("socket")).unwrap();
+        assert!(is_listening_unix_socket(listener.as_raw_fd()));
+
+        let (stream, _) = net::UnixStream::pair().unwrap();
+        assert!(!is_listening_unix_socket(stream.as_raw_fd()));
+        let file = std::fs::File::open("/dev/null").unwrap();
+        assert!(!is_listening_unix_socket(file.as_raw_fd()));
+    }
+}
@@ -8,6 +8,7 @@ use std::net::TcpStream;
 use std::os::unix::net::UnixStream;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
     vec,
 };
 #[cfg(feature = "tokio")]
@@ -21,15 +22,20 @@ use uds_windows::UnixStream;
 #[cfg(all(feature = "vsock", not(feature = "tokio")))]
 use vsock::VsockStream;
 
-use zvariant::{ObjectPath, Str};
+use zvariant::{Endian, ObjectPath, Str};
 
+#[cfg(any(unix, feature = "p2p"))]
+use crate::fdo::ConnectionCredentials;
 use crate::{
     address::{self, Address},
-    names::{InterfaceName, WellKnownName},
-    object_server::{ArcInterface, Interface},
+    message::header::MAX_MESSAGE_SIZE,
+    names::{InterfaceName, OwnedUniqueName, WellKnownName},
+    object_server::{ArcInterface, Interface, ObjectTree},
     Connection, Error, Executor, Guid, OwnedGuid, Result,
 };
 
+#[cfg(feature = "p2p")]
+use super::handshake::AuthPolicy;
 use super::{
     handshake::{AuthMechanism, Authenticated},
     socket::{BoxedSplit, ReadHalf, Split, WriteHalf},
@@ -50,6 +56,8 @@ enum Target {
     Address(Address),
     Socket(Split<Box<dyn ReadHalf>, Box<dyn WriteHalf>>),
     AuthenticatedSocket(Split<Box<dyn ReadHalf>, Box<dyn WriteHalf>>),
+    #[cfg(feature = "p2p")]
+    Authenticated(Authenticated),
 }
 
 type Interfaces<'a> = HashMap<ObjectPath<'a>, HashMap<InterfaceName<'static>, ArcInterface>>;
@@ -60,6 +68,11 @@ type Interfaces<'a> = HashMap<ObjectPath<'a>, HashMap<InterfaceName<'static>, Ar
 pub struct Builder<'a> {
     target: Option<Target>,
     max_queued: Option<usize>,
+    journal_capacity: usize,
+    strict_sender_validation: bool,
+    method_timeout: Option<Duration>,
+    endian: Option<Endian>,
+    max_message_size: Option<usize>,
     // This is only set for p2p server case or pre-authenticated sockets.
     guid: Option<Guid<'a>>,
     #[cfg(feature = "p2p")]
@@ -70,8 +83,12 @@ pub struct Builder<'a> {
     auth_mechanisms: Option<VecDeque<AuthMechanism>>,
     #[cfg(feature = "bus-impl")]
     unique_name: Option<crate::names::UniqueName<'a>>,
+    #[cfg(feature = "p2p")]
+    auth_policy: Option<AuthPolicy>,
     cookie_context: Option<super::handshake::CookieContext<'a>>,
     cookie_id: Option<usize>,
+    #[cfg(unix)]
+    allowed_peer_uids: Option<HashSet<u32>>,
 }
 
 assert_impl_all!(Builder<'_>: Send, Sync, Unpin);
@@ -186,6 +203,20 @@ impl<'a> Builder<'a> {
         Ok(builder)
     }
 
+    /// Create a builder for a connection that will use the given authenticated socket.
+    ///
+    /// This is the way to get a [`Connection`] out of a client authenticated through a
+    /// [`SaslServer`]. Unlike [`Builder::authenticated_socket`], any messages the client already
+    /// sent during the handshake are preserved.
+    ///
+    /// This method is only available when the `p2p` feature is enabled.
+    ///
+    /// [`SaslServer`]: super::SaslServer
+    #[cfg(feature = "p2p")]
+    pub fn authenticated(auth: Authenticated) -> Self {
+        Self::new(Target::Authenticated(auth))
+    }
+
     /// Specify the mechanism to use during authentication.
     pub fn auth_mechanism(self, auth_mechanism: AuthMechanism) -> Self {
         #[allow(deprecated)]
@@ -200,6 +231,57 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Specify the mechanisms offered to clients during authentication.
+    ///
+    /// This is only valid for server connections. If not specified, only the `EXTERNAL` mechanism
+    /// is offered.
+    ///
+    /// This method is only available when the `p2p` feature is enabled.
+    #[cfg(feature = "p2p")]
+    pub fn offered_auth_mechanisms<I>(mut self, auth_mechanisms: I) -> Self
+    where
+        I: IntoIterator<Item = AuthMechanism>,
+    {
+        self.auth_mechanisms = Some(auth_mechanisms.into_iter().collect());
+
+        self
+    }
+
+    /// Decide whether to accept a client, once it successfully authenticated.
+    ///
+    /// `policy` is called with the credentials of the client, as reported by the OS, and the
+    /// mechanism the client authenticated with. If it returns `false`, the client is rejected, just
+    /// like on an authentication failure, and can retry with another mechanism.
+    ///
+    /// This is only valid for server connections. This method is only available when the `p2p`
+    /// feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zbus::connection::Builder;
+    /// # use zbus::{AuthMechanism, Guid};
+    /// #
+    /// // Only accept anonymous clients, and clients running as the user with ID 1000.
+    /// let _builder = Builder::address("unix:path=/tmp/zbus-auth-policy-example")?
+    ///     .server(Guid::generate())?
+    ///     .p2p()
+    ///     .offered_auth_mechanisms([AuthMechanism::External, AuthMechanism::Anonymous])
+    ///     .auth_policy(|creds, mech| {
+    ///         mech == AuthMechanism::Anonymous || creds.unix_user_id() == Some(1000)
+    ///     });
+    /// # Ok::<_, zbus::Error>(())
+    /// ```
+    #[cfg(feature = "p2p")]
+    pub fn auth_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&ConnectionCredentials, AuthMechanism) -> bool + Send + Sync + 'static,
+    {
+        self.auth_policy = Some(AuthPolicy::new(policy));
+
+        self
+    }
+
     /// The cookie context to use during authentication.
     ///
     /// This is only used when the `cookie` authentication mechanism is enabled and only valid for
@@ -231,6 +313,59 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Only allow a peer running as one of the given users.
+    ///
+    /// The credentials of the peer are checked right after connecting to the socket and before
+    /// authenticating or, for `Builder::authenticated`, against the credentials obtained during
+    /// the handshake. If the peer's user ID can not be determined or is not one of `uids`,
+    /// [`Builder::build`] fails with [`Error::Handshake`].
+    ///
+    /// This method is only available on Unix platforms. See also
+    /// [`Builder::trusted_system_bus_peer`].
+    #[cfg(unix)]
+    pub fn allowed_peer_uids<I>(mut self, uids: I) -> Self
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        self.allowed_peer_uids
+            .get_or_insert_with(HashSet::new)
+            .extend(uids);
+
+        self
+    }
+
+    /// Only allow a peer running as `root` or as the system bus user (`messagebus` or `dbus`).
+    ///
+    /// This is meant for system bus connections in hostile environments, where
+    /// `DBUS_SYSTEM_BUS_ADDRESS` could have been spoofed to point at a rogue socket. Since
+    /// [`Builder::system`] respects this environment variable, verifying the owner of the socket
+    /// ensures that the connection is made to the real system bus.
+    ///
+    /// This method is only available on Unix platforms.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use zbus::connection::Builder;
+    ///
+    /// let conn = Builder::system()?.trusted_system_bus_peer().build().await?;
+    /// # drop(conn);
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    #[cfg(unix)]
+    pub fn trusted_system_bus_peer(self) -> Self {
+        use nix::unistd::User;
+
+        let bus_users = ["messagebus", "dbus"]
+            .into_iter()
+            .filter_map(|name| User::from_name(name).ok().flatten())
+            .map(|user| user.uid.as_raw());
+
+        self.allowed_peer_uids(std::iter::once(0).chain(bus_users))
+    }
+
     /// The to-be-created connection will be a peer-to-peer connection.
     ///
     /// This method is only available when the `p2p` feature is enabled.
@@ -293,6 +428,52 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Set the capacity of the message journal.
+    ///
+    /// By default, the journal is disabled. See [`Connection::set_journal_capacity`] for details.
+    pub fn journal_capacity(mut self, capacity: usize) -> Self {
+        self.journal_capacity = capacity;
+
+        self
+    }
+
+    /// Enable or disable strict sender validation of replies and signals.
+    ///
+    /// It's disabled by default. See [`Connection::set_strict_sender_validation`] for details.
+    pub fn strict_sender_validation(mut self, enabled: bool) -> Self {
+        self.strict_sender_validation = enabled;
+
+        self
+    }
+
+    /// Set the default timeout of method calls.
+    ///
+    /// There is no timeout by default. See [`Connection::set_method_timeout`] for details.
+    pub fn method_timeout(mut self, timeout: Duration) -> Self {
+        self.method_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set the endianness of the method calls and signals built by the connection.
+    ///
+    /// The default is the native endianness. See [`Connection::set_endian`] for details.
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = Some(endian);
+
+        self
+    }
+
+    /// Set the maximum size of the messages received on the connection.
+    ///
+    /// The default, and maximum, is 128 MiB as per the D-Bus specification. See
+    /// [`Connection::max_message_size`] for details.
+    pub fn max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = Some(max);
+
+        self
+    }
+
     /// Enable or disable the internal executor thread.
     ///
     /// The thread is enabled by default.
@@ -325,6 +506,24 @@ impl<'a> Builder<'a> {
         Ok(self)
     }
 
+    /// Register all the interfaces of an [`ObjectTree`] to be served, with `prefix` as its root.
+    ///
+    /// This is similar to [`zbus::ObjectServer::mount`], except that the interfaces are available
+    /// immediately after the connection is established. As with [`Builder::serve_at`], interfaces
+    /// previously added with the same name at the same path are replaced.
+    pub fn mount<P>(mut self, prefix: P, tree: ObjectTree) -> Result<Self>
+    where
+        P: TryInto<ObjectPath<'a>>,
+        P::Error: Into<Error>,
+    {
+        let prefix = prefix.try_into().map_err(Into::into)?;
+        for (path, interfaces) in tree.into_interfaces(&prefix) {
+            self.interfaces.entry(path).or_default().extend(interfaces);
+        }
+
+        Ok(self)
+    }
+
     /// Register a well-known name for this connection on the bus.
     ///
     /// This is similar to [`zbus::Connection::request_name`], except the name is requested as part
@@ -396,65 +595,51 @@ impl<'a> Builder<'a> {
         #[cfg(feature = "bus-impl")]
         let unique_name = self.unique_name.take().map(Into::into);
 
-        #[allow(unused_mut)]
-        let (mut stream, server_guid, authenticated) = self.target_connect().await?;
-        let mut auth = if authenticated {
-            let (socket_read, socket_write) = stream.take();
-            Authenticated {
-                #[cfg(unix)]
-                cap_unix_fd: socket_read.can_pass_unix_fd(),
-                socket_read: Some(socket_read),
-                socket_write,
-                // SAFETY: `server_guid` is provided as arg of `Builder::authenticated_socket`.
-                server_guid: server_guid.unwrap(),
-                already_received_bytes: vec![],
-                unique_name,
-            }
-        } else {
+        let mut auth = match self.target.take() {
             #[cfg(feature = "p2p")]
-            match self.guid {
-                None => {
-                    // SASL Handshake
-                    Authenticated::client(stream, server_guid, self.auth_mechanisms, is_bus_conn)
-                        .await?
+            Some(Target::Authenticated(mut auth)) => {
+                // The handshake happened elsewhere, so the peer has to be checked here.
+                #[cfg(unix)]
+                if self.allowed_peer_uids.is_some() {
+                    let creds = match auth.peer_credentials.take() {
+                        Some(creds) => creds,
+                        None => {
+                            // SAFETY: `Authenticated` is always built with `socket_read` set.
+                            let socket_read = auth.socket_read.as_mut().unwrap();
+                            socket_read.peer_credentials().await?
+                        }
+                    };
+                    self.check_peer_uid(&creds)?;
+                    auth.peer_credentials = Some(creds);
                 }
-                Some(guid) => {
-                    if !self.p2p {
-                        return Err(Error::Unsupported);
-                    }
-
-                    let creds = stream.read_mut().peer_credentials().await?;
-                    #[cfg(unix)]
-                    let client_uid = creds.unix_user_id();
-                    #[cfg(windows)]
-                    let client_sid = creds.into_windows_sid();
-
-                    Authenticated::server(
-                        stream,
-                        guid.to_owned().into(),
-                        #[cfg(unix)]
-                        client_uid,
-                        #[cfg(windows)]
-                        client_sid,
-                        self.auth_mechanisms,
-                        self.cookie_id,
-                        self.cookie_context.unwrap_or_default(),
-                        unique_name,
-                    )
-                    .await?
+                if unique_name.is_some() {
+                    auth.unique_name = unique_name;
                 }
+
+                auth
             }
+            target => {
+                self.target = target;
 
-            #[cfg(not(feature = "p2p"))]
-            Authenticated::client(stream, server_guid, self.auth_mechanisms, is_bus_conn).await?
+                self.authenticate(unique_name, is_bus_conn).await?
+            }
         };
 
         // SAFETY: `Authenticated` is always built with these fields set to `Some`.
         let socket_read = auth.socket_read.take().unwrap();
         let already_received_bytes = auth.already_received_bytes.drain(..).collect();
 
-        let mut conn = Connection::new(auth, is_bus_conn, executor).await?;
+        let max_message_size = self
+            .max_message_size
+            .map_or(MAX_MESSAGE_SIZE, |max| max.min(MAX_MESSAGE_SIZE));
+        let mut conn = Connection::new(auth, is_bus_conn, executor, max_message_size).await?;
         conn.set_max_queued(self.max_queued.unwrap_or(DEFAULT_MAX_QUEUED));
+        conn.set_journal_capacity(self.journal_capacity);
+        conn.set_strict_sender_validation(self.strict_sender_validation);
+        conn.set_method_timeout(self.method_timeout);
+        if let Some(endian) = self.endian {
+            conn.set_endian(endian);
+        }
 
         if !self.interfaces.is_empty() {
             let object_server = conn.sync_object_server(false, None);
@@ -493,16 +678,115 @@ impl<'a> Builder<'a> {
             #[cfg(feature = "p2p")]
             p2p: false,
             max_queued: None,
+            journal_capacity: 0,
+            strict_sender_validation: false,
+            method_timeout: None,
+            endian: None,
+            max_message_size: None,
             guid: None,
             internal_executor: true,
             interfaces: HashMap::new(),
             names: HashSet::new(),
             auth_mechanisms: None,
+            #[cfg(feature = "p2p")]
+            auth_policy: None,
             #[cfg(feature = "bus-impl")]
             unique_name: None,
             cookie_id: None,
             cookie_context: None,
+            #[cfg(unix)]
+            allowed_peer_uids: None,
+        }
+    }
+
+    #[cfg(unix)]
+    fn check_peer_uid(&self, creds: &ConnectionCredentials) -> Result<()> {
+        let Some(allowed_uids) = &self.allowed_peer_uids else {
+            return Ok(());
+        };
+
+        match creds.unix_user_id() {
+            Some(uid) if allowed_uids.contains(&uid) => Ok(()),
+            Some(uid) => Err(Error::Handshake(format!(
+                "peer is running as user {uid}, which is not allowed"
+            ))),
+            None => Err(Error::Handshake(
+                "failed to determine the user of the peer".into(),
+            )),
+        }
+    }
+
+    async fn authenticate(
+        &mut self,
+        unique_name: Option<OwnedUniqueName>,
+        is_bus_conn: bool,
+    ) -> Result<Authenticated> {
+        #[allow(unused_mut)]
+        let (mut stream, server_guid, authenticated) = self.target_connect().await?;
+        #[cfg(unix)]
+        if self.allowed_peer_uids.is_some() {
+            let creds = stream.read_mut().peer_credentials().await?;
+            self.check_peer_uid(&creds)?;
         }
+        let auth = if authenticated {
+            let (socket_read, socket_write) = stream.take();
+            Authenticated {
+                #[cfg(unix)]
+                cap_unix_fd: socket_read.can_pass_unix_fd(),
+                socket_read: Some(socket_read),
+                socket_write,
+                // SAFETY: `server_guid` is provided as arg of `Builder::authenticated_socket`.
+                server_guid: server_guid.unwrap(),
+                already_received_bytes: vec![],
+                unique_name,
+                #[cfg(feature = "p2p")]
+                peer_credentials: None,
+            }
+        } else {
+            #[cfg(feature = "p2p")]
+            match self.guid.take() {
+                None => {
+                    // SASL Handshake
+                    Authenticated::client(
+                        stream,
+                        server_guid,
+                        self.auth_mechanisms.take(),
+                        is_bus_conn,
+                    )
+                    .await?
+                }
+                Some(guid) => {
+                    if !self.p2p {
+                        return Err(Error::Unsupported);
+                    }
+
+                    let creds = stream.read_mut().peer_credentials().await?;
+
+                    Authenticated::server(
+                        stream,
+                        guid.to_owned().into(),
+                        creds,
+                        self.auth_mechanisms.take(),
+                        self.auth_policy.take(),
+                        self.cookie_id,
+                        self.cookie_context.take().unwrap_or_default(),
+                        unique_name,
+                    )
+                    .await?
+                }
+            }
+
+            #[cfg(not(feature = "p2p"))]
+            Authenticated::client(
+                stream,
+                server_guid,
+                self.auth_mechanisms.take(),
+                is_bus_conn,
+            )
+            .await?
+        };
+
+        Ok(auth)
     }
 
     async fn target_connect(&mut self) -> Result<(BoxedSplit, Option<OwnedGuid>, bool)> {
@@ -542,6 +826,8 @@ impl<'a> Builder<'a> {
                 guid = self.guid.take().map(Into::into);
                 stream
             }
+            #[cfg(feature = "p2p")]
+            Target::Authenticated(_) => unreachable!("already authenticated"),
         };
 
         Ok((split, guid, authenticated))
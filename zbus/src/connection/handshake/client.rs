@@ -50,7 +50,7 @@ impl Client {
     /// Returns the next command to send to the server.
     async fn handle_cookie_challenge(&mut self, data: Vec<u8>) -> Result<Command> {
         let context = std::str::from_utf8(&data)
-            .map_err(|_| Error::Handshake("Cookie context was not valid UTF-8".into()))?;
+            .map_err(|e| Error::handshake_cause("Cookie context was not valid UTF-8", e))?;
         let mut split = context.split_ascii_whitespace();
         let context = split
             .next()
@@ -61,7 +61,7 @@ impl Client {
             .ok_or_else(|| Error::Handshake("Missing cookie ID".into()))?;
         let id = id
             .parse()
-            .map_err(|e| Error::Handshake(format!("Invalid cookie ID `{id}`: {e}")))?;
+            .map_err(|e| Error::handshake_cause(format!("Invalid cookie ID `{id}`"), e))?;
         let server_challenge = split
             .next()
             .ok_or_else(|| Error::Handshake("Missing cookie challenge".into()))?;
@@ -97,14 +97,15 @@ impl Client {
     async fn send_zero_byte(&mut self) -> Result<()> {
         let write = self.common.socket_mut().write_mut();
 
-        let written = match write.send_zero_byte().await.map_err(|e| {
-            Error::Handshake(format!("Could not send zero byte with credentials: {}", e))
-        })? {
-            // This likely means that the socket type is unable to send SCM_CREDS.
-            // Let's try to send the 0 byte as a regular message.
-            None => write.sendmsg(&[0], &[]).await?,
-            Some(n) => n,
-        };
+        let written =
+            match write.send_zero_byte().await.map_err(|e| {
+                Error::handshake_cause("Could not send zero byte with credentials", e)
+            })? {
+                // This likely means that the socket type is unable to send SCM_CREDS.
+                // Let's try to send the 0 byte as a regular message.
+                None => write.sendmsg(&[0], &[]).await?,
+                Some(n) => n,
+            };
 
         if written != 1 {
             return Err(Error::Handshake(
@@ -278,6 +279,8 @@ impl Handshake for Client {
             cap_unix_fd,
             already_received_bytes: recv_buffer,
             unique_name,
+            #[cfg(feature = "p2p")]
+            peer_credentials: None,
         })
     }
 }
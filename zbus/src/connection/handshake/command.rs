@@ -5,7 +5,8 @@ use crate::{AuthMechanism, Error, Guid, OwnedGuid, Result};
 // The plain-text SASL profile authentication protocol described here:
 // <https://dbus.freedesktop.org/doc/dbus-specification.html#auth-protocol>
 //
-// These are all the known commands, which can be parsed from or serialized to text.
+// These are all the known commands, which can be parsed from or serialized to text. Any other
+// command, e.g `EXTENSION_` ones, is parsed as `Unknown` so it can be answered with `ERROR`.
 #[derive(Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub(super) enum Command {
@@ -18,6 +19,7 @@ pub(super) enum Command {
     Rejected(Vec<AuthMechanism>),
     Ok(OwnedGuid),
     AgreeUnixFD,
+    Unknown(String),
 }
 
 impl From<&Command> for Vec<u8> {
@@ -55,6 +57,7 @@ impl fmt::Display for Command {
             }
             Command::Ok(guid) => write!(f, "OK {guid}"),
             Command::AgreeUnixFD => write!(f, "AGREE_UNIX_FD"),
+            Command::Unknown(cmd) => write!(f, "{cmd}"),
         }
     }
 }
@@ -66,14 +69,11 @@ impl FromStr for Command {
         let mut words = s.split_ascii_whitespace();
         let cmd = match words.next() {
             Some("AUTH") => {
-                let mech = if let Some(m) = words.next() {
-                    Some(m.parse()?)
-                } else {
-                    None
-                };
-                let resp = match words.next() {
-                    Some(resp) => Some(hex::decode(resp)?),
-                    None => None,
+                // An unknown mechanism is to be rejected, like a missing one.
+                let mech = words.next().and_then(|m| m.parse().ok());
+                let resp = match (&mech, words.next()) {
+                    (Some(_), Some(resp)) => Some(hex::decode(resp)?),
+                    _ => None,
                 };
                 Command::Auth(mech, resp)
             }
@@ -100,7 +100,7 @@ impl FromStr for Command {
                 Command::Ok(Guid::from_str(guid)?.into())
             }
             Some("AGREE_UNIX_FD") => Command::AgreeUnixFD,
-            _ => return Err(Error::Handshake(format!("Unknown command: {s}"))),
+            _ => Command::Unknown(s.into()),
         };
         Ok(cmd)
     }
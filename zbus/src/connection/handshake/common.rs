@@ -113,7 +113,7 @@ impl Common {
         let mut n_received_commands = 0;
         'outer: loop {
             while let Some(lf_index) = self.recv_buffer.iter().position(|b| *b == b'\n') {
-                if self.recv_buffer[lf_index - 1] != b'\r' {
+                if lf_index == 0 || self.recv_buffer[lf_index - 1] != b'\r' {
                     return Err(Error::Handshake("Invalid line ending in handshake".into()));
                 }
 
@@ -133,7 +133,7 @@ impl Common {
 
                 let line_bytes = self.recv_buffer.drain(..=lf_index);
                 let line = std::str::from_utf8(&line_bytes.as_slice()[start_index..])
-                    .map_err(|e| Error::Handshake(e.to_string()))?;
+                    .map_err(|e| Error::handshake_cause("Invalid command line", e))?;
 
                 trace!("Reading {line}");
                 commands.push(line.parse()?);
@@ -64,10 +64,13 @@ impl Cookie {
                 })?
                 .parse()
                 .map_err(|e| {
-                    Error::Handshake(format!(
-                        "Failed to parse cookie ID in file `{}` at line {n}: {e}",
-                        path.display(),
-                    ))
+                    Error::handshake_cause(
+                        format!(
+                            "Failed to parse cookie ID in file `{}` at line {n}",
+                            path.display(),
+                        ),
+                        e,
+                    )
                 })?;
             let _ = split.next().ok_or_else(|| {
                 Error::Handshake(format!(
@@ -109,7 +112,7 @@ impl Cookie {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CookieContext<'c>(Str<'c>);
 
 impl<'c> TryFrom<Str<'c>> for CookieContext<'c> {
@@ -142,6 +145,6 @@ impl Default for CookieContext<'_> {
 
 impl From<hex::FromHexError> for Error {
     fn from(e: hex::FromHexError) -> Self {
-        Error::Handshake(format!("Invalid hexcode: {e}"))
+        Error::handshake_cause("Invalid hexcode", e)
     }
 }
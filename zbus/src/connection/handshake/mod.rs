@@ -4,15 +4,22 @@ mod command;
 mod common;
 mod cookies;
 #[cfg(feature = "p2p")]
+mod sasl_server;
+#[cfg(feature = "p2p")]
 mod server;
 
 use async_trait::async_trait;
 #[cfg(unix)]
 use nix::unistd::Uid;
+use static_assertions::assert_impl_all;
+#[cfg(feature = "p2p")]
+use std::sync::Arc;
 use std::{collections::VecDeque, fmt::Debug};
 use zbus_names::OwnedUniqueName;
 use zvariant::Str;
 
+#[cfg(feature = "p2p")]
+use crate::fdo::ConnectionCredentials;
 #[cfg(windows)]
 use crate::win32;
 use crate::{Error, OwnedGuid, Result};
@@ -26,14 +33,13 @@ use common::Common;
 use cookies::Cookie;
 pub(crate) use cookies::CookieContext;
 #[cfg(feature = "p2p")]
+pub use sasl_server::SaslServer;
+#[cfg(feature = "p2p")]
 use server::Server;
 
 /// The result of a finalized handshake
 ///
-/// The result of a finalized [`ClientHandshake`] or [`ServerHandshake`].
-///
-/// [`ClientHandshake`]: struct.ClientHandshake.html
-/// [`ServerHandshake`]: struct.ServerHandshake.html
+/// On the server side, this is obtained through [`SaslServer::authenticate`].
 #[derive(Debug)]
 pub struct Authenticated {
     pub(crate) socket_write: Box<dyn WriteHalf>,
@@ -46,11 +52,16 @@ pub struct Authenticated {
     pub(crate) socket_read: Option<Box<dyn ReadHalf>>,
     pub(crate) already_received_bytes: Vec<u8>,
     pub(crate) unique_name: Option<OwnedUniqueName>,
+    /// The credentials of the client, on the server side.
+    #[cfg(feature = "p2p")]
+    pub(crate) peer_credentials: Option<ConnectionCredentials>,
 }
 
+assert_impl_all!(Authenticated: Send, Sync, Unpin);
+
 impl Authenticated {
     /// Create a client-side `Authenticated` for the given `socket`.
-    pub async fn client(
+    pub(crate) async fn client(
         socket: BoxedSplit,
         server_guid: Option<OwnedGuid>,
         mechanisms: Option<VecDeque<AuthMechanism>>,
@@ -63,14 +74,16 @@ impl Authenticated {
 
     /// Create a server-side `Authenticated` for the given `socket`.
     ///
-    /// The function takes `client_uid` on Unix only. On Windows, it takes `client_sid` instead.
+    /// The `client_credentials` are used for `EXTERNAL` authentication and are passed to the
+    /// `auth_policy`, if any.
     #[cfg(feature = "p2p")]
-    pub async fn server(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn server(
         socket: BoxedSplit,
         guid: OwnedGuid,
-        #[cfg(unix)] client_uid: Option<u32>,
-        #[cfg(windows)] client_sid: Option<String>,
+        client_credentials: ConnectionCredentials,
         auth_mechanisms: Option<VecDeque<AuthMechanism>>,
+        auth_policy: Option<AuthPolicy>,
         cookie_id: Option<usize>,
         cookie_context: CookieContext<'_>,
         unique_name: Option<OwnedUniqueName>,
@@ -78,11 +91,9 @@ impl Authenticated {
         Server::new(
             socket,
             guid,
-            #[cfg(unix)]
-            client_uid,
-            #[cfg(windows)]
-            client_sid,
+            client_credentials,
             auth_mechanisms,
+            auth_policy,
             cookie_id,
             cookie_context,
             unique_name,
@@ -90,6 +101,69 @@ impl Authenticated {
         .perform()
         .await
     }
+
+    /// The GUID of the server.
+    #[cfg(feature = "p2p")]
+    pub fn server_guid(&self) -> &OwnedGuid {
+        &self.server_guid
+    }
+
+    /// Whether both sides agreed on passing file descriptors.
+    #[cfg(all(unix, feature = "p2p"))]
+    pub fn cap_unix_fd(&self) -> bool {
+        self.cap_unix_fd
+    }
+
+    /// The credentials of the client, as used during authentication.
+    ///
+    /// This is only set on the server side.
+    #[cfg(feature = "p2p")]
+    pub fn peer_credentials(&self) -> Option<&ConnectionCredentials> {
+        self.peer_credentials.as_ref()
+    }
+
+    /// Split into the two halves of the socket and the bytes received after the handshake.
+    ///
+    /// The peer may send its first messages without waiting for the end of the handshake so the
+    /// returned bytes are the beginning of the message stream and must be processed before
+    /// reading anything from the socket.
+    #[cfg(feature = "p2p")]
+    pub fn into_parts(mut self) -> (Box<dyn ReadHalf>, Box<dyn WriteHalf>, Vec<u8>) {
+        // SAFETY: `Authenticated` is always built with `socket_read` set to `Some`.
+        let socket_read = self.socket_read.take().unwrap();
+
+        (socket_read, self.socket_write, self.already_received_bytes)
+    }
+}
+
+/// A function deciding whether to accept a client, given its credentials and the mechanism it
+/// successfully authenticated with.
+#[cfg(feature = "p2p")]
+#[derive(Clone)]
+pub(crate) struct AuthPolicy(Arc<AuthPolicyFn>);
+
+#[cfg(feature = "p2p")]
+type AuthPolicyFn = dyn Fn(&ConnectionCredentials, AuthMechanism) -> bool + Send + Sync;
+
+#[cfg(feature = "p2p")]
+impl AuthPolicy {
+    pub(crate) fn new<F>(policy: F) -> Self
+    where
+        F: Fn(&ConnectionCredentials, AuthMechanism) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(policy))
+    }
+
+    pub(crate) fn allows(&self, credentials: &ConnectionCredentials, mech: AuthMechanism) -> bool {
+        (self.0)(credentials, mech)
+    }
+}
+
+#[cfg(feature = "p2p")]
+impl Debug for AuthPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthPolicy").finish_non_exhaustive()
+    }
 }
 
 #[async_trait]
@@ -134,7 +208,7 @@ fn sasl_auth_id() -> Result<String> {
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "tokio"))]
-    use async_std::io::{Write as AsyncWrite, WriteExt};
+    use async_std::io::{Read as AsyncRead, ReadExt, Write as AsyncWrite, WriteExt};
     use futures_util::future::join;
     use ntest::timeout;
     #[cfg(not(feature = "tokio"))]
@@ -142,7 +216,7 @@ mod tests {
     use test_log::test;
     #[cfg(feature = "tokio")]
     use tokio::{
-        io::{AsyncWrite, AsyncWriteExt},
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
         net::UnixStream,
     };
 
@@ -150,7 +224,10 @@ mod tests {
 
     use crate::{Guid, Socket};
 
-    fn create_async_socket_pair() -> (impl AsyncWrite + Socket, impl AsyncWrite + Socket) {
+    fn create_async_socket_pair() -> (
+        impl AsyncRead + AsyncWrite + Unpin + Socket,
+        impl AsyncRead + AsyncWrite + Unpin + Socket,
+    ) {
         // Tokio needs us to call the sync function from async context. :shrug:
         let (p0, p1) = crate::utils::block_on(async { UnixStream::pair().unwrap() });
 
@@ -179,7 +256,8 @@ mod tests {
         let server = Server::new(
             p1.into(),
             guid,
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+            None,
             None,
             None,
             CookieContext::default(),
@@ -204,7 +282,8 @@ mod tests {
         let server = Server::new(
             p1.into(),
             Guid::generate().into(),
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+            None,
             None,
             None,
             CookieContext::default(),
@@ -234,7 +313,8 @@ mod tests {
         let server = Server::new(
             p1.into(),
             Guid::generate().into(),
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+            None,
             None,
             None,
             CookieContext::default(),
@@ -262,7 +342,8 @@ mod tests {
         let server = Server::new(
             p1.into(),
             Guid::generate().into(),
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+            None,
             None,
             None,
             CookieContext::default(),
@@ -281,9 +362,10 @@ mod tests {
         let server = Server::new(
             p1.into(),
             Guid::generate().into(),
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
             Some(vec![AuthMechanism::Anonymous].into()),
             None,
+            None,
             CookieContext::default(),
             None,
         )
@@ -300,9 +382,10 @@ mod tests {
         let server = Server::new(
             p1.into(),
             Guid::generate().into(),
-            Some(Uid::effective().into()),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
             Some(vec![AuthMechanism::Anonymous].into()),
             None,
+            None,
             CookieContext::default(),
             None,
         )
@@ -312,4 +395,182 @@ mod tests {
             .unwrap();
         crate::utils::block_on(server.perform()).unwrap();
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn client_compatibility() {
+        let id = hex::encode(sasl_auth_id().unwrap());
+        // The commands sent by the client, the responses expected from the server and whether FD
+        // passing is agreed on.
+        let cases = [
+            // sd-bus, using the credentials of the socket.
+            (
+                "\0AUTH EXTERNAL\r\nDATA\r\nNEGOTIATE_UNIX_FD\r\nBEGIN\r\n".to_string(),
+                &["DATA", "OK", "AGREE_UNIX_FD"][..],
+                true,
+            ),
+            // Older GLib, without FD passing.
+            (
+                format!("\0AUTH EXTERNAL {id}\r\nBEGIN\r\n"),
+                &["OK"][..],
+                false,
+            ),
+            // Asking for the supported mechanisms first.
+            (
+                format!("\0AUTH\r\nAUTH EXTERNAL {id}\r\nBEGIN\r\n"),
+                &["REJECTED", "OK"][..],
+                false,
+            ),
+            // Unknown mechanisms and commands.
+            (
+                format!(
+                    "\0AUTH KERBEROS_V4 abcd\r\nEXTENSION_FOO\r\nAUTH EXTERNAL {id}\r\nBEGIN\r\n"
+                ),
+                &["REJECTED", "ERROR", "OK"][..],
+                false,
+            ),
+            // FD passing negotiated before authentication.
+            (
+                format!(
+                    "\0NEGOTIATE_UNIX_FD\r\nAUTH EXTERNAL {id}\r\nNEGOTIATE_UNIX_FD\r\nBEGIN\r\n"
+                ),
+                &["ERROR", "OK", "AGREE_UNIX_FD"][..],
+                true,
+            ),
+            // Cancelled authentication.
+            (
+                format!("\0AUTH EXTERNAL\r\nCANCEL\r\nAUTH EXTERNAL {id}\r\nBEGIN\r\n"),
+                &["DATA", "REJECTED", "OK"][..],
+                false,
+            ),
+        ];
+
+        for (commands, responses, cap_unix_fd) in cases {
+            let (mut p0, p1) = create_async_socket_pair();
+            let server = Server::new(
+                p1.into(),
+                Guid::generate().into(),
+                ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+                None,
+                None,
+                None,
+                CookieContext::default(),
+                None,
+            )
+            .unwrap();
+
+            crate::utils::block_on(p0.write_all(commands.as_bytes())).unwrap();
+            let server = crate::utils::block_on(server.perform()).unwrap();
+            assert_eq!(server.cap_unix_fd, cap_unix_fd, "{commands:?}");
+
+            let mut received = String::new();
+            while received.matches("\r\n").count() < responses.len() {
+                let mut buf = [0; 1024];
+                let n = crate::utils::block_on(p0.read(&mut buf)).unwrap();
+                received.push_str(std::str::from_utf8(&buf[..n]).unwrap());
+            }
+            let received: Vec<_> = received
+                .lines()
+                .map(|line| line.split_ascii_whitespace().next().unwrap())
+                .collect();
+            assert_eq!(received, responses, "{commands:?}");
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn auth_policy() {
+        use std::sync::Mutex;
+
+        let (mut p0, p1) = create_async_socket_pair();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let seen_clone = seen.clone();
+        let policy = AuthPolicy::new(move |creds, mech| {
+            assert_eq!(creds.unix_user_id(), Some(Uid::effective().into()));
+            seen_clone.lock().unwrap().push(mech);
+
+            mech == AuthMechanism::Anonymous
+        });
+        let server = Server::new(
+            p1.into(),
+            Guid::generate().into(),
+            ConnectionCredentials::default().set_unix_user_id(Uid::effective().into()),
+            Some(vec![AuthMechanism::External, AuthMechanism::Anonymous].into()),
+            Some(policy),
+            None,
+            CookieContext::default(),
+            None,
+        )
+        .unwrap();
+
+        // The EXTERNAL authentication is valid but rejected by the policy.
+        crate::utils::block_on(
+            p0.write_all(
+                format!(
+                    "\0AUTH EXTERNAL {}\r\nAUTH ANONYMOUS abcd\r\nBEGIN\r\n",
+                    hex::encode(sasl_auth_id().unwrap())
+                )
+                .as_bytes(),
+            ),
+        )
+        .unwrap();
+        crate::utils::block_on(server.perform()).unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [AuthMechanism::External, AuthMechanism::Anonymous]
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn sasl_server() {
+        let sasl = SaslServer::new(Guid::generate())
+            .unwrap()
+            .auth_mechanisms([AuthMechanism::Anonymous]);
+
+        // The same server authenticates any number of clients.
+        for _ in 0..2 {
+            let (mut p0, p1) = create_async_socket_pair();
+            crate::utils::block_on(p0.write_all(b"\0AUTH ANONYMOUS abcd\r\nBEGIN\r\nhello"))
+                .unwrap();
+            let auth = crate::utils::block_on(sasl.authenticate(p1)).unwrap();
+
+            assert_eq!(auth.server_guid(), sasl.guid());
+            assert_eq!(
+                auth.peer_credentials().unwrap().unix_user_id(),
+                Some(Uid::effective().into())
+            );
+            let (_, _, received) = auth.into_parts();
+            assert_eq!(received, b"hello");
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn sasl_server_allowed_peer_uids() {
+        let sasl = SaslServer::new(Guid::generate())
+            .unwrap()
+            .auth_mechanisms([AuthMechanism::Anonymous]);
+        let uid: u32 = Uid::effective().into();
+
+        for (allowed, accepted) in [(uid, true), (uid + 1, false)] {
+            let (mut p0, p1) = create_async_socket_pair();
+            crate::utils::block_on(p0.write_all(b"\0AUTH ANONYMOUS abcd\r\nBEGIN\r\n")).unwrap();
+            let auth = crate::utils::block_on(sasl.authenticate(p1)).unwrap();
+
+            // The peer is checked even though the builder doesn't handle the handshake.
+            let res = crate::utils::block_on(
+                crate::connection::Builder::authenticated(auth)
+                    .p2p()
+                    .allowed_peer_uids([allowed])
+                    .build(),
+            );
+            match res {
+                Ok(_) => assert!(accepted),
+                Err(Error::Handshake(_)) => assert!(!accepted),
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+    }
 }
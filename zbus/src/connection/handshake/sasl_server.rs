@@ -0,0 +1,190 @@
+use static_assertions::assert_impl_all;
+use std::collections::VecDeque;
+use zvariant::Str;
+
+use crate::{fdo::ConnectionCredentials, Guid};
+
+use super::{
+    AuthMechanism, AuthPolicy, Authenticated, BoxedSplit, CookieContext, Error, OwnedGuid, Result,
+};
+
+/// The server side of the D-Bus authentication handshake.
+///
+/// This is the SASL implementation [`Builder`] uses for server connections, made available on its
+/// own for projects implementing a bus or a broker: it negotiates the authentication mechanism
+/// with the client, verifies its identity and negotiates file descriptor passing. A `SaslServer`
+/// holds no per-client state so the same one can authenticate any number of clients,
+/// concurrently.
+///
+/// Once a client is authenticated, the resulting [`Authenticated`] can either be turned into a
+/// [`Connection`] through [`Builder::authenticated`], or be taken apart with
+/// [`Authenticated::into_parts`] to handle the client's messages directly.
+///
+/// This type is only available when the `p2p` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(all(unix, not(feature = "tokio")))]
+/// # zbus::block_on(async {
+/// use std::os::unix::net::UnixStream;
+/// use zbus::{
+///     connection::{Builder, SaslServer},
+///     AuthMechanism, Guid,
+/// };
+///
+/// let sasl = SaslServer::new(Guid::generate())?
+///     .auth_mechanisms([AuthMechanism::External, AuthMechanism::Anonymous])
+///     // Only accept anonymous clients, and clients running as root.
+///     .auth_policy(|creds, mech| {
+///         mech == AuthMechanism::Anonymous || creds.unix_user_id() == Some(0)
+///     });
+///
+/// let (server_socket, client_socket) = UnixStream::pair()?;
+/// let server_socket = async_io::Async::new(server_socket)?;
+/// let (auth, client) = futures_util::try_join!(
+///     sasl.authenticate(server_socket),
+///     Builder::unix_stream(client_socket)
+///         .p2p()
+///         .auth_mechanism(AuthMechanism::Anonymous)
+///         .build(),
+/// )?;
+/// assert_eq!(auth.server_guid(), client.server_guid());
+///
+/// let server = Builder::authenticated(auth).p2p().build().await?;
+/// # drop((client, server));
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// [`Builder`]: crate::connection::Builder
+/// [`Builder::authenticated`]: crate::connection::Builder::authenticated
+/// [`Connection`]: crate::Connection
+#[derive(Debug, Clone)]
+pub struct SaslServer {
+    guid: OwnedGuid,
+    auth_mechanisms: Option<VecDeque<AuthMechanism>>,
+    auth_policy: Option<AuthPolicy>,
+    cookie_id: Option<usize>,
+    cookie_context: CookieContext<'static>,
+}
+
+assert_impl_all!(SaslServer: Send, Sync, Unpin);
+
+impl SaslServer {
+    /// Create a server identifying itself to clients with the given `guid`.
+    pub fn new<G>(guid: G) -> Result<Self>
+    where
+        G: TryInto<Guid<'static>>,
+        G::Error: Into<Error>,
+    {
+        Ok(Self {
+            guid: guid.try_into().map_err(Into::into)?.into(),
+            auth_mechanisms: None,
+            auth_policy: None,
+            cookie_id: None,
+            cookie_context: CookieContext::default(),
+        })
+    }
+
+    /// Specify the mechanisms offered to clients.
+    ///
+    /// If not specified, only the `EXTERNAL` mechanism is offered.
+    pub fn auth_mechanisms<I>(mut self, auth_mechanisms: I) -> Self
+    where
+        I: IntoIterator<Item = AuthMechanism>,
+    {
+        self.auth_mechanisms = Some(auth_mechanisms.into_iter().collect());
+
+        self
+    }
+
+    /// Decide whether to accept a client, once it successfully authenticated.
+    ///
+    /// See [`Builder::auth_policy`] for details.
+    ///
+    /// [`Builder::auth_policy`]: crate::connection::Builder::auth_policy
+    pub fn auth_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&ConnectionCredentials, AuthMechanism) -> bool + Send + Sync + 'static,
+    {
+        self.auth_policy = Some(AuthPolicy::new(policy));
+
+        self
+    }
+
+    /// The cookie context to use for the `DBUS_COOKIE_SHA1` mechanism.
+    ///
+    /// If not specified, the default cookie context of `org_freedesktop_general` will be used.
+    ///
+    /// # Errors
+    ///
+    /// If the given string is not a valid cookie context.
+    pub fn cookie_context<C>(mut self, context: C) -> Result<Self>
+    where
+        C: Into<Str<'static>>,
+    {
+        self.cookie_context = context.into().try_into()?;
+
+        Ok(self)
+    }
+
+    /// The ID of the cookie to use for the `DBUS_COOKIE_SHA1` mechanism.
+    ///
+    /// If not specified, the first cookie found in the cookie context file will be used.
+    pub fn cookie_id(mut self, id: usize) -> Self {
+        self.cookie_id = Some(id);
+
+        self
+    }
+
+    /// The GUID of the server.
+    pub fn guid(&self) -> &OwnedGuid {
+        &self.guid
+    }
+
+    /// Authenticate the client on the other end of `socket`.
+    ///
+    /// The credentials of the client are queried from the socket. They're used to verify its
+    /// identity for the `EXTERNAL` mechanism and passed to the [`SaslServer::auth_policy`], if
+    /// any.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Handshake`] if the client fails to authenticate.
+    pub async fn authenticate<S>(&self, socket: S) -> Result<Authenticated>
+    where
+        S: Into<BoxedSplit>,
+    {
+        let mut socket = socket.into();
+        let credentials = socket.read_mut().peer_credentials().await?;
+
+        self.authenticate_with_credentials(socket, credentials)
+            .await
+    }
+
+    /// Authenticate the client on the other end of `socket`, given its credentials.
+    ///
+    /// This is useful for transports which can't report the credentials of the peer, but where
+    /// they're known by other means.
+    pub async fn authenticate_with_credentials<S>(
+        &self,
+        socket: S,
+        credentials: ConnectionCredentials,
+    ) -> Result<Authenticated>
+    where
+        S: Into<BoxedSplit>,
+    {
+        Authenticated::server(
+            socket.into(),
+            self.guid.clone(),
+            credentials,
+            self.auth_mechanisms.clone(),
+            self.auth_policy.clone(),
+            self.cookie_id,
+            self.cookie_context.clone(),
+            None,
+        )
+        .await
+    }
+}
@@ -3,11 +3,11 @@ use sha1::{Digest, Sha1};
 use std::collections::VecDeque;
 use tracing::{instrument, trace};
 
-use crate::names::OwnedUniqueName;
+use crate::{fdo::ConnectionCredentials, names::OwnedUniqueName};
 
 use super::{
-    random_ascii, sasl_auth_id, AuthMechanism, Authenticated, BoxedSplit, Command, Common, Cookie,
-    CookieContext, Error, Handshake, OwnedGuid, Result,
+    random_ascii, sasl_auth_id, AuthMechanism, AuthPolicy, Authenticated, BoxedSplit, Command,
+    Common, Cookie, CookieContext, Error, Handshake, OwnedGuid, Result,
 };
 
 /*
@@ -30,22 +30,21 @@ pub struct Server<'s> {
     common: Common,
     step: ServerHandshakeStep,
     guid: OwnedGuid,
-    #[cfg(unix)]
-    client_uid: Option<u32>,
-    #[cfg(windows)]
-    client_sid: Option<String>,
+    client_credentials: ConnectionCredentials,
+    auth_policy: Option<AuthPolicy>,
     cookie_id: Option<usize>,
     cookie_context: CookieContext<'s>,
     unique_name: Option<OwnedUniqueName>,
 }
 
 impl<'s> Server<'s> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         socket: BoxedSplit,
         guid: OwnedGuid,
-        #[cfg(unix)] client_uid: Option<u32>,
-        #[cfg(windows)] client_sid: Option<String>,
+        client_credentials: ConnectionCredentials,
         mechanisms: Option<VecDeque<AuthMechanism>>,
+        auth_policy: Option<AuthPolicy>,
         cookie_id: Option<usize>,
         cookie_context: CookieContext<'s>,
         unique_name: Option<OwnedUniqueName>,
@@ -63,10 +62,8 @@ impl<'s> Server<'s> {
         Ok(Server {
             common: Common::new(socket, mechanisms),
             step: ServerHandshakeStep::WaitingForAuth,
-            #[cfg(unix)]
-            client_uid,
-            #[cfg(windows)]
-            client_sid,
+            client_credentials,
+            auth_policy,
             cookie_id,
             cookie_context,
             guid,
@@ -75,7 +72,14 @@ impl<'s> Server<'s> {
     }
 
     #[instrument(skip(self))]
-    async fn auth_ok(&mut self) -> Result<()> {
+    async fn auth_ok(&mut self, mech: AuthMechanism) -> Result<()> {
+        if let Some(policy) = &self.auth_policy {
+            if !policy.allows(&self.client_credentials, mech) {
+                trace!("Client rejected by the authentication policy");
+                return self.rejected_error().await;
+            }
+        }
+
         let guid = self.guid.clone();
         let cmd = Command::Ok(guid);
         trace!("Sending authentication OK");
@@ -88,22 +92,28 @@ impl<'s> Server<'s> {
     async fn check_external_auth(&mut self, sasl_id: &[u8]) -> Result<()> {
         let auth_ok = {
             let id = std::str::from_utf8(sasl_id)
-                .map_err(|e| Error::Handshake(format!("Invalid ID: {e}")))?;
+                .map_err(|e| Error::handshake_cause("Invalid ID", e))?;
             #[cfg(unix)]
             {
                 let uid = id
                     .parse::<u32>()
-                    .map_err(|e| Error::Handshake(format!("Invalid UID: {e}")))?;
-                self.client_uid.map(|u| u == uid).unwrap_or(false)
+                    .map_err(|e| Error::handshake_cause("Invalid UID", e))?;
+                self.client_credentials
+                    .unix_user_id()
+                    .map(|u| u == uid)
+                    .unwrap_or(false)
             }
             #[cfg(windows)]
             {
-                self.client_sid.as_ref().map(|u| u == id).unwrap_or(false)
+                self.client_credentials
+                    .windows_sid()
+                    .map(|u| u == id)
+                    .unwrap_or(false)
             }
         };
 
         if auth_ok {
-            self.auth_ok().await
+            self.auth_ok(AuthMechanism::External).await
         } else {
             self.rejected_error().await
         }
@@ -115,8 +125,8 @@ impl<'s> Server<'s> {
             Some(cookie_id) => Cookie::lookup(&self.cookie_context, cookie_id).await?,
             None => Cookie::first(&self.cookie_context).await?,
         };
-        let id = std::str::from_utf8(sasl_id)
-            .map_err(|e| Error::Handshake(format!("Invalid ID: {e}")))?;
+        let id =
+            std::str::from_utf8(sasl_id).map_err(|e| Error::handshake_cause("Invalid ID", e))?;
         if sasl_auth_id()? != id {
             // While the spec will make you believe that DBUS_COOKIE_SHA1 can be used to
             // authenticate any user, it is not even possible (or correct) for the server to manage
@@ -140,7 +150,7 @@ impl<'s> Server<'s> {
             Error::Handshake("Expected DBUS_COOKIE_SHA1 authentication challenge response".into())
         })?;
         let client_auth = std::str::from_utf8(&auth_data)
-            .map_err(|e| Error::Handshake(format!("Invalid COOKIE authentication data: {e}")))?;
+            .map_err(|e| Error::handshake_cause("Invalid COOKIE authentication data", e))?;
         let mut split = client_auth.split_ascii_whitespace();
         let client_challenge = split
             .next()
@@ -152,7 +162,7 @@ impl<'s> Server<'s> {
         let sha1 = hex::encode(Sha1::digest(sec));
 
         if sha1 == client_sha1 {
-            self.auth_ok().await
+            self.auth_ok(AuthMechanism::Cookie).await
         } else {
             self.rejected_error().await
         }
@@ -208,7 +218,7 @@ impl<'s> Server<'s> {
                         self.step = ServerHandshakeStep::WaitingForData(mech);
                     }
                     (Some(AuthMechanism::Anonymous), Some(_)) => {
-                        self.auth_ok().await?;
+                        self.auth_ok(AuthMechanism::Anonymous).await?;
                     }
                     (Some(AuthMechanism::External), Some(sasl_id)) => {
                         self.check_external_auth(sasl_id).await?;
@@ -223,7 +233,15 @@ impl<'s> Server<'s> {
                 trace!("Received CANCEL or ERROR command from the client");
                 self.rejected_error().await?;
             }
-            _ => self.unsupported_command_error().await?,
+            Command::Begin => {
+                return Err(Error::Handshake(
+                    "Received BEGIN command before authentication".into(),
+                ));
+            }
+            cmd => {
+                trace!("Received unexpected command from the client: {cmd}");
+                self.unsupported_command_error().await?
+            }
         }
 
         Ok(())
@@ -237,13 +255,29 @@ impl<'s> Server<'s> {
         trace!("Waiting for authentication data");
         let reply = self.common.read_command().await?;
         match (mech, reply) {
-            (AuthMechanism::External, Command::Data(None)) => self.auth_ok().await?,
+            (AuthMechanism::External, Command::Data(None)) => {
+                self.auth_ok(AuthMechanism::External).await?
+            }
             (AuthMechanism::External, Command::Data(Some(data))) => {
                 self.check_external_auth(&data).await?;
             }
-            (AuthMechanism::Anonymous, Command::Data(_)) => self.auth_ok().await?,
+            (AuthMechanism::Anonymous, Command::Data(_)) => {
+                self.auth_ok(AuthMechanism::Anonymous).await?
+            }
             (_, Command::Data(_)) => self.rejected_error().await?,
-            (_, _) => self.unsupported_command_error().await?,
+            (_, Command::Cancel | Command::Error(_)) => {
+                trace!("Received CANCEL or ERROR command from the client");
+                self.rejected_error().await?;
+            }
+            (_, Command::Begin) => {
+                return Err(Error::Handshake(
+                    "Received BEGIN command before authentication".into(),
+                ));
+            }
+            (_, cmd) => {
+                trace!("Received unexpected command from the client: {cmd}");
+                self.unsupported_command_error().await?
+            }
         }
         Ok(())
     }
@@ -279,7 +313,10 @@ impl<'s> Server<'s> {
                 }
                 self.step = ServerHandshakeStep::WaitingForBegin;
             }
-            _ => self.unsupported_command_error().await?,
+            cmd => {
+                trace!("Received unexpected command from the client: {cmd}");
+                self.unsupported_command_error().await?
+            }
         }
 
         Ok(())
@@ -304,6 +341,7 @@ impl Handshake for Server<'_> {
             cap_unix_fd,
             already_received_bytes: recv_buffer,
             unique_name: self.unique_name,
+            peer_credentials: Some(self.client_credentials),
         })
     }
 }
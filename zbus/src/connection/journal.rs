@@ -0,0 +1,178 @@
+//! The message journal of a connection.
+//!
+//! When enabled through [`Builder::journal_capacity`] or [`Connection::set_journal_capacity`],
+//! the connection keeps a record of the last messages it has sent and received, which can be
+//! retrieved with [`Connection::journal`]. This is useful for capturing the context of an error in
+//! production, without running a separate bus monitor.
+//!
+//! [`Builder::journal_capacity`]: super::Builder::journal_capacity
+//! [`Connection::set_journal_capacity`]: super::Connection::set_journal_capacity
+//! [`Connection::journal`]: super::Connection::journal
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+use static_assertions::assert_impl_all;
+
+use crate::Message;
+
+/// The direction of a message recorded in the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The message was sent on the connection.
+    Sent,
+    /// The message was received on the connection.
+    Received,
+}
+
+/// A message recorded in the journal.
+///
+/// The [`fmt::Display`] implementation gives a one-line summary of the entry, which is handy for
+/// dumping the journal to logs.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    direction: Direction,
+    time: SystemTime,
+    message: Message,
+}
+
+assert_impl_all!(Entry: Send, Sync, Unpin);
+
+impl Entry {
+    /// The direction of the message.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The time at which the message was sent or received.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// The message.
+    ///
+    /// **Note:** Messages are recorded whole, so any file descriptors they carry are kept open
+    /// until the entry is evicted from the journal and dropped.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Sent => "sent",
+            Direction::Received => "received",
+        };
+        let time = self
+            .time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        write!(
+            f,
+            "[{}.{:06}] {direction} #{}: {}",
+            time.as_secs(),
+            time.subsec_micros(),
+            self.message.primary_header().serial_num(),
+            self.message
+        )
+    }
+}
+
+/// A bounded journal of messages, evicting the oldest entries first.
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    capacity: AtomicUsize,
+    entries: Mutex<VecDeque<Entry>>,
+}
+
+impl Journal {
+    pub(crate) fn record(&self, direction: Direction, message: &Message) {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("poisoned lock");
+        while entries.len() >= capacity {
+            entries.pop_front();
+        }
+        entries.push_back(Entry {
+            direction,
+            time: SystemTime::now(),
+            message: message.clone(),
+        });
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+
+        let mut entries = self.entries.lock().expect("poisoned lock");
+        let excess = entries.len().saturating_sub(capacity);
+        entries.drain(..excess);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<Entry> {
+        let entries = self.entries.lock().expect("poisoned lock");
+
+        entries.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn eviction() {
+        let journal = Journal::default();
+        let msg = |member| {
+            Message::signal("/", "org.zbus.Journal", member)
+                .unwrap()
+                .build(&())
+                .unwrap()
+        };
+
+        journal.record(Direction::Sent, &msg("Disabled"));
+        assert!(journal.entries().is_empty());
+
+        journal.set_capacity(2);
+        journal.record(Direction::Sent, &msg("First"));
+        journal.record(Direction::Received, &msg("Second"));
+        journal.record(Direction::Sent, &msg("Third"));
+        let members: Vec<_> = journal
+            .entries()
+            .iter()
+            .map(|e| {
+                (
+                    e.direction(),
+                    e.message().header().member().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            members,
+            [
+                (Direction::Received, "Second".to_string()),
+                (Direction::Sent, "Third".to_string()),
+            ]
+        );
+
+        journal.set_capacity(1);
+        let entries = journal.entries();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string().contains("sent #"));
+    }
+}
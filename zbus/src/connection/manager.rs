@@ -0,0 +1,123 @@
+use static_assertions::assert_impl_all;
+use std::{
+    future::Future,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{async_lock::Mutex, connection::State, Connection, Result};
+
+/// Shared connections to the session and system buses.
+///
+/// Applications talking to both buses otherwise need to pass their connections around everywhere.
+/// A `BusManager` instead creates each connection the first time it's asked for and hands out
+/// clones of it afterwards. If a connection gets disconnected, a new one is created on the next
+/// request.
+///
+/// Most applications only need the process-wide instance, see [`BusManager::global`]. Clones of a
+/// `BusManager` share its connections.
+///
+/// # Example
+///
+/// ```no_run
+/// use zbus::connection::BusManager;
+///
+/// # zbus::block_on(async {
+/// let session = BusManager::global().session().await?;
+/// // Same connection, wherever it's requested from.
+/// let same = BusManager::global().session().await?;
+/// assert_eq!(session.unique_name(), same.unique_name());
+///
+/// // Likewise for the system bus.
+/// let _system = BusManager::global().system().await?;
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct BusManager {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    session: Mutex<Option<Connection>>,
+    system: Mutex<Option<Connection>>,
+}
+
+assert_impl_all!(BusManager: Send, Sync, Unpin);
+
+static GLOBAL: OnceLock<BusManager> = OnceLock::new();
+
+impl BusManager {
+    /// Create a new manager, without any connection yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide manager.
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// A connection to the session bus.
+    ///
+    /// The connection is created on the first call, or if the previous one got disconnected.
+    pub async fn session(&self) -> Result<Connection> {
+        connection(&self.inner.session, Connection::session).await
+    }
+
+    /// A connection to the system bus.
+    ///
+    /// The connection is created on the first call, or if the previous one got disconnected.
+    pub async fn system(&self) -> Result<Connection> {
+        connection(&self.inner.system, Connection::system).await
+    }
+}
+
+async fn connection<F, Fut>(slot: &Mutex<Option<Connection>>, connect: F) -> Result<Connection>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Connection>>,
+{
+    // The lock is held while connecting, so concurrent callers end up sharing the same connection.
+    let mut slot = slot.lock().await;
+    if let Some(conn) = &*slot {
+        if matches!(conn.state(), State::Connected) {
+            return Ok(conn.clone());
+        }
+    }
+
+    let conn = connect().await?;
+    *slot = Some(conn.clone());
+
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::BusManager;
+    use crate::Result;
+
+    #[test]
+    #[timeout(15000)]
+    fn shared_connection() {
+        crate::utils::block_on(test_shared_connection()).unwrap();
+    }
+
+    async fn test_shared_connection() -> Result<()> {
+        let manager = BusManager::new();
+        let conn = manager.session().await?;
+        let name = conn.unique_name().unwrap().to_owned();
+        assert_eq!(manager.session().await?.unique_name(), Some(&name));
+
+        // A new connection replaces the disconnected one.
+        conn.close().await?;
+        let conn = manager.session().await?;
+        assert_ne!(conn.unique_name(), Some(&name));
+        assert_eq!(manager.session().await?.unique_name(), conn.unique_name());
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender as Broadcaster};
 use enumflags2::BitFlags;
 use event_listener::{Event, EventListener};
-use ordered_stream::{OrderedFuture, OrderedStream, PollResult};
+use ordered_stream::OrderedFuture;
 use static_assertions::assert_impl_all;
 use std::{
     collections::HashMap,
@@ -10,12 +10,18 @@ use std::{
     num::NonZeroU32,
     ops::Deref,
     pin::Pin,
-    sync::{Arc, OnceLock, Weak},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, Weak,
+    },
     task::{Context, Poll},
+    time::Duration,
 };
 use tracing::{debug, info_span, instrument, trace, trace_span, warn, Instrument};
-use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, WellKnownName};
-use zvariant::ObjectPath;
+use zbus_names::{
+    BusName, ErrorName, InterfaceName, MemberName, OwnedBusName, OwnedUniqueName, WellKnownName,
+};
+use zvariant::{Endian, ObjectPath, OwnedValue, Value, NATIVE_ENDIAN};
 
 use futures_core::Future;
 use futures_util::StreamExt;
@@ -24,10 +30,9 @@ use crate::{
     async_lock::Mutex,
     blocking,
     fdo::{self, ConnectionCredentials, RequestNameFlags, RequestNameReply},
-    message::{Flags, Message, Type},
+    message::{Flags, Message, Sequence, Type},
     proxy::CacheProperties,
-    DBusError, Error, Executor, MatchRule, MessageStream, ObjectServer, OwnedGuid, OwnedMatchRule,
-    Result, Task,
+    DBusError, Error, Executor, MatchRule, ObjectServer, OwnedGuid, OwnedMatchRule, Result, Task,
 };
 
 mod builder;
@@ -36,14 +41,39 @@ pub use builder::Builder;
 pub mod socket;
 pub use socket::Socket;
 
+pub mod journal;
+
+#[cfg(all(unix, feature = "p2p"))]
+mod activation;
+#[cfg(all(unix, feature = "p2p"))]
+pub use activation::ActivationListener;
+
+use journal::{Direction, Journal};
+
 mod socket_reader;
 use socket_reader::SocketReader;
 
+mod sticky;
+use sticky::StickySignals;
+
+mod pending_replies;
+use pending_replies::PendingReplies;
+
+mod manager;
+pub use manager::BusManager;
+
+mod state;
+pub use state::State;
+use state::Termination;
+
 pub(crate) mod handshake;
+#[cfg(not(feature = "p2p"))]
 use handshake::Authenticated;
+#[cfg(feature = "p2p")]
+pub use handshake::{Authenticated, SaslServer};
 
 const DEFAULT_MAX_QUEUED: usize = 64;
-const DEFAULT_MAX_METHOD_RETURN_QUEUED: usize = 8;
+const BUS_NAME: &str = "org.freedesktop.DBus";
 
 /// Inner state shared by Connection and WeakConnection
 #[derive(Debug)]
@@ -67,13 +97,25 @@ pub(crate) struct ConnectionInner {
     socket_reader_task: OnceLock<Task<()>>,
 
     pub(crate) msg_receiver: InactiveReceiver<Result<Message>>,
-    pub(crate) method_return_receiver: InactiveReceiver<Result<Message>>,
     msg_senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
 
     subscriptions: Mutex<Subscriptions>,
+    sticky_signals: Arc<Mutex<StickySignals>>,
+    pending_replies: Arc<std::sync::Mutex<PendingReplies>>,
+    termination: Arc<Termination>,
+
+    journal: Arc<Journal>,
+
+    strict_sender_validation: Arc<AtomicBool>,
+    method_timeout: std::sync::Mutex<Option<Duration>>,
+    endian: std::sync::Mutex<Endian>,
+    max_message_size: usize,
 
     object_server: OnceLock<blocking::ObjectServer>,
     object_server_dispatch_task: OnceLock<Task<()>>,
+    // Set, and the event notified, once the object server task is done setting up.
+    object_server_started: AtomicBool,
+    object_server_started_event: Event,
 }
 
 type Subscriptions = HashMap<OwnedMatchRule, (u64, InactiveReceiver<Result<Message>>)>;
@@ -202,8 +244,9 @@ assert_impl_all!(Connection: Send, Sync, Unpin);
 /// population whose task is scheduled later.
 #[derive(Debug)]
 pub(crate) struct PendingMethodCall {
-    stream: Option<MessageStream>,
+    reply: Option<Receiver<Result<Message>>>,
     serial: NonZeroU32,
+    pending_replies: Arc<std::sync::Mutex<PendingReplies>>,
 }
 
 impl Future for PendingMethodCall {
@@ -230,48 +273,49 @@ impl OrderedFuture for PendingMethodCall {
         before: Option<&Self::Ordering>,
     ) -> Poll<Option<(Self::Ordering, Self::Output)>> {
         let this = self.get_mut();
-        if let Some(stream) = &mut this.stream {
-            loop {
-                match Pin::new(&mut *stream).poll_next_before(cx, before) {
-                    Poll::Ready(PollResult::Item {
-                        data: Ok(msg),
-                        ordering,
-                    }) => {
-                        if msg.header().reply_serial() != Some(this.serial) {
-                            continue;
-                        }
-                        let res = match msg.message_type() {
-                            Type::Error => Err(msg.into()),
-                            Type::MethodReturn => Ok(msg),
-                            _ => continue,
-                        };
-                        this.stream = None;
-                        return Poll::Ready(Some((ordering, res)));
-                    }
-                    Poll::Ready(PollResult::Item {
-                        data: Err(e),
-                        ordering,
-                    }) => {
-                        return Poll::Ready(Some((ordering, Err(e))));
-                    }
-
-                    Poll::Ready(PollResult::NoneBefore) => {
-                        return Poll::Ready(None);
-                    }
-                    Poll::Ready(PollResult::Terminated) => {
-                        return Poll::Ready(None);
-                    }
-                    Poll::Pending => return Poll::Pending,
-                }
+        let Some(reply) = &mut this.reply else {
+            return Poll::Ready(None);
+        };
+        let res = match reply.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(msg))) => {
+                let ordering = msg.recv_position();
+                let res = match msg.message_type() {
+                    Type::Error => Err(msg.into()),
+                    _ => Ok(msg),
+                };
+
+                Some((ordering, res))
             }
+            Poll::Ready(Some(Err(e))) => Some((Sequence::LAST, Err(e))),
+            Poll::Ready(None) => None,
+            // The reply is routed by the socket reader task, which would have done so already if
+            // it was received before the message `before` comes from.
+            Poll::Pending if before.is_some() => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        this.reply = None;
+
+        Poll::Ready(res)
+    }
+}
+
+impl Drop for PendingMethodCall {
+    fn drop(&mut self) {
+        if self.reply.is_some() {
+            self.pending_replies
+                .lock()
+                .expect("lock poisoned")
+                .unregister(self.serial);
         }
-        Poll::Ready(None)
     }
 }
 
 impl Connection {
     /// Send `msg` to the peer.
     pub async fn send(&self, msg: &Message) -> Result<()> {
+        if let Some(error) = self.inner.termination.error() {
+            return Err(error.clone());
+        }
         #[cfg(unix)]
         if !msg.data().fds().is_empty() && !self.inner.cap_unix_fd {
             return Err(Error::Unsupported);
@@ -280,7 +324,34 @@ impl Connection {
         self.inner.activity_event.notify(usize::MAX);
         let mut write = self.inner.socket_write.lock().await;
 
-        write.send_message(msg).await
+        write.send_message(msg).await?;
+        self.inner.journal.record(Direction::Sent, msg);
+
+        Ok(())
+    }
+
+    /// Send a raw, already encoded message to the peer.
+    ///
+    /// This is useful for bridging zbus with other D-Bus implementations, e.g forwarding messages
+    /// between a container's bus and the host bus, without decoding and re-encoding each message.
+    /// Only the message header is parsed and the length of `bytes` is validated against it. The
+    /// raw frame of received messages, including their FDs, is available through [`Message::data`].
+    ///
+    /// **Note:** The message is sent as is so it's up to the caller to ensure that its serial
+    /// number does not clash with that of the messages sent by zbus on this connection, if replies
+    /// to it are expected.
+    pub async fn send_raw(
+        &self,
+        bytes: Vec<u8>,
+        #[cfg(unix)] fds: Vec<zvariant::OwnedFd>,
+    ) -> Result<()> {
+        let msg = Message::from_frame(
+            bytes,
+            #[cfg(unix)]
+            fds,
+        )?;
+
+        self.send(&msg).await
     }
 
     /// Send a method call.
@@ -289,6 +360,9 @@ impl Connection {
     ///
     /// On successful reply, an `Ok(Message)` is returned. On error, an `Err` is returned. D-Bus
     /// error replies are returned as [`Error::MethodError`].
+    ///
+    /// If a [default method timeout][Connection::set_method_timeout] is set and no reply is
+    /// received in time, [`Error::Timeout`] is returned.
     pub async fn call_method<'d, 'p, 'i, 'm, D, P, I, M, B>(
         &self,
         destination: Option<D>,
@@ -297,6 +371,85 @@ impl Connection {
         method_name: M,
         body: &B,
     ) -> Result<Message>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+    {
+        let pending = self
+            .call_method_raw(
+                destination,
+                path,
+                interface,
+                method_name,
+                BitFlags::empty(),
+                body,
+            )
+            .await?
+            .ok_or(Error::InvalidReply)?;
+
+        self.wait_reply(pending, self.method_timeout()).await
+    }
+
+    /// Send a method call and wait for the reply for at most `timeout`.
+    ///
+    /// This is the same as [`Connection::call_method`], except that [`Error::Timeout`] is returned
+    /// if no reply is received within `timeout`, regardless of the default method timeout. A reply
+    /// arriving after that is discarded.
+    pub async fn call_method_with_timeout<'d, 'p, 'i, 'm, D, P, I, M, B>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        interface: Option<I>,
+        method_name: M,
+        body: &B,
+        timeout: Duration,
+    ) -> Result<Message>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+    {
+        let pending = self
+            .call_method_raw(
+                destination,
+                path,
+                interface,
+                method_name,
+                BitFlags::empty(),
+                body,
+            )
+            .await?
+            .ok_or(Error::InvalidReply)?;
+
+        self.wait_reply(pending, Some(timeout)).await
+    }
+
+    /// Send a method call without expecting a reply.
+    ///
+    /// This sets the `NoReplyExpected` flag on the method-call message and returns as soon as it's
+    /// sent, without waiting for a round trip to the peer. This is useful for high-frequency
+    /// notifications. Note that any error from the peer is silently lost.
+    pub async fn call_method_noreply<'d, 'p, 'i, 'm, D, P, I, M, B>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        interface: Option<I>,
+        method_name: M,
+        body: &B,
+    ) -> Result<()>
     where
         D: TryInto<BusName<'d>>,
         P: TryInto<ObjectPath<'p>>,
@@ -313,12 +466,123 @@ impl Connection {
             path,
             interface,
             method_name,
-            BitFlags::empty(),
+            Flags::NoReplyExpected.into(),
             body,
         )
-        .await?
-        .expect("no reply")
         .await
+        .map(|_| ())
+    }
+
+    /// Get the value of a property.
+    ///
+    /// This calls the `Get` method of the `org.freedesktop.DBus.Properties` interface of the
+    /// object and unwraps the value from the returned variant. It's convenient for one-off
+    /// queries but, unlike [`Proxy::get_property`], it doesn't cache anything.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use zbus::Connection;
+    ///
+    /// let conn = Connection::system().await?;
+    /// let features: Vec<String> = conn
+    ///     .get_property(
+    ///         Some("org.freedesktop.DBus"),
+    ///         "/org/freedesktop/DBus",
+    ///         "org.freedesktop.DBus",
+    ///         "Features",
+    ///     )
+    ///     .await?;
+    /// # drop(features);
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// [`Proxy::get_property`]: crate::Proxy::get_property
+    pub async fn get_property<'d, 'p, 'i, D, P, I, T>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        interface: I,
+        property_name: &str,
+    ) -> Result<T>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+        let reply = self
+            .call_method(
+                destination,
+                path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &(interface, property_name),
+            )
+            .await?;
+        let value: OwnedValue = reply.body().deserialize()?;
+
+        value.try_into().map_err(Into::into)
+    }
+
+    /// Set the value of a property.
+    ///
+    /// This calls the `Set` method of the `org.freedesktop.DBus.Properties` interface of the
+    /// object, wrapping `value` in a variant.
+    pub async fn set_property<'d, 'p, 'i, 't, D, P, I, T>(
+        &self,
+        destination: Option<D>,
+        path: P,
+        interface: I,
+        property_name: &str,
+        value: T,
+    ) -> Result<()>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        T: 't + Into<Value<'t>>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+        self.call_method(
+            destination,
+            path,
+            Some("org.freedesktop.DBus.Properties"),
+            "Set",
+            &(interface, property_name, value.into()),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Wait for the reply to `pending`, for at most `timeout` if set.
+    ///
+    /// On timeout, `pending` is dropped, so the reply is discarded if it arrives later.
+    pub(crate) async fn wait_reply(
+        &self,
+        pending: PendingMethodCall,
+        timeout: Option<Duration>,
+    ) -> Result<Message> {
+        match timeout {
+            Some(timeout) => crate::timeout::timeout(timeout, pending)
+                .await
+                .unwrap_or_else(|| {
+                    debug!("Method call timed out after {:?}", timeout);
+
+                    Err(Error::Timeout(timeout))
+                }),
+            None => pending.await,
+        }
     }
 
     /// Send a method call.
@@ -351,7 +615,7 @@ impl Connection {
         M::Error: Into<Error>,
         B: serde::ser::Serialize + zvariant::DynamicType,
     {
-        let mut builder = Message::method(path, method_name)?;
+        let mut builder = Message::method(path, method_name)?.endian(self.endian());
         if let Some(sender) = self.unique_name() {
             builder = builder.sender(sender)?
         }
@@ -366,22 +630,128 @@ impl Connection {
         }
         let msg = builder.build(body)?;
 
-        let msg_receiver = self.inner.method_return_receiver.activate_cloned();
-        let stream = Some(MessageStream::for_subscription_channel(
-            msg_receiver,
-            // This is a lie but we only use the stream internally so it's fine.
-            None,
-            self,
-        ));
-        let serial = msg.primary_header().serial_num();
-        self.send(&msg).await?;
+        let reply_sender = match msg.header().destination() {
+            Some(destination)
+                if self.strict_sender_validation()
+                    && self.is_bus()
+                    && !flags.contains(Flags::NoReplyExpected) =>
+            {
+                if let BusName::WellKnown(name) = destination {
+                    if name.as_str() != BUS_NAME {
+                        self.track_name_owner(name).await?;
+                    }
+                }
+
+                Some(destination.to_owned().into())
+            }
+            _ => None,
+        };
+
         if flags.contains(Flags::NoReplyExpected) {
+            // No need to keep track of a reply that won't come.
+            self.send(&msg).await?;
+
             Ok(None)
         } else {
-            Ok(Some(PendingMethodCall { stream, serial }))
+            self.send_method_call(&msg, reply_sender).await.map(Some)
         }
     }
 
+    async fn send_method_call(
+        &self,
+        msg: &Message,
+        reply_sender: Option<OwnedBusName>,
+    ) -> Result<PendingMethodCall> {
+        let serial = msg.primary_header().serial_num();
+        // Register the call before sending it, so the reply can't be missed.
+        let reply = self
+            .inner
+            .pending_replies
+            .lock()
+            .expect("lock poisoned")
+            .register(serial, reply_sender);
+        let pending = PendingMethodCall {
+            reply: Some(reply),
+            serial,
+            pending_replies: self.inner.pending_replies.clone(),
+        };
+        self.send(msg).await?;
+
+        Ok(pending)
+    }
+
+    /// Keep track of the owner of the well-known `name`, to validate the sender of replies.
+    ///
+    /// The first call for a name subscribes to its `NameOwnerChanged` signals and asks the bus for
+    /// its current owner. The signals then keep it up to date, for as long as the connection lives.
+    /// Concurrent calls for the same name wait for the first one to be done.
+    async fn track_name_owner(&self, name: &WellKnownName<'_>) -> Result<()> {
+        let subscription = {
+            let mut pending_replies = self.inner.pending_replies.lock().expect("lock poisoned");
+            if pending_replies.is_name_owner_tracked(name) {
+                return Ok(());
+            }
+            pending_replies.track_name_owner(name)
+        };
+        let mut subscribed = subscription.lock().await;
+        if self
+            .inner
+            .pending_replies
+            .lock()
+            .expect("lock poisoned")
+            .is_name_owner_tracked(name)
+        {
+            // Another call got the owner while we were waiting.
+            return Ok(());
+        }
+
+        if !*subscribed {
+            let rule = MatchRule::builder()
+                .msg_type(Type::Signal)
+                .sender(BUS_NAME)?
+                .interface(BUS_NAME)?
+                .member("NameOwnerChanged")?
+                .add_arg(name.as_str())?
+                .build();
+            let add_match = Message::method("/org/freedesktop/DBus", "AddMatch")?
+                .destination(BUS_NAME)?
+                .interface(BUS_NAME)?
+                .build(&rule.to_string())?;
+            self.send_method_call(&add_match, Some(BUS_NAME.try_into()?))
+                .await?
+                .await?;
+            *subscribed = true;
+        }
+
+        let get_name_owner = Message::method("/org/freedesktop/DBus", "GetNameOwner")?
+            .destination(BUS_NAME)?
+            .interface(BUS_NAME)?
+            .build(name)?;
+        let reply = self
+            .send_method_call(&get_name_owner, Some(BUS_NAME.try_into()?))
+            .await?
+            .await;
+        let (owner, position) = match reply {
+            Ok(reply) => (
+                Some(reply.body().deserialize::<OwnedUniqueName>()?.into_inner()),
+                reply.recv_position(),
+            ),
+            Err(Error::MethodError(error_name, _, reply))
+                if error_name == "org.freedesktop.DBus.Error.NameHasNoOwner" =>
+            {
+                (None, reply.recv_position())
+            }
+            Err(e) => return Err(e),
+        };
+        self.inner
+            .pending_replies
+            .lock()
+            .expect("lock poisoned")
+            .set_name_owner(name, owner, position);
+
+        Ok(())
+    }
+
     /// Emit a signal.
     ///
     /// Create a signal message, and send it over the connection.
@@ -404,7 +774,7 @@ impl Connection {
         M::Error: Into<Error>,
         B: serde::ser::Serialize + zvariant::DynamicType,
     {
-        let mut b = Message::signal(path, interface, signal_name)?;
+        let mut b = Message::signal(path, interface, signal_name)?.endian(self.endian());
         if let Some(sender) = self.unique_name() {
             b = b.sender(sender)?;
         }
@@ -419,7 +789,10 @@ impl Connection {
     /// Reply to a message.
     ///
     /// Given an existing message (likely a method call), send a reply back to the caller with the
-    /// given `body`.
+    /// given `body`. Any file descriptors in `body` are sent along.
+    ///
+    /// For full control over the header of the reply, create it through [`Message::method_reply`]
+    /// and send it with [`Connection::send`] instead.
     pub async fn reply<B>(&self, call: &Message, body: &B) -> Result<()>
     where
         B: serde::ser::Serialize + zvariant::DynamicType,
@@ -436,6 +809,8 @@ impl Connection {
     ///
     /// Given an existing message (likely a method call), send an error reply back to the caller
     /// with the given `error_name` and `body`.
+    ///
+    /// See [`Connection::reply`] for sending a customized reply.
     pub async fn reply_error<'e, E, B>(&self, call: &Message, error_name: E, body: &B) -> Result<()>
     where
         B: serde::ser::Serialize + zvariant::DynamicType,
@@ -809,6 +1184,125 @@ impl Connection {
         self.inner.msg_receiver.clone().set_capacity(max);
     }
 
+    /// The capacity of the message journal.
+    ///
+    /// This is `0` (the default) if the journal is disabled.
+    pub fn journal_capacity(&self) -> usize {
+        self.inner.journal.capacity()
+    }
+
+    /// Set the capacity of the message journal.
+    ///
+    /// If `capacity` is non-zero, the connection keeps a record of the last `capacity` messages it
+    /// has sent and received. Setting it to `0` disables the journal. If the journal currently holds
+    /// more entries than `capacity`, the oldest ones are dropped.
+    ///
+    /// See the [`journal`] module documentation for details.
+    pub fn set_journal_capacity(&self, capacity: usize) {
+        self.inner.journal.set_capacity(capacity);
+    }
+
+    /// Whether strict sender validation is enabled.
+    ///
+    /// See [`Connection::set_strict_sender_validation`] for details.
+    pub fn strict_sender_validation(&self) -> bool {
+        self.inner.strict_sender_validation.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable strict sender validation.
+    ///
+    /// A well-behaved message bus sets the sender of each message it routes, so the sender can be
+    /// trusted. On buses that don't enforce this, any peer could send a reply to a method call it
+    /// didn't receive, or a signal pretending to come from another peer. When this is enabled on
+    /// a bus connection:
+    ///
+    /// * Replies to method calls are only accepted from the destination of the call, or from the
+    ///   bus itself. If the destination is a well-known name, replies are accepted from any peer
+    ///   owning the name while the call is pending. For this, the connection keeps track of the
+    ///   owners of the names it calls, which costs two additional round trips to the bus on the
+    ///   first call to each name. Replies from any other peer are ignored.
+    /// * Signals are only delivered to the streams of match rules with a `sender` if they have a
+    ///   sender. Moreover only the bus itself is allowed to send as `org.freedesktop.DBus`. Note
+    ///   that a well-known `sender` in a match rule can not be resolved here; streams from
+    ///   [`crate::Proxy::receive_signal`] take care of ignoring signals not coming from the
+    ///   current owner of the name.
+    ///
+    /// This is disabled by default.
+    pub fn set_strict_sender_validation(&self, enabled: bool) {
+        self.inner
+            .strict_sender_validation
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// The default timeout of method calls, if any.
+    ///
+    /// See [`Connection::set_method_timeout`] for details.
+    pub fn method_timeout(&self) -> Option<Duration> {
+        *self.inner.method_timeout.lock().expect("lock poisoned")
+    }
+
+    /// Set the default timeout of method calls.
+    ///
+    /// If no reply to a method call made through [`Connection::call_method`] or a
+    /// [`crate::Proxy`] is received within `timeout`, the call fails with [`Error::Timeout`] and
+    /// the reply is discarded if it arrives later. `None`, the default, means waiting for the reply
+    /// forever.
+    pub fn set_method_timeout(&self, timeout: Option<Duration>) {
+        *self.inner.method_timeout.lock().expect("lock poisoned") = timeout;
+    }
+
+    /// The endianness of the method calls and signals built by the connection.
+    ///
+    /// See [`Connection::set_endian`] for details.
+    pub fn endian(&self) -> Endian {
+        *self.inner.endian.lock().expect("lock poisoned")
+    }
+
+    /// Set the endianness of the method calls and signals built by the connection.
+    ///
+    /// This applies to the messages built by [`Connection::call_method`],
+    /// [`Connection::emit_signal`] and the related methods, hence to the method calls of
+    /// [`crate::Proxy`] and the signals emitted by the [`crate::ObjectServer`] as well. Replies
+    /// are always encoded with the endianness of the method call they are for. Messages received
+    /// are decoded according to their own endianness.
+    ///
+    /// The default is the native endianness.
+    pub fn set_endian(&self, endian: Endian) {
+        *self.inner.endian.lock().expect("lock poisoned") = endian;
+    }
+
+    /// The maximum size of the messages received on the connection.
+    ///
+    /// Larger messages are rejected as soon as their header is received, which terminates the
+    /// connection with [`Error::ExcessData`]. This is 128 MiB, the maximum allowed by the D-Bus
+    /// specification, unless set otherwise through [`Builder::max_message_size`].
+    pub fn max_message_size(&self) -> usize {
+        self.inner.max_message_size
+    }
+
+    /// The entries of the message journal, oldest first.
+    ///
+    /// This is empty if the journal is disabled. See [`Connection::set_journal_capacity`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use zbus::connection::Builder;
+    ///
+    /// let conn = Builder::session()?.journal_capacity(32).build().await?;
+    ///
+    /// // Something went wrong, let's see what happened recently.
+    /// for entry in conn.journal() {
+    ///     eprintln!("{entry}");
+    /// }
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn journal(&self) -> Vec<journal::Entry> {
+        self.inner.journal.entries()
+    }
+
     /// The server's GUID.
     pub fn server_guid(&self) -> &OwnedGuid {
         &self.inner.server_guid
@@ -934,7 +1428,12 @@ impl Connection {
                                 builder = builder.destination(&**unique_name).expect("unique name");
                             }
                             let rule = builder.build();
-                            match conn.add_match(rule.into(), None).await {
+                            let res = conn.add_match(rule.into(), None).await;
+                            conn.inner
+                                .object_server_started
+                                .store(true, Ordering::Release);
+                            conn.inner.object_server_started_event.notify(usize::MAX);
+                            match res {
                                 Ok(stream) => stream,
                                 Err(e) => {
                                     // Very unlikely but can happen I guess if connection is closed.
@@ -1001,19 +1500,60 @@ impl Connection {
         });
     }
 
+    /// Wait for the object server task, if it was started, to be ready to receive method calls.
+    ///
+    /// Method calls received before that are not dispatched, which matters for peer-to-peer
+    /// connections, where calls can arrive right after an interface is registered.
+    pub(crate) async fn object_server_started(&self) {
+        if self.inner.object_server_dispatch_task.get().is_none() {
+            return;
+        }
+
+        while !self.inner.object_server_started.load(Ordering::Acquire) {
+            let listener = self.inner.object_server_started_event.listen();
+            if self.inner.object_server_started.load(Ordering::Acquire) {
+                break;
+            }
+            listener.await;
+        }
+    }
+
     pub(crate) async fn add_match(
         &self,
         rule: OwnedMatchRule,
         max_queued: Option<usize>,
     ) -> Result<Receiver<Result<Message>>> {
+        self.subscribe(rule, max_queued, false)
+            .await
+            .map(|(receiver, _)| receiver)
+    }
+
+    /// Same as `add_match` but also retains the last signal per interface, member and path
+    /// matching `rule`, and returns the ones retained so far.
+    pub(crate) async fn add_sticky_match(
+        &self,
+        rule: OwnedMatchRule,
+        max_queued: Option<usize>,
+    ) -> Result<(Receiver<Result<Message>>, Vec<Message>)> {
+        self.subscribe(rule, max_queued, true).await
+    }
+
+    async fn subscribe(
+        &self,
+        rule: OwnedMatchRule,
+        max_queued: Option<usize>,
+        sticky: bool,
+    ) -> Result<(Receiver<Result<Message>>, Vec<Message>)> {
         use std::collections::hash_map::Entry;
 
         if self.inner.msg_senders.lock().await.is_empty() {
             // This only happens if socket reader task has errored out.
-            return Err(Error::InputOutput(Arc::new(io::Error::new(
-                io::ErrorKind::BrokenPipe,
-                "Socket reader task has errored out",
-            ))));
+            return Err(self.inner.termination.error().cloned().unwrap_or_else(|| {
+                Error::InputOutput(Arc::new(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "Socket reader task has errored out",
+                )))
+            }));
         }
 
         let mut subscriptions = self.inner.subscriptions.lock().await;
@@ -1032,13 +1572,13 @@ impl Connection {
                         .await?;
                 }
                 e.insert((1, receiver.clone().deactivate()));
-                self.inner
-                    .msg_senders
-                    .lock()
-                    .await
-                    .insert(Some(rule), sender);
+                let mut msg_senders = self.inner.msg_senders.lock().await;
+                if sticky {
+                    self.inner.sticky_signals.lock().await.enable(rule.clone());
+                }
+                msg_senders.insert(Some(rule), sender);
 
-                Ok(receiver)
+                Ok((receiver, vec![]))
             }
             Entry::Occupied(mut e) => {
                 let (num_subscriptions, receiver) = e.get_mut();
@@ -1048,8 +1588,18 @@ impl Connection {
                         receiver.set_capacity(max_queued);
                     }
                 }
+                if !sticky {
+                    return Ok((receiver.activate_cloned(), vec![]));
+                }
+
+                // Hold the senders lock so that the socket reader can't retain or broadcast any
+                // signal between us taking the retained signals and activating the receiver.
+                let _msg_senders = self.inner.msg_senders.lock().await;
+                let mut sticky_signals = self.inner.sticky_signals.lock().await;
+                sticky_signals.enable(rule.clone());
+                let last = sticky_signals.last(&rule);
 
-                Ok(receiver.activate_cloned())
+                Ok((receiver.activate_cloned(), last))
             }
         }
     }
@@ -1074,12 +1624,10 @@ impl Connection {
                             .remove_match_rule(rule.clone())
                             .await?;
                     }
-                    e.remove();
-                    self.inner
-                        .msg_senders
-                        .lock()
-                        .await
-                        .remove(&Some(rule.into()));
+                    let rule = e.remove_entry().0;
+                    let mut msg_senders = self.inner.msg_senders.lock().await;
+                    self.inner.sticky_signals.lock().await.disable(&rule);
+                    msg_senders.remove(&Some(rule));
                 }
                 Ok(true)
             }
@@ -1098,6 +1646,7 @@ impl Connection {
         auth: Authenticated,
         #[allow(unused)] bus_connection: bool,
         executor: Executor<'static>,
+        max_message_size: usize,
     ) -> Result<Self> {
         #[cfg(unix)]
         let cap_unix_fd = auth.cap_unix_fd;
@@ -1115,19 +1664,10 @@ impl Connection {
         let (msg_sender, msg_receiver) = create_msg_broadcast_channel!(DEFAULT_MAX_QUEUED);
         let mut msg_senders = HashMap::new();
         msg_senders.insert(None, msg_sender);
-
-        // The special method return & error channel.
-        let (method_return_sender, method_return_receiver) =
-            create_msg_broadcast_channel!(DEFAULT_MAX_METHOD_RETURN_QUEUED);
-        let rule = MatchRule::builder()
-            .msg_type(Type::MethodReturn)
-            .build()
-            .into();
-        msg_senders.insert(Some(rule), method_return_sender.clone());
-        let rule = MatchRule::builder().msg_type(Type::Error).build().into();
-        msg_senders.insert(Some(rule), method_return_sender);
         let msg_senders = Arc::new(Mutex::new(msg_senders));
         let subscriptions = Mutex::new(HashMap::new());
+        let sticky_signals = Arc::new(Mutex::new(StickySignals::default()));
+        let pending_replies = Arc::new(std::sync::Mutex::new(PendingReplies::default()));
 
         let connection = Self {
             inner: Arc::new(ConnectionInner {
@@ -1140,13 +1680,22 @@ impl Connection {
                 bus_conn: bus_connection,
                 unique_name: OnceLock::new(),
                 subscriptions,
+                sticky_signals,
+                pending_replies,
+                termination: Arc::new(Termination::default()),
+                journal: Arc::new(Journal::default()),
+                strict_sender_validation: Arc::new(AtomicBool::new(false)),
+                method_timeout: std::sync::Mutex::new(None),
+                endian: std::sync::Mutex::new(NATIVE_ENDIAN),
+                max_message_size,
                 object_server: OnceLock::new(),
                 object_server_dispatch_task: OnceLock::new(),
+                object_server_started: AtomicBool::new(false),
+                object_server_started_event: Event::new(),
                 executor,
                 socket_reader_task: OnceLock::new(),
                 msg_senders,
                 msg_receiver,
-                method_return_receiver,
                 registered_names: Mutex::new(HashMap::new()),
             }),
         };
@@ -1168,6 +1717,22 @@ impl Connection {
         Builder::system()?.build().await
     }
 
+    /// The process-wide shared connection to the session/user message bus.
+    ///
+    /// The connection is created on the first call and the same one is returned afterwards, unless
+    /// it gets disconnected, in which case a new one is created. See [`BusManager`].
+    pub async fn session_shared() -> Result<Self> {
+        BusManager::global().session().await
+    }
+
+    /// The process-wide shared connection to the system-wide message bus.
+    ///
+    /// The connection is created on the first call and the same one is returned afterwards, unless
+    /// it gets disconnected, in which case a new one is created. See [`BusManager`].
+    pub async fn system_shared() -> Result<Self> {
+        BusManager::global().system().await
+    }
+
     /// Returns a listener, notified on various connection activity.
     ///
     /// This function is meant for the caller to implement idle or timeout on inactivity.
@@ -1197,13 +1762,43 @@ impl Connection {
     /// After this call, all reading and writing operations will fail.
     pub async fn close(self) -> Result<()> {
         self.inner.activity_event.notify(usize::MAX);
-        self.inner
+        let res = self
+            .inner
             .socket_write
             .lock()
             .await
             .close()
             .await
-            .map_err(Into::into)
+            .map_err(Into::into);
+        self.inner
+            .termination
+            .terminate(Error::InputOutput(Arc::new(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "Connection closed",
+            ))));
+
+        res
+    }
+
+    /// The state of the connection.
+    ///
+    /// Once the connection is terminated, e.g because the peer closed it, an I/O error occurred on
+    /// the socket, or [`Connection::close`] was called, it's [`State::Disconnected`] with the
+    /// error that terminated it. This state is shared by all the clones of the connection and is
+    /// final: from then on, sending messages and creating message streams fail with that same
+    /// error, and pending method calls fail with it as well.
+    pub fn state(&self) -> State {
+        match self.inner.termination.error() {
+            Some(error) => State::Disconnected(error.clone()),
+            None => State::Connected,
+        }
+    }
+
+    /// Wait for the connection to be terminated.
+    ///
+    /// Returns the error that terminated the connection. See [`Connection::state`] for details.
+    pub async fn disconnected(&self) -> Error {
+        self.inner.termination.wait().await
     }
 
     pub(crate) fn init_socket_reader(
@@ -1218,8 +1813,14 @@ impl Connection {
                 SocketReader::new(
                     socket_read,
                     inner.msg_senders.clone(),
+                    inner.sticky_signals.clone(),
+                    inner.pending_replies.clone(),
+                    inner.termination.clone(),
                     already_read,
                     inner.activity_event.clone(),
+                    inner.journal.clone(),
+                    inner.strict_sender_validation.clone(),
+                    inner.max_message_size,
                 )
                 .spawn(&inner.executor),
             )
@@ -1342,6 +1943,122 @@ mod tests {
         let name_has_owner = dbus.name_has_owner(name.try_into().unwrap()).await.unwrap();
         assert!(!name_has_owner);
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn strict_sender_validation() {
+        crate::utils::block_on(test_strict_sender_validation()).unwrap();
+    }
+
+    async fn test_strict_sender_validation() -> Result<()> {
+        struct Strict(u32);
+
+        #[crate::interface(name = "org.zbus.Strict")]
+        impl Strict {
+            fn ping(&self) -> u32 {
+                self.0
+            }
+        }
+        let name = "org.zbus.StrictSenderValidation";
+        let service = Builder::session()?
+            .serve_at("/org/zbus/Strict", Strict(42))?
+            .build()
+            .await?;
+        service
+            .request_name_with_flags(name, RequestNameFlags::AllowReplacement.into())
+            .await?;
+
+        let conn = Builder::session()?
+            .strict_sender_validation(true)
+            .build()
+            .await?;
+        assert!(conn.strict_sender_validation());
+
+        // Replies from the owner of a well-known name.
+        let reply = conn
+            .call_method(
+                Some(name),
+                "/org/zbus/Strict",
+                Some("org.zbus.Strict"),
+                "Ping",
+                &(),
+            )
+            .await?;
+        assert_eq!(reply.body().deserialize::<u32>()?, 42);
+
+        // Replies from the new owner, once the name changed hands.
+        let new_service = Builder::session()?
+            .serve_at("/org/zbus/Strict", Strict(43))?
+            .build()
+            .await?;
+        new_service
+            .request_name_with_flags(name, RequestNameFlags::ReplaceExisting.into())
+            .await?;
+        let reply = conn
+            .call_method(
+                Some(name),
+                "/org/zbus/Strict",
+                Some("org.zbus.Strict"),
+                "Ping",
+                &(),
+            )
+            .await?;
+        assert_eq!(reply.body().deserialize::<u32>()?, 43);
+
+        // Replies from the bus itself.
+        let dbus = DBusProxy::builder(&conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        assert!(dbus.name_has_owner(name.try_into()?).await?);
+
+        // Errors from the bus for names without an owner.
+        let res = conn
+            .call_method(
+                Some("org.zbus.StrictSenderValidation.NoOwner"),
+                "/org/zbus/Strict",
+                Some("org.zbus.Strict"),
+                "Ping",
+                &(),
+            )
+            .await;
+        assert!(matches!(res, Err(Error::MethodError(_, _, _))));
+
+        // Concurrent first calls subscribe to the owner changes of the name only once.
+        let conn = Builder::session()?
+            .strict_sender_validation(true)
+            .build()
+            .await?;
+        let match_rules = || async {
+            let reply = service
+                .call_method(
+                    Some("org.freedesktop.DBus"),
+                    "/org/freedesktop/DBus",
+                    Some("org.freedesktop.DBus.Debug.Stats"),
+                    "GetConnectionStats",
+                    &(conn.unique_name().unwrap()),
+                )
+                .await?;
+            let stats: HashMap<String, zvariant::OwnedValue> = reply.body().deserialize()?;
+            u32::try_from(&stats["MatchRules"]).map_err(Error::from)
+        };
+        let before = match_rules().await?;
+        let ping = || {
+            conn.call_method(
+                Some(name),
+                "/org/zbus/Strict",
+                Some("org.zbus.Strict"),
+                "Ping",
+                &(),
+            )
+        };
+        let (first, second) = futures_util::try_join!(ping(), ping())?;
+        assert_eq!(first.body().deserialize::<u32>()?, 43);
+        assert_eq!(second.body().deserialize::<u32>()?, 43);
+        assert_eq!(match_rules().await?, before + 1);
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "p2p")]
@@ -1352,7 +2069,7 @@ mod p2p_tests {
     use test_log::test;
     use zvariant::{Endian, NATIVE_ENDIAN};
 
-    use crate::{AuthMechanism, Guid};
+    use crate::{AuthMechanism, Guid, MessageStream};
 
     use super::*;
 
@@ -1531,6 +2248,52 @@ mod p2p_tests {
         )
     }
 
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn max_message_size() {
+        crate::utils::block_on(test_max_message_size()).unwrap();
+    }
+
+    #[cfg(unix)]
+    async fn test_max_message_size() -> Result<()> {
+        #[cfg(not(feature = "tokio"))]
+        use std::os::unix::net::UnixStream;
+        #[cfg(feature = "tokio")]
+        use tokio::net::UnixStream;
+
+        let guid = Guid::generate();
+        let (p0, p1) = UnixStream::pair().unwrap();
+        let (client, server) = futures_util::try_join!(
+            Builder::unix_stream(p1).p2p().build(),
+            Builder::unix_stream(p0)
+                .server(guid)
+                .unwrap()
+                .p2p()
+                .max_message_size(1024)
+                .build(),
+        )?;
+        assert_eq!(server.max_message_size(), 1024);
+        assert_eq!(client.max_message_size(), 128 * 1024 * 1024);
+        let mut stream = MessageStream::from(&server);
+
+        let msg = Message::signal("/", "org.zbus.p2p", "Small")?.build(&vec![0u8; 100])?;
+        client.send(&msg).await?;
+        let received = stream.try_next().await?.unwrap();
+        assert_eq!(received.header().member().unwrap(), "Small");
+
+        // The message is rejected from its header and the connection terminated.
+        let msg = Message::signal("/", "org.zbus.p2p", "Big")?.build(&vec![0u8; 2048])?;
+        client.send(&msg).await?;
+        assert!(matches!(stream.try_next().await, Err(Error::ExcessData)));
+        assert!(matches!(
+            server.state(),
+            State::Disconnected(Error::ExcessData)
+        ));
+
+        Ok(())
+    }
+
     // Compile-test only since we don't have a VM setup to run this with/in.
     #[cfg(any(
         all(feature = "vsock", not(feature = "tokio")),
@@ -1682,6 +2445,381 @@ mod p2p_tests {
         test_p2p(server1, client1, server2, client2).await
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn recv_seq_and_time() {
+        crate::utils::block_on(test_recv_seq_and_time()).unwrap();
+    }
+
+    async fn test_recv_seq_and_time() -> Result<()> {
+        let (sender, receiver) = create_channel_pair().await;
+        let mut stream = MessageStream::from(&receiver);
+
+        for _ in 0..2 {
+            let msg = Message::signal("/org/zbus/p2p", "org.zbus.p2p", "Ping")?.build(&())?;
+            assert_eq!(msg.recv_seq(), 0);
+            assert!(msg.recv_time().is_none());
+            sender.send(&msg).await?;
+        }
+
+        let first = stream.try_next().await?.unwrap();
+        let second = stream.try_next().await?.unwrap();
+        assert_eq!(second.recv_seq(), first.recv_seq() + 1);
+        assert!(second.recv_position() > first.recv_position());
+        assert!(second.recv_time().unwrap() >= first.recv_time().unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn sticky_signals() {
+        crate::utils::block_on(test_sticky_signals()).unwrap();
+    }
+
+    async fn test_sticky_signals() -> Result<()> {
+        let (sender, receiver) = create_channel_pair().await;
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .interface("org.zbus.p2p")?
+            .member("State")?
+            .build();
+        let mut first = MessageStream::for_sticky_match_rule(rule.clone(), &receiver, None).await?;
+
+        for (path, state) in [("/a", 1u32), ("/b", 2), ("/a", 3)] {
+            let msg = Message::signal(path, "org.zbus.p2p", "State")?.build(&state)?;
+            sender.send(&msg).await?;
+        }
+        for _ in 0..3 {
+            first.try_next().await?.unwrap();
+        }
+
+        // Only the last signal of each path is replayed, in the order they were received.
+        let mut second = MessageStream::for_sticky_match_rule(rule, &receiver, None).await?;
+        let msg = second.try_next().await?.unwrap();
+        assert_eq!(msg.header().path().unwrap(), "/b");
+        assert_eq!(msg.body().deserialize::<u32>()?, 2);
+        let msg = second.try_next().await?.unwrap();
+        assert_eq!(msg.header().path().unwrap(), "/a");
+        assert_eq!(msg.body().deserialize::<u32>()?, 3);
+
+        // New signals follow.
+        let msg = Message::signal("/b", "org.zbus.p2p", "State")?.build(&4u32)?;
+        sender.send(&msg).await?;
+        let msg = second.try_next().await?.unwrap();
+        assert_eq!(msg.body().deserialize::<u32>()?, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn concurrent_method_calls() {
+        crate::utils::block_on(test_concurrent_method_calls()).unwrap();
+    }
+
+    async fn test_concurrent_method_calls() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let mut calls = MessageStream::from(&service);
+
+        let call = |i: u32| {
+            let client = client.clone();
+            async move {
+                let reply = client
+                    .call_method(None::<()>, "/", Some("org.zbus.p2p"), "Echo", &i)
+                    .await?;
+
+                reply.body().deserialize::<u32>()
+            }
+        };
+        let serve = async {
+            let mut received = vec![];
+            while received.len() < 3 {
+                let msg = calls.try_next().await?.unwrap();
+                if msg.message_type() == Type::MethodCall {
+                    received.push(msg);
+                }
+            }
+            // Each reply must reach its own call, whatever the order.
+            for msg in received.iter().rev() {
+                service
+                    .reply(msg, &msg.body().deserialize::<u32>()?)
+                    .await?;
+            }
+
+            Ok::<_, Error>(())
+        };
+        let (first, second, third, ()) = futures_util::try_join!(call(1), call(2), call(3), serve)?;
+        assert_eq!((first, second, third), (1, 2, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn disconnection() {
+        crate::utils::block_on(test_disconnection()).unwrap();
+    }
+
+    async fn test_disconnection() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let clone = client.clone();
+        assert!(matches!(client.state(), State::Connected));
+        let mut stream = MessageStream::from(&client);
+
+        let call = client.call_method(None::<()>, "/", Some("org.zbus.p2p"), "Echo", &());
+        let (res, ()) = futures_util::join!(call, async { service.close().await.unwrap() });
+        let Err(Error::InputOutput(e)) = res else {
+            panic!("unexpected reply: {res:?}");
+        };
+        let kind = e.kind();
+
+        // All handles observe the same terminal error.
+        assert!(matches!(clone.disconnected().await, Error::InputOutput(e) if e.kind() == kind));
+        assert!(matches!(
+            client.state(),
+            State::Disconnected(Error::InputOutput(e)) if e.kind() == kind
+        ));
+        assert!(matches!(
+            stream.try_next().await,
+            Err(Error::InputOutput(e)) if e.kind() == kind
+        ));
+        assert!(stream.try_next().await?.is_none());
+        let msg = Message::signal("/", "org.zbus.p2p", "Ping")?.build(&())?;
+        assert!(matches!(
+            clone.send(&msg).await,
+            Err(Error::InputOutput(e)) if e.kind() == kind
+        ));
+        assert!(matches!(
+            MessageStream::for_match_rule(MatchRule::signal().build(), &client, None).await,
+            Err(Error::InputOutput(e)) if e.kind() == kind
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn connection_endian() {
+        crate::utils::block_on(test_connection_endian()).unwrap();
+    }
+
+    async fn test_connection_endian() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let endian = match NATIVE_ENDIAN {
+            Endian::Little => Endian::Big,
+            Endian::Big => Endian::Little,
+        };
+        assert_eq!(client.endian(), NATIVE_ENDIAN);
+        client.set_endian(endian);
+        service.set_endian(endian);
+        let mut calls = MessageStream::from(&service);
+
+        let call = client.call_method(None::<()>, "/", Some("org.zbus.p2p"), "Echo", &1u64);
+        let serve = async {
+            loop {
+                let msg = calls.try_next().await?.unwrap();
+                if msg.message_type() == Type::MethodCall {
+                    assert_eq!(Endian::from(msg.primary_header().endian_sig()), endian);
+                    service
+                        .emit_signal(None::<()>, "/", "org.zbus.p2p", "Echoed", &())
+                        .await?;
+                    service
+                        .reply(&msg, &msg.body().deserialize::<u64>()?)
+                        .await?;
+
+                    return Ok::<_, Error>(());
+                }
+            }
+        };
+        let mut stream = MessageStream::from(&client);
+        let (reply, ()) = futures_util::try_join!(call, serve)?;
+        assert_eq!(Endian::from(reply.primary_header().endian_sig()), endian);
+        assert_eq!(reply.body().deserialize::<u64>()?, 1);
+        let signal = loop {
+            let msg = stream.try_next().await?.unwrap();
+            if msg.message_type() == Type::Signal {
+                break msg;
+            }
+        };
+        assert_eq!(Endian::from(signal.primary_header().endian_sig()), endian);
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn method_timeout() {
+        crate::utils::block_on(test_method_timeout()).unwrap();
+    }
+
+    async fn test_method_timeout() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let mut calls = MessageStream::from(&service);
+        let timeout = Duration::from_millis(50);
+
+        // The service doesn't reply in time.
+        let res = client
+            .call_method_with_timeout(
+                None::<()>,
+                "/",
+                Some("org.zbus.p2p"),
+                "Echo",
+                &1u32,
+                timeout,
+            )
+            .await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+        let late_call = loop {
+            let msg = calls.try_next().await?.unwrap();
+            if msg.message_type() == Type::MethodCall {
+                break msg;
+            }
+        };
+
+        // The late reply must be discarded, not taken as the reply to the next call.
+        client.set_method_timeout(Some(timeout));
+        assert_eq!(client.method_timeout(), Some(timeout));
+        let call = client.call_method(None::<()>, "/", Some("org.zbus.p2p"), "Echo", &2u32);
+        let serve = async {
+            service.reply(&late_call, &1u32).await?;
+            loop {
+                let msg = calls.try_next().await?.unwrap();
+                if msg.message_type() == Type::MethodCall {
+                    service
+                        .reply(&msg, &msg.body().deserialize::<u32>()?)
+                        .await?;
+
+                    return Ok::<_, Error>(());
+                }
+            }
+        };
+        let (reply, ()) = futures_util::try_join!(call, serve)?;
+        assert_eq!(reply.body().deserialize::<u32>()?, 2);
+
+        // And the default timeout applies to calls without an explicit one.
+        let res = client
+            .call_method(None::<()>, "/", Some("org.zbus.p2p"), "Echo", &3u32)
+            .await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn method_call_noreply() {
+        crate::utils::block_on(test_method_call_noreply()).unwrap();
+    }
+
+    async fn test_method_call_noreply() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let mut calls = MessageStream::from(&service);
+
+        // Returns without a reply from the service.
+        for i in 0..3u32 {
+            client
+                .call_method_noreply(None::<()>, "/", Some("org.zbus.p2p"), "Notify", &i)
+                .await?;
+        }
+        for i in 0..3u32 {
+            let msg = calls.try_next().await?.unwrap();
+            assert_eq!(msg.message_type(), Type::MethodCall);
+            assert!(msg
+                .primary_header()
+                .flags()
+                .contains(Flags::NoReplyExpected));
+            assert_eq!(msg.body().deserialize::<u32>()?, i);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn properties() {
+        crate::utils::block_on(test_properties()).unwrap();
+    }
+
+    async fn test_properties() -> Result<()> {
+        struct Counter(u32);
+
+        #[crate::interface(name = "org.zbus.Counter")]
+        impl Counter {
+            #[zbus(property)]
+            fn count(&self) -> u32 {
+                self.0
+            }
+
+            #[zbus(property)]
+            fn set_count(&mut self, count: u32) {
+                self.0 = count;
+            }
+        }
+
+        let (a, b) = socket::Channel::pair();
+        let guid = crate::Guid::generate();
+        // Serve the object before the connection is up, so the first call can't be missed.
+        let (_service, client) = futures_util::try_join!(
+            Builder::authenticated_socket(a, guid.clone())?
+                .p2p()
+                .serve_at("/counter", Counter(1))?
+                .build(),
+            Builder::authenticated_socket(b, guid)?.p2p().build(),
+        )?;
+
+        let count: u32 = client
+            .get_property(None::<()>, "/counter", "org.zbus.Counter", "Count")
+            .await?;
+        assert_eq!(count, 1);
+        client
+            .set_property(None::<()>, "/counter", "org.zbus.Counter", "Count", 42u32)
+            .await?;
+        let count: u32 = client
+            .get_property(None::<()>, "/counter", "org.zbus.Counter", "Count")
+            .await?;
+        assert_eq!(count, 42);
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn message_stream_views() {
+        crate::utils::block_on(test_message_stream_views()).unwrap();
+    }
+
+    async fn test_message_stream_views() -> Result<()> {
+        let (client, service) = create_channel_pair().await;
+        let stream = MessageStream::from(&service);
+        let mut calls = stream.method_calls();
+        let mut signals = stream.signals();
+        let mut pings = stream.filter_rule("member='Ping'")?.method_calls();
+        drop(stream);
+
+        client
+            .emit_signal(None::<()>, "/", "org.zbus.p2p", "Changed", &())
+            .await?;
+        for method in ["Reset", "Ping"] {
+            client
+                .call_method_noreply(None::<()>, "/", Some("org.zbus.p2p"), method, &())
+                .await?;
+        }
+
+        // Each view gets its share of the same messages.
+        let signal = signals.try_next().await?.unwrap();
+        assert_eq!(signal.header().member().unwrap().as_str(), "Changed");
+        for method in ["Reset", "Ping"] {
+            let call = calls.try_next().await?.unwrap();
+            assert_eq!(call.header().member().unwrap().as_str(), method);
+        }
+        let ping = pings.try_next().await?.unwrap();
+        assert_eq!(ping.message_type(), Type::MethodCall);
+        assert_eq!(ping.header().member().unwrap().as_str(), "Ping");
+
+        Ok(())
+    }
+
     async fn create_channel_pair() -> (Connection, Connection) {
         let (a, b) = socket::Channel::pair();
 
@@ -0,0 +1,271 @@
+use std::{collections::HashMap, num::NonZeroU32, sync::Arc};
+
+use async_broadcast::{broadcast, Receiver, Sender};
+use tracing::{trace, warn};
+use zbus_names::{
+    BusName, OwnedBusName, OwnedUniqueName, OwnedWellKnownName, UniqueName, WellKnownName,
+};
+
+use super::BUS_NAME;
+use crate::{
+    async_lock::Mutex,
+    message::{Sequence, Type},
+    Error, Message, Result,
+};
+
+/// The method calls awaiting a reply, keyed by their serial number.
+///
+/// The socket reader routes each reply directly to the call it's for, instead of all pending calls
+/// looking at every reply.
+#[derive(Debug, Default)]
+pub(crate) struct PendingReplies {
+    calls: HashMap<NonZeroU32, PendingReply>,
+    // The owners of the well-known names replies are expected from, kept up to date from the
+    // `NameOwnerChanged` signals of the bus.
+    name_owners: HashMap<OwnedWellKnownName, NameOwner>,
+    // Set once the socket reader stopped, after which no reply will ever be routed.
+    closed: bool,
+}
+
+#[derive(Debug)]
+struct PendingReply {
+    sender: Sender<Result<Message>>,
+    // The only peer, besides the bus, allowed to reply, if sender validation is enabled.
+    reply_sender: Option<OwnedBusName>,
+    // If `reply_sender` is a well-known name, its owners since the call was registered.
+    owners: Vec<OwnedUniqueName>,
+}
+
+#[derive(Debug, Default)]
+struct NameOwner {
+    owner: Option<OwnedUniqueName>,
+    // The position of the message the owner was last learned from.
+    position: Sequence,
+    resolved: bool,
+    // Held while subscribing to the signals, set once subscribed, so that's only done once.
+    subscription: Arc<Mutex<bool>>,
+}
+
+impl PendingReplies {
+    /// Register a method call with the given `serial`, returning the receiver of its reply.
+    ///
+    /// If the connection is closed, the receiver is closed as well.
+    pub(crate) fn register(
+        &mut self,
+        serial: NonZeroU32,
+        reply_sender: Option<OwnedBusName>,
+    ) -> Receiver<Result<Message>> {
+        let (sender, receiver) = broadcast(1);
+        if !self.closed {
+            let owners = match reply_sender.as_deref() {
+                Some(BusName::WellKnown(name)) => self
+                    .name_owners
+                    .get(name.as_str())
+                    .and_then(|o| o.owner.clone())
+                    .into_iter()
+                    .collect(),
+                _ => vec![],
+            };
+            self.calls.insert(
+                serial,
+                PendingReply {
+                    sender,
+                    reply_sender,
+                    owners,
+                },
+            );
+        }
+
+        receiver
+    }
+
+    /// Forget about the method call with the given `serial`.
+    pub(crate) fn unregister(&mut self, serial: NonZeroU32) {
+        self.calls.remove(&serial);
+    }
+
+    /// Whether the owner of `name` is known and kept track of.
+    pub(crate) fn is_name_owner_tracked(&self, name: &WellKnownName<'_>) -> bool {
+        self.name_owners
+            .get(name.as_str())
+            .map(|o| o.resolved)
+            .unwrap_or(false)
+    }
+
+    /// Start keeping track of the owner of `name` from the `NameOwnerChanged` signals.
+    ///
+    /// This must be called before subscribing to the signals, and followed by
+    /// [`PendingReplies::set_name_owner`] with the current owner. The returned slot is to be held
+    /// while subscribing, and set once subscribed.
+    pub(crate) fn track_name_owner(&mut self, name: &WellKnownName<'_>) -> Arc<Mutex<bool>> {
+        self.name_owners
+            .entry(name.to_owned().into())
+            .or_default()
+            .subscription
+            .clone()
+    }
+
+    /// Set the owner of `name`, as learned from the message at `position`.
+    ///
+    /// This is ignored if a more recent `NameOwnerChanged` signal was already routed.
+    pub(crate) fn set_name_owner(
+        &mut self,
+        name: &WellKnownName<'_>,
+        owner: Option<UniqueName<'_>>,
+        position: Sequence,
+    ) {
+        let name_owner = self.name_owners.entry(name.to_owned().into()).or_default();
+        name_owner.resolved = true;
+        if position >= name_owner.position {
+            name_owner.owner = owner.map(|o| o.to_owned().into());
+            name_owner.position = position;
+        }
+    }
+
+    /// Route `msg` to the method call it's a reply to, if any.
+    pub(crate) fn route(&mut self, msg: &Message) {
+        match msg.message_type() {
+            Type::MethodReturn | Type::Error => (),
+            Type::Signal => return self.update_name_owner(msg),
+            Type::MethodCall => return,
+        }
+        let header = msg.header();
+        let Some(serial) = header.reply_serial() else {
+            return;
+        };
+        let Some(call) = self.calls.get(&serial) else {
+            return;
+        };
+
+        if let Some(reply_sender) = &call.reply_sender {
+            let sender = header.sender().map(|s| s.as_str());
+            let is_owner = call.owners.iter().any(|o| Some(o.as_str()) == sender);
+            if sender != Some(reply_sender.as_str()) && sender != Some(BUS_NAME) && !is_owner {
+                warn!(
+                    "Ignoring reply from `{:?}` to a method call to `{}`",
+                    sender, reply_sender,
+                );
+
+                return;
+            }
+        }
+
+        if let Some(call) = self.calls.remove(&serial) {
+            if let Err(e) = call.sender.try_broadcast(Ok(msg.clone())) {
+                trace!("Error routing reply to method call {serial}: {:?}", e);
+            }
+        }
+    }
+
+    /// Fail all the pending method calls with `error` and close all subsequent ones.
+    pub(crate) fn close(&mut self, error: &Error) {
+        self.closed = true;
+        for (_, call) in self.calls.drain() {
+            let _ = call.sender.try_broadcast(Err(error.clone()));
+        }
+    }
+
+    // Keep track of the new owner of a name, if `msg` is a `NameOwnerChanged` signal from the bus.
+    fn update_name_owner(&mut self, msg: &Message) {
+        let header = msg.header();
+        if header.sender().map(|s| s.as_str()) != Some(BUS_NAME)
+            || header.interface().map(|i| i.as_str()) != Some(BUS_NAME)
+            || header.member().map(|m| m.as_str()) != Some("NameOwnerChanged")
+        {
+            return;
+        }
+        let body = msg.body();
+        let Ok((name, _, new_owner)) = body.deserialize::<(BusName<'_>, &str, &str)>() else {
+            return;
+        };
+        let BusName::WellKnown(name) = name else {
+            return;
+        };
+        let Some(name_owner) = self.name_owners.get_mut(name.as_str()) else {
+            return;
+        };
+        let new_owner = UniqueName::try_from(new_owner)
+            .ok()
+            .map(OwnedUniqueName::from);
+        name_owner.owner = new_owner.clone();
+        name_owner.position = msg.recv_position();
+
+        // Replies are accepted from any peer that owned the name while the call was pending.
+        let Some(new_owner) = new_owner else {
+            return;
+        };
+        for call in self.calls.values_mut() {
+            if let Some(BusName::WellKnown(call_name)) = call.reply_sender.as_deref() {
+                if *call_name == name && !call.owners.contains(&new_owner) {
+                    call.owners.push(new_owner.clone());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn name_owner_changed(name: &str, old: &str, new: &str) -> Message {
+        Message::signal("/org/freedesktop/DBus", BUS_NAME, "NameOwnerChanged")
+            .unwrap()
+            .sender(BUS_NAME)
+            .unwrap()
+            .build(&(name, old, new))
+            .unwrap()
+    }
+
+    fn reply_from(call: &Message, sender: &str) -> Message {
+        Message::method_reply(call)
+            .unwrap()
+            .sender(sender)
+            .unwrap()
+            .build(&())
+            .unwrap()
+    }
+
+    #[test]
+    fn owner_changes_during_call() {
+        let name = WellKnownName::from_static_str("org.zbus.Owned").unwrap();
+        let mut pending = PendingReplies::default();
+        pending.track_name_owner(&name);
+        pending.set_name_owner(
+            &name,
+            Some(UniqueName::from_static_str(":1.1").unwrap()),
+            Sequence::default(),
+        );
+        assert!(pending.is_name_owner_tracked(&name));
+
+        let call = Message::method("/org/zbus/Owned", "Ping")
+            .unwrap()
+            .destination("org.zbus.Owned")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let serial = call.primary_header().serial_num();
+        let mut receiver = pending.register(serial, Some(BusName::from(name.clone()).into()));
+
+        // The name changes hands and the call is delivered to the new owner.
+        pending.route(&name_owner_changed("org.zbus.Owned", ":1.1", ":1.2"));
+        pending.route(&reply_from(&call, ":1.3"));
+        assert!(receiver.try_recv().is_err());
+        pending.route(&reply_from(&call, ":1.2"));
+        let reply = receiver.try_recv().unwrap().unwrap();
+        assert_eq!(reply.header().sender().unwrap(), ":1.2");
+
+        // Later calls expect the new owner right away.
+        let call = Message::method("/org/zbus/Owned", "Ping")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let serial = call.primary_header().serial_num();
+        let mut receiver = pending.register(serial, Some(BusName::from(name).into()));
+        pending.route(&reply_from(&call, ":1.1"));
+        assert!(receiver.try_recv().is_err());
+        pending.route(&reply_from(&call, ":1.2"));
+        assert!(receiver.try_recv().unwrap().is_ok());
+    }
+}
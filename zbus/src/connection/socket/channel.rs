@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, time::Instant};
 
 use async_broadcast::{broadcast, Receiver, Sender};
 
@@ -60,12 +60,15 @@ pub struct Reader(Receiver<Message>);
 impl super::ReadHalf for Reader {
     async fn receive_message(
         &mut self,
-        _seq: u64,
+        seq: u64,
         _already_received_bytes: &mut Vec<u8>,
     ) -> crate::Result<Message> {
-        self.0.recv().await.map_err(|e| {
+        let msg = self.0.recv().await.map_err(|e| {
             crate::Error::InputOutput(io::Error::new(io::ErrorKind::BrokenPipe, e).into())
-        })
+        })?;
+
+        // The message was stamped by the sending end, if at all, so re-stamp it as received here.
+        Message::from_raw_parts(msg.data().clone(), seq, Some(Instant::now()))
     }
 
     async fn peer_credentials(&mut self) -> io::Result<ConnectionCredentials> {
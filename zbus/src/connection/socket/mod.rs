@@ -12,18 +12,18 @@ mod vsock;
 
 #[cfg(not(feature = "tokio"))]
 use async_io::Async;
-use std::io;
 #[cfg(not(feature = "tokio"))]
 use std::sync::Arc;
+use std::{io, time::Instant};
 use tracing::trace;
 
 use crate::{
     fdo::ConnectionCredentials,
     message::{
         header::{MAX_MESSAGE_SIZE, MIN_MESSAGE_SIZE},
-        PrimaryHeader,
+        take_buffer, PrimaryHeader,
     },
-    padding_for_8_bytes, Message,
+    Message,
 };
 #[cfg(unix)]
 use std::os::fd::{AsFd, BorrowedFd, OwnedFd};
@@ -88,103 +88,31 @@ pub trait ReadHalf: std::fmt::Debug + Send + Sync + 'static {
         seq: u64,
         already_received_bytes: &mut Vec<u8>,
     ) -> crate::Result<Message> {
-        #[cfg(unix)]
-        let mut fds = vec![];
-        let mut bytes = if already_received_bytes.len() < MIN_MESSAGE_SIZE {
-            let mut bytes = vec![];
-            if !already_received_bytes.is_empty() {
-                std::mem::swap(already_received_bytes, &mut bytes);
-            }
-            let mut pos = bytes.len();
-            bytes.resize(MIN_MESSAGE_SIZE, 0);
-            // We don't have enough data to make a proper message header yet.
-            // Some partial read may be in raw_in_buffer, so we try to complete it
-            // until we have MIN_MESSAGE_SIZE bytes
-            //
-            // Given that MIN_MESSAGE_SIZE is 16, this codepath is actually extremely unlikely
-            // to be taken more than once
-            while pos < MIN_MESSAGE_SIZE {
-                let res = self.recvmsg(&mut bytes[pos..]).await?;
-                let len = {
-                    #[cfg(unix)]
-                    {
-                        fds.extend(res.1);
-                        res.0
-                    }
-                    #[cfg(not(unix))]
-                    {
-                        res
-                    }
-                };
-                pos += len;
-                if len == 0 {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        "failed to receive message",
-                    )
-                    .into());
-                }
-            }
-
-            bytes
-        } else {
-            already_received_bytes.drain(..MIN_MESSAGE_SIZE).collect()
-        };
+        receive_with_recvmsg(self, seq, already_received_bytes, MAX_MESSAGE_SIZE).await
+    }
 
-        let (primary_header, fields_len) = PrimaryHeader::read(&bytes)?;
-        let header_len = MIN_MESSAGE_SIZE + fields_len as usize;
-        let body_padding = padding_for_8_bytes(header_len);
-        let body_len = primary_header.body_len() as usize;
-        let total_len = header_len + body_padding + body_len;
-        if total_len > MAX_MESSAGE_SIZE {
+    /// Receive a message on the socket, no larger than `max_size` bytes.
+    ///
+    /// This is what zbus calls to receive messages, with the limit set through
+    /// [`crate::connection::Builder::max_message_size`]. Larger messages must be rejected with
+    /// [`crate::Error::ExcessData`], ideally as soon as their primary header is received, so a
+    /// peer can't make zbus allocate more memory than that.
+    ///
+    /// The default implementation calls [`ReadHalf::receive_message`] and then checks the size of
+    /// the message. The implementations provided by zbus check the size before receiving the
+    /// rest of the message.
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<Message> {
+        let msg = self.receive_message(seq, already_received_bytes).await?;
+        if msg.data().len() > max_size {
             return Err(crate::Error::ExcessData);
         }
 
-        // By this point we have a full primary header, so we know the exact length of the complete
-        // message.
-        if !already_received_bytes.is_empty() {
-            // still have some bytes buffered.
-            let pending = total_len - bytes.len();
-            let to_take = std::cmp::min(pending, already_received_bytes.len());
-            bytes.extend(already_received_bytes.drain(..to_take));
-        }
-        let mut pos = bytes.len();
-        bytes.resize(total_len, 0);
-
-        // Read the rest, if any
-        while pos < total_len {
-            let res = self.recvmsg(&mut bytes[pos..]).await?;
-            let read = {
-                #[cfg(unix)]
-                {
-                    fds.extend(res.1);
-                    res.0
-                }
-                #[cfg(not(unix))]
-                {
-                    res
-                }
-            };
-            pos += read;
-            if read == 0 {
-                return Err(crate::Error::InputOutput(
-                    std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        "failed to receive message",
-                    )
-                    .into(),
-                ));
-            }
-        }
-
-        // If we reach here, the message is complete; return it
-        let endian = Endian::from(primary_header.endian_sig());
-        let ctxt = Context::new_dbus(endian, 0);
-        #[cfg(unix)]
-        let bytes = serialized::Data::new_fds(bytes, ctxt, fds);
-        #[cfg(not(unix))]
-        let bytes = serialized::Data::new(bytes, ctxt);
-        Message::from_raw_parts(bytes, seq)
+        Ok(msg)
     }
 
     /// Attempt to receive bytes from the socket.
@@ -227,21 +155,29 @@ pub trait WriteHalf: std::fmt::Debug + Send + Sync + 'static {
         let serial = msg.primary_header().serial_num();
 
         trace!("Sending message: {:?}", msg);
-        let mut pos = 0;
-        while pos < data.len() {
-            #[cfg(unix)]
-            let fds = if pos == 0 {
-                data.fds().iter().map(|f| f.as_fd()).collect()
-            } else {
-                vec![]
-            };
-            pos += self
+        #[cfg(unix)]
+        let mut fds: Vec<_> = data.fds().iter().map(|f| f.as_fd()).collect();
+        let mut written = 0;
+        while written < data.len() {
+            let n = self
                 .sendmsg(
-                    &data[pos..],
+                    &data[written..],
                     #[cfg(unix)]
                     &fds,
                 )
                 .await?;
+            if n == 0 {
+                return Err(crate::Error::InputOutput(
+                    io::Error::new(io::ErrorKind::WriteZero, "failed to send message").into(),
+                ));
+            }
+            written += n;
+            trace!("Sent {written}/{} bytes of message", data.len());
+
+            // The FDs go out with the first chunk written and hence must not be sent again, even
+            // if the write was partial.
+            #[cfg(unix)]
+            fds.clear();
         }
         trace!("Sent message with serial: {}", serial);
 
@@ -297,6 +233,99 @@ pub trait WriteHalf: std::fmt::Debug + Send + Sync + 'static {
     }
 }
 
+/// Receive a message using [`ReadHalf::recvmsg`], rejecting it if it's larger than `max_size`.
+pub(super) async fn receive_with_recvmsg<R: ReadHalf + ?Sized>(
+    socket: &mut R,
+    seq: u64,
+    already_received_bytes: &mut Vec<u8>,
+    max_size: usize,
+) -> crate::Result<Message> {
+    #[cfg(unix)]
+    let mut fds = vec![];
+    // The primary header is received on the stack, so that the buffer of the whole message can be
+    // allocated once, with the exact size advertised in it.
+    let mut primary = [0u8; MIN_MESSAGE_SIZE];
+    let mut pos = already_received_bytes.len().min(MIN_MESSAGE_SIZE);
+    primary[..pos].copy_from_slice(&already_received_bytes[..pos]);
+    already_received_bytes.drain(..pos);
+    // Given that MIN_MESSAGE_SIZE is 16, this loop is actually extremely unlikely to run more
+    // than once.
+    while pos < MIN_MESSAGE_SIZE {
+        let res = socket.recvmsg(&mut primary[pos..]).await?;
+        let len = {
+            #[cfg(unix)]
+            {
+                fds.extend(res.1);
+                res.0
+            }
+            #[cfg(not(unix))]
+            {
+                res
+            }
+        };
+        pos += len;
+        if len == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "failed to receive message",
+            )
+            .into());
+        }
+    }
+
+    let (primary_header, _) = PrimaryHeader::read(&primary)?;
+    // Check the advertised length before allocating anything for it.
+    let total_len = Message::frame_len(&primary)?;
+    if total_len > max_size {
+        return Err(crate::Error::ExcessData);
+    }
+
+    let mut bytes = take_buffer(total_len);
+    bytes.extend_from_slice(&primary);
+    if !already_received_bytes.is_empty() {
+        // still have some bytes buffered.
+        let to_take = std::cmp::min(total_len - MIN_MESSAGE_SIZE, already_received_bytes.len());
+        bytes.extend(already_received_bytes.drain(..to_take));
+    }
+    let mut pos = bytes.len();
+    bytes.resize(total_len, 0);
+
+    // Read the rest, if any
+    while pos < total_len {
+        let res = socket.recvmsg(&mut bytes[pos..]).await?;
+        let read = {
+            #[cfg(unix)]
+            {
+                fds.extend(res.1);
+                res.0
+            }
+            #[cfg(not(unix))]
+            {
+                res
+            }
+        };
+        pos += read;
+        if read == 0 {
+            return Err(crate::Error::InputOutput(
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "failed to receive message",
+                )
+                .into(),
+            ));
+        }
+    }
+
+    // If we reach here, the message is complete; return it
+    let endian = Endian::from(primary_header.endian_sig());
+    let ctxt = Context::new_dbus(endian, 0);
+    #[cfg(unix)]
+    let bytes = serialized::Data::new_fds(bytes, ctxt, fds);
+    #[cfg(not(unix))]
+    let bytes = serialized::Data::new(bytes, ctxt);
+    Message::from_raw_parts(bytes, seq, Some(Instant::now()))
+}
+
 #[async_trait::async_trait]
 impl ReadHalf for Box<dyn ReadHalf> {
     fn can_pass_unix_fd(&self) -> bool {
@@ -311,6 +340,17 @@ impl ReadHalf for Box<dyn ReadHalf> {
         (**self).receive_message(seq, already_received_bytes).await
     }
 
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<Message> {
+        (**self)
+            .receive_message_with_max_size(seq, already_received_bytes, max_size)
+            .await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
         (**self).recvmsg(buf).await
     }
@@ -376,3 +416,69 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    /// A socket writing at most `max` bytes per `sendmsg` call.
+    #[derive(Debug)]
+    struct ShortWrites {
+        max: usize,
+        written: Vec<u8>,
+        fds_per_call: Vec<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl WriteHalf for ShortWrites {
+        async fn sendmsg(
+            &mut self,
+            buffer: &[u8],
+            #[cfg(unix)] fds: &[BorrowedFd<'_>],
+        ) -> io::Result<usize> {
+            let n = buffer.len().min(self.max);
+            self.written.extend_from_slice(&buffer[..n]);
+            #[cfg(unix)]
+            self.fds_per_call.push(fds.len());
+            #[cfg(not(unix))]
+            self.fds_per_call.push(0);
+
+            Ok(n)
+        }
+
+        async fn close(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn short_writes() {
+        #[cfg(unix)]
+        let stdout = std::io::stdout();
+        let msg = Message::method("/", "Do")
+            .unwrap()
+            .build(&(
+                #[cfg(unix)]
+                zvariant::Fd::from(&stdout),
+                "a body long enough to need several writes",
+            ))
+            .unwrap();
+        let mut socket = ShortWrites {
+            max: 7,
+            written: vec![],
+            fds_per_call: vec![],
+        };
+
+        crate::utils::block_on(socket.send_message(&msg)).unwrap();
+        assert_eq!(socket.written, &msg.data()[..]);
+        assert_eq!(socket.fds_per_call.len(), msg.data().len().div_ceil(7));
+        let expected_fds = if cfg!(unix) { 1 } else { 0 };
+        assert_eq!(socket.fds_per_call[0], expected_fds);
+        assert!(socket.fds_per_call[1..].iter().all(|n| *n == 0));
+
+        // A socket that doesn't make progress must not result in an endless loop.
+        socket.max = 0;
+        assert!(crate::utils::block_on(socket.send_message(&msg)).is_err());
+    }
+}
@@ -13,6 +13,15 @@ use super::{Socket, Split};
 #[cfg(not(feature = "tokio"))]
 #[async_trait::async_trait]
 impl ReadHalf for Arc<Async<TcpStream>> {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
         match futures_util::AsyncReadExt::read(&mut self.as_ref(), buf).await {
             Err(e) => Err(e),
@@ -96,6 +105,15 @@ impl Socket for tokio::net::TcpStream {
 #[cfg(feature = "tokio")]
 #[async_trait::async_trait]
 impl ReadHalf for tokio::net::tcp::OwnedReadHalf {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
         use tokio::io::{AsyncReadExt, ReadBuf};
 
@@ -28,6 +28,15 @@ use crate::utils::FDS_MAX;
 #[cfg(all(unix, not(feature = "tokio")))]
 #[async_trait::async_trait]
 impl super::ReadHalf for Arc<Async<UnixStream>> {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
         poll_fn(|cx| {
             let (len, fds) = loop {
@@ -121,6 +130,15 @@ impl super::Socket for tokio::net::UnixStream {
 #[cfg(all(unix, feature = "tokio"))]
 #[async_trait::async_trait]
 impl super::ReadHalf for tokio::net::unix::OwnedReadHalf {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
         let stream = self.as_ref();
         poll_fn(|cx| {
@@ -207,6 +225,15 @@ impl super::WriteHalf for tokio::net::unix::OwnedWriteHalf {
 #[cfg(all(windows, not(feature = "tokio")))]
 #[async_trait::async_trait]
 impl super::ReadHalf for Arc<Async<UnixStream>> {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
         match futures_util::AsyncReadExt::read(&mut self.as_ref(), buf).await {
             Err(e) => Err(e),
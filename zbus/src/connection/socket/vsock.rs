@@ -4,6 +4,15 @@ use super::{Socket, Split};
 #[cfg(all(feature = "vsock", not(feature = "tokio")))]
 #[async_trait::async_trait]
 impl super::ReadHalf for std::sync::Arc<async_io::Async<vsock::VsockStream>> {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
         match futures_util::AsyncReadExt::read(&mut self.as_ref(), buf).await {
             Err(e) => Err(e),
@@ -64,6 +73,15 @@ impl Socket for tokio_vsock::VsockStream {
 #[cfg(feature = "tokio-vsock")]
 #[async_trait::async_trait]
 impl super::ReadHalf for tokio_vsock::ReadHalf {
+    async fn receive_message_with_max_size(
+        &mut self,
+        seq: u64,
+        already_received_bytes: &mut Vec<u8>,
+        max_size: usize,
+    ) -> crate::Result<crate::Message> {
+        super::receive_with_recvmsg(self, seq, already_received_bytes, max_size).await
+    }
+
     async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
         use tokio::io::{AsyncReadExt, ReadBuf};
 
@@ -1,10 +1,23 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use event_listener::Event;
 use tracing::{debug, instrument, trace};
 
 use crate::{
-    async_lock::Mutex, connection::MsgBroadcaster, Executor, Message, OwnedMatchRule, Task,
+    async_lock::Mutex,
+    connection::{
+        journal::{Direction, Journal},
+        state::Termination,
+        MsgBroadcaster, PendingReplies, StickySignals,
+    },
+    message::Type,
+    Executor, MatchRule, Message, OwnedMatchRule, Task,
 };
 
 use super::socket::ReadHalf;
@@ -13,24 +26,43 @@ use super::socket::ReadHalf;
 pub(crate) struct SocketReader {
     socket: Box<dyn ReadHalf>,
     senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
+    sticky_signals: Arc<Mutex<StickySignals>>,
+    pending_replies: Arc<std::sync::Mutex<PendingReplies>>,
+    termination: Arc<Termination>,
     already_received_bytes: Vec<u8>,
     prev_seq: u64,
     activity_event: Arc<Event>,
+    journal: Arc<Journal>,
+    strict_sender_validation: Arc<AtomicBool>,
+    max_message_size: usize,
 }
 
 impl SocketReader {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         socket: Box<dyn ReadHalf>,
         senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
+        sticky_signals: Arc<Mutex<StickySignals>>,
+        pending_replies: Arc<std::sync::Mutex<PendingReplies>>,
+        termination: Arc<Termination>,
         already_received_bytes: Vec<u8>,
         activity_event: Arc<Event>,
+        journal: Arc<Journal>,
+        strict_sender_validation: Arc<AtomicBool>,
+        max_message_size: usize,
     ) -> Self {
         Self {
             socket,
             senders,
+            sticky_signals,
+            pending_replies,
+            termination,
             already_received_bytes,
             prev_seq: 0,
             activity_event,
+            journal,
+            strict_sender_validation,
+            max_message_size,
         }
     }
 
@@ -45,15 +77,48 @@ impl SocketReader {
             trace!("Waiting for message on the socket..");
             let msg = self.read_socket().await;
             match &msg {
-                Ok(msg) => trace!("Message received on the socket: {:?}", msg),
+                Ok(msg) => {
+                    trace!("Message received on the socket: {:?}", msg);
+                    self.journal.record(Direction::Received, msg);
+                }
                 Err(e) => trace!("Error reading from the socket: {:?}", e),
             };
 
+            // From here on, all handles of the connection observe the terminal error, which is
+            // the one reported to the streams and the pending method calls.
+            let msg = match msg {
+                Ok(msg) => Ok(msg),
+                Err(e) => {
+                    self.termination.terminate(e);
+                    Err(self.termination.error().cloned().expect("terminated"))
+                }
+            };
+
+            {
+                let mut pending_replies = self.pending_replies.lock().expect("lock poisoned");
+                match &msg {
+                    Ok(msg) => pending_replies.route(msg),
+                    Err(e) => pending_replies.close(e),
+                }
+            }
+
+            let strict = self.strict_sender_validation.load(Ordering::Relaxed);
             let mut senders = self.senders.lock().await;
+            let mut sticky_signals = match &msg {
+                Ok(msg) if msg.message_type() == Type::Signal => {
+                    Some(self.sticky_signals.lock().await)
+                }
+                _ => None,
+            };
             for (rule, sender) in &mut *senders {
                 if let Ok(msg) = &msg {
                     if let Some(rule) = rule.as_ref() {
                         match rule.matches(msg) {
+                            Ok(true) if strict && !sender_is_valid(rule, msg) => {
+                                debug!("Ignoring message from an invalid sender: {:?}", msg);
+
+                                continue;
+                            }
                             Ok(true) => (),
                             Ok(false) => continue,
                             Err(e) => {
@@ -62,10 +127,13 @@ impl SocketReader {
                                 continue;
                             }
                         }
+
+                        if let Some(sticky_signals) = &mut sticky_signals {
+                            sticky_signals.record(rule, msg);
+                        }
                     }
                 }
 
-                sender.set_await_active(true);
                 if let Err(e) = sender.broadcast_direct(msg.clone()).await {
                     // An error would be due to either of these:
                     //
@@ -80,6 +148,7 @@ impl SocketReader {
                     );
                 }
             }
+            drop(sticky_signals);
             trace!("Broadcasted to all streams: {:?}", msg);
 
             if msg.is_err() {
@@ -97,10 +166,28 @@ impl SocketReader {
         let seq = self.prev_seq + 1;
         let msg = self
             .socket
-            .receive_message(seq, &mut self.already_received_bytes)
+            .receive_message_with_max_size(
+                seq,
+                &mut self.already_received_bytes,
+                self.max_message_size,
+            )
             .await?;
         self.prev_seq = seq;
 
         Ok(msg)
     }
 }
+
+// The sender of a message can't be matched against a well-known name but we can at least ensure
+// that there is one, and that only the bus sends messages as itself.
+fn sender_is_valid(rule: &MatchRule<'_>, msg: &Message) -> bool {
+    let Some(rule_sender) = rule.sender() else {
+        return true;
+    };
+    let header = msg.header();
+    let Some(sender) = header.sender() else {
+        return false;
+    };
+
+    rule_sender.as_str() != "org.freedesktop.DBus" || sender.as_str() == "org.freedesktop.DBus"
+}
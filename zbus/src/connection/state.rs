@@ -0,0 +1,59 @@
+use std::sync::OnceLock;
+
+use event_listener::Event;
+use static_assertions::assert_impl_all;
+
+use crate::Error;
+
+/// The state of a [`crate::Connection`].
+///
+/// See [`crate::Connection::state`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum State {
+    /// The connection is usable.
+    Connected,
+    /// The connection was terminated, with the given error.
+    ///
+    /// This is the final state of a connection: all subsequent operations on it fail with this
+    /// error.
+    Disconnected(Error),
+}
+
+assert_impl_all!(State: Send, Sync, Unpin);
+
+/// The terminal error of a connection, shared by all its handles and its socket reader.
+#[derive(Debug, Default)]
+pub(crate) struct Termination {
+    error: OnceLock<Error>,
+    event: Event,
+}
+
+impl Termination {
+    /// Terminate the connection with `error`, unless it's already terminated.
+    pub(crate) fn terminate(&self, error: Error) {
+        if self.error.set(error).is_ok() {
+            self.event.notify(usize::MAX);
+        }
+    }
+
+    /// The error the connection was terminated with, if it was.
+    pub(crate) fn error(&self) -> Option<&Error> {
+        self.error.get()
+    }
+
+    /// Wait for the connection to be terminated.
+    pub(crate) async fn wait(&self) -> Error {
+        loop {
+            if let Some(error) = self.error() {
+                return error.clone();
+            }
+            let listener = self.event.listen();
+            // Avoid missing a termination between the check and creating the listener.
+            if let Some(error) = self.error() {
+                return error.clone();
+            }
+            listener.await;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use zbus_names::{OwnedInterfaceName, OwnedMemberName};
+use zvariant::OwnedObjectPath;
+
+use crate::{message::Type, Message, OwnedMatchRule};
+
+type SignalKey = (OwnedInterfaceName, OwnedMemberName, OwnedObjectPath);
+
+/// The last signal received for each interface, member and path, per sticky subscription.
+#[derive(Debug, Default)]
+pub(crate) struct StickySignals {
+    rules: HashMap<OwnedMatchRule, HashMap<SignalKey, Message>>,
+}
+
+impl StickySignals {
+    /// Start retaining signals matching `rule`, if not already doing so.
+    pub(crate) fn enable(&mut self, rule: OwnedMatchRule) {
+        self.rules.entry(rule).or_default();
+    }
+
+    /// Stop retaining signals matching `rule` and forget the ones retained so far.
+    pub(crate) fn disable(&mut self, rule: &OwnedMatchRule) {
+        self.rules.remove(rule);
+    }
+
+    /// Retain `msg`, which matched `rule`, if signals matching `rule` are to be retained.
+    pub(crate) fn record(&mut self, rule: &OwnedMatchRule, msg: &Message) {
+        let Some(signals) = self.rules.get_mut(rule) else {
+            return;
+        };
+        if msg.message_type() != Type::Signal {
+            return;
+        }
+
+        let header = msg.header();
+        if let (Some(interface), Some(member), Some(path)) =
+            (header.interface(), header.member(), header.path())
+        {
+            let key = (
+                interface.clone().into(),
+                member.clone().into(),
+                path.clone().into(),
+            );
+            signals.insert(key, msg.clone());
+        }
+    }
+
+    /// The retained signals for `rule`, in the order they were received.
+    pub(crate) fn last(&self, rule: &OwnedMatchRule) -> Vec<Message> {
+        let mut signals: Vec<_> = self
+            .rules
+            .get(rule)
+            .map(|signals| signals.values().cloned().collect())
+            .unwrap_or_default();
+        signals.sort_by_key(|msg| msg.recv_position());
+
+        signals
+    }
+}
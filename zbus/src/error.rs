@@ -1,5 +1,5 @@
 use static_assertions::assert_impl_all;
-use std::{convert::Infallible, error, fmt, io, sync::Arc};
+use std::{convert::Infallible, error, fmt, io, sync::Arc, time::Duration};
 use zbus_names::{Error as NamesError, InterfaceName, OwnedErrorName};
 use zvariant::{Error as VariantError, ObjectPath};
 
@@ -33,6 +33,9 @@ pub enum Error {
     IncorrectEndian,
     /// Initial handshake error.
     Handshake(String),
+    /// Initial handshake error caused by another error, which is its
+    /// [source](error::Error::source).
+    HandshakeCause(String, Arc<dyn error::Error + Send + Sync>),
     /// Unexpected or incorrect reply.
     InvalidReply,
     /// A D-Bus method error reply.
@@ -61,16 +64,124 @@ pub enum Error {
     InvalidSerial,
     /// The given interface already exists at the given path.
     InterfaceExists(InterfaceName<'static>, ObjectPath<'static>),
+    /// Nothing was received within the given timeout, such as the reply to a method call.
+    ///
+    /// The field is the timeout that expired, not a measure of the time actually spent waiting.
+    Timeout(Duration),
+    /// The message header advertises a major protocol version that is not supported.
+    UnsupportedProtocolVersion(u8),
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
 
+impl Error {
+    /// Whether the error is due to the connection being closed or lost.
+    ///
+    /// This is the case for I/O errors signifying a broken or closed connection, and for the
+    /// `org.freedesktop.DBus.Error.Disconnected` D-Bus error. A new connection is needed to
+    /// continue communicating with the peer.
+    pub fn is_disconnect(&self) -> bool {
+        match self {
+            Error::InputOutput(e) => matches!(
+                e.kind(),
+                io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            Error::MethodError(_, _, _) => {
+                matches!(fdo::Error::from(self.clone()), fdo::Error::Disconnected(_))
+            }
+            Error::FDO(e) => match &**e {
+                fdo::Error::ZBus(e) => e.is_disconnect(),
+                e => matches!(e, fdo::Error::Disconnected(_)),
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether a method call failed because no reply was received within the local method timeout.
+    ///
+    /// The timeout is the one given to [`crate::Connection::call_method_with_timeout`], or the
+    /// [default method timeout][crate::Connection::set_method_timeout] of the connection, so the
+    /// time spent waiting is known to the caller. Contrary to [`Error::is_no_reply`], the call may
+    /// still be processed by the peer.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout(_) => true,
+            Error::FDO(e) => matches!(&**e, fdo::Error::ZBus(e) if e.is_timeout()),
+            _ => false,
+        }
+    }
+
+    /// Whether a method call failed because the bus reported that no reply was received.
+    ///
+    /// This is the `org.freedesktop.DBus.Error.NoReply` D-Bus error, sent by the bus when its own
+    /// timeout (typically 25 seconds) elapses before the peer replies, or when the peer leaves the
+    /// bus without replying. This is different from a local [timeout][Error::is_timeout], and from
+    /// the connection to the bus itself being [lost][Error::is_disconnect].
+    pub fn is_no_reply(&self) -> bool {
+        match self {
+            Error::MethodError(name, _, _) => name.as_str() == "org.freedesktop.DBus.Error.NoReply",
+            Error::FDO(e) => match &**e {
+                fdo::Error::ZBus(e) => e.is_no_reply(),
+                e => matches!(e, fdo::Error::NoReply(_)),
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the error is transient and retrying the same operation on the same connection
+    /// might succeed.
+    ///
+    /// This is the case for interrupted or timed out I/O, method calls that timed out, and for D-Bus
+    /// errors signifying a timeout, the lack of a reply or exhausted resources.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::InputOutput(e) => matches!(
+                e.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ),
+            Error::Timeout(_) => true,
+            Error::MethodError(_, _, _) => is_retryable_fdo(&fdo::Error::from(self.clone())),
+            Error::FDO(e) => match &**e {
+                fdo::Error::ZBus(e) => e.is_retryable(),
+                e => is_retryable_fdo(e),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Error {
+    /// A handshake error with the given description, caused by `cause`.
+    pub(crate) fn handshake_cause<E>(description: impl Into<String>, cause: E) -> Self
+    where
+        E: error::Error + Send + Sync + 'static,
+    {
+        Error::HandshakeCause(description.into(), Arc::new(cause))
+    }
+}
+
+fn is_retryable_fdo(e: &fdo::Error) -> bool {
+    matches!(
+        e,
+        fdo::Error::NoReply(_)
+            | fdo::Error::Timeout(_)
+            | fdo::Error::TimedOut(_)
+            | fdo::Error::LimitsExceeded(_)
+            | fdo::Error::NoMemory(_)
+    )
+}
+
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Address(_), Self::Address(_)) => true,
             (Self::InterfaceNotFound, Self::InterfaceNotFound) => true,
             (Self::Handshake(_), Self::Handshake(_)) => true,
+            (Self::HandshakeCause(_, _), Self::HandshakeCause(_, _)) => true,
             (Self::InvalidReply, Self::InvalidReply) => true,
             (Self::ExcessData, Self::ExcessData) => true,
             (Self::IncorrectEndian, Self::IncorrectEndian) => true,
@@ -88,6 +199,8 @@ impl PartialEq for Error {
             (Error::InputOutput(_), Self::InputOutput(_)) => false,
             (Self::Failure(s1), Self::Failure(s2)) => s1 == s2,
             (Self::InterfaceExists(s1, s2), Self::InterfaceExists(o1, o2)) => s1 == o1 && s2 == o2,
+            (Self::Timeout(s), Self::Timeout(o)) => s == o,
+            (Self::UnsupportedProtocolVersion(s), Self::UnsupportedProtocolVersion(o)) => s == o,
             (_, _) => false,
         }
     }
@@ -98,9 +211,10 @@ impl error::Error for Error {
         match self {
             Error::InterfaceNotFound => None,
             Error::Address(_) => None,
-            Error::InputOutput(e) => Some(e),
+            Error::InputOutput(e) => Some(&**e),
             Error::ExcessData => None,
             Error::Handshake(_) => None,
+            Error::HandshakeCause(_, e) => Some(&**e),
             Error::IncorrectEndian => None,
             Error::Variant(e) => Some(e),
             Error::Names(e) => Some(e),
@@ -108,7 +222,7 @@ impl error::Error for Error {
             Error::MethodError(_, _, _) => None,
             Error::InvalidGUID => None,
             Error::Unsupported => None,
-            Error::FDO(e) => Some(e),
+            Error::FDO(e) => Some(&**e),
             Error::InvalidField => None,
             Error::MissingField => None,
             Error::NameTaken => None,
@@ -117,6 +231,8 @@ impl error::Error for Error {
             Error::MissingParameter(_) => None,
             Error::InvalidSerial => None,
             Error::InterfaceExists(_, _) => None,
+            Error::Timeout(_) => None,
+            Error::UnsupportedProtocolVersion(_) => None,
         }
     }
 }
@@ -129,6 +245,7 @@ impl fmt::Display for Error {
             Error::ExcessData => write!(f, "excess data"),
             Error::InputOutput(e) => write!(f, "I/O error: {e}"),
             Error::Handshake(e) => write!(f, "D-Bus handshake failed: {e}"),
+            Error::HandshakeCause(e, cause) => write!(f, "D-Bus handshake failed: {e}: {cause}"),
             Error::IncorrectEndian => write!(f, "incorrect endian"),
             Error::InvalidField => write!(f, "invalid message field"),
             Error::Variant(e) => write!(f, "{e}"),
@@ -152,6 +269,10 @@ impl fmt::Display for Error {
             }
             Error::InvalidSerial => write!(f, "Serial number in the message header is 0"),
             Error::InterfaceExists(i, p) => write!(f, "Interface `{i}` already exists at `{p}`"),
+            Error::Timeout(timeout) => write!(f, "Nothing received within the {timeout:?} timeout"),
+            Error::UnsupportedProtocolVersion(version) => {
+                write!(f, "Unsupported D-Bus protocol version {version}")
+            }
         }
     }
 }
@@ -164,6 +285,7 @@ impl Clone for Error {
             Error::ExcessData => Error::ExcessData,
             Error::InputOutput(e) => Error::InputOutput(e.clone()),
             Error::Handshake(e) => Error::Handshake(e.clone()),
+            Error::HandshakeCause(e, cause) => Error::HandshakeCause(e.clone(), cause.clone()),
             Error::IncorrectEndian => Error::IncorrectEndian,
             Error::InvalidField => Error::InvalidField,
             Error::Variant(e) => Error::Variant(e.clone()),
@@ -182,6 +304,10 @@ impl Clone for Error {
             Error::MissingParameter(p) => Error::MissingParameter(p),
             Error::InvalidSerial => Error::InvalidSerial,
             Error::InterfaceExists(i, p) => Error::InterfaceExists(i.clone(), p.clone()),
+            Error::Timeout(timeout) => Error::Timeout(*timeout),
+            Error::UnsupportedProtocolVersion(version) => {
+                Error::UnsupportedProtocolVersion(*version)
+            }
         }
     }
 }
@@ -253,3 +379,100 @@ impl From<Message> for Error {
 
 /// Alias for a `Result` with the error type `zbus::Error`.
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn classification() {
+        let e = Error::from(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(e.is_disconnect());
+        assert!(!e.is_retryable());
+        let e = Error::from(io::Error::from(io::ErrorKind::TimedOut));
+        assert!(!e.is_disconnect());
+        assert!(e.is_retryable());
+
+        let call = Message::method("/", "foo")
+            .unwrap()
+            .destination(":1.2")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        for (name, disconnect, no_reply, retryable) in [
+            (
+                "org.freedesktop.DBus.Error.Disconnected",
+                true,
+                false,
+                false,
+            ),
+            ("org.freedesktop.DBus.Error.NoReply", false, true, true),
+            (
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                false,
+                false,
+                false,
+            ),
+            ("org.zbus.Error.Custom", false, false, false),
+        ] {
+            let reply = Message::method_error(&call, name)
+                .unwrap()
+                .build(&("details"))
+                .unwrap();
+            let e = Error::from(reply);
+            assert_eq!(e.is_disconnect(), disconnect, "{name}");
+            assert_eq!(e.is_no_reply(), no_reply, "{name}");
+            assert!(!e.is_timeout(), "{name}");
+            assert_eq!(e.is_retryable(), retryable, "{name}");
+
+            let e = Error::from(fdo::Error::from(e));
+            assert_eq!(e.is_disconnect(), disconnect, "{name}");
+            assert_eq!(e.is_no_reply(), no_reply, "{name}");
+            assert!(!e.is_timeout(), "{name}");
+            assert_eq!(e.is_retryable(), retryable, "{name}");
+        }
+
+        assert!(!Error::InvalidReply.is_disconnect());
+        assert!(!Error::InvalidReply.is_retryable());
+        assert!(!Error::Timeout(Duration::ZERO).is_disconnect());
+        assert!(!Error::Timeout(Duration::ZERO).is_no_reply());
+        assert!(Error::Timeout(Duration::ZERO).is_timeout());
+        assert!(Error::Timeout(Duration::ZERO).is_retryable());
+        assert!(Error::from(fdo::Error::ZBus(Error::Timeout(Duration::ZERO))).is_timeout());
+    }
+
+    #[test]
+    fn source_chain() {
+        use std::error::Error as _;
+
+        let io = io::Error::from(io::ErrorKind::ConnectionReset);
+        let e = Error::FDO(Box::new(fdo::Error::ZBus(Error::from(io))));
+        let mut source = e.source();
+        let mut chain = 0;
+        while let Some(e) = source {
+            if let Some(io) = e.downcast_ref::<io::Error>() {
+                assert_eq!(io.kind(), io::ErrorKind::ConnectionReset);
+                break;
+            }
+            source = e.source();
+            chain += 1;
+        }
+        assert!(source.is_some());
+        assert_eq!(chain, 2);
+        assert!(e.is_disconnect());
+
+        let parse = "cookie".parse::<usize>().unwrap_err();
+        let e = Error::handshake_cause("Invalid cookie ID `cookie`", parse.clone());
+        assert_eq!(
+            e.source()
+                .unwrap()
+                .downcast_ref::<std::num::ParseIntError>(),
+            Some(&parse)
+        );
+        assert_eq!(
+            e.to_string(),
+            format!("D-Bus handshake failed: Invalid cookie ID `cookie`: {parse}")
+        );
+    }
+}
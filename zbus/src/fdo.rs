@@ -220,6 +220,10 @@ impl Properties {
 }
 
 /// The type returned by the [`ObjectManagerProxy::get_managed_objects`] method.
+///
+/// To get typed data instead, call the method through [`crate::Proxy::call`] with a
+/// `HashMap<OwnedObjectPath, T>` reply type, where `T` derives [`zvariant::DeserializeDict`] with
+/// the `a{sa{sv}}` signature and has an optional field for each interface of interest.
 pub type ManagedObjects =
     HashMap<OwnedObjectPath, HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>>;
 
@@ -169,7 +169,12 @@ pub use connection::Socket;
 
 pub mod blocking;
 
-pub use zbus_macros::{interface, proxy, DBusError};
+pub mod prelude;
+
+#[cfg(feature = "p2p")]
+pub mod bus_proxy;
+
+pub use zbus_macros::{interface, proxy, test, DBusError};
 // Old names used for backwards compatibility
 pub use zbus_macros::{dbus_interface, dbus_proxy};
 
@@ -310,6 +315,72 @@ mod tests {
         f.metadata().unwrap();
     }
 
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn fdpass_signal() {
+        block_on(test_fdpass_signal()).unwrap();
+    }
+
+    #[cfg(unix)]
+    async fn test_fdpass_signal() -> Result<()> {
+        use futures_util::StreamExt;
+        use std::{
+            fs::File,
+            io::{Read, Seek, Write},
+        };
+        use zvariant::{Fd, OwnedFd};
+
+        struct FileShare;
+
+        #[crate::interface(name = "org.zbus.FileShare")]
+        impl FileShare {
+            #[zbus(signal)]
+            async fn shared(ctxt: &SignalContext<'_>, name: &str, file: Fd<'_>) -> Result<()>;
+        }
+
+        #[crate::proxy(
+            interface = "org.zbus.FileShare",
+            default_path = "/org/zbus/FileShare",
+            gen_blocking = false
+        )]
+        trait FileShare {
+            #[zbus(signal)]
+            fn shared(&self, name: String, file: OwnedFd) -> Result<()>;
+        }
+
+        let service = Connection::session().await?;
+        let client = Connection::session().await?;
+        let proxy = FileShareProxy::new(&client, service.unique_name().unwrap().to_owned()).await?;
+        let mut shared_stream = proxy.receive_shared().await?;
+
+        let path = std::env::temp_dir().join(format!("zbus-fdpass-signal-{}", std::process::id()));
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        file.write_all(b"shared over D-Bus").unwrap();
+        file.rewind().unwrap();
+
+        // The fd is attached to the signal message on emission, and resolved from it on reception.
+        let ctxt = SignalContext::new(&service, "/org/zbus/FileShare")?;
+        FileShare::shared(&ctxt, "greeting", Fd::from(&file)).await?;
+
+        let signal = shared_stream.next().await.unwrap();
+        let args = signal.args()?;
+        assert_eq!(args.name(), "greeting");
+        let mut received = File::from(args.file);
+        let mut contents = String::new();
+        received.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "shared over D-Bus");
+
+        Ok(())
+    }
+
     #[test]
     #[instrument]
     #[timeout(15000)]
@@ -860,6 +931,103 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn interface_extends() {
+        block_on(test_interface_extends()).unwrap();
+    }
+
+    async fn test_interface_extends() -> Result<()> {
+        use futures_util::StreamExt;
+
+        #[derive(Default)]
+        struct Describable {
+            description: String,
+        }
+
+        #[crate::interface(name = "org.freedesktop.zbus.Describable")]
+        impl Describable {
+            #[zbus(property)]
+            fn description(&self) -> &str {
+                &self.description
+            }
+
+            #[zbus(property)]
+            fn set_description(&mut self, description: String) {
+                self.description = description;
+            }
+
+            #[zbus(signal)]
+            async fn renamed(ctxt: &SignalContext<'_>, name: &str) -> Result<()>;
+        }
+
+        #[derive(Default)]
+        struct Device {
+            base: Describable,
+        }
+
+        impl AsRef<Describable> for Device {
+            fn as_ref(&self) -> &Describable {
+                &self.base
+            }
+        }
+
+        impl AsMut<Describable> for Device {
+            fn as_mut(&mut self) -> &mut Describable {
+                &mut self.base
+            }
+        }
+
+        #[crate::interface(name = "org.freedesktop.zbus.Device", extends = "Describable")]
+        impl Device {
+            fn reset(&self) {}
+        }
+
+        // The members of the base are all seen under the name of the extending interface.
+        #[crate::proxy(
+            interface = "org.freedesktop.zbus.Device",
+            default_path = "/org/freedesktop/zbus/Device"
+        )]
+        trait Device {
+            #[zbus(property)]
+            fn description(&self) -> Result<String>;
+
+            #[zbus(property)]
+            fn set_description(&self, description: &str) -> Result<()>;
+
+            #[zbus(signal)]
+            fn renamed(&self, name: &str) -> Result<()>;
+        }
+
+        let service = crate::connection::Builder::session()?
+            .serve_at("/org/freedesktop/zbus/Device", Device::default())?
+            .build()
+            .await?;
+        let client_conn = Connection::session().await?;
+        let client = DeviceProxy::builder(&client_conn)
+            .destination(service.unique_name().unwrap().to_owned())?
+            .build()
+            .await?;
+        let mut renamed = client.receive_renamed().await?;
+        let mut description_changed = client.receive_description_changed().await;
+        // The initial value.
+        assert_eq!(description_changed.next().await.unwrap().get().await?, "");
+
+        let iface_ref = service
+            .object_server()
+            .interface::<_, Device>("/org/freedesktop/zbus/Device")
+            .await?;
+        Describable::renamed(iface_ref.signal_context(), "Router").await?;
+        let signal = renamed.next().await.unwrap();
+        assert_eq!(signal.args()?.name(), &"Router");
+
+        client.set_description("A router").await?;
+        let changed = description_changed.next().await.unwrap();
+        assert_eq!(changed.get().await?, "A router");
+
+        Ok(())
+    }
+
     #[test]
     #[timeout(15000)]
     fn issue_260() {
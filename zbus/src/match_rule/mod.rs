@@ -7,12 +7,11 @@ use std::{
 
 use serde::{de, Deserialize, Serialize};
 use static_assertions::assert_impl_all;
-use zvariant::Structure;
 
 use crate::{
     message::Type,
     names::{BusName, InterfaceName, MemberName, UniqueName},
-    zvariant::{ObjectPath, Str, Type as VariantType},
+    zvariant::{ObjectPath, OwnedObjectPath, Str, Type as VariantType},
     Error, Result,
 };
 
@@ -110,6 +109,56 @@ impl<'m> MatchRule<'m> {
         Builder::new()
     }
 
+    /// Create a builder for a `MatchRule` on signals.
+    ///
+    /// This is a shorthand for `MatchRule::builder().msg_type(Type::Signal)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zbus::MatchRule;
+    ///
+    /// let rule = MatchRule::signal()
+    ///     .interface("org.freedesktop.DBus.Properties")?
+    ///     .member("PropertiesChanged")?
+    ///     .path_namespace("/org/zbus")?
+    ///     .arg(0, "org.zbus.Device")?
+    ///     .build();
+    /// assert_eq!(
+    ///     rule.to_string(),
+    ///     "type='signal',\
+    ///      interface='org.freedesktop.DBus.Properties',\
+    ///      member='PropertiesChanged',\
+    ///      path_namespace='/org/zbus',\
+    ///      arg0='org.zbus.Device'",
+    /// );
+    /// # Ok::<_, zbus::Error>(())
+    /// ```
+    pub fn signal() -> Builder<'m> {
+        Builder::new().msg_type(Type::Signal)
+    }
+
+    /// Create a builder for a `MatchRule` on method calls.
+    ///
+    /// This is a shorthand for `MatchRule::builder().msg_type(Type::MethodCall)`.
+    pub fn method_call() -> Builder<'m> {
+        Builder::new().msg_type(Type::MethodCall)
+    }
+
+    /// Create a builder for a `MatchRule` on method returns.
+    ///
+    /// This is a shorthand for `MatchRule::builder().msg_type(Type::MethodReturn)`.
+    pub fn method_return() -> Builder<'m> {
+        Builder::new().msg_type(Type::MethodReturn)
+    }
+
+    /// Create a builder for a `MatchRule` on errors.
+    ///
+    /// This is a shorthand for `MatchRule::builder().msg_type(Type::Error)`.
+    pub fn error() -> Builder<'m> {
+        Builder::new().msg_type(Type::Error)
+    }
+
     /// The sender, if set.
     pub fn sender(&self) -> Option<&BusName<'_>> {
         self.sender.as_ref()
@@ -268,7 +317,7 @@ impl<'m> MatchRule<'m> {
             };
             match path_spec {
                 PathSpec::Path(path) if path != msg_path => return Ok(false),
-                PathSpec::PathNamespace(path_ns) if !msg_path.starts_with(path_ns.as_str()) => {
+                PathSpec::PathNamespace(path_ns) if !path_in_namespace(msg_path, path_ns) => {
                     return Ok(false);
                 }
                 PathSpec::Path(_) | PathSpec::PathNamespace(_) => (),
@@ -277,48 +326,53 @@ impl<'m> MatchRule<'m> {
 
         // The arg0 namespace.
         if let Some(arg0_ns) = self.arg0ns() {
-            if let Ok(arg0) = msg.body().deserialize_unchecked::<BusName<'_>>() {
-                match arg0.strip_prefix(arg0_ns.as_str()) {
-                    None => return Ok(false),
-                    Some(s) if !s.is_empty() && !s.starts_with('.') => return Ok(false),
-                    _ => (),
-                }
-            } else {
+            let Some(arg0) = msg.arg0_str() else {
                 return Ok(false);
+            };
+            match arg0.strip_prefix(arg0_ns.as_str()) {
+                None => return Ok(false),
+                Some(s) if !s.is_empty() && !s.starts_with('.') => return Ok(false),
+                _ => (),
             }
         }
 
         // Args
-        if self.args().is_empty() && self.arg_paths().is_empty() {
-            return Ok(true);
+        match (self.args(), self.arg_paths()) {
+            ([], []) => return Ok(true),
+            // The most common case, which doesn't need going through the body.
+            ([(0, arg)], []) => return Ok(msg.arg0_str() == Some(arg.as_str())),
+            _ => (),
         }
+        // Only decode the arguments the rule is about.
         let body = msg.body();
-        let structure = match body.deserialize::<Structure<'_>>() {
-            Ok(s) => s,
-            Err(_) => return Ok(false),
+        let Ok(mut cursor) = body.cursor() else {
+            return Ok(false);
         };
-        let args = structure.fields();
-
-        for (i, arg) in self.args() {
-            match args.get(*i as usize) {
-                Some(msg_arg) => match <&str>::try_from(msg_arg) {
-                    Ok(msg_arg) if arg != msg_arg => return Ok(false),
-                    Ok(_) => (),
-                    Err(_) => return Ok(false),
-                },
-                None => return Ok(false),
-            }
-        }
-
-        // Path args
-        for (i, path) in self.arg_paths() {
-            match args.get(*i as usize) {
-                Some(msg_arg) => match <ObjectPath<'_>>::try_from(msg_arg) {
-                    Ok(msg_arg) if *path != msg_arg => return Ok(false),
-                    Ok(_) => (),
-                    Err(_) => return Ok(false),
-                },
-                None => return Ok(false),
+        let last = self
+            .args()
+            .iter()
+            .map(|(i, _)| *i)
+            .chain(self.arg_paths().iter().map(|(i, _)| *i))
+            .max()
+            .unwrap_or_default();
+        for i in 0..=last {
+            let arg = self.args().iter().find(|(idx, _)| *idx == i);
+            let path = self.arg_paths().iter().find(|(idx, _)| *idx == i);
+            let matches = match (arg, path) {
+                (None, None) => matches!(cursor.skip(), Ok(true)),
+                (Some((_, arg)), None) => matches!(
+                    cursor.deserialize_next::<String>(),
+                    Ok(Some(msg_arg)) if *arg == msg_arg.as_str(),
+                ),
+                (None, Some((_, path))) => matches!(
+                    cursor.deserialize_next::<OwnedObjectPath>(),
+                    Ok(Some(msg_arg)) if *path == *msg_arg,
+                ),
+                // An argument can't be both a string and an object path.
+                (Some(_), Some(_)) => false,
+            };
+            if !matches {
+                return Ok(false);
             }
         }
 
@@ -374,6 +428,14 @@ impl Display for MatchRule<'_> {
     }
 }
 
+// Whether `path` is `namespace` itself or any of its descendants.
+fn path_in_namespace(path: &ObjectPath<'_>, namespace: &ObjectPath<'_>) -> bool {
+    match path.strip_prefix(namespace.as_str()) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || namespace.ends_with('/'),
+        None => false,
+    }
+}
+
 fn write_match_rule_string_component(
     f: &mut std::fmt::Formatter<'_>,
     key: &str,
@@ -576,3 +638,64 @@ impl PartialEq<MatchRule<'_>> for OwnedMatchRule {
         self.0 == *other
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{path_in_namespace, MatchRule};
+    use crate::{message::Message, zvariant::ObjectPath};
+    use test_log::test;
+
+    #[test]
+    fn matches_args() {
+        let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Changed")
+            .unwrap()
+            .build(&(
+                "org.zbus.Meter.Level",
+                vec![0u8; 1024],
+                ObjectPath::from_static_str_unchecked("/org/zbus/Meter/1"),
+                "left",
+            ))
+            .unwrap();
+        let matches = |rule: &str| MatchRule::try_from(rule).unwrap().matches(&msg).unwrap();
+
+        assert!(matches("arg0='org.zbus.Meter.Level'"));
+        assert!(!matches("arg0='org.zbus.Meter'"));
+        assert!(matches("arg0namespace='org.zbus.Meter'"));
+        assert!(!matches("arg0namespace='org.zbus.Met'"));
+        assert!(matches("arg0='org.zbus.Meter.Level',arg3='left'"));
+        assert!(!matches("arg0='org.zbus.Meter.Level',arg3='right'"));
+        assert!(matches("arg2path='/org/zbus/Meter/1',arg3='left'"));
+        assert!(!matches("arg2path='/org/zbus/Meter/2'"));
+        // Type mismatches and missing arguments.
+        assert!(!matches("arg1='left'"));
+        assert!(!matches("arg3path='/org/zbus/Meter/1'"));
+        assert!(!matches("arg4='left'"));
+
+        let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Level")
+            .unwrap()
+            .build(&42u32)
+            .unwrap();
+        assert!(!MatchRule::try_from("arg0='42'")
+            .unwrap()
+            .matches(&msg)
+            .unwrap());
+    }
+
+    #[test]
+    fn path_namespace() {
+        let in_namespace = |path, namespace| {
+            path_in_namespace(
+                &ObjectPath::from_static_str_unchecked(path),
+                &ObjectPath::from_static_str_unchecked(namespace),
+            )
+        };
+
+        assert!(in_namespace("/org/zbus", "/org/zbus"));
+        assert!(in_namespace("/org/zbus/Meter", "/org/zbus"));
+        assert!(!in_namespace("/org/zbusX", "/org/zbus"));
+        assert!(!in_namespace("/org/zbusX/Meter", "/org/zbus"));
+        assert!(!in_namespace("/org", "/org/zbus"));
+        assert!(in_namespace("/org/zbus", "/"));
+        assert!(in_namespace("/", "/"));
+    }
+}
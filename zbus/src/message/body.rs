@@ -1,9 +1,15 @@
+use enumflags2::BitFlags;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use static_assertions::assert_impl_all;
 use zvariant::{
     serialized::{self, Data},
-    Signature, Type,
+    Signature, Structure, Type,
 };
 
-use crate::{Error, Message, Result};
+use crate::{
+    message::{coercion::coerce_body, Coercion},
+    Error, Message, Result,
+};
 
 /// The body of a message.
 ///
@@ -20,18 +26,65 @@ impl Body {
     }
 
     /// Deserialize the body using the contained signature.
+    ///
+    /// See also [`Message::deserialize_body`], for borrowing from the message instead.
     pub fn deserialize<'s, B>(&'s self) -> Result<B>
     where
         B: zvariant::DynamicDeserialize<'s>,
     {
-        let body_sig = self
-            .signature()
-            .unwrap_or_else(|| Signature::from_static_str_unchecked(""));
-
-        self.data
-            .deserialize_for_dynamic_signature(body_sig)
-            .map_err(Error::from)
-            .map(|b| b.0)
+        self.msg.deserialize_body()
+    }
+
+    /// Deserialize the body, coercing it to the expected signature if needed.
+    ///
+    /// This is similar to [`Body::deserialize`], except that if the signature of the body doesn't
+    /// match the signature of `B`, the given `coercions` are attempted on the body before giving
+    /// up. This comes in handy when dealing with services that don't quite stick to their own
+    /// interface specification. Coercions are applied recursively to structure fields and array
+    /// elements, but not to dictionaries.
+    ///
+    /// # Errors
+    ///
+    /// If the body can't be coerced to the signature of `B`, the error from the strict
+    /// deserialization is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use enumflags2::BitFlags;
+    /// use zbus::message::{Coercion, Message};
+    ///
+    /// // A reply with `u(s)` signature, while `ts` is expected.
+    /// let reply = Message::signal("/", "org.zbus.Example", "Reply")?.build(&(42u32, ("hi",)))?;
+    /// let body = reply.body();
+    ///
+    /// assert!(body.deserialize::<(u64, String)>().is_err());
+    /// let (num, s): (u64, String) = body.deserialize_coerced(BitFlags::all())?;
+    /// assert_eq!(num, 42);
+    /// assert_eq!(s, "hi");
+    ///
+    /// // Only widening allowed.
+    /// assert!(body
+    ///     .deserialize_coerced::<(u64, String)>(Coercion::WidenNumeric.into())
+    ///     .is_err());
+    /// # Ok::<(), zbus::Error>(())
+    /// ```
+    pub fn deserialize_coerced<B>(&self, coercions: BitFlags<Coercion>) -> Result<B>
+    where
+        B: DeserializeOwned + Type,
+    {
+        let err = match self.deserialize() {
+            Ok(body) => return Ok(body),
+            Err(e) => e,
+        };
+        if coercions.is_empty() {
+            return Err(err);
+        }
+
+        match self.deserialize::<Structure<'_>>() {
+            Ok(structure) => coerce_body(structure.into_fields(), coercions).ok_or(err),
+            Err(_) => Err(err),
+        }
     }
 
     /// Deserialize the body (without checking signature matching).
@@ -52,6 +105,28 @@ impl Body {
         self.msg.inner.quick_fields.signature(&self.msg)
     }
 
+    /// A cursor over the arguments of the body.
+    ///
+    /// Unlike [`Body::deserialize`], this allows to look at the arguments one by one, skipping the
+    /// ones that are not of interest without decoding them.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of the body is invalid.
+    pub fn cursor(&self) -> Result<BodyCursor<'_>> {
+        let signatures = match self.signature() {
+            Some(signature) => signature.complete_types()?,
+            None => vec![],
+        };
+
+        Ok(BodyCursor {
+            body: self,
+            signatures,
+            next: 0,
+            pos: 0,
+        })
+    }
+
     /// The length of the body in bytes.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -72,3 +147,94 @@ impl Body {
         &self.msg
     }
 }
+
+/// A cursor over the arguments of a message [`Body`].
+///
+/// Created by [`Body::cursor`]. Arguments are either decoded or skipped, one complete type at a
+/// time. Skipping an argument doesn't allocate and arrays are skipped as a whole, regardless of
+/// their size. This comes in handy when only the first few arguments of a large body are needed,
+/// e.g. for routing or logging.
+///
+/// # Example
+///
+/// ```
+/// use zbus::message::Message;
+///
+/// let samples = vec![0u8; 1024 * 1024];
+/// let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Samples")?
+///     .build(&(&samples, "left", 42u32))?;
+/// let body = msg.body();
+/// let mut cursor = body.cursor()?;
+///
+/// assert_eq!(cursor.peek_signature().unwrap(), "ay");
+/// assert!(cursor.skip()?);
+/// assert_eq!(cursor.deserialize_next::<String>()?.unwrap(), "left");
+/// // A mismatch doesn't move the cursor.
+/// assert!(cursor.deserialize_next::<String>().is_err());
+/// assert_eq!(cursor.deserialize_next::<u32>()?, Some(42));
+/// assert_eq!(cursor.deserialize_next::<u32>()?, None);
+/// assert!(!cursor.skip()?);
+/// # Ok::<(), zbus::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct BodyCursor<'b> {
+    body: &'b Body,
+    signatures: Vec<Signature<'b>>,
+    // Index of the next argument in `signatures`.
+    next: usize,
+    // Offset of the next argument in the body.
+    pos: usize,
+}
+
+assert_impl_all!(BodyCursor<'_>: Send, Sync, Unpin);
+
+impl<'b> BodyCursor<'b> {
+    /// The signature of the next argument, or `None` if all arguments were consumed.
+    pub fn peek_signature(&self) -> Option<&Signature<'b>> {
+        self.signatures.get(self.next)
+    }
+
+    /// Deserialize the next argument, or return `None` if all arguments were consumed.
+    ///
+    /// # Errors
+    ///
+    /// If the argument can't be deserialized to `T`, in which case the cursor is not moved.
+    pub fn deserialize_next<T>(&mut self) -> Result<Option<T>>
+    where
+        T: DeserializeOwned + Type,
+    {
+        let Some(signature) = self.signatures.get(self.next) else {
+            return Ok(None);
+        };
+        let (arg, size) = self
+            .body
+            .data
+            .slice(self.pos..)
+            .deserialize_for_dynamic_signature(signature.clone())?;
+        self.advance(size);
+
+        Ok(Some(arg))
+    }
+
+    /// Skip the next argument without decoding it.
+    ///
+    /// Returns `false` if all arguments were already consumed.
+    pub fn skip(&mut self) -> Result<bool> {
+        let Some(signature) = self.signatures.get(self.next) else {
+            return Ok(false);
+        };
+        let (_, size) = self
+            .body
+            .data
+            .slice(self.pos..)
+            .deserialize_for_signature::<_, IgnoredAny>(signature.clone())?;
+        self.advance(size);
+
+        Ok(true)
+    }
+
+    fn advance(&mut self, size: usize) {
+        self.next += 1;
+        self.pos += size;
+    }
+}
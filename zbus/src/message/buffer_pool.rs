@@ -0,0 +1,129 @@
+//! A pool of message buffers.
+//!
+//! Receiving messages at a high rate, e.g. in a bus monitor, would otherwise mean allocating and
+//! freeing a buffer for every single message.
+
+use std::sync::Mutex;
+
+use zvariant::serialized::Data;
+
+// Bounds on what the pool holds on to, so it never retains more than 2 MiB.
+const MAX_BUFFERS: usize = 32;
+const MAX_BUFFER_CAPACITY: usize = 64 * 1024;
+
+static POOL: BufferPool = BufferPool::new();
+
+/// An empty buffer with at least the given capacity, reused from the pool if possible.
+pub(crate) fn take(capacity: usize) -> Vec<u8> {
+    POOL.take(capacity)
+}
+
+/// Give the buffer of `data` back to the pool, if `data` is the last reference to it.
+pub(crate) fn recycle(data: Data<'static, 'static>) {
+    POOL.recycle_data(data)
+}
+
+#[derive(Debug)]
+struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+}
+
+impl BufferPool {
+    const fn new() -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn take(&self, capacity: usize) -> Vec<u8> {
+        if capacity <= MAX_BUFFER_CAPACITY {
+            let buffer = self.buffers.lock().expect("poisoned lock").pop();
+            if let Some(mut buffer) = buffer {
+                buffer.reserve_exact(capacity);
+
+                return buffer;
+            }
+        }
+
+        Vec::with_capacity(capacity)
+    }
+
+    fn recycle_data(&self, data: Data<'static, 'static>) {
+        if let Ok(buffer) = data.try_into_buffer() {
+            self.recycle(buffer);
+        }
+    }
+
+    // Keep `buffer`, unless the pool is full or the buffer is too big to keep around.
+    fn recycle(&self, mut buffer: Vec<u8>) {
+        if buffer.capacity() > MAX_BUFFER_CAPACITY {
+            return;
+        }
+        buffer.clear();
+
+        let mut buffers = self.buffers.lock().expect("poisoned lock");
+        if buffers.len() < MAX_BUFFERS {
+            buffers.push(buffer);
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buffers.lock().expect("poisoned lock").len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use test_log::test;
+
+    #[test]
+    fn limits() {
+        let pool = BufferPool::new();
+        let buffer = pool.take(128);
+        let ptr = buffer.as_ptr();
+        pool.recycle(buffer);
+        assert_eq!(pool.len(), 1);
+        let buffer = pool.take(64);
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 128);
+        assert_eq!(pool.len(), 0);
+
+        // Too big to be kept, or taken from the pool.
+        pool.recycle(Vec::with_capacity(MAX_BUFFER_CAPACITY + 1));
+        assert_eq!(pool.len(), 0);
+        pool.recycle(buffer);
+        let buffer = pool.take(MAX_BUFFER_CAPACITY + 1);
+        assert_ne!(buffer.as_ptr(), ptr);
+        assert_eq!(pool.len(), 1);
+
+        for _ in 0..MAX_BUFFERS * 2 {
+            pool.recycle(Vec::with_capacity(MAX_BUFFER_CAPACITY));
+        }
+        assert_eq!(pool.len(), MAX_BUFFERS);
+    }
+
+    #[test]
+    fn recycle_message_data() {
+        let pool = BufferPool::new();
+        let msg = Message::signal("/org/zbus/Pool", "org.zbus.Pool", "Recycled")
+            .unwrap()
+            .build(&("data",))
+            .unwrap();
+        let data = msg.data().clone();
+        let ptr = data.bytes().as_ptr();
+
+        // The buffer is only given back once the last reference to it is gone.
+        let clone = msg.clone();
+        pool.recycle_data(clone.data().clone());
+        assert_eq!(pool.len(), 0);
+        drop((msg, clone));
+        pool.recycle_data(data);
+        assert_eq!(pool.len(), 1);
+        let buffer = pool.take(0);
+        assert_eq!(buffer.as_ptr(), ptr);
+    }
+}
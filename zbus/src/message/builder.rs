@@ -1,5 +1,7 @@
 use std::{
     io::{Cursor, Write},
+    mem::ManuallyDrop,
+    num::NonZeroU32,
     sync::Arc,
 };
 #[cfg(unix)]
@@ -12,17 +14,17 @@ use zvariant::{serialized, Endian};
 use crate::{
     message::{Field, FieldCode, Fields, Flags, Header, Message, PrimaryHeader, Sequence, Type},
     utils::padding_for_8_bytes,
-    zvariant::{serialized::Context, DynamicType, ObjectPath, Signature},
+    zvariant::{serialized::Context, DynamicType, ObjectPath, Signature, Str},
     EndianSig, Error, Result,
 };
 
 use crate::message::{fields::QuickFields, header::MAX_MESSAGE_SIZE};
 
 #[cfg(unix)]
-type BuildGenericResult = Vec<OwnedFd>;
+pub(super) type BuildGenericResult = Vec<OwnedFd>;
 
 #[cfg(not(unix))]
-type BuildGenericResult = ();
+pub(super) type BuildGenericResult = ();
 
 macro_rules! dbus_context {
     ($self:ident, $n_bytes_before: expr) => {
@@ -37,7 +39,29 @@ pub struct Builder<'a> {
 }
 
 impl<'a> Builder<'a> {
-    fn new(msg_type: Type) -> Self {
+    /// Create a builder for a message of the given type, without any header fields.
+    ///
+    /// [`Message::method`], [`Message::signal`], [`Message::method_reply`] and
+    /// [`Message::method_error`] are more convenient and set the fields required for each type.
+    /// This constructor allows setting all the fields incrementally instead, e.g for building
+    /// unusual messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zbus::message::{Builder, Flags, Type};
+    ///
+    /// let msg = Builder::new(Type::MethodCall)
+    ///     .destination("org.zbus.Example")?
+    ///     .path("/org/zbus/Example")?
+    ///     .interface("org.zbus.Example")?
+    ///     .member("Ping")?
+    ///     .with_flags(Flags::NoReplyExpected)?
+    ///     .build(&("pong", 42u32))?;
+    /// assert!(msg.primary_header().flags().contains(Flags::NoReplyExpected));
+    /// # Ok::<_, zbus::Error>(())
+    /// ```
+    pub fn new(msg_type: Type) -> Self {
         let primary = PrimaryHeader::new(msg_type, 0);
         let fields = Fields::new();
         let header = Header::new(primary, fields);
@@ -105,6 +129,22 @@ impl<'a> Builder<'a> {
         Ok(self)
     }
 
+    /// Whether the method call may trigger interactive authorization.
+    ///
+    /// This sets or clears the [`Flags::AllowInteractiveAuth`] flag, allowing services guarded by
+    /// polkit to prompt the user for authorization on this particular call. Unlike
+    /// [`Builder::with_flags`], this can also be used to clear the flag.
+    pub fn allow_interactive_auth(mut self, allow: bool) -> Self {
+        let mut flags = self.header.primary().flags();
+        if allow {
+            flags |= Flags::AllowInteractiveAuth;
+        } else {
+            flags.remove(Flags::AllowInteractiveAuth);
+        }
+        self.header.primary_mut().set_flags(flags);
+        self
+    }
+
     /// Set the unique name of the sending connection.
     pub fn sender<'s: 'a, S>(mut self, sender: S) -> Result<Self>
     where
@@ -153,7 +193,8 @@ impl<'a> Builder<'a> {
         Ok(self)
     }
 
-    fn error_name<'e: 'a, E>(mut self, error: E) -> Result<Self>
+    /// Set the name of the error, for messages of type [`Type::Error`].
+    pub fn error_name<'e: 'a, E>(mut self, error: E) -> Result<Self>
     where
         E: TryInto<ErrorName<'e>>,
         E::Error: Into<Error>,
@@ -164,6 +205,13 @@ impl<'a> Builder<'a> {
         Ok(self)
     }
 
+    /// Set the serial number of the message this message is a reply to.
+    pub fn reply_serial(mut self, serial: NonZeroU32) -> Self {
+        self.header.fields_mut().replace(Field::ReplySerial(serial));
+
+        self
+    }
+
     /// Set the name of the connection this message is intended for.
     pub fn destination<'d: 'a, D>(mut self, destination: D) -> Result<Self>
     where
@@ -176,10 +224,35 @@ impl<'a> Builder<'a> {
         Ok(self)
     }
 
+    /// Set an opaque ID correlating this message with others.
+    ///
+    /// This is useful for propagating a trace ID across services for distributed tracing. Replies
+    /// built for a message carrying a correlation ID (through [`Message::method_reply`] or
+    /// [`Message::method_error`]) inherit the same ID and the `ObjectServer` records it in the
+    /// tracing span of the method call dispatch.
+    ///
+    /// **Note:** This is a zbus-specific extension to the D-Bus protocol, transmitted as an extra
+    /// header field. Peers are required by the specification to ignore header fields they don't
+    /// know about and message buses may strip them, so the ID is only reliably received by zbus
+    /// peers on peer-to-peer connections.
+    pub fn correlation_id<'c: 'a, C>(mut self, id: C) -> Self
+    where
+        C: Into<Str<'c>>,
+    {
+        self.header
+            .fields_mut()
+            .replace(Field::CorrelationId(id.into()));
+
+        self
+    }
+
     fn reply_to(mut self, reply_to: &Header<'_>) -> Result<Self> {
-        let serial = reply_to.primary().serial_num();
-        self.header.fields_mut().replace(Field::ReplySerial(serial));
-        self = self.endian(reply_to.primary().endian_sig().into());
+        self = self
+            .reply_serial(reply_to.primary().serial_num())
+            .endian(reply_to.primary().endian_sig().into());
+        if let Some(id) = reply_to.correlation_id() {
+            self = self.correlation_id(id.to_owned());
+        }
 
         if let Some(sender) = reply_to.sender() {
             self.destination(sender.to_owned())
@@ -276,7 +349,11 @@ impl<'a> Builder<'a> {
         )
     }
 
-    fn build_generic<WriteFunc>(
+    pub(super) fn context(&self) -> Context {
+        dbus_context!(self, 0)
+    }
+
+    pub(super) fn build_generic<WriteFunc>(
         self,
         mut signature: Signature<'_>,
         body_size: serialized::Size,
@@ -341,9 +418,11 @@ impl<'a> Builder<'a> {
             inner: Arc::new(super::Inner {
                 primary_header,
                 quick_fields,
-                bytes,
+                body: ManuallyDrop::new(bytes.slice(body_offset..)),
+                bytes: ManuallyDrop::new(bytes),
                 body_offset,
                 recv_seq: Sequence::default(),
+                recv_time: None,
             }),
         })
     }
@@ -362,8 +441,11 @@ impl<'m> From<Header<'m>> for Builder<'m> {
 
 #[cfg(test)]
 mod tests {
-    use super::Message;
-    use crate::Error;
+    use super::{Builder, Message};
+    use crate::{
+        message::{Flags, Type},
+        Error,
+    };
     use test_log::test;
 
     #[test]
@@ -384,4 +466,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn incremental() -> Result<(), Error> {
+        let call = Message::method("/", "ping")?.build(&())?;
+        let serial = call.primary_header().serial_num();
+
+        let error = Builder::new(Type::Error)
+            .error_name("org.zbus.Error.Oops")?
+            .reply_serial(serial)
+            .destination(":1.42")?
+            .build(&"oops")?;
+        let header = error.header();
+        assert_eq!(error.message_type(), Type::Error);
+        assert_eq!(header.error_name().unwrap(), "org.zbus.Error.Oops");
+        assert_eq!(header.reply_serial(), Some(serial));
+        assert_eq!(header.destination().unwrap(), ":1.42");
+        assert_eq!(error.body().deserialize::<&str>()?, "oops");
+
+        assert!(matches!(
+            Builder::new(Type::Signal).with_flags(Flags::NoReplyExpected),
+            Err(Error::InvalidField)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn correlation_id() -> Result<(), Error> {
+        let call = Message::method("/", "ping")?
+            .correlation_id("trace-1234")
+            .build(&())?;
+        assert_eq!(call.header().correlation_id().unwrap(), "trace-1234");
+
+        let reply = Message::method_reply(&call)?.build(&())?;
+        assert_eq!(reply.header().correlation_id().unwrap(), "trace-1234");
+
+        let call = Message::method("/", "ping")?.build(&())?;
+        assert!(call.header().correlation_id().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn allow_interactive_auth() -> Result<(), Error> {
+        let call = Message::method("/", "ping")?
+            .allow_interactive_auth(true)
+            .build(&())?;
+        assert!(call
+            .primary_header()
+            .flags()
+            .contains(Flags::AllowInteractiveAuth));
+
+        let call = Message::method("/", "ping")?
+            .with_flags(Flags::AllowInteractiveAuth)?
+            .allow_interactive_auth(false)
+            .build(&())?;
+        assert!(!call
+            .primary_header()
+            .flags()
+            .contains(Flags::AllowInteractiveAuth));
+
+        Ok(())
+    }
 }
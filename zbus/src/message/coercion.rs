@@ -0,0 +1,220 @@
+use enumflags2::{bitflags, BitFlags};
+use serde::de::DeserializeOwned;
+use static_assertions::assert_impl_all;
+use zvariant::{
+    serialized::Context, Array, Signature, Structure, StructureBuilder, Type, Value, LE,
+};
+
+/// Coercions to attempt when a message body doesn't have the expected signature.
+///
+/// Services in the wild don't always stick to the signatures they advertise. These coercions allow
+/// decoding such bodies anyway, as long as no information is lost in the process. See
+/// [`Body::deserialize_coerced`](super::Body::deserialize_coerced) for details.
+#[bitflags]
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Coercion {
+    /// Unwrap a structure with a single field, when the type of the field is expected instead.
+    ///
+    /// For example, `(s)` can be decoded as `s`.
+    UnwrapStruct = 0x1,
+    /// Widen an integer to a larger integer type that can represent all its values, or to a
+    /// double.
+    ///
+    /// For example, `i` can be decoded as `x` or `d`, and `u` as `t`, `x` or `d`. Narrowing is never
+    /// attempted.
+    WidenNumeric = 0x2,
+}
+
+assert_impl_all!(Coercion: Send, Sync, Unpin);
+
+/// Coerce the body `fields` to the signature of `B` and decode it.
+pub(super) fn coerce_body<B>(fields: Vec<Value<'_>>, coercions: BitFlags<Coercion>) -> Option<B>
+where
+    B: DeserializeOwned + Type,
+{
+    let expected = B::signature();
+    let value = if fields.len() == 1 {
+        // A single argument is never wrapped in a structure on the wire.
+        let expected = single_field(&expected).unwrap_or(expected);
+        let field = fields.into_iter().next()?;
+
+        coerce(field, expected.as_str(), coercions)?
+    } else {
+        let structure = fields
+            .into_iter()
+            .fold(StructureBuilder::new(), StructureBuilder::append_field)
+            .build();
+
+        coerce(Value::Structure(structure), expected.as_str(), coercions)?
+    };
+    let structure = match value {
+        Value::Structure(structure) => structure,
+        value => StructureBuilder::new().append_field(value).build(),
+    };
+
+    let ctxt = Context::new_dbus(LE, 0);
+    let data = zvariant::to_bytes(ctxt, &structure).ok()?;
+
+    data.deserialize_for_dynamic_signature(structure.signature())
+        .ok()
+        .map(|(body, _)| body)
+}
+
+fn coerce<'v>(
+    value: Value<'v>,
+    expected: &str,
+    coercions: BitFlags<Coercion>,
+) -> Option<Value<'v>> {
+    if value.value_signature() == expected {
+        return Some(value);
+    }
+
+    match value {
+        Value::Structure(structure) => coerce_structure(structure, expected, coercions),
+        Value::Array(array) => coerce_array(array, expected, coercions),
+        value if coercions.contains(Coercion::WidenNumeric) => widen(value, expected),
+        _ => None,
+    }
+}
+
+fn coerce_structure<'v>(
+    structure: Structure<'v>,
+    expected: &str,
+    coercions: BitFlags<Coercion>,
+) -> Option<Value<'v>> {
+    let mut fields = structure.into_fields();
+
+    let types = Signature::try_from(expected)
+        .ok()
+        .and_then(|expected| struct_fields(&expected));
+    if let Some(types) = types {
+        if types.len() == fields.len() {
+            let structure = fields
+                .into_iter()
+                .zip(types)
+                .try_fold(StructureBuilder::new(), |builder, (field, ty)| {
+                    coerce(field, ty.as_str(), coercions).map(|field| builder.append_field(field))
+                })?
+                .build();
+
+            return Some(Value::Structure(structure));
+        }
+    }
+
+    if coercions.contains(Coercion::UnwrapStruct) && fields.len() == 1 {
+        return coerce(fields.pop()?, expected, coercions);
+    }
+
+    None
+}
+
+fn coerce_array<'v>(
+    array: Array<'v>,
+    expected: &str,
+    coercions: BitFlags<Coercion>,
+) -> Option<Value<'v>> {
+    // Dictionaries are not coerced.
+    let element = expected.strip_prefix('a').filter(|e| !e.starts_with('{'))?;
+    let signature = Signature::try_from(element).ok()?.to_owned();
+    let mut coerced = Array::new(signature);
+    for value in array.inner() {
+        let value = coerce(value.try_clone().ok()?, element, coercions)?;
+        coerced.append(value).ok()?;
+    }
+
+    Some(Value::Array(coerced))
+}
+
+fn widen(value: Value<'_>, expected: &str) -> Option<Value<'static>> {
+    let value = match (value, expected) {
+        (Value::U8(v), "n") => Value::I16(v.into()),
+        (Value::U8(v), "q") => Value::U16(v.into()),
+        (Value::U8(v), "i") => Value::I32(v.into()),
+        (Value::U8(v), "u") => Value::U32(v.into()),
+        (Value::U8(v), "x") => Value::I64(v.into()),
+        (Value::U8(v), "t") => Value::U64(v.into()),
+        (Value::U8(v), "d") => Value::F64(v.into()),
+        (Value::I16(v), "i") => Value::I32(v.into()),
+        (Value::I16(v), "x") => Value::I64(v.into()),
+        (Value::I16(v), "d") => Value::F64(v.into()),
+        (Value::U16(v), "i") => Value::I32(v.into()),
+        (Value::U16(v), "u") => Value::U32(v.into()),
+        (Value::U16(v), "x") => Value::I64(v.into()),
+        (Value::U16(v), "t") => Value::U64(v.into()),
+        (Value::U16(v), "d") => Value::F64(v.into()),
+        (Value::I32(v), "x") => Value::I64(v.into()),
+        (Value::I32(v), "d") => Value::F64(v.into()),
+        (Value::U32(v), "x") => Value::I64(v.into()),
+        (Value::U32(v), "t") => Value::U64(v.into()),
+        (Value::U32(v), "d") => Value::F64(v.into()),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// If `signature` is a structure with a single field, the signature of the field.
+fn single_field<'s>(signature: &Signature<'s>) -> Option<Signature<'s>> {
+    let mut fields = struct_fields(signature)?;
+
+    match fields.len() {
+        1 => fields.pop(),
+        _ => None,
+    }
+}
+
+/// If `signature` is a structure, the signatures of its fields.
+fn struct_fields<'s>(signature: &Signature<'s>) -> Option<Vec<Signature<'s>>> {
+    if !signature.starts_with('(') || !signature.ends_with(')') {
+        return None;
+    }
+
+    signature
+        .slice(1..signature.len() - 1)
+        .complete_types()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn split() {
+        let signature = |s| Signature::try_from(s).unwrap();
+        assert_eq!(
+            struct_fields(&signature("(ua(sv)a{sv}(i(yy)))")).unwrap(),
+            ["u", "a(sv)", "a{sv}", "(i(yy))"]
+        );
+        assert_eq!(struct_fields(&signature("a(ss)")), None);
+        assert_eq!(single_field(&signature("(a{sv})")).unwrap(), "a{sv}");
+        assert_eq!(single_field(&signature("(ss)")), None);
+    }
+
+    #[test]
+    fn coerce_fields() {
+        let all = BitFlags::all();
+
+        let fields = vec![Value::from(42i32), Value::from("hello")];
+        let body: (i64, String) = coerce_body(fields, all).unwrap();
+        assert_eq!(body, (42, "hello".to_string()));
+
+        let fields = || {
+            vec![Value::Structure(
+                StructureBuilder::new().add_field(7u8).build(),
+            )]
+        };
+        let body: u32 = coerce_body(fields(), all).unwrap();
+        assert_eq!(body, 7);
+        assert!(coerce_body::<u32>(fields(), Coercion::WidenNumeric.into()).is_none());
+
+        let fields = vec![Value::from(vec![1u32, 2, 3])];
+        let body: Vec<f64> = coerce_body(fields, all).unwrap();
+        assert_eq!(body, [1., 2., 3.]);
+
+        let fields = vec![Value::from(-1i32)];
+        assert!(coerce_body::<u64>(fields, all).is_none());
+    }
+}
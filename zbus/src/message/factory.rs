@@ -0,0 +1,203 @@
+use std::{io::Cursor, mem::ManuallyDrop, sync::Arc};
+
+use static_assertions::assert_impl_all;
+use zvariant::{serialized, DynamicType, Endian, Signature};
+
+use crate::{
+    message::{
+        builder::{BuildGenericResult, Builder},
+        fields::QuickFields,
+        header::{next_serial_num, MAX_MESSAGE_SIZE},
+        Header, Message, PrimaryHeader, Sequence,
+    },
+    Error, Result,
+};
+
+/// A factory of messages sharing the same header.
+///
+/// Creating a message involves serializing all its header fields. When sending many messages with
+/// the same header, for example signals emitted at a high rate, a `Factory` allows to serialize
+/// the header only once. Each message built from it only differs in its serial number and body.
+///
+/// All the bodies must have the signature given on creation. Bodies with file descriptors are
+/// supported but go through the regular [`Builder`] path.
+///
+/// # Example
+///
+/// ```
+/// use zbus::message::{Factory, Message};
+///
+/// let builder = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Level")?;
+/// let factory = Factory::new(builder, "u")?;
+/// for level in 0u32..3 {
+///     let msg = factory.build(&level)?;
+///     assert_eq!(msg.body().deserialize::<u32>()?, level);
+///     // Send `msg`..
+/// }
+/// # Ok::<_, zbus::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Factory {
+    // The serialized header, including the padding before the body.
+    header: Vec<u8>,
+    primary_header: PrimaryHeader,
+    quick_fields: QuickFields,
+    signature: Signature<'static>,
+}
+
+assert_impl_all!(Factory: Send, Sync, Unpin);
+
+impl Factory {
+    /// Create a factory for messages with the header of `builder` and bodies of the given
+    /// `signature`.
+    pub fn new<'b, S>(builder: Builder<'_>, signature: S) -> Result<Self>
+    where
+        S: TryInto<Signature<'b>>,
+        S::Error: Into<Error>,
+    {
+        let signature = strip_struct_delimiters(signature.try_into().map_err(Into::into)?);
+        let ctxt = builder.context();
+        let empty_body = serialized::Size::new(0, ctxt);
+        #[allow(clippy::unit_arg)]
+        let template = builder.build_generic(signature.clone(), empty_body, |_| {
+            Ok(BuildGenericResult::default())
+        })?;
+        let inner = &template.inner;
+
+        Ok(Self {
+            header: inner.bytes[..inner.body_offset].to_vec(),
+            primary_header: inner.primary_header.clone(),
+            quick_fields: inner.quick_fields,
+            signature: signature.to_owned(),
+        })
+    }
+
+    /// The signature of the message bodies.
+    pub fn signature(&self) -> &Signature<'static> {
+        &self.signature
+    }
+
+    /// Build a [`Message`] with the given body.
+    ///
+    /// # Errors
+    ///
+    /// [`zvariant::Error::SignatureMismatch`] if the signature of `body` is not the one of the
+    /// factory.
+    pub fn build<B>(&self, body: &B) -> Result<Message>
+    where
+        B: serde::ser::Serialize + DynamicType,
+    {
+        let signature = strip_struct_delimiters(body.dynamic_signature());
+        if signature != self.signature {
+            return Err(Error::Variant(zvariant::Error::SignatureMismatch(
+                signature.to_owned(),
+                format!("`{}`", self.signature),
+            )));
+        }
+
+        let ctxt = serialized::Context::new_dbus(self.primary_header.endian_sig().into(), 0);
+        let body_size = zvariant::serialized_size(ctxt, body)?;
+        #[cfg(unix)]
+        if body_size.num_fds() != 0 {
+            // The header needs an additional field for the FDs.
+            let data = serialized::Data::new(&self.header[..], ctxt);
+            let (mut header, _): (Header<'_>, _) = data.deserialize()?;
+            header.primary_mut().set_serial_num(next_serial_num());
+
+            return Builder::from(header).build(body);
+        }
+
+        let body_offset = self.header.len();
+        let total_len = body_offset + body_size.size();
+        if total_len > MAX_MESSAGE_SIZE {
+            return Err(Error::ExcessData);
+        }
+        let body_len: u32 = body_size.size().try_into().map_err(|_| Error::ExcessData)?;
+        let serial_num = next_serial_num();
+
+        let mut bytes = Vec::with_capacity(total_len);
+        bytes.extend_from_slice(&self.header);
+        // The body length and the serial number are the 2 `u32` after the first 4 bytes.
+        let (body_len_bytes, serial_num_bytes) = match ctxt.endian() {
+            Endian::Little => (body_len.to_le_bytes(), serial_num.get().to_le_bytes()),
+            Endian::Big => (body_len.to_be_bytes(), serial_num.get().to_be_bytes()),
+        };
+        bytes[4..8].copy_from_slice(&body_len_bytes);
+        bytes[8..12].copy_from_slice(&serial_num_bytes);
+        let mut cursor = Cursor::new(&mut bytes);
+        cursor.set_position(body_offset as u64);
+        // SAFETY: There are no FDs involved.
+        unsafe { zvariant::to_writer(&mut cursor, ctxt, body) }?;
+
+        let mut primary_header = self.primary_header.clone();
+        primary_header.set_body_len(body_len);
+        primary_header.set_serial_num(serial_num);
+
+        let bytes = serialized::Data::new(bytes, ctxt);
+
+        Ok(Message {
+            inner: Arc::new(super::Inner {
+                primary_header,
+                quick_fields: self.quick_fields,
+                body: ManuallyDrop::new(bytes.slice(body_offset..)),
+                bytes: ManuallyDrop::new(bytes),
+                body_offset,
+                recv_seq: Sequence::default(),
+                recv_time: None,
+            }),
+        })
+    }
+}
+
+// The `Signature` header field doesn't include the outer parentheses of a structure body.
+fn strip_struct_delimiters(signature: Signature<'_>) -> Signature<'_> {
+    if signature.starts_with(zvariant::STRUCT_SIG_START_STR) {
+        signature.slice(1..signature.len() - 1)
+    } else {
+        signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Factory;
+    use crate::{message::Message, Error};
+    use test_log::test;
+
+    #[test]
+    fn build() -> Result<(), Error> {
+        let builder =
+            Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Level")?.destination(":1.42")?;
+        let factory = Factory::new(builder.clone(), "(su)")?;
+
+        let first = factory.build(&("first", 1u32))?;
+        let second = factory.build(&("second", 2u32))?;
+        assert!(second.primary_header().serial_num() > first.primary_header().serial_num());
+
+        // Same bytes as the messages built the regular way, except for the serial number.
+        let expected = builder.build(&("second", 2u32))?;
+        assert_eq!(second.data()[..8], expected.data()[..8]);
+        assert_eq!(second.data()[12..], expected.data()[12..]);
+
+        let header = second.header();
+        assert_eq!(header.path().unwrap(), "/org/zbus/Meter");
+        assert_eq!(header.member().unwrap(), "Level");
+        assert_eq!(header.destination().unwrap(), ":1.42");
+        assert_eq!(header.signature().unwrap(), "su");
+        assert_eq!(
+            header.primary().serial_num(),
+            second.primary_header().serial_num()
+        );
+        assert_eq!(
+            second.body().deserialize::<(&str, u32)>()?,
+            ("second", 2u32)
+        );
+
+        assert!(matches!(
+            factory.build(&1u32),
+            Err(Error::Variant(zvariant::Error::SignatureMismatch(_, _)))
+        ));
+
+        Ok(())
+    }
+}
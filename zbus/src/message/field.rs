@@ -8,7 +8,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use static_assertions::assert_impl_all;
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
-use zvariant::{ObjectPath, Signature, Type, Value};
+use zvariant::{ObjectPath, Signature, Str, Type, Value};
 
 /// The message field code.
 ///
@@ -40,6 +40,12 @@ pub(super) enum FieldCode {
     Signature = 8,
     /// Code for [`Field::UnixFDs`](enum.Field.html#variant.UnixFDs)
     UnixFDs = 9,
+    /// Code for [`Field::CorrelationId`](enum.Field.html#variant.CorrelationId)
+    ///
+    /// This is a zbus-specific extension and not part of the D-Bus specification. The code is
+    /// picked far from the range used by the specification so that it's unlikely to ever clash
+    /// with a code defined by it.
+    CorrelationId = b'z',
 }
 
 assert_impl_all!(FieldCode: Send, Sync, Unpin);
@@ -57,6 +63,7 @@ impl<'f> Field<'f> {
             Field::Sender(_) => FieldCode::Sender,
             Field::Signature(_) => FieldCode::Signature,
             Field::UnixFDs(_) => FieldCode::UnixFDs,
+            Field::CorrelationId(_) => FieldCode::CorrelationId,
         }
     }
 }
@@ -92,6 +99,8 @@ pub(super) enum Field<'f> {
     Signature(Signature<'f>),
     /// The number of Unix file descriptors that accompany the message.
     UnixFDs(u32),
+    /// An opaque ID correlating this message with others (zbus extension).
+    CorrelationId(Str<'f>),
 }
 
 assert_impl_all!(Field<'_>: Send, Sync, Unpin);
@@ -117,6 +126,7 @@ impl<'f> Serialize for Field<'f> {
             Field::Sender(value) => (FieldCode::Sender, value.as_str().into()),
             Field::Signature(value) => (FieldCode::Signature, value.as_ref().into()),
             Field::UnixFDs(value) => (FieldCode::UnixFDs, (*value).into()),
+            Field::CorrelationId(value) => (FieldCode::CorrelationId, value.as_str().into()),
         };
 
         tuple.serialize(serializer)
@@ -162,6 +172,9 @@ impl<'de: 'f, 'f> Deserialize<'de> for Field<'f> {
                 Field::Signature(Signature::try_from(value).map_err(D::Error::custom)?)
             }
             FieldCode::UnixFDs => Field::UnixFDs(u32::try_from(value).map_err(D::Error::custom)?),
+            FieldCode::CorrelationId => {
+                Field::CorrelationId(Str::try_from(value).map_err(D::Error::custom)?)
+            }
         })
     }
 }
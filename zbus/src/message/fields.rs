@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use static_assertions::assert_impl_all;
 use std::num::NonZeroU32;
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
-use zvariant::{ObjectPath, Signature, Type};
+use zvariant::{ObjectPath, Signature, Str, Type};
 
 use crate::{
     message::{Field, FieldCode, Header, Message},
@@ -152,6 +152,7 @@ pub(crate) struct QuickFields {
     sender: FieldPos,
     signature: FieldPos,
     unix_fds: Option<u32>,
+    correlation_id: FieldPos,
 }
 
 impl QuickFields {
@@ -166,6 +167,7 @@ impl QuickFields {
             sender: FieldPos::new(buf, header.sender()),
             signature: FieldPos::new(buf, header.signature()),
             unix_fds: header.unix_fds(),
+            correlation_id: FieldPos::new(buf, header.correlation_id()),
         })
     }
 
@@ -204,6 +206,10 @@ impl QuickFields {
     pub fn unix_fds(&self) -> Option<u32> {
         self.unix_fds
     }
+
+    pub fn correlation_id<'m>(&self, msg: &'m Message) -> Option<Str<'m>> {
+        self.correlation_id.read(msg.data())
+    }
 }
 
 impl<'m> Default for Fields<'m> {
@@ -11,7 +11,7 @@ use static_assertions::assert_impl_all;
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
 use zvariant::{
     serialized::{self, Context},
-    Endian, ObjectPath, Signature, Type as VariantType,
+    Endian, ObjectPath, Signature, Str, Type as VariantType,
 };
 
 use crate::{
@@ -23,6 +23,13 @@ pub(crate) const PRIMARY_HEADER_SIZE: usize = 12;
 pub(crate) const MIN_MESSAGE_SIZE: usize = PRIMARY_HEADER_SIZE + 4;
 pub(crate) const MAX_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 
+/// The major version of the D-Bus protocol supported by zbus.
+///
+/// Received messages advertising any other version are rejected with
+/// [`Error::UnsupportedProtocolVersion`], as their encoding can't be trusted to be the one zbus
+/// knows about.
+pub const PROTOCOL_VERSION: u8 = 1;
+
 /// D-Bus code for endianness.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Deserialize_repr, PartialEq, Eq, Serialize_repr, VariantType)]
@@ -138,9 +145,9 @@ impl PrimaryHeader {
             endian_sig: NATIVE_ENDIAN_SIG,
             msg_type,
             flags: BitFlags::empty(),
-            protocol_version: 1,
+            protocol_version: PROTOCOL_VERSION,
             body_len,
-            serial_num: SERIAL_NUM.fetch_add(1, SeqCst).try_into().unwrap(),
+            serial_num: next_serial_num(),
         }
     }
 
@@ -155,8 +162,13 @@ impl PrimaryHeader {
     pub(crate) fn read_from_data(
         data: &serialized::Data<'_, '_>,
     ) -> Result<(PrimaryHeader, u32), Error> {
-        let (primary_header, size) = data.deserialize()?;
+        let (primary_header, size): (PrimaryHeader, _) = data.deserialize()?;
         assert_eq!(size, PRIMARY_HEADER_SIZE);
+        if primary_header.protocol_version != PROTOCOL_VERSION {
+            return Err(Error::UnsupportedProtocolVersion(
+                primary_header.protocol_version,
+            ));
+        }
         let (fields_len, _) = data.slice(PRIMARY_HEADER_SIZE..).deserialize()?;
         Ok((primary_header, fields_len))
     }
@@ -193,14 +205,14 @@ impl PrimaryHeader {
 
     /// The major version of the protocol the message is compliant to.
     ///
-    /// Currently only `1` is valid.
+    /// Currently only [`PROTOCOL_VERSION`] is valid.
     pub fn protocol_version(&self) -> u8 {
         self.protocol_version
     }
 
     /// Set the major version of the protocol the message is compliant to.
     ///
-    /// Currently only `1` is valid.
+    /// Currently only [`PROTOCOL_VERSION`] is valid.
     pub fn set_protocol_version(&mut self, version: u8) {
         self.protocol_version = version;
     }
@@ -351,13 +363,27 @@ impl<'m> Header<'m> {
     pub fn unix_fds(&self) -> Option<u32> {
         get_field_u32!(self, UnixFDs)
     }
+
+    /// The correlation ID of the message, if any.
+    ///
+    /// This is a zbus-specific extension to the D-Bus protocol. See
+    /// [`Builder::correlation_id`](super::Builder::correlation_id) for details.
+    pub fn correlation_id(&self) -> Option<&Str<'m>> {
+        get_field!(self, CorrelationId)
+    }
 }
 
 static SERIAL_NUM: AtomicU32 = AtomicU32::new(1);
 
+/// Allocate a new serial number for an outgoing message.
+pub(crate) fn next_serial_num() -> NonZeroU32 {
+    SERIAL_NUM.fetch_add(1, SeqCst).try_into().unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::message::{Field, Fields, Header, PrimaryHeader, Type};
+    use super::PROTOCOL_VERSION;
+    use crate::message::{Field, Fields, Header, Message, PrimaryHeader, Type};
 
     use std::error::Error;
     use test_log::test;
@@ -408,4 +434,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn protocol_version() -> Result<(), Box<dyn Error>> {
+        let msg = Message::signal("/", "org.zbus.Test", "Ping")?.build(&())?;
+        assert_eq!(msg.primary_header().protocol_version(), PROTOCOL_VERSION);
+
+        // A future major version is rejected rather than misparsed.
+        let mut bytes = msg.data().to_vec();
+        bytes[3] = PROTOCOL_VERSION + 1;
+        let res = Message::from_frame(
+            bytes,
+            #[cfg(unix)]
+            vec![],
+        );
+        assert!(matches!(
+            res,
+            Err(crate::Error::UnsupportedProtocolVersion(v)) if v == PROTOCOL_VERSION + 1
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,243 @@
+use std::num::NonZeroU32;
+
+use static_assertions::assert_impl_all;
+use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
+use zvariant::ObjectPath;
+
+use crate::{
+    message::{Message, Type},
+    Error, Result,
+};
+
+/// A [`Message`], viewed according to its type.
+///
+/// Returned by [`Message::kind`]. Each variant only gives access to the header fields that are
+/// meaningful for its type of message, and the ones the specification requires for it are
+/// guaranteed to be present.
+///
+/// # Example
+///
+/// ```
+/// use zbus::message::{Kind, Message};
+///
+/// let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Level")?.build(&42u32)?;
+/// match msg.kind()? {
+///     Kind::Signal(signal) => {
+///         assert_eq!(signal.interface(), "org.zbus.Meter");
+///         assert_eq!(signal.member(), "Level");
+///     }
+///     _ => unreachable!(),
+/// }
+/// # Ok::<(), zbus::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub enum Kind<'m> {
+    /// A method call.
+    MethodCall(MethodCall<'m>),
+    /// A successful reply to a method call.
+    MethodReturn(MethodReturn<'m>),
+    /// An error reply to a method call.
+    Error(ErrorReply<'m>),
+    /// A signal emission.
+    Signal(Signal<'m>),
+}
+
+assert_impl_all!(Kind<'_>: Send, Sync, Unpin);
+
+impl<'m> Kind<'m> {
+    pub(super) fn new(msg: &'m Message) -> Result<Self> {
+        let fields = &msg.inner.quick_fields;
+        let kind = match msg.message_type() {
+            Type::MethodCall => Kind::MethodCall(MethodCall {
+                msg,
+                path: fields.path(msg).ok_or(Error::MissingField)?,
+                member: fields.member(msg).ok_or(Error::MissingField)?,
+            }),
+            Type::MethodReturn => Kind::MethodReturn(MethodReturn {
+                msg,
+                reply_serial: fields.reply_serial().ok_or(Error::MissingField)?,
+            }),
+            Type::Error => Kind::Error(ErrorReply {
+                msg,
+                error_name: fields.error_name(msg).ok_or(Error::MissingField)?,
+                reply_serial: fields.reply_serial().ok_or(Error::MissingField)?,
+            }),
+            Type::Signal => Kind::Signal(Signal {
+                msg,
+                path: fields.path(msg).ok_or(Error::MissingField)?,
+                interface: fields.interface(msg).ok_or(Error::MissingField)?,
+                member: fields.member(msg).ok_or(Error::MissingField)?,
+            }),
+        };
+
+        Ok(kind)
+    }
+
+    /// The message itself.
+    pub fn message(&self) -> &'m Message {
+        match self {
+            Kind::MethodCall(m) => m.msg,
+            Kind::MethodReturn(m) => m.msg,
+            Kind::Error(m) => m.msg,
+            Kind::Signal(m) => m.msg,
+        }
+    }
+}
+
+// The header fields that are optional for all types of messages.
+macro_rules! common_accessors {
+    () => {
+        /// The message itself.
+        pub fn message(&self) -> &'m Message {
+            self.msg
+        }
+
+        /// The name of the connection this message is intended for, if any.
+        pub fn destination(&self) -> Option<BusName<'m>> {
+            self.msg.inner.quick_fields.destination(self.msg)
+        }
+
+        /// The unique name of the sending connection, if any.
+        pub fn sender(&self) -> Option<UniqueName<'m>> {
+            self.msg.inner.quick_fields.sender(self.msg)
+        }
+    };
+}
+
+/// A method call [`Message`].
+///
+/// See [`Kind`].
+#[derive(Debug, Clone)]
+pub struct MethodCall<'m> {
+    msg: &'m Message,
+    path: ObjectPath<'m>,
+    member: MemberName<'m>,
+}
+
+impl<'m> MethodCall<'m> {
+    common_accessors!();
+
+    /// The object to call the method on.
+    pub fn path(&self) -> &ObjectPath<'m> {
+        &self.path
+    }
+
+    /// The interface of the method, if given.
+    pub fn interface(&self) -> Option<InterfaceName<'m>> {
+        self.msg.inner.quick_fields.interface(self.msg)
+    }
+
+    /// The name of the method.
+    pub fn member(&self) -> &MemberName<'m> {
+        &self.member
+    }
+}
+
+/// A method return [`Message`].
+///
+/// See [`Kind`].
+#[derive(Debug, Clone)]
+pub struct MethodReturn<'m> {
+    msg: &'m Message,
+    reply_serial: NonZeroU32,
+}
+
+impl<'m> MethodReturn<'m> {
+    common_accessors!();
+
+    /// The serial number of the method call this is a reply to.
+    pub fn reply_serial(&self) -> NonZeroU32 {
+        self.reply_serial
+    }
+}
+
+/// An error [`Message`].
+///
+/// See [`Kind`].
+#[derive(Debug, Clone)]
+pub struct ErrorReply<'m> {
+    msg: &'m Message,
+    error_name: ErrorName<'m>,
+    reply_serial: NonZeroU32,
+}
+
+impl<'m> ErrorReply<'m> {
+    common_accessors!();
+
+    /// The name of the error.
+    pub fn error_name(&self) -> &ErrorName<'m> {
+        &self.error_name
+    }
+
+    /// The serial number of the method call this is a reply to.
+    pub fn reply_serial(&self) -> NonZeroU32 {
+        self.reply_serial
+    }
+}
+
+/// A signal [`Message`].
+///
+/// See [`Kind`].
+#[derive(Debug, Clone)]
+pub struct Signal<'m> {
+    msg: &'m Message,
+    path: ObjectPath<'m>,
+    interface: InterfaceName<'m>,
+    member: MemberName<'m>,
+}
+
+impl<'m> Signal<'m> {
+    common_accessors!();
+
+    /// The object emitting the signal.
+    pub fn path(&self) -> &ObjectPath<'m> {
+        &self.path
+    }
+
+    /// The interface of the signal.
+    pub fn interface(&self) -> &InterfaceName<'m> {
+        &self.interface
+    }
+
+    /// The name of the signal.
+    pub fn member(&self) -> &MemberName<'m> {
+        &self.member
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Kind;
+    use crate::{message::Message, Error};
+    use test_log::test;
+
+    #[test]
+    fn kind() -> Result<(), Error> {
+        let call = Message::method("/org/zbus/Meter", "Reset")?
+            .destination(":1.42")?
+            .build(&())?;
+        let Kind::MethodCall(view) = call.kind()? else {
+            panic!("not a method call");
+        };
+        assert_eq!(view.path().as_str(), "/org/zbus/Meter");
+        assert_eq!(view.member().as_str(), "Reset");
+        assert!(view.interface().is_none());
+        assert_eq!(view.destination().unwrap(), ":1.42");
+
+        let reply = Message::method_reply(&call)?.build(&())?;
+        let Kind::MethodReturn(view) = reply.kind()? else {
+            panic!("not a method return");
+        };
+        assert_eq!(view.reply_serial(), call.primary_header().serial_num());
+
+        let error = Message::method_error(&call, "org.zbus.Error.Failed")?.build(&())?;
+        let Kind::Error(view) = error.kind()? else {
+            panic!("not an error");
+        };
+        assert_eq!(view.error_name().as_str(), "org.zbus.Error.Failed");
+        assert_eq!(view.reply_serial(), call.primary_header().serial_num());
+        assert_eq!(view.message().message_type(), crate::message::Type::Error);
+
+        Ok(())
+    }
+}
@@ -1,15 +1,21 @@
 //! D-Bus Message.
-use std::{fmt, num::NonZeroU32, sync::Arc};
+use std::{fmt, mem::ManuallyDrop, num::NonZeroU32, sync::Arc, time::Instant};
 
 use static_assertions::assert_impl_all;
 use zbus_names::{ErrorName, InterfaceName, MemberName};
-use zvariant::{serialized, Endian};
+use zvariant::{serialized, Basic, Endian, Signature};
 
 use crate::{utils::padding_for_8_bytes, zvariant::ObjectPath, Error, Result};
 
+mod buffer_pool;
+pub(crate) use buffer_pool::take as take_buffer;
+
 mod builder;
 pub use builder::Builder;
 
+mod factory;
+pub use factory::Factory;
+
 mod field;
 use field::{Field, FieldCode};
 
@@ -17,11 +23,19 @@ mod fields;
 use fields::{Fields, QuickFields};
 
 mod body;
-pub use body::Body;
+pub use body::{Body, BodyCursor};
+
+mod coercion;
+pub use coercion::Coercion;
+
+mod kind;
+pub use kind::{ErrorReply, Kind, MethodCall, MethodReturn, Signal};
 
 pub(crate) mod header;
 use header::MIN_MESSAGE_SIZE;
-pub use header::{EndianSig, Flags, Header, PrimaryHeader, Type, NATIVE_ENDIAN_SIG};
+pub use header::{
+    EndianSig, Flags, Header, PrimaryHeader, Type, NATIVE_ENDIAN_SIG, PROTOCOL_VERSION,
+};
 
 /// A position in the stream of [`Message`] objects received by a single [`zbus::Connection`].
 ///
@@ -43,6 +57,23 @@ impl Sequence {
 /// the [`Message::body`] method, and use [`Body`] methods to deserialize it. You may also access
 /// the header and other details with the various other getters.
 ///
+/// Cloning a message is cheap: the clones share the same, reference-counted, data. This is what
+/// allows a received message to be handed to any number of streams without copying it.
+///
+/// The alternate form of its [`Display`](fmt::Display) implementation (`{:#}`) includes a preview
+/// of the arguments, which comes in handy for logging. It's truncated to the given precision, if
+/// any, or to 256 characters:
+///
+/// ```
+/// # use zbus::message::Message;
+/// let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Changed")?
+///     .build(&("level", 42u32))?;
+/// assert_eq!(format!("{msg}"), "Signal Changed");
+/// assert_eq!(format!("{msg:#}"), r#"Signal Changed ("level", uint32 42)"#);
+/// assert_eq!(format!("{msg:#.8}"), r#"Signal Changed ("level"…"#);
+/// # Ok::<(), zbus::Error>(())
+/// ```
+///
 /// Also provided are constructors for messages of different types. These will mainly be useful for
 /// very advanced use cases as typically you will want to create a message for immediate dispatch
 /// and hence use the API provided by [`Connection`], even when using the low-level API.
@@ -60,9 +91,22 @@ pub struct Message {
 pub(super) struct Inner {
     pub(crate) primary_header: PrimaryHeader,
     pub(crate) quick_fields: QuickFields,
-    pub(crate) bytes: serialized::Data<'static, 'static>,
+    // Only taken on drop, to give the buffer back to the pool.
+    pub(crate) bytes: ManuallyDrop<serialized::Data<'static, 'static>>,
+    // The body part of `bytes`, kept around so the body can be deserialized borrowing from `self`.
+    pub(crate) body: ManuallyDrop<serialized::Data<'static, 'static>>,
     pub(crate) body_offset: usize,
     pub(crate) recv_seq: Sequence,
+    pub(crate) recv_time: Option<Instant>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // SAFETY: Neither `body` nor `bytes` is used anymore after this. The body goes first, as it
+        // shares the buffer with `bytes`.
+        unsafe { ManuallyDrop::drop(&mut self.body) };
+        buffer_pool::recycle(unsafe { ManuallyDrop::take(&mut self.bytes) });
+    }
 }
 
 assert_impl_all!(Message: Send, Sync, Unpin);
@@ -100,12 +144,18 @@ impl Message {
     }
 
     /// Create a builder for message of type [`Type::MethodReturn`].
+    ///
+    /// The destination, reply serial number, endianness and correlation ID of the reply are taken
+    /// from `call`. The rest of the header can be customized through the returned builder before
+    /// building the reply. Any file descriptors in its body are attached to the reply.
     pub fn method_reply(call: &Self) -> Result<Builder<'_>> {
         #[allow(deprecated)]
         Builder::method_return(&call.header())
     }
 
     /// Create a builder for message of type [`Type::Error`].
+    ///
+    /// Just like [`Message::method_reply`], the builder is pre-populated from `call`.
     pub fn method_error<'b, 'e: 'b, E>(call: &Self, name: E) -> Result<Builder<'b>>
     where
         E: TryInto<ErrorName<'e>>,
@@ -125,13 +175,47 @@ impl Message {
     ///
     /// This method is unsafe as bytes may have an invalid encoding.
     pub unsafe fn from_bytes(bytes: serialized::Data<'static, 'static>) -> Result<Self> {
-        Self::from_raw_parts(bytes, 0)
+        Self::from_raw_parts(bytes, 0, None)
+    }
+
+    /// Create a message from a complete, encoded frame, validating its length against the one
+    /// advertised in the header.
+    pub(crate) fn from_frame(
+        bytes: Vec<u8>,
+        #[cfg(unix)] fds: Vec<zvariant::OwnedFd>,
+    ) -> Result<Self> {
+        let expected_len = Self::frame_len(&bytes)?;
+        if expected_len != bytes.len() {
+            return Err(Error::Failure(format!(
+                "frame is {} bytes long but its header describes a {expected_len} bytes message",
+                bytes.len()
+            )));
+        }
+
+        let (primary_header, _) = PrimaryHeader::read(&bytes)?;
+        let ctxt = serialized::Context::new_dbus(primary_header.endian_sig().into(), 0);
+        #[cfg(unix)]
+        let bytes = serialized::Data::new_fds(bytes, ctxt, fds);
+        #[cfg(not(unix))]
+        let bytes = serialized::Data::new(bytes, ctxt);
+
+        Self::from_raw_parts(bytes, 0, None)
+    }
+
+    /// The length of the encoded message frame that `bytes` starts with.
+    pub(crate) fn frame_len(bytes: &[u8]) -> Result<usize> {
+        zbus_inspect::frame_len(bytes).map_err(|e| match e {
+            zbus_inspect::Error::ExcessData(_) => Error::ExcessData,
+            zbus_inspect::Error::InvalidEndian(_) => Error::IncorrectEndian,
+            e => Error::Failure(e.to_string()),
+        })
     }
 
     /// Create a message from its full contents
     pub(crate) fn from_raw_parts(
         bytes: serialized::Data<'static, 'static>,
         recv_seq: u64,
+        recv_time: Option<Instant>,
     ) -> Result<Self> {
         let endian = Endian::from(EndianSig::try_from(bytes[0])?);
         if endian != bytes.context().endian() {
@@ -149,13 +233,53 @@ impl Message {
             inner: Arc::new(Inner {
                 primary_header,
                 quick_fields,
-                bytes,
+                body: ManuallyDrop::new(bytes.slice(body_offset.min(bytes.len())..)),
+                bytes: ManuallyDrop::new(bytes),
                 body_offset,
                 recv_seq: Sequence { recv_seq },
+                recv_time,
             }),
         })
     }
 
+    /// Copy the message with a new serial number and optionally, a different reply serial.
+    ///
+    /// Used for forwarding messages between connections, which each have their own serials.
+    #[cfg(feature = "p2p")]
+    pub(crate) fn with_new_serial(&self, reply_serial: Option<NonZeroU32>) -> Result<Self> {
+        let mut header = self.header();
+        header
+            .primary_mut()
+            .set_serial_num(header::next_serial_num());
+        if let Some(serial) = reply_serial {
+            header.fields_mut().replace(Field::ReplySerial(serial));
+        }
+
+        let ctxt = serialized::Context::new_dbus(header.primary().endian_sig().into(), 0);
+        let hdr_len = *zvariant::serialized_size(ctxt, &header)?;
+        let body_offset = hdr_len + padding_for_8_bytes(hdr_len);
+        let mut bytes = Vec::with_capacity(body_offset + self.inner.body.len());
+        // SAFETY: The header doesn't contain any FDs.
+        unsafe { zvariant::to_writer(&mut std::io::Cursor::new(&mut bytes), ctxt, &header) }?;
+        bytes.resize(body_offset, 0);
+        bytes.extend_from_slice(&self.inner.body);
+
+        #[cfg(unix)]
+        let bytes = {
+            let fds = self
+                .data()
+                .fds()
+                .iter()
+                .map(|fd| fd.try_to_owned().map(zvariant::OwnedFd::from))
+                .collect::<zvariant::Result<Vec<_>>>()?;
+            serialized::Data::new_fds(bytes, ctxt, fds)
+        };
+        #[cfg(not(unix))]
+        let bytes = serialized::Data::new(bytes, ctxt);
+
+        Self::from_raw_parts(bytes, 0, None)
+    }
+
     pub fn primary_header(&self) -> &PrimaryHeader {
         &self.inner.primary_header
     }
@@ -195,6 +319,9 @@ impl Message {
         if let Some(u) = quick_fields.unix_fds() {
             fields.add(Field::UnixFDs(u));
         }
+        if let Some(c) = quick_fields.correlation_id(self) {
+            fields.add(Field::CorrelationId(c));
+        }
 
         Header::new(self.inner.primary_header.clone(), fields)
     }
@@ -204,6 +331,19 @@ impl Message {
         self.inner.primary_header.msg_type()
     }
 
+    /// A view of the message specific to its type.
+    ///
+    /// See [`Kind`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MissingField`] if a header field required for the type of the message is missing.
+    /// Messages built through the constructors for each type, such as [`Message::method`], always
+    /// have them. Received messages, and the ones built with [`Builder::new`], might not.
+    pub fn kind(&self) -> Result<Kind<'_>> {
+        Kind::new(self)
+    }
+
     /// The object to send a call to, or the object a signal is emitted from.
     #[deprecated(
         since = "4.0.0",
@@ -267,10 +407,81 @@ impl Message {
     /// # Ok(()) })().unwrap()
     /// ```
     pub fn body(&self) -> Body {
-        Body::new(
-            self.inner.bytes.slice(self.inner.body_offset..),
-            self.clone(),
-        )
+        Body::new((*self.inner.body).clone(), self.clone())
+    }
+
+    /// Deserialize the body, borrowing from the message.
+    ///
+    /// This is the same as deserializing the [`Body`] returned by [`Message::body`], except that
+    /// the deserialized value can borrow from the message itself. Large `ay` arguments, for
+    /// instance, can then be deserialized to a `&[u8]` pointing into the message, with no copy
+    /// and without having to keep the [`Body`] around.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zbus::message::Message;
+    /// fn thumbnail(msg: &Message) -> zbus::Result<(&str, &[u8])> {
+    ///     msg.deserialize_body()
+    /// }
+    ///
+    /// let msg = Message::method("/org/zbus/Thumbnailer", "Thumbnail")?
+    ///     .build(&("image/png", &[0x89u8, b'P', b'N', b'G'][..]))?;
+    /// assert_eq!(thumbnail(&msg)?, ("image/png", &b"\x89PNG"[..]));
+    /// # Ok::<(), zbus::Error>(())
+    /// ```
+    pub fn deserialize_body<'m, B>(&'m self) -> Result<B>
+    where
+        B: zvariant::DynamicDeserialize<'m>,
+    {
+        let signature = self
+            .inner
+            .quick_fields
+            .signature(self)
+            .unwrap_or_else(|| Signature::from_static_str_unchecked(""));
+
+        self.inner
+            .body
+            .deserialize_for_dynamic_signature(signature)
+            .map_err(Error::from)
+            .map(|b| b.0)
+    }
+
+    /// The first argument of the body, if it's a string.
+    ///
+    /// This is a lot cheaper than deserializing the body, as only the first argument is looked at.
+    /// It's what match rules with `arg0` or `arg0namespace` are checked against, e.g. to filter
+    /// `NameOwnerChanged` signals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zbus::message::Message;
+    /// let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Changed")?
+    ///     .build(&("level", vec![0u8; 1024]))?;
+    /// assert_eq!(msg.arg0_str(), Some("level"));
+    ///
+    /// let msg = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Level")?.build(&42u32)?;
+    /// assert_eq!(msg.arg0_str(), None);
+    /// # Ok::<(), zbus::Error>(())
+    /// ```
+    pub fn arg0_str(&self) -> Option<&str> {
+        let signature = self.inner.quick_fields.signature(self)?;
+        if !signature.starts_with(<&str>::SIGNATURE_CHAR) {
+            return None;
+        }
+
+        // A string is its `u32` length, its bytes and a nul byte. As the body is 8-bytes aligned,
+        // there is no padding before it.
+        let body = &self.inner.bytes[self.inner.body_offset..];
+        let len = body.get(..4)?.try_into().ok()?;
+        let len = match self.inner.bytes.context().endian() {
+            Endian::Little => u32::from_le_bytes(len),
+            Endian::Big => u32::from_be_bytes(len),
+        };
+        let bytes = body.get(4..)?.get(..usize::try_from(len).ok()?)?;
+
+        std::str::from_utf8(bytes).ok()
     }
 
     /// Get a reference to the underlying byte encoding of the message.
@@ -288,6 +499,25 @@ impl Message {
     pub fn recv_position(&self) -> Sequence {
         self.inner.recv_seq
     }
+
+    /// The receive sequence number of the message.
+    ///
+    /// This is the numeric value behind [`Message::recv_position`]: messages received on the same
+    /// [`zbus::Connection`] are numbered consecutively, which allows detecting gaps when
+    /// consuming only a subset of them. It's `0` for messages that were not received from a
+    /// connection.
+    pub fn recv_seq(&self) -> u64 {
+        self.inner.recv_seq.recv_seq
+    }
+
+    /// The time at which the message was received.
+    ///
+    /// This is a monotonic timestamp, taken as soon as the message has been fully read from the
+    /// socket, so it can be used for measuring latencies. It's `None` for messages that were not
+    /// received from a connection.
+    pub fn recv_time(&self) -> Option<Instant> {
+        self.inner.recv_time
+    }
 }
 
 impl fmt::Debug for Message {
@@ -314,6 +544,9 @@ impl fmt::Debug for Message {
         if let Some(s) = self.body().signature() {
             msg.field("body", &s);
         }
+        if let Some(id) = h.correlation_id() {
+            msg.field("correlation-id", &id);
+        }
         #[cfg(unix)]
         {
             msg.field("fds", &self.data().fds());
@@ -366,6 +599,16 @@ impl fmt::Display for Message {
             write!(f, " from {s}")?;
         }
 
+        if let Some(signature) = self.inner.quick_fields.signature(self) {
+            if f.alternate() {
+                let args = zbus_inspect::ArgsPreview::new(&self.inner.body, signature);
+                match f.precision() {
+                    Some(max_len) => write!(f, " {args:.max_len$}")?,
+                    None => write!(f, " {args}")?,
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -425,4 +668,142 @@ mod tests {
             .unwrap();
         assert_eq!(e.to_string(), "Error org.freedesktop.zbus.Error: kaboom!");
     }
+
+    #[test]
+    fn from_frame() {
+        let m = Message::method("/", "do").unwrap().build(&"foo").unwrap();
+        let bytes = m.data().to_vec();
+
+        let frame = Message::from_frame(
+            bytes.clone(),
+            #[cfg(unix)]
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(frame.to_string(), "Method call do");
+        assert_eq!(frame.body().deserialize::<&str>().unwrap(), "foo");
+        assert_eq!(Message::frame_len(&bytes).unwrap(), bytes.len());
+        assert_eq!(Message::frame_len(&bytes[..16]).unwrap(), bytes.len());
+        assert!(Message::frame_len(&bytes[..15]).is_err());
+
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+        assert!(Message::from_frame(
+            truncated,
+            #[cfg(unix)]
+            vec![],
+        )
+        .is_err());
+        assert!(Message::from_frame(
+            vec![b'l'; 8],
+            #[cfg(unix)]
+            vec![],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn body_cursor() {
+        use std::collections::HashMap;
+        use zvariant::Value;
+
+        let props = HashMap::from([("level", Value::from(7u32)), ("name", Value::from("meter"))]);
+        let m = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Changed")
+            .unwrap()
+            .build(&(
+                Value::from((1u8, "nested")),
+                &props,
+                vec![3u64; 100],
+                "last",
+            ))
+            .unwrap();
+        let body = m.body();
+        let mut cursor = body.cursor().unwrap();
+
+        assert_eq!(cursor.peek_signature().unwrap(), "v");
+        assert!(cursor.skip().unwrap());
+        assert_eq!(cursor.peek_signature().unwrap(), "a{sv}");
+        assert!(cursor.skip().unwrap());
+        assert!(cursor.deserialize_next::<u64>().is_err());
+        assert_eq!(
+            cursor
+                .deserialize_next::<Vec<u64>>()
+                .unwrap()
+                .unwrap()
+                .len(),
+            100
+        );
+        assert_eq!(
+            cursor.deserialize_next::<String>().unwrap().unwrap(),
+            "last"
+        );
+        assert!(cursor.peek_signature().is_none());
+        assert!(!cursor.skip().unwrap());
+
+        let m = Message::method("/", "Ping").unwrap().build(&()).unwrap();
+        let body = m.body();
+        let mut cursor = body.cursor().unwrap();
+        assert!(cursor.deserialize_next::<u32>().unwrap().is_none());
+    }
+
+    #[test]
+    fn args_preview() {
+        use std::collections::HashMap;
+        use zvariant::{ObjectPath, Structure, Value};
+
+        // The preview matches the display of the decoded arguments.
+        let m = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Changed")
+            .unwrap()
+            .build(&(
+                Value::from((1u8, "nested")),
+                HashMap::from([("level", Value::from(7u32))]),
+                vec![3u64; 3],
+                ObjectPath::from_static_str_unchecked("/org/zbus/Meter"),
+                Vec::<String>::new(),
+                -2.0f64,
+            ))
+            .unwrap();
+        let args = m.deserialize_body::<Structure<'_>>().unwrap();
+        assert_eq!(format!("{m:#}"), format!("Signal Changed {args}"));
+
+        // Only what fits in the preview is decoded.
+        let samples = vec![0u8; 1024 * 1024];
+        let m = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Samples")
+            .unwrap()
+            .build(&(&samples, "left"))
+            .unwrap();
+        assert_eq!(
+            format!("{m:#.24}"),
+            "Signal Samples ([byte 0x00, 0x00, 0x00,…"
+        );
+        assert_eq!(format!("{m:#}").chars().count(), 15 + 256 + 1);
+
+        // Containers nested too deeply are elided.
+        let mut value = Value::from(42u32);
+        for _ in 0..10 {
+            value = Value::Value(Box::new(value));
+        }
+        let m = Message::signal("/org/zbus/Meter", "org.zbus.Meter", "Nested")
+            .unwrap()
+            .build(&(value, "last"))
+            .unwrap();
+        assert_eq!(
+            format!("{m:#}"),
+            r#"Signal Nested (<<<<<<<<…>>>>>>>>, "last")"#
+        );
+    }
+
+    #[test]
+    fn borrowed_body() {
+        let payload = vec![7u8; 4096];
+        let m = Message::method("/", "Store")
+            .unwrap()
+            .build(&("name", &payload))
+            .unwrap();
+
+        let (name, data): (&str, &[u8]) = m.deserialize_body().unwrap();
+        assert_eq!(name, "name");
+        assert_eq!(data, payload);
+        // No copy involved.
+        assert!(m.data().as_ptr_range().contains(&data.as_ptr()));
+    }
 }
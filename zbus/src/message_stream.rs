@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -9,11 +10,11 @@ use futures_core::stream;
 use futures_util::stream::FusedStream;
 use ordered_stream::{OrderedStream, PollResult};
 use static_assertions::assert_impl_all;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use crate::{
     connection::ConnectionInner,
-    message::{Message, Sequence},
+    message::{Message, Sequence, Type},
     AsyncDrop, Connection, MatchRule, OwnedMatchRule, Result,
 };
 
@@ -144,9 +145,133 @@ impl MessageStream {
         ))
     }
 
+    /// Create a message stream for the given match rule, that replays the last signals.
+    ///
+    /// This is the same as [`MessageStream::for_match_rule`], except that the connection also
+    /// retains the last signal received for each combination of interface, member and path
+    /// matching `rule`. The stream first yields the signals retained so far, in the order they
+    /// were received, followed by any new messages matching `rule`.
+    ///
+    /// This is useful for signals that broadcast a state, as it saves new subscribers from having
+    /// to query the current state separately.
+    ///
+    /// Signals are only retained from the creation of the first sticky stream for `rule` and until
+    /// all streams for `rule` are dropped.
+    pub async fn for_sticky_match_rule<R>(
+        rule: R,
+        conn: &Connection,
+        max_queued: Option<usize>,
+    ) -> Result<Self>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<crate::Error>,
+    {
+        let rule = rule.try_into().map_err(Into::into)?;
+        let (msg_receiver, last) = conn.add_sticky_match(rule.clone(), max_queued).await?;
+
+        let mut stream = Self::for_subscription_channel(msg_receiver, Some(rule), conn);
+        stream.inner.replay = last.into();
+
+        Ok(stream)
+    }
+
     /// The associated match rule, if any.
     pub fn match_rule(&self) -> Option<MatchRule<'_>> {
-        self.inner.match_rule.as_deref().cloned()
+        self.inner
+            .match_rule
+            .as_deref()
+            .map(|rule| (**rule).clone())
+    }
+
+    /// A view of this stream, only yielding the messages that also match `rule`.
+    ///
+    /// The view is a new stream, reading from the same source as this one from now on: both
+    /// receive all the messages, so consuming one doesn't affect the other. Unlike
+    /// [`MessageStream::for_match_rule`], `rule` is not registered with the bus but only applied
+    /// locally, so the view can only yield messages this stream would have yielded. Any match rule
+    /// of this stream stays registered until this stream and all its views are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use zbus::{Connection, MessageStream};
+    ///
+    /// # zbus::block_on(async {
+    /// let conn = Connection::session().await?;
+    /// let stream = MessageStream::from(&conn);
+    /// let mut replies = stream.filter_rule("type='method_return',sender='org.freedesktop.DBus'")?;
+    /// let mut errors = stream.errors();
+    /// drop(stream);
+    ///
+    /// conn.call_method(
+    ///     Some("org.freedesktop.DBus"),
+    ///     "/org/freedesktop/DBus",
+    ///     Some("org.freedesktop.DBus.Peer"),
+    ///     "Ping",
+    ///     &(),
+    /// )
+    /// .await?;
+    /// let reply = replies.try_next().await?.unwrap();
+    /// assert_eq!(reply.message_type(), zbus::message::Type::MethodReturn);
+    ///
+    /// conn.call_method(
+    ///     Some("org.freedesktop.DBus"),
+    ///     "/org/freedesktop/DBus",
+    ///     Some("org.freedesktop.DBus"),
+    ///     "NoSuchMethod",
+    ///     &(),
+    /// )
+    /// .await
+    /// .unwrap_err();
+    /// let error = errors.try_next().await?.unwrap();
+    /// assert_eq!(error.message_type(), zbus::message::Type::Error);
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// # Caveats
+    ///
+    /// Since this method relies on [`MatchRule::matches`], it inherits its caveats.
+    pub fn filter_rule<R>(&self, rule: R) -> Result<Self>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<crate::Error>,
+    {
+        let rule = rule.try_into().map_err(Into::into)?;
+        let mut view = self.clone();
+        view.inner.filters.push(rule);
+
+        Ok(view)
+    }
+
+    /// A view of this stream, only yielding method calls.
+    ///
+    /// See [`MessageStream::filter_rule`] for details.
+    pub fn method_calls(&self) -> Self {
+        self.msg_type_view(Type::MethodCall)
+    }
+
+    /// A view of this stream, only yielding signals.
+    ///
+    /// See [`MessageStream::filter_rule`] for details.
+    pub fn signals(&self) -> Self {
+        self.msg_type_view(Type::Signal)
+    }
+
+    /// A view of this stream, only yielding error replies.
+    ///
+    /// See [`MessageStream::filter_rule`] for details.
+    pub fn errors(&self) -> Self {
+        self.msg_type_view(Type::Error)
+    }
+
+    fn msg_type_view(&self, msg_type: Type) -> Self {
+        let rule = MatchRule::builder().msg_type(msg_type).build();
+        let mut view = self.clone();
+        view.inner.filters.push(rule.into());
+
+        view
     }
 
     /// The maximum number of messages to queue for this stream.
@@ -175,7 +300,9 @@ impl MessageStream {
             inner: Inner {
                 conn_inner,
                 msg_receiver,
-                match_rule: rule,
+                match_rule: rule.map(Arc::new),
+                filters: vec![],
+                replay: VecDeque::new(),
             },
         }
     }
@@ -186,8 +313,18 @@ impl stream::Stream for MessageStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.get_mut();
+        while let Some(msg) = this.inner.replay.pop_front() {
+            if this.inner.passes_filters(&msg) {
+                return Poll::Ready(Some(Ok(msg)));
+            }
+        }
 
-        Pin::new(&mut this.inner.msg_receiver).poll_next(cx)
+        loop {
+            match Pin::new(&mut this.inner.msg_receiver).poll_next(cx) {
+                Poll::Ready(Some(Ok(msg))) if !this.inner.passes_filters(&msg) => continue,
+                poll => return poll,
+            }
+        }
     }
 }
 
@@ -232,7 +369,7 @@ impl OrderedStream for MessageStream {
 
 impl FusedStream for MessageStream {
     fn is_terminated(&self) -> bool {
-        self.inner.msg_receiver.is_terminated()
+        self.inner.replay.is_empty() && self.inner.msg_receiver.is_terminated()
     }
 }
 
@@ -246,6 +383,8 @@ impl From<Connection> for MessageStream {
                 conn_inner,
                 msg_receiver,
                 match_rule: None,
+                filters: vec![],
+                replay: VecDeque::new(),
             },
         }
     }
@@ -275,7 +414,30 @@ impl From<&MessageStream> for Connection {
 struct Inner {
     conn_inner: Arc<ConnectionInner>,
     msg_receiver: ActiveReceiver<Result<Message>>,
-    match_rule: Option<OwnedMatchRule>,
+    // Shared between clones and views, so that the rule is only removed once all are dropped.
+    match_rule: Option<Arc<OwnedMatchRule>>,
+    // Rules applied locally, for views.
+    filters: Vec<OwnedMatchRule>,
+    // Retained signals to yield before any new message.
+    replay: VecDeque<Message>,
+}
+
+impl Inner {
+    fn passes_filters(&self, msg: &Message) -> bool {
+        self.filters.iter().all(|rule| match rule.matches(msg) {
+            Ok(matches) => matches,
+            Err(e) => {
+                debug!("Error matching message against rule: {:?}", e);
+
+                false
+            }
+        })
+    }
+
+    /// The match rule, if this is the last stream using it.
+    fn take_match_rule(&mut self) -> Option<OwnedMatchRule> {
+        self.match_rule.take().and_then(Arc::into_inner)
+    }
 }
 
 impl Drop for Inner {
@@ -284,7 +446,7 @@ impl Drop for Inner {
             inner: self.conn_inner.clone(),
         };
 
-        if let Some(rule) = self.match_rule.take() {
+        if let Some(rule) = self.take_match_rule() {
             conn.queue_remove_match(rule);
         }
     }
@@ -297,7 +459,7 @@ impl AsyncDrop for MessageStream {
             inner: self.inner.conn_inner.clone(),
         };
 
-        if let Some(rule) = self.inner.match_rule.take() {
+        if let Some(rule) = self.inner.take_match_rule() {
             if let Err(e) = conn.remove_match(rule).await {
                 warn!("Failed to remove match rule: {}", e);
             }
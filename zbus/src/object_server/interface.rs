@@ -72,6 +72,18 @@ pub trait Interface: Any + Send + Sync {
     where
         Self: Sized;
 
+    /// Prepare `ctxt` for emitting the signals of this interface.
+    ///
+    /// Interfaces extending another one make the signals of the base interface, including its
+    /// `PropertiesChanged` signals, be emitted under their own name. The default implementation
+    /// returns `ctxt` as is.
+    fn extend_signal_context(ctxt: SignalContext<'_>) -> SignalContext<'_>
+    where
+        Self: Sized,
+    {
+        ctxt
+    }
+
     /// Whether each method call will be handled from a different spawned task.
     ///
     /// Note: When methods are called from separate tasks, they may not be run in the order in which
@@ -140,6 +152,14 @@ pub trait Interface: Any + Send + Sync {
 
     /// Write introspection XML to the writer, with the given indentation level.
     fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize);
+
+    /// Write introspection XML of the interface members to the writer, with the given indentation
+    /// level.
+    ///
+    /// Unlike [`Interface::introspect_to_writer`], this doesn't write the enclosing `interface`
+    /// element. It's used for merging the members of this interface into an interface extending
+    /// it. The default implementation writes nothing.
+    fn introspect_members_to_writer(&self, _writer: &mut dyn Write, _level: usize) {}
 }
 
 /// A type for a reference counted Interface trait-object, with associated run-time details and a
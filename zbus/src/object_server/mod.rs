@@ -1,18 +1,21 @@
 //! The object server API.
 
 use event_listener::{Event, EventListener};
+use futures_util::FutureExt;
 use serde::Serialize;
 use std::{
+    any::Any,
     collections::{hash_map::Entry, HashMap},
     fmt::Write,
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    panic::{catch_unwind, AssertUnwindSafe},
     sync::Arc,
 };
-use tracing::{debug, instrument, trace, trace_span, Instrument};
+use tracing::{debug, instrument, trace, trace_span, warn, Instrument};
 
 use static_assertions::assert_impl_all;
-use zbus_names::InterfaceName;
+use zbus_names::{InterfaceName, MemberName};
 use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Signature, Type, Value};
 
 use crate::{
@@ -31,6 +34,9 @@ pub use interface::{DispatchResult, Interface};
 mod signal_context;
 pub use signal_context::SignalContext;
 
+mod tree;
+pub use tree::ObjectTree;
+
 /// Opaque structure that derefs to an `Interface` type.
 pub struct InterfaceDeref<'d, I> {
     iface: RwLockReadGuard<'d, dyn Interface>,
@@ -518,6 +524,25 @@ impl ObjectServer {
     {
         let path = path.try_into().map_err(Into::into)?;
         let mut root = self.root().write().await;
+        let added = self
+            .add_arc_interface_locked(&mut root, path, name, arc_iface)
+            .await?;
+        drop(root);
+        if added {
+            self.connection().object_server_started().await;
+        }
+
+        Ok(added)
+    }
+
+    // Add the interface to the tree of `root`, which the caller holds the write lock of.
+    async fn add_arc_interface_locked(
+        &self,
+        root: &mut Node,
+        path: ObjectPath<'_>,
+        name: InterfaceName<'static>,
+        arc_iface: ArcInterface,
+    ) -> Result<bool> {
         let (node, manager_path) = root.get_child_mut(&path, true);
         let node = node.unwrap();
         let added = node.add_arc_interface(name.clone(), arc_iface);
@@ -556,6 +581,47 @@ impl ObjectServer {
         Ok(added)
     }
 
+    /// Register all the interfaces of an [`ObjectTree`], with `prefix` as its root.
+    ///
+    /// # Errors
+    ///
+    /// If any of the interfaces already exists at its path, [`Error::InterfaceExists`] is
+    /// returned and none of the interfaces are registered.
+    pub async fn mount<'p, P>(&self, prefix: P, tree: ObjectTree) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let prefix = prefix.try_into().map_err(Into::into)?;
+        let interfaces: Vec<_> = tree.into_interfaces(&prefix).collect();
+        // The check and the additions are done under the same lock, so that no conflicting
+        // interface can be registered in between.
+        let mut root = self.root().write().await;
+        for (path, ifaces) in &interfaces {
+            let Some(node) = root.get_child(path) else {
+                continue;
+            };
+            if let Some(name) = ifaces.keys().find(|n| node.interfaces.contains_key(*n)) {
+                return Err(Error::InterfaceExists(name.clone(), path.clone()));
+            }
+        }
+
+        let mut added = false;
+        for (path, ifaces) in interfaces {
+            for (name, iface) in ifaces {
+                added |= self
+                    .add_arc_interface_locked(&mut root, path.clone(), name, iface)
+                    .await?;
+            }
+        }
+        drop(root);
+        if added {
+            self.connection().object_server_started().await;
+        }
+
+        Ok(())
+    }
+
     /// Unregister a D-Bus [`Interface`] at a given path.
     ///
     /// If there are no more interfaces left at that path, destroys the object as well.
@@ -658,7 +724,7 @@ impl ObjectServer {
 
         let conn = self.connection();
         // SAFETY: We know that there is a valid path on the node as we already converted w/o error.
-        let ctxt = SignalContext::new(&conn, path).unwrap().into_owned();
+        let ctxt = I::extend_signal_context(SignalContext::new(&conn, path).unwrap()).into_owned();
 
         Ok(InterfaceRef {
             ctxt,
@@ -684,17 +750,17 @@ impl ObjectServer {
         trace!("acquiring read lock on interface `{}`", iface_name);
         let read_lock = iface.read().await;
         trace!("acquired read lock on interface `{}`", iface_name);
-        match read_lock.call(self, connection, msg, member.as_ref()) {
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            read_lock.call(self, connection, msg, member.as_ref())
+        }));
+        match res.map_err(|payload| method_panicked(member, payload))? {
             DispatchResult::NotFound => {
                 return Err(fdo::Error::UnknownMethod(format!(
                     "Unknown method '{member}'"
                 )));
             }
             DispatchResult::Async(f) => {
-                return f.await.map_err(|e| match e {
-                    Error::FDO(e) => *e,
-                    e => fdo::Error::Failed(format!("{e}")),
-                });
+                return dispatch_result(f, member).await;
             }
             DispatchResult::RequiresMut => {}
         }
@@ -702,14 +768,17 @@ impl ObjectServer {
         trace!("acquiring write lock on interface `{}`", iface_name);
         let mut write_lock = iface.write().await;
         trace!("acquired write lock on interface `{}`", iface_name);
-        match write_lock.call_mut(self, connection, msg, member.as_ref()) {
+        let iface_mut = &mut *write_lock;
+        let res = catch_unwind(AssertUnwindSafe(move || {
+            // Move the reference in, so the result can borrow from it.
+            let iface_mut = iface_mut;
+            iface_mut.call_mut(self, connection, msg, member.as_ref())
+        }));
+        match res.map_err(|payload| method_panicked(member, payload))? {
             DispatchResult::NotFound => {}
             DispatchResult::RequiresMut => {}
             DispatchResult::Async(f) => {
-                return f.await.map_err(|e| match e {
-                    Error::FDO(e) => *e,
-                    e => fdo::Error::Failed(format!("{e}")),
-                });
+                return dispatch_result(f, member).await;
             }
         }
         drop(write_lock);
@@ -767,9 +836,15 @@ impl ObjectServer {
                     async move {
                         let server = connection.object_server();
                         let hdr = msg.header();
-                        server
+                        if let Err(e) = server
                             .dispatch_call_to_iface(iface, &connection, &msg, &hdr)
                             .await
+                        {
+                            debug!("Returning error: {}", e);
+                            connection.reply_dbus_error(&hdr, e).await?;
+                        }
+
+                        Ok::<_, Error>(())
                     }
                     .instrument(trace_span!("{}", task_name)),
                     &task_name,
@@ -794,7 +869,10 @@ impl ObjectServer {
     ///   the caller through the associated server connection.
     ///
     /// Returns an error if the message is malformed.
-    #[instrument(skip(self))]
+    #[instrument(
+        skip(self),
+        fields(correlation_id = hdr.correlation_id().map(|id| id.as_str()))
+    )]
     pub(crate) async fn dispatch_call(&self, msg: &Message, hdr: &Header<'_>) -> Result<()> {
         let conn = self.connection();
 
@@ -814,6 +892,32 @@ impl ObjectServer {
     }
 }
 
+// Await the future of a method call, turning a panic into an error reply so that the object
+// server keeps serving subsequent calls.
+async fn dispatch_result(
+    f: impl std::future::Future<Output = Result<()>>,
+    member: &MemberName<'_>,
+) -> fdo::Result<()> {
+    match AssertUnwindSafe(f).catch_unwind().await {
+        Ok(res) => res.map_err(|e| match e {
+            Error::FDO(e) => *e,
+            e => fdo::Error::Failed(format!("{e}")),
+        }),
+        Err(payload) => Err(method_panicked(member, payload)),
+    }
+}
+
+fn method_panicked(member: &MemberName<'_>, payload: Box<dyn Any + Send>) -> fdo::Error {
+    let reason = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown reason");
+    warn!("Method '{member}' panicked: {reason}");
+
+    fdo::Error::Failed(format!("Method '{member}' panicked: {reason}"))
+}
+
 impl From<crate::blocking::ObjectServer> for ObjectServer {
     fn from(server: crate::blocking::ObjectServer) -> Self {
         server.into_inner()
@@ -1,4 +1,4 @@
-use zbus_names::BusName;
+use zbus_names::{BusName, InterfaceName};
 
 use crate::{zvariant::ObjectPath, Connection, Error, Result};
 
@@ -13,6 +13,7 @@ pub struct SignalContext<'s> {
     conn: Connection,
     path: ObjectPath<'s>,
     destination: Option<BusName<'s>>,
+    extended_interfaces: Vec<(InterfaceName<'static>, InterfaceName<'static>)>,
 }
 
 impl<'s> SignalContext<'s> {
@@ -27,6 +28,7 @@ impl<'s> SignalContext<'s> {
                 conn: conn.clone(),
                 path: p,
                 destination: None,
+                extended_interfaces: vec![],
             })
             .map_err(Into::into)
     }
@@ -37,6 +39,7 @@ impl<'s> SignalContext<'s> {
             conn,
             path,
             destination: None,
+            extended_interfaces: vec![],
         }
     }
 
@@ -51,6 +54,45 @@ impl<'s> SignalContext<'s> {
         self
     }
 
+    /// Emit the signals of the `base` interface under the name of the `derived` interface.
+    ///
+    /// Interfaces extending another one (see the `extends` attribute of [`crate::interface`])
+    /// expose the members of the base interface as their own, so the signals of the base need to be
+    /// emitted under the name of the extending interface for clients to see them. You don't
+    /// typically need to call this method yourself: the signal context of an
+    /// [`crate::InterfaceRef`] and the one passed to the property setters of the base are already
+    /// set up for it.
+    pub fn set_extended_interface(
+        mut self,
+        base: InterfaceName<'static>,
+        derived: InterfaceName<'static>,
+    ) -> Self {
+        self.extended_interfaces.push((base, derived));
+
+        self
+    }
+
+    /// The name of the interface to emit the signals of `interface` under.
+    ///
+    /// This is `interface` itself, unless it's extended by another interface (see
+    /// [`SignalContext::set_extended_interface`]).
+    pub fn interface_name(&self, interface: InterfaceName<'static>) -> InterfaceName<'static> {
+        let mut name = interface;
+        // Bounded, in case of a cycle.
+        for _ in 0..self.extended_interfaces.len() {
+            match self
+                .extended_interfaces
+                .iter()
+                .find(|(base, _)| *base == name)
+            {
+                Some((_, derived)) => name = derived.clone(),
+                None => break,
+            }
+        }
+
+        name
+    }
+
     /// Get a reference to the associated connection.
     pub fn connection(&self) -> &Connection {
         &self.conn
@@ -72,6 +114,7 @@ impl<'s> SignalContext<'s> {
             conn: self.conn.clone(),
             path: self.path.to_owned(),
             destination: self.destination.as_ref().map(|d| d.to_owned()),
+            extended_interfaces: self.extended_interfaces.clone(),
         }
     }
 
@@ -81,6 +124,7 @@ impl<'s> SignalContext<'s> {
             conn: self.conn,
             path: self.path.into_owned(),
             destination: self.destination.map(|d| d.into_owned()),
+            extended_interfaces: self.extended_interfaces,
         }
     }
 }
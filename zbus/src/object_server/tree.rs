@@ -0,0 +1,205 @@
+use static_assertions::assert_impl_all;
+use std::collections::HashMap;
+use zbus_names::InterfaceName;
+use zvariant::ObjectPath;
+
+use super::{ArcInterface, Interface};
+use crate::{Error, Result};
+
+type Interfaces = HashMap<InterfaceName<'static>, ArcInterface>;
+
+/// A tree of D-Bus objects, not yet served on any connection.
+///
+/// This allows reusable components, such as the implementation of a standard D-Bus API, to ship
+/// a whole hierarchy of objects, and applications to graft it into their own at the path of their
+/// choosing, through [`ObjectServer::mount`] or [`connection::Builder::mount`].
+///
+/// Paths in the tree are relative to the prefix it's mounted at: the interfaces at `/` in the tree
+/// end up at the prefix itself, while those at `/Player` end up at `<prefix>/Player`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{connection, interface, object_server::ObjectTree};
+///
+/// struct Root;
+///
+/// #[interface(name = "org.example.Component")]
+/// impl Root {
+///     fn version(&self) -> u32 {
+///         1
+///     }
+/// }
+///
+/// struct Child;
+///
+/// #[interface(name = "org.example.Component.Child")]
+/// impl Child {}
+///
+/// // This would be provided by the component crate.
+/// fn component() -> zbus::Result<ObjectTree> {
+///     ObjectTree::new().at("/", Root)?.at("/child", Child)
+/// }
+///
+/// # async_io::block_on(async {
+/// // Serves `Root` at `/org/example/App/Component` and `Child` at
+/// // `/org/example/App/Component/child`.
+/// let _connection = connection::Builder::session()?
+///     .mount("/org/example/App/Component", component()?)?
+///     .build()
+///     .await?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+///
+/// [`ObjectServer::mount`]: crate::ObjectServer::mount
+/// [`connection::Builder::mount`]: crate::connection::Builder::mount
+#[derive(Debug, Default)]
+pub struct ObjectTree {
+    interfaces: HashMap<ObjectPath<'static>, Interfaces>,
+}
+
+assert_impl_all!(ObjectTree: Send, Sync, Unpin);
+
+impl ObjectTree {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an [`Interface`] at the given path, relative to the root of the tree.
+    ///
+    /// Any previously added interface with the same name at the same path is replaced.
+    pub fn at<'p, P, I>(mut self, path: P, iface: I) -> Result<Self>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?.into_owned();
+        self.interfaces
+            .entry(path)
+            .or_default()
+            .insert(I::name(), ArcInterface::new(iface));
+
+        Ok(self)
+    }
+
+    /// Whether the tree has no interfaces.
+    pub fn is_empty(&self) -> bool {
+        self.interfaces.is_empty()
+    }
+
+    /// The interfaces of the tree, at their absolute path once it's mounted at `prefix`.
+    pub(crate) fn into_interfaces(
+        self,
+        prefix: &ObjectPath<'_>,
+    ) -> impl Iterator<Item = (ObjectPath<'static>, Interfaces)> {
+        let prefix = prefix.as_str().trim_end_matches('/').to_owned();
+
+        self.interfaces.into_iter().map(move |(path, interfaces)| {
+            let path = match path.as_str() {
+                "/" if prefix.is_empty() => path,
+                "/" => ObjectPath::from_string_unchecked(prefix.clone()),
+                p => ObjectPath::from_string_unchecked(format!("{prefix}{p}")),
+            };
+
+            (path, interfaces)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connection, fdo::Peer, interface, utils::block_on, Connection, Proxy};
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    fn mounted_paths() {
+        let tree = ObjectTree::new()
+            .at("/", Peer)
+            .unwrap()
+            .at("/a/b", Peer)
+            .unwrap();
+        assert!(!tree.is_empty());
+
+        let mut paths: Vec<_> = tree
+            .into_interfaces(&ObjectPath::from_static_str_unchecked("/"))
+            .map(|(p, _)| p.to_string())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, ["/", "/a/b"]);
+
+        let tree = ObjectTree::new()
+            .at("/", Peer)
+            .unwrap()
+            .at("/a/b", Peer)
+            .unwrap();
+        let mut paths: Vec<_> = tree
+            .into_interfaces(&ObjectPath::from_static_str_unchecked("/org/zbus"))
+            .map(|(p, _)| p.to_string())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, ["/org/zbus", "/org/zbus/a/b"]);
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn mount() {
+        block_on(test_mount()).unwrap();
+    }
+
+    async fn test_mount() -> Result<()> {
+        struct Greeter(&'static str);
+
+        #[interface(name = "org.zbus.Greeter")]
+        impl Greeter {
+            fn greet(&self) -> String {
+                self.0.to_string()
+            }
+        }
+
+        let tree = ObjectTree::new()
+            .at("/", Greeter("root"))?
+            .at("/child", Greeter("child"))?;
+        let service = connection::Builder::session()?
+            .mount("/org/zbus/Mounted", tree)?
+            .build()
+            .await?;
+        let conn = Connection::session().await?;
+        let greet = |path: &'static str| {
+            let conn = &conn;
+            let service = &service;
+            async move {
+                let proxy = Proxy::new(
+                    conn,
+                    service.unique_name().unwrap().to_owned(),
+                    path,
+                    "org.zbus.Greeter",
+                )
+                .await?;
+
+                proxy.call::<_, _, String>("Greet", &()).await
+            }
+        };
+        assert_eq!(greet("/org/zbus/Mounted").await?, "root");
+        assert_eq!(greet("/org/zbus/Mounted/child").await?, "child");
+
+        // A conflicting tree is rejected as a whole.
+        let tree = ObjectTree::new()
+            .at("/other", Greeter("other"))?
+            .at("/child", Greeter("conflict"))?;
+        let res = service
+            .object_server()
+            .mount("/org/zbus/Mounted", tree)
+            .await;
+        assert!(matches!(res, Err(Error::InterfaceExists(_, p)) if p == "/org/zbus/Mounted/child"));
+        assert_eq!(greet("/org/zbus/Mounted/child").await?, "child");
+        assert!(greet("/org/zbus/Mounted/other").await.is_err());
+
+        Ok(())
+    }
+}
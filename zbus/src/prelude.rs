@@ -0,0 +1,31 @@
+//! The zbus prelude.
+//!
+//! This module re-exports the traits that are needed in almost every program using zbus, so they
+//! can be brought into scope with a single glob import:
+//!
+//! ```
+//! use zbus::prelude::*;
+//!
+//! # zbus::block_on(async {
+//! let connection = zbus::Connection::session().await?;
+//! let proxy = zbus::fdo::DBusProxy::new(&connection).await?;
+//! let mut name_owner_changed = proxy.receive_name_owner_changed().await?;
+//!
+//! // `StreamExt` from the prelude provides `next`.
+//! let _next = name_owner_changed.next();
+//! # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+//! # }).unwrap();
+//! ```
+//!
+//! Since zbus also re-exports [`zvariant`](crate::zvariant), [`zbus_names`](crate::names) and all
+//! the macros, depending on `zbus` alone is enough for most programs and guarantees that the
+//! versions of these crates are always compatible with each other.
+//!
+//! The extension traits are imported anonymously so that they don't clash with any other items
+//! of the same name in your code.
+
+pub use crate::{object_server::Interface, proxy::ProxyDefault, DBusError};
+pub use zvariant::{DynamicDeserialize, DynamicType, Type};
+
+pub use crate::{blocking::proxy::ProxyImpl as _, proxy::ProxyImpl as _};
+pub use futures_util::{SinkExt as _, StreamExt as _, TryStreamExt as _};
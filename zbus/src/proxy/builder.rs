@@ -1,10 +1,11 @@
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 
+use enumflags2::BitFlags;
 use static_assertions::assert_impl_all;
 use zbus_names::{BusName, InterfaceName};
 use zvariant::{ObjectPath, Str};
 
-use crate::{proxy::ProxyInner, Connection, Error, Proxy, Result};
+use crate::{message::Coercion, proxy::ProxyInner, Connection, Error, Proxy, Result};
 
 /// The properties caching mode.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +31,9 @@ pub struct Builder<'a, T = ()> {
     proxy_type: PhantomData<T>,
     cache: CacheProperties,
     uncached_properties: Option<HashSet<Str<'a>>>,
+    reply_coercions: BitFlags<Coercion>,
+    auto_start: bool,
+    allow_interactive_auth: bool,
 }
 
 impl<'a, T> Clone for Builder<'a, T> {
@@ -41,6 +45,9 @@ impl<'a, T> Clone for Builder<'a, T> {
             interface: self.interface.clone(),
             cache: self.cache,
             uncached_properties: self.uncached_properties.clone(),
+            reply_coercions: self.reply_coercions,
+            auto_start: self.auto_start,
+            allow_interactive_auth: self.allow_interactive_auth,
             proxy_type: PhantomData,
         }
     }
@@ -95,6 +102,45 @@ impl<'a, T> Builder<'a, T> {
         self
     }
 
+    /// Set the coercions to attempt on replies with unexpected signatures.
+    ///
+    /// This only affects [`Proxy::call_coerced`]. By default, all [`Coercion`] variants are
+    /// attempted.
+    #[must_use]
+    pub fn reply_coercions(mut self, coercions: BitFlags<Coercion>) -> Self {
+        self.reply_coercions = coercions;
+        self
+    }
+
+    /// Whether method calls should activate the destination service if needed.
+    ///
+    /// By default, the bus starts the service owning the destination name on method calls, if
+    /// it's not running already. When disabled, method calls fail with
+    /// [`fdo::Error::ServiceUnknown`] instead. If the owner of the destination is being tracked
+    /// (see [`Proxy::owner`]) and is known to be missing, they fail right away, without a round
+    /// trip to the bus.
+    ///
+    /// [`fdo::Error::ServiceUnknown`]: crate::fdo::Error::ServiceUnknown
+    #[must_use]
+    pub fn auto_start(mut self, auto_start: bool) -> Self {
+        self.auto_start = auto_start;
+        self
+    }
+
+    /// Whether method calls may trigger interactive authorization.
+    ///
+    /// Services guarded by polkit, such as systemd or NetworkManager, can prompt the user for
+    /// authorization on a privileged method call, but only if the caller allows it by setting the
+    /// `ALLOW_INTERACTIVE_AUTHORIZATION` flag on the call. When enabled, this flag is set on all
+    /// method calls made through the proxy. It's disabled by default.
+    ///
+    /// Note that the call may then take a long time to complete, while the user is being prompted.
+    #[must_use]
+    pub fn allow_interactive_auth(mut self, allow: bool) -> Self {
+        self.allow_interactive_auth = allow;
+        self
+    }
+
     pub(crate) fn build_internal(self) -> Result<Proxy<'a>> {
         let conn = self.conn;
         let destination = self
@@ -113,6 +159,9 @@ impl<'a, T> Builder<'a, T> {
                 interface,
                 cache,
                 uncached_properties,
+                self.reply_coercions,
+                self.auto_start,
+                self.allow_interactive_auth,
             )),
         })
     }
@@ -158,6 +207,9 @@ where
                 .map(|i| InterfaceName::from_static_str(i).expect("invalid interface name")),
             cache: CacheProperties::default(),
             uncached_properties: None,
+            reply_coercions: BitFlags::all(),
+            auto_start: true,
+            allow_interactive_auth: false,
             proxy_type: PhantomData,
         }
     }
@@ -14,20 +14,23 @@ use std::{
     pin::Pin,
     sync::{Arc, OnceLock, RwLock, RwLockReadGuard},
     task::{Context, Poll},
+    time::Duration,
 };
 use tracing::{debug, info_span, instrument, trace, Instrument};
 
-use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
+use zbus_names::{BusName, InterfaceName, MemberName, OwnedUniqueName, UniqueName};
 use zvariant::{ObjectPath, OwnedValue, Str, Value};
 
 use crate::{
     fdo::{self, IntrospectableProxy, NameOwnerChanged, PropertiesChangedStream, PropertiesProxy},
-    message::{Flags, Message, Sequence, Type},
+    message::{Coercion, Flags, Message, Sequence, Type},
     AsyncDrop, Connection, Error, Executor, MatchRule, MessageStream, OwnedMatchRule, Result, Task,
 };
 
 mod builder;
 pub use builder::{Builder, CacheProperties, ProxyDefault};
+mod owner;
+use owner::OwnerTracker;
 
 /// A client-side interface proxy.
 ///
@@ -103,6 +106,13 @@ pub(crate) struct ProxyInner<'a> {
     /// Set of properties which do not get cached, by name.
     /// This overrides proxy-level caching behavior.
     uncached_properties: HashSet<Str<'a>>,
+    /// Coercions to attempt on replies by [`Proxy::call_coerced`].
+    reply_coercions: BitFlags<Coercion>,
+    /// Whether method calls can activate the destination.
+    auto_start: bool,
+    allow_interactive_auth: bool,
+    /// Tracker of the destination owner, if it's a well-known name.
+    owner_tracker: OnceLock<(Arc<OwnerTracker>, Task<()>)>,
 }
 
 impl Drop for ProxyInnerStatic {
@@ -487,6 +497,7 @@ impl PropertiesCache {
 }
 
 impl<'a> ProxyInner<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         conn: Connection,
         destination: BusName<'a>,
@@ -494,6 +505,9 @@ impl<'a> ProxyInner<'a> {
         interface: InterfaceName<'a>,
         cache: CacheProperties,
         uncached_properties: HashSet<Str<'a>>,
+        reply_coercions: BitFlags<Coercion>,
+        auto_start: bool,
+        allow_interactive_auth: bool,
     ) -> Self {
         let property_cache = match cache {
             CacheProperties::Yes | CacheProperties::Lazily => Some(OnceLock::new()),
@@ -509,6 +523,10 @@ impl<'a> ProxyInner<'a> {
             interface,
             property_cache,
             uncached_properties,
+            reply_coercions,
+            auto_start,
+            allow_interactive_auth,
+            owner_tracker: OnceLock::new(),
         }
     }
 
@@ -638,6 +656,87 @@ impl<'a> Proxy<'a> {
         &self.inner.interface
     }
 
+    /// The coercions attempted on replies by [`Proxy::call_coerced`].
+    pub fn reply_coercions(&self) -> BitFlags<Coercion> {
+        self.inner.reply_coercions
+    }
+
+    /// Whether method calls through this proxy can activate the destination service.
+    ///
+    /// See [`Builder::auto_start`] for details.
+    pub fn auto_start(&self) -> bool {
+        self.inner.auto_start
+    }
+
+    /// Whether method calls through this proxy may trigger interactive authorization.
+    ///
+    /// See [`Builder::allow_interactive_auth`] for details.
+    pub fn allow_interactive_auth(&self) -> bool {
+        self.inner.allow_interactive_auth
+    }
+
+    /// The current owner of the destination name.
+    ///
+    /// If the destination is a well-known name, the first call starts keeping track of its owner
+    /// in the background, through the `NameOwnerChanged` signal, so that subsequent calls are
+    /// answered without any round trip to the bus. `None` means that the name currently has no
+    /// owner. Use [`Proxy::receive_owner_changed`] to get notified of owner changes.
+    ///
+    /// If the destination is a unique name, it's returned as is. On peer-to-peer connections,
+    /// where names don't mean anything, `None` is always returned.
+    pub async fn owner(&self) -> Result<Option<OwnedUniqueName>> {
+        match (self.owner_tracker(), &self.inner.destination) {
+            (Some(tracker), _) => tracker.owner().await,
+            (None, BusName::Unique(name)) if self.connection().is_bus() => {
+                Ok(Some(name.to_owned().into()))
+            }
+            (None, _) => Ok(None),
+        }
+    }
+
+    /// Get the owner tracker, starting it in the background if needed.
+    ///
+    /// Returns `None` if the destination is not a well-known name on a bus.
+    fn owner_tracker(&self) -> Option<&Arc<OwnerTracker>> {
+        let name = match &self.inner.destination {
+            BusName::WellKnown(name) if self.connection().is_bus() => name,
+            _ => return None,
+        };
+        let (tracker, _) = self
+            .inner
+            .owner_tracker
+            .get_or_init(|| OwnerTracker::new(self.connection(), name.to_owned()));
+
+        Some(tracker)
+    }
+
+    /// The flags to set on method calls, failing early if the destination is known to be missing.
+    fn method_call_flags(&self) -> Result<BitFlags<Flags>> {
+        let flags = if self.inner.allow_interactive_auth {
+            Flags::AllowInteractiveAuth.into()
+        } else {
+            BitFlags::empty()
+        };
+        if self.inner.auto_start {
+            return Ok(flags);
+        }
+
+        // Only rely on the tracker if it's already running, we don't want to wait for it.
+        let tracked = self
+            .inner
+            .owner_tracker
+            .get()
+            .and_then(|(tracker, _)| tracker.current());
+        if let Some(Ok(None)) = tracked {
+            return Err(Error::FDO(Box::new(fdo::Error::ServiceUnknown(format!(
+                "The name {} has no owner",
+                self.inner.destination
+            )))));
+        }
+
+        Ok(flags | Flags::NoAutoStart)
+    }
+
     /// Introspect the associated object, and return the XML description.
     ///
     /// See the [xml](xml/index.html) module for parsing the
@@ -824,17 +923,22 @@ impl<'a> Proxy<'a> {
         M::Error: Into<Error>,
         B: serde::ser::Serialize + zvariant::DynamicType,
     {
-        self.inner
-            .inner_without_borrows
-            .conn
-            .call_method(
+        let flags = self.method_call_flags()?;
+        let conn = &self.inner.inner_without_borrows.conn;
+
+        let pending = conn
+            .call_method_raw(
                 Some(&self.inner.destination),
                 self.inner.path.as_str(),
                 Some(&self.inner.interface),
                 method_name,
+                flags,
                 body,
             )
-            .await
+            .await?
+            .ok_or(Error::InvalidReply)?;
+
+        conn.wait_reply(pending, conn.method_timeout()).await
     }
 
     /// Call a method and return the reply body.
@@ -854,6 +958,26 @@ impl<'a> Proxy<'a> {
         reply.body().deserialize()
     }
 
+    /// Call a method and return the reply body, coercing it to the expected type if needed.
+    ///
+    /// This is similar to [`Proxy::call`], except that if the reply doesn't have the expected
+    /// signature, the [coercions configured for this proxy][rc] are attempted on it before giving
+    /// up. See [`Body::deserialize_coerced`] for details.
+    ///
+    /// [rc]: crate::proxy::Builder::reply_coercions
+    /// [`Body::deserialize_coerced`]: crate::message::Body::deserialize_coerced
+    pub async fn call_coerced<'m, M, B, R>(&self, method_name: M, body: &B) -> Result<R>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: serde::de::DeserializeOwned + zvariant::Type,
+    {
+        let reply = self.call_method(method_name, body).await?;
+
+        reply.body().deserialize_coerced(self.inner.reply_coercions)
+    }
+
     /// Call a method and return the reply body, optionally supplying a set of
     /// method flags to control the way the method call message is sent and handled.
     ///
@@ -875,11 +999,10 @@ impl<'a> Proxy<'a> {
         B: serde::ser::Serialize + zvariant::DynamicType,
         R: for<'d> zvariant::DynamicDeserialize<'d>,
     {
-        let flags = flags.iter().map(Flags::from).collect::<BitFlags<_>>();
-        match self
-            .inner
-            .inner_without_borrows
-            .conn
+        let flags =
+            flags.iter().map(Flags::from).collect::<BitFlags<_>>() | self.method_call_flags()?;
+        let conn = &self.inner.inner_without_borrows.conn;
+        match conn
             .call_method_raw(
                 Some(self.destination()),
                 self.path(),
@@ -890,7 +1013,12 @@ impl<'a> Proxy<'a> {
             )
             .await?
         {
-            Some(reply) => reply.await?.body().deserialize().map(Some),
+            Some(pending) => conn
+                .wait_reply(pending, conn.method_timeout())
+                .await?
+                .body()
+                .deserialize()
+                .map(Some),
             None => Ok(None),
         }
     }
@@ -909,6 +1037,96 @@ impl<'a> Proxy<'a> {
         Ok(())
     }
 
+    /// Call several methods back-to-back and return their replies, in the same order.
+    ///
+    /// All the calls are sent before waiting for any reply, so the round-trips to the peer overlap
+    /// instead of adding up. This can cut the total latency of a sequence of independent calls
+    /// considerably, e.g during the initialization of an application, especially on high-latency
+    /// connections such as remote buses over TCP.
+    ///
+    /// The bodies of all the calls must be of the same type, e.g `&()` for calls without arguments.
+    /// If any of the calls fails, the first error (in the order of the calls) is returned. Note that
+    /// the calls are all sent nevertheless, so the following ones may have been processed by the
+    /// peer.
+    ///
+    /// The [method timeout](crate::connection::Builder::method_timeout) of the connection, if any,
+    /// applies to the whole batch rather than to each call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use zbus::{Connection, Proxy};
+    /// # async_io::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let proxy = Proxy::new(
+    ///     &connection,
+    ///     "org.freedesktop.DBus",
+    ///     "/org/freedesktop/DBus",
+    ///     "org.freedesktop.DBus",
+    /// )
+    /// .await?;
+    ///
+    /// let replies = proxy
+    ///     .call_pipelined([
+    ///         ("GetId", &()),
+    ///         ("ListNames", &()),
+    ///         ("ListActivatableNames", &()),
+    ///     ])
+    ///     .await?;
+    /// let id: String = replies[0].body().deserialize()?;
+    /// let names: Vec<String> = replies[1].body().deserialize()?;
+    /// let activatable: Vec<String> = replies[2].body().deserialize()?;
+    /// println!("Bus {id} has {} names, {} activatable", names.len(), activatable.len());
+    /// # Ok::<(), Box<dyn Error + Send + Sync>>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn call_pipelined<'m, 'b, C, M, B>(&self, calls: C) -> Result<Vec<Message>>
+    where
+        C: IntoIterator<Item = (M, &'b B)>,
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType + 'b,
+    {
+        let flags = self.method_call_flags()?;
+        let conn = &self.inner.inner_without_borrows.conn;
+
+        let mut pending_calls = vec![];
+        for (method_name, body) in calls {
+            let pending = conn
+                .call_method_raw(
+                    Some(&self.inner.destination),
+                    self.inner.path.as_str(),
+                    Some(&self.inner.interface),
+                    method_name,
+                    flags,
+                    body,
+                )
+                .await?
+                .ok_or(Error::InvalidReply)?;
+            pending_calls.push(pending);
+        }
+
+        let replies = async {
+            let mut replies = Vec::with_capacity(pending_calls.len());
+            for pending in pending_calls {
+                replies.push(pending.await?);
+            }
+
+            Ok(replies)
+        };
+        match conn.method_timeout() {
+            Some(timeout) => crate::timeout::timeout(timeout, replies)
+                .await
+                .unwrap_or_else(|| {
+                    debug!("Pipelined method calls timed out after {:?}", timeout);
+
+                    Err(Error::Timeout(timeout))
+                }),
+            None => replies.await,
+        }
+    }
+
     /// Create a stream for signal named `signal_name`.
     pub async fn receive_signal<'m, M>(&self, signal_name: M) -> Result<SignalStream<'m>>
     where
@@ -983,6 +1201,80 @@ impl<'a> Proxy<'a> {
         }
     }
 
+    /// Wait until the value of a property satisfies `condition`.
+    ///
+    /// The current value of the property is checked first, followed by each new value it's
+    /// changed to, until one satisfies `condition`. That value is returned.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Timeout`] is returned if no such value was seen within `timeout`. Since changes
+    /// are tracked through the property cache, [`Error::Unsupported`] is returned if the current
+    /// value doesn't satisfy `condition` and caching is not enabled on this proxy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::{error::Error, time::Duration};
+    /// # use zbus::{Connection, Proxy};
+    /// #
+    /// # async_io::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let proxy = Proxy::new(
+    ///     &connection,
+    ///     "org.freedesktop.systemd1",
+    ///     "/org/freedesktop/systemd1/unit/dbus_2eservice",
+    ///     "org.freedesktop.systemd1.Unit",
+    /// )
+    /// .await?;
+    /// let state: String = proxy
+    ///     .wait_for_property(
+    ///         "ActiveState",
+    ///         |state: &String| state != "activating",
+    ///         Duration::from_secs(10),
+    ///     )
+    ///     .await?;
+    /// println!("The service is {state}");
+    /// # Ok::<(), Box<dyn Error + Send + Sync>>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn wait_for_property<T, F>(
+        &self,
+        name: &str,
+        mut condition: F,
+        timeout: Duration,
+    ) -> Result<T>
+    where
+        T: TryFrom<OwnedValue> + Unpin,
+        T::Error: Into<Error>,
+        F: FnMut(&T) -> bool,
+    {
+        use futures_util::StreamExt;
+
+        // Listen for changes before fetching the current value, so none can be missed.
+        let mut changes = self.receive_property_changed::<T>(name).await;
+        let wait = async {
+            let value = self.get_property(name).await?;
+            if condition(&value) {
+                return Ok(value);
+            }
+
+            while let Some(change) = changes.next().await {
+                let value = change.get().await?;
+                if condition(&value) {
+                    return Ok(value);
+                }
+            }
+
+            // The stream only ends if caching is disabled.
+            Err(Error::Unsupported)
+        };
+
+        crate::timeout::timeout(timeout, wait)
+            .await
+            .unwrap_or(Err(Error::Timeout(timeout)))
+    }
+
     /// Get a stream to receive destination owner changed events.
     ///
     /// If the proxy destination is a unique name, the stream will be notified of the peer
@@ -1115,6 +1407,23 @@ impl<'a> SignalStream<'a> {
         self.signal_name.as_ref()
     }
 
+    /// The unique name of the peer the signals are currently accepted from, if any.
+    ///
+    /// If the destination of the proxy is a well-known name, this follows its owner changes.
+    pub fn owner(&self) -> Option<&UniqueName<'static>> {
+        self.src_unique_name.as_ref()
+    }
+
+    /// Also report the owner changes of the destination name, in order with the signals.
+    ///
+    /// If the destination of the proxy is a well-known name, the stream keeps delivering signals
+    /// across owner changes, e.g when the service is restarted. The returned stream additionally
+    /// yields a [`SignalEvent::OwnerChanged`] when that happens, so that any state derived from
+    /// previous signals can be resynchronized with the new owner.
+    pub fn with_owner_changes(self) -> SignalEventStream<'a> {
+        SignalEventStream(self)
+    }
+
     async fn new(
         proxy: Proxy<'_>,
         signal_name: Option<MemberName<'a>>,
@@ -1248,20 +1557,53 @@ impl<'a> SignalStream<'a> {
         })
     }
 
-    fn filter(&mut self, msg: &Message) -> Result<bool> {
+    fn filter(&mut self, msg: Message) -> Result<Option<SignalEvent>> {
         let header = msg.header();
         let sender = header.sender();
         if sender == self.src_unique_name.as_ref() {
-            return Ok(true);
+            return Ok(Some(SignalEvent::Signal(msg)));
         }
 
         // The src_unique_name must be maintained in lock-step with the applied filter
         if let Some(signal) = NameOwnerChanged::from_message(msg.clone()) {
             let args = signal.args()?;
-            self.src_unique_name = args.new_owner().as_ref().map(|n| n.to_owned());
+            let new_owner = args.new_owner().as_ref().map(|n| n.to_owned());
+            if new_owner != self.src_unique_name {
+                self.src_unique_name = new_owner.clone();
+
+                return Ok(Some(SignalEvent::OwnerChanged(new_owner)));
+            }
         }
 
-        Ok(false)
+        Ok(None)
+    }
+
+    fn poll_event(
+        &mut self,
+        cx: &mut Context<'_>,
+        before: Option<&Sequence>,
+        owner_changes: bool,
+    ) -> Poll<PollResult<Sequence, SignalEvent>> {
+        loop {
+            match ready!(OrderedStream::poll_next_before(
+                Pin::new(&mut self.stream),
+                cx,
+                before
+            )) {
+                PollResult::Item { data, ordering } => {
+                    let Ok(msg) = data else {
+                        continue;
+                    };
+                    match self.filter(msg) {
+                        Ok(Some(SignalEvent::OwnerChanged(_))) if !owner_changes => (),
+                        Ok(Some(data)) => return Poll::Ready(PollResult::Item { data, ordering }),
+                        Ok(None) | Err(_) => (),
+                    }
+                }
+                PollResult::Terminated => return Poll::Ready(PollResult::Terminated),
+                PollResult::NoneBefore => return Poll::Ready(PollResult::NoneBefore),
+            }
+        }
     }
 }
 
@@ -1284,27 +1626,12 @@ impl<'a> OrderedStream for SignalStream<'a> {
         cx: &mut Context<'_>,
         before: Option<&Self::Ordering>,
     ) -> Poll<PollResult<Self::Ordering, Self::Data>> {
-        let this = self.get_mut();
-        loop {
-            match ready!(OrderedStream::poll_next_before(
-                Pin::new(&mut this.stream),
-                cx,
-                before
-            )) {
-                PollResult::Item { data, ordering } => {
-                    if let Ok(msg) = data {
-                        if let Ok(true) = this.filter(&msg) {
-                            return Poll::Ready(PollResult::Item {
-                                data: msg,
-                                ordering,
-                            });
-                        }
-                    }
-                }
-                PollResult::Terminated => return Poll::Ready(PollResult::Terminated),
-                PollResult::NoneBefore => return Poll::Ready(PollResult::NoneBefore),
-            }
-        }
+        self.get_mut().poll_event(cx, before, false).map(|res| {
+            res.map_data(|event| match event {
+                SignalEvent::Signal(msg) => msg,
+                SignalEvent::OwnerChanged(_) => unreachable!("owner changes are not reported"),
+            })
+        })
     }
 }
 
@@ -1325,6 +1652,73 @@ impl AsyncDrop for SignalStream<'_> {
     }
 }
 
+/// An event of a [`SignalEventStream`].
+#[derive(Clone, Debug)]
+pub enum SignalEvent {
+    /// A signal from the current owner of the destination.
+    Signal(Message),
+    /// The destination name got a new owner, or lost its owner if `None`.
+    ///
+    /// Signals following this event come from the new owner.
+    OwnerChanged(Option<UniqueName<'static>>),
+}
+
+assert_impl_all!(SignalEvent: Send, Sync, Unpin);
+
+/// A [`stream::Stream`] implementation that yields signals and owner changes of the destination.
+///
+/// Use [`SignalStream::with_owner_changes`] to create an instance of this type.
+#[derive(Debug)]
+pub struct SignalEventStream<'a>(SignalStream<'a>);
+
+assert_impl_all!(SignalEventStream<'_>: Send, Sync, Unpin);
+
+impl<'a> SignalEventStream<'a> {
+    /// The signal name.
+    pub fn name(&self) -> Option<&MemberName<'a>> {
+        self.0.name()
+    }
+
+    /// The unique name of the peer the signals are currently accepted from, if any.
+    pub fn owner(&self) -> Option<&UniqueName<'static>> {
+        self.0.owner()
+    }
+}
+
+impl<'a> stream::Stream for SignalEventStream<'a> {
+    type Item = SignalEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        OrderedStream::poll_next_before(self, cx, None).map(|res| res.into_data())
+    }
+}
+
+impl<'a> OrderedStream for SignalEventStream<'a> {
+    type Data = SignalEvent;
+    type Ordering = Sequence;
+
+    fn poll_next_before(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        before: Option<&Self::Ordering>,
+    ) -> Poll<PollResult<Self::Ordering, Self::Data>> {
+        self.get_mut().0.poll_event(cx, before, true)
+    }
+}
+
+impl<'a> stream::FusedStream for SignalEventStream<'a> {
+    fn is_terminated(&self) -> bool {
+        stream::FusedStream::is_terminated(&self.0)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncDrop for SignalEventStream<'_> {
+    async fn async_drop(self) {
+        self.0.async_drop().await
+    }
+}
+
 impl<'a> From<crate::blocking::Proxy<'a>> for Proxy<'a> {
     fn from(proxy: crate::blocking::Proxy<'a>) -> Self {
         proxy.into_inner()
@@ -1535,4 +1929,308 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn owner_tracking() {
+        block_on(test_owner_tracking()).unwrap();
+    }
+
+    async fn test_owner_tracking() -> Result<()> {
+        let conn = Connection::session().await?;
+        let proxy: Proxy<'_> = Builder::new(&conn)
+            .destination("org.zbus.OwnerTracking")?
+            .path("/org/zbus/OwnerTracking")?
+            .interface("org.freedesktop.DBus.Peer")?
+            .auto_start(false)
+            .build()
+            .await?;
+        assert!(!proxy.auto_start());
+        assert_eq!(proxy.owner().await?, None);
+
+        // The name is known to have no owner so the call fails without a round trip.
+        match proxy.call_method("Ping", &()).await {
+            Err(Error::FDO(e)) => assert!(matches!(*e, fdo::Error::ServiceUnknown(_))),
+            r => panic!("unexpected result: {r:?}"),
+        }
+
+        let service_conn = connection::Builder::session()?
+            .name("org.zbus.OwnerTracking")?
+            .build()
+            .await?;
+        let unique_name = service_conn.unique_name().unwrap().to_owned();
+        while proxy.owner().await?.is_none() {
+            #[cfg(not(feature = "tokio"))]
+            async_io::Timer::after(std::time::Duration::from_millis(10)).await;
+
+            #[cfg(feature = "tokio")]
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(proxy.owner().await?, Some(unique_name));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn allow_interactive_auth() {
+        block_on(test_allow_interactive_auth()).unwrap();
+    }
+
+    async fn test_allow_interactive_auth() -> Result<()> {
+        #[proxy(
+            interface = "org.zbus.Interactive",
+            default_path = "/org/zbus/Interactive",
+            allow_interactive_auth
+        )]
+        trait Interactive {
+            #[zbus(no_reply)]
+            fn reboot(&self) -> Result<()>;
+        }
+
+        let conn = Connection::session().await?;
+        let service = Connection::session().await?;
+        let mut calls = MessageStream::from(&service);
+        let proxy = InteractiveProxy::builder(&conn)
+            .destination(service.unique_name().unwrap())?
+            .build()
+            .await?;
+        assert!(proxy.inner().allow_interactive_auth());
+
+        proxy.reboot().await?;
+        let call = loop {
+            let msg = calls.next().await.unwrap()?;
+            if msg.header().member().map(|m| m.as_str()) == Some("Reboot") {
+                break msg;
+            }
+        };
+        let flags = call.primary_header().flags();
+        assert!(flags.contains(Flags::AllowInteractiveAuth));
+        assert!(flags.contains(Flags::NoReplyExpected));
+
+        let proxy = Builder::<Proxy<'_>>::new(&conn)
+            .destination(service.unique_name().unwrap())?
+            .path("/org/zbus/Interactive")?
+            .interface("org.zbus.Interactive")?
+            .build()
+            .await?;
+        assert!(!proxy.allow_interactive_auth());
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn signal_owner_changes() {
+        block_on(test_signal_owner_changes()).unwrap();
+    }
+
+    async fn test_signal_owner_changes() -> Result<()> {
+        let well_known = "org.zbus.SignalOwnerChanges";
+        let conn = Connection::session().await?;
+        let proxy: Proxy<'_> = Builder::new(&conn)
+            .destination(well_known)?
+            .path("/org/zbus/SignalOwnerChanges")?
+            .interface("org.zbus.SignalOwnerChanges")?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        let mut stream = proxy.receive_signal("Ping").await?.with_owner_changes();
+        assert_eq!(stream.owner(), None);
+
+        // Each new owner of the name gets its signals delivered on the same stream.
+        for i in 0..2u32 {
+            let service = connection::Builder::session()?
+                .name(well_known)?
+                .build()
+                .await?;
+            let unique_name = service.unique_name().unwrap().to_owned();
+            match stream.next().await.unwrap() {
+                SignalEvent::OwnerChanged(Some(owner)) => assert_eq!(owner, *unique_name),
+                e => panic!("unexpected event: {e:?}"),
+            }
+            assert_eq!(stream.owner(), Some(&*unique_name));
+
+            service
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/SignalOwnerChanges",
+                    "org.zbus.SignalOwnerChanges",
+                    "Ping",
+                    &i,
+                )
+                .await?;
+            match stream.next().await.unwrap() {
+                SignalEvent::Signal(msg) => assert_eq!(msg.body().deserialize::<u32>()?, i),
+                e => panic!("unexpected event: {e:?}"),
+            }
+
+            drop(service);
+            match stream.next().await.unwrap() {
+                SignalEvent::OwnerChanged(None) => (),
+                e => panic!("unexpected event: {e:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn call_pipelined() {
+        block_on(test_call_pipelined()).unwrap();
+    }
+
+    async fn test_call_pipelined() -> Result<()> {
+        struct Echo;
+
+        #[interface(name = "org.zbus.Echo")]
+        impl Echo {
+            fn echo(&self, s: &str) -> String {
+                s.to_string()
+            }
+
+            fn fail(&self, s: &str) -> fdo::Result<()> {
+                Err(fdo::Error::Failed(s.to_string()))
+            }
+        }
+
+        let service = connection::Builder::session()?
+            .serve_at("/org/zbus/Echo", Echo)?
+            .build()
+            .await?;
+        let conn = Connection::session().await?;
+        let proxy: Proxy<'_> = Builder::new(&conn)
+            .destination(service.unique_name().unwrap())?
+            .path("/org/zbus/Echo")?
+            .interface("org.zbus.Echo")?
+            .build()
+            .await?;
+
+        let replies = proxy
+            .call_pipelined([("Echo", &"one"), ("Echo", &"two"), ("Echo", &"three")])
+            .await?;
+        let replies = replies
+            .iter()
+            .map(|r| r.body().deserialize::<String>())
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(replies, ["one", "two", "three"]);
+
+        let res = proxy
+            .call_pipelined([("Echo", &"one"), ("Fail", &"two"), ("Fail", &"three")])
+            .await;
+        assert!(matches!(res, Err(Error::MethodError(_, Some(e), _)) if e == "two"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn call_pipelined_timeout() {
+        block_on(test_call_pipelined_timeout()).unwrap();
+    }
+
+    async fn test_call_pipelined_timeout() -> Result<()> {
+        struct Slow;
+
+        #[interface(name = "org.zbus.Slow")]
+        impl Slow {
+            // Taking `&mut self` ensures the calls are handled one after the other.
+            async fn sleep(&mut self) {
+                let duration = std::time::Duration::from_millis(200);
+
+                #[cfg(not(feature = "tokio"))]
+                async_io::Timer::after(duration).await;
+
+                #[cfg(feature = "tokio")]
+                tokio::time::sleep(duration).await;
+            }
+        }
+
+        let service = connection::Builder::session()?
+            .serve_at("/org/zbus/Slow", Slow)?
+            .build()
+            .await?;
+        let conn = Connection::session().await?;
+        let proxy: Proxy<'_> = Builder::new(&conn)
+            .destination(service.unique_name().unwrap())?
+            .path("/org/zbus/Slow")?
+            .interface("org.zbus.Slow")?
+            .build()
+            .await?;
+
+        // Each reply arrives within the timeout of the previous one, but not the whole batch.
+        let timeout = std::time::Duration::from_millis(500);
+        conn.set_method_timeout(Some(timeout));
+        let res = proxy
+            .call_pipelined([("Sleep", &()), ("Sleep", &()), ("Sleep", &())])
+            .await;
+        assert_eq!(res.unwrap_err(), Error::Timeout(timeout));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn wait_for_property() {
+        block_on(test_wait_for_property()).unwrap();
+    }
+
+    async fn test_wait_for_property() -> Result<()> {
+        struct Counter(u32);
+
+        #[interface(name = "org.zbus.Counter")]
+        impl Counter {
+            #[zbus(property)]
+            fn count(&self) -> u32 {
+                self.0
+            }
+        }
+
+        let service = connection::Builder::session()?
+            .serve_at("/org/zbus/Counter", Counter(0))?
+            .build()
+            .await?;
+        let conn = Connection::session().await?;
+        let proxy: Proxy<'_> = Builder::new(&conn)
+            .destination(service.unique_name().unwrap())?
+            .path("/org/zbus/Counter")?
+            .interface("org.zbus.Counter")?
+            .build()
+            .await?;
+        let timeout = Duration::from_secs(5);
+
+        // The current value is checked first.
+        let count: u32 = proxy
+            .wait_for_property("Count", |c| *c == 0, timeout)
+            .await?;
+        assert_eq!(count, 0);
+        let res = proxy
+            .wait_for_property("Count", |c: &u32| *c == 1, Duration::from_millis(100))
+            .await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+
+        let increment = async {
+            let iface = service
+                .object_server()
+                .interface::<_, Counter>("/org/zbus/Counter")
+                .await?;
+            for _ in 0..3 {
+                let mut counter = iface.get_mut().await;
+                counter.0 += 1;
+                counter.count_changed(iface.signal_context()).await?;
+            }
+
+            Ok::<_, Error>(())
+        };
+        let (count, res) = futures_util::future::join(
+            proxy.wait_for_property("Count", |c: &u32| *c >= 3, timeout),
+            increment,
+        )
+        .await;
+        res?;
+        assert_eq!(count?, 3);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,102 @@
+use event_listener::Event;
+use std::sync::{Arc, RwLock};
+use tracing::{debug, info_span, trace, Instrument};
+
+use zbus_names::{OwnedUniqueName, WellKnownName};
+
+use crate::{fdo, proxy::CacheProperties, Connection, Result, Task};
+
+/// Keeps track of the current owner of a well-known name.
+#[derive(Debug)]
+pub(crate) struct OwnerTracker {
+    // `None` until the initial lookup is complete.
+    owner: RwLock<Option<Result<Option<OwnedUniqueName>>>>,
+    ready: Event,
+}
+
+impl OwnerTracker {
+    /// Start tracking the owner of `name` in a background task.
+    pub(crate) fn new(conn: &Connection, name: WellKnownName<'static>) -> (Arc<Self>, Task<()>) {
+        let tracker = Arc::new(Self {
+            owner: RwLock::new(None),
+            ready: Event::new(),
+        });
+
+        let task_name = format!("{name} owner tracker");
+        let tracker_clone = tracker.clone();
+        let conn_clone = conn.clone();
+        let tracking = async move { tracker_clone.track(conn_clone, name).await }
+            .instrument(info_span!("{}", task_name));
+        let task = conn.executor().spawn(tracking, &task_name);
+
+        (tracker, task)
+    }
+
+    /// The current owner, waiting for the initial lookup to complete if needed.
+    pub(crate) async fn owner(&self) -> Result<Option<OwnedUniqueName>> {
+        loop {
+            let listener = self.ready.listen();
+            if let Some(owner) = self.current() {
+                return owner;
+            }
+
+            listener.await;
+        }
+    }
+
+    /// The current owner, or `None` if the initial lookup is not complete yet.
+    pub(crate) fn current(&self) -> Option<Result<Option<OwnedUniqueName>>> {
+        self.owner.read().expect("lock poisoned").clone()
+    }
+
+    async fn track(&self, conn: Connection, name: WellKnownName<'static>) {
+        use futures_util::StreamExt;
+
+        // Subscribe before the lookup so we don't miss any change in between.
+        let init = async {
+            let dbus_proxy = fdo::DBusProxy::builder(&conn)
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await?;
+            let changes = dbus_proxy
+                .receive_name_owner_changed_with_args(&[(0, name.as_str())])
+                .await?;
+            let owner = match dbus_proxy.get_name_owner(name.as_ref().into()).await {
+                Ok(owner) => Some(owner),
+                Err(fdo::Error::NameHasNoOwner(_)) => None,
+                Err(e) => return Err(e.into()),
+            };
+
+            Ok((changes, owner))
+        };
+        let mut changes = match init.await {
+            Ok((changes, owner)) => {
+                self.set(Ok(owner));
+
+                changes
+            }
+            Err(e) => {
+                debug!("Failed to look up the owner of `{name}`: {e}");
+                self.set(Err(e));
+
+                return;
+            }
+        };
+
+        while let Some(signal) = changes.next().await {
+            if let Ok(args) = signal.args() {
+                let owner = args
+                    .new_owner()
+                    .as_ref()
+                    .map(|owner| owner.to_owned().into());
+                trace!("Owner of `{name}` changed to {owner:?}");
+                self.set(Ok(owner));
+            }
+        }
+    }
+
+    fn set(&self, owner: Result<Option<OwnedUniqueName>>) {
+        *self.owner.write().expect("lock poisoned") = Some(owner);
+        self.ready.notify(usize::MAX);
+    }
+}
@@ -62,6 +62,10 @@ trait MyIface {
 
     fn test_error(&self) -> zbus::Result<()>;
 
+    fn test_custom_error(&self) -> Result<(), MyIfaceError>;
+
+    fn test_panic(&self) -> zbus::Result<()>;
+
     fn test_single_struct_arg(&self, arg: ArgStructTest) -> zbus::Result<()>;
 
     fn test_single_struct_ret(&self) -> zbus::Result<ArgStructTest>;
@@ -232,6 +236,12 @@ impl MyIfaceImpl {
         Err(zbus::fdo::Error::Failed("error raised".to_string()))
     }
 
+    #[instrument]
+    fn test_panic(&self) {
+        debug!("`TestPanic` called.");
+        panic!("panic raised");
+    }
+
     #[instrument]
     fn test_custom_error(&self) -> Result<(), MyIfaceError> {
         debug!("`TestCustomError` called.");
@@ -654,6 +664,19 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
     assert_eq!(proxy.cached_count()?, None);
 
     proxy.test_header().await?;
+    // A panicking method results in an error reply and doesn't stop the service.
+    match proxy.test_panic().await.unwrap_err() {
+        zbus::Error::MethodError(name, Some(description), _) => {
+            assert_eq!(name, "org.freedesktop.DBus.Error.Failed");
+            assert_eq!(description, "Method 'TestPanic' panicked: panic raised");
+        }
+        e => panic!("unexpected error: {e:?}"),
+    }
+    // Error replies are mapped to the variants of the custom error type.
+    match proxy.test_custom_error().await.unwrap_err() {
+        MyIfaceError::SomethingWentWrong(description) => assert_eq!(description, "oops"),
+        e => panic!("unexpected error: {e:?}"),
+    }
     proxy
         .test_single_struct_arg(ArgStructTest {
             foo: 1,
@@ -0,0 +1,80 @@
+use static_assertions::assert_impl_all;
+use std::{error, fmt};
+
+/// The error type for `zbus_inspect`.
+///
+/// The various errors that can be reported by this crate.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Invalid encoding of the header or of the body.
+    Variant(zvariant::Error),
+    /// Invalid name in a header field.
+    Names(zbus_names::Error),
+    /// The frame is shorter than the fixed-length part of the header, of 16 bytes.
+    Incomplete(usize),
+    /// The length of the frame, the first, doesn't match the one described by its header, the
+    /// second.
+    LengthMismatch(usize, usize),
+    /// The message is larger than the maximum size of 128 MiB.
+    ExcessData(u64),
+    /// Invalid endianness signature.
+    InvalidEndian(u8),
+    /// Invalid message type.
+    InvalidMessageType(u8),
+    /// Unsupported protocol version.
+    UnsupportedProtocolVersion(u8),
+    /// The serial number of the message is zero.
+    InvalidSerial,
+    /// A header field isn't of the type required by the specification.
+    InvalidField(u8),
+}
+
+assert_impl_all!(Error: Send, Sync, Unpin);
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Variant(e) => Some(e),
+            Error::Names(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Variant(e) => write!(f, "{e}"),
+            Error::Names(e) => write!(f, "{e}"),
+            Error::Incomplete(len) => {
+                write!(f, "frame of {len} bytes is too short for a D-Bus message")
+            }
+            Error::LengthMismatch(len, expected) => write!(
+                f,
+                "frame is {len} bytes long but its header describes a {expected} bytes message"
+            ),
+            Error::ExcessData(len) => write!(f, "message of {len} bytes is too large"),
+            Error::InvalidEndian(c) => write!(f, "invalid endianness signature `{c:#04x}`"),
+            Error::InvalidMessageType(t) => write!(f, "invalid message type {t}"),
+            Error::UnsupportedProtocolVersion(v) => write!(f, "unsupported protocol version {v}"),
+            Error::InvalidSerial => write!(f, "serial number of the message is zero"),
+            Error::InvalidField(code) => write!(f, "header field {code} is of the wrong type"),
+        }
+    }
+}
+
+impl From<zvariant::Error> for Error {
+    fn from(val: zvariant::Error) -> Self {
+        Error::Variant(val)
+    }
+}
+
+impl From<zbus_names::Error> for Error {
+    fn from(val: zbus_names::Error) -> Self {
+        Error::Names(val)
+    }
+}
+
+/// Alias for a `Result` with the error type `zbus_inspect::Error`.
+pub type Result<T> = std::result::Result<T, Error>;
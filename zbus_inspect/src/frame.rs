@@ -0,0 +1,462 @@
+use static_assertions::assert_impl_all;
+use std::{fmt, num::NonZeroU32};
+use zbus_names::{
+    BusName, ErrorName, InterfaceName, MemberName, OwnedBusName, OwnedErrorName,
+    OwnedInterfaceName, OwnedMemberName,
+};
+use zvariant::{
+    serialized::{Context, Data},
+    Endian, ObjectPath, OwnedObjectPath, OwnedSignature, Signature, Value,
+};
+
+use crate::{ArgsPreview, Error, Result};
+
+// The length of the fixed part of the header, up to and including the length of the fields.
+const MIN_MESSAGE_SIZE: usize = 16;
+const MAX_MESSAGE_SIZE: u64 = 128 * 1024 * 1024;
+const PROTOCOL_VERSION: u8 = 1;
+
+/// The length of the encoded message frame that `bytes` starts with.
+///
+/// Only the first 16 bytes of the frame, which advertise the lengths of the header and body, need
+/// to be available. This tells where each frame ends in a stream of messages.
+pub fn frame_len(bytes: &[u8]) -> Result<usize> {
+    if bytes.len() < MIN_MESSAGE_SIZE {
+        return Err(Error::Incomplete(bytes.len()));
+    }
+    let endian = endian(bytes[0])?;
+    let body_len = endian.read_u32(&bytes[4..8]);
+    let fields_len = endian.read_u32(&bytes[12..16]);
+    let header_len = MIN_MESSAGE_SIZE as u64 + u64::from(fields_len);
+    let len = header_len + padding_for_8_bytes(header_len) + u64::from(body_len);
+    if len > MAX_MESSAGE_SIZE {
+        return Err(Error::ExcessData(len));
+    }
+
+    Ok(len as usize)
+}
+
+/// The type of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    /// Method call.
+    MethodCall = 1,
+    /// A reply to a method call.
+    MethodReturn = 2,
+    /// An error in response to a method call.
+    Error = 3,
+    /// Signal emission.
+    Signal = 4,
+}
+
+/// An encoded D-Bus message frame, and a structured view of its header.
+///
+/// The header is validated and decoded when the frame is parsed, while the body is only decoded
+/// on demand, e.g through [`Frame::body`] or [`Frame::args`].
+///
+/// The [`Display`](fmt::Display) implementation gives a one-line summary of the message. Its
+/// alternate form (`{:#}`) adds a [preview of the arguments](ArgsPreview), truncated to the given
+/// precision, if any.
+#[derive(Debug, Clone)]
+pub struct Frame<'b> {
+    bytes: &'b [u8],
+    message_type: MessageType,
+    flags: u8,
+    serial: NonZeroU32,
+    fields: Fields,
+    body: Data<'b, 'static>,
+}
+
+assert_impl_all!(Frame<'_>: Send, Sync, Unpin);
+
+impl<'b> Frame<'b> {
+    /// Parse a complete message frame.
+    ///
+    /// The length of `bytes` must be the one advertised in the header, which [`frame_len`] gives.
+    /// Header fields unknown to the specification are ignored.
+    pub fn parse(bytes: &'b [u8]) -> Result<Self> {
+        let len = frame_len(bytes)?;
+        if len != bytes.len() {
+            return Err(Error::LengthMismatch(bytes.len(), len));
+        }
+
+        let endian = endian(bytes[0])?;
+        let message_type = match bytes[1] {
+            1 => MessageType::MethodCall,
+            2 => MessageType::MethodReturn,
+            3 => MessageType::Error,
+            4 => MessageType::Signal,
+            t => return Err(Error::InvalidMessageType(t)),
+        };
+        if bytes[3] != PROTOCOL_VERSION {
+            return Err(Error::UnsupportedProtocolVersion(bytes[3]));
+        }
+        let serial = NonZeroU32::new(endian.read_u32(&bytes[8..12])).ok_or(Error::InvalidSerial)?;
+
+        let ctxt = Context::new_dbus(endian, 0);
+        let data = Data::new(bytes, ctxt);
+        let fields_len = endian.read_u32(&bytes[12..16]) as usize;
+        let header_len = MIN_MESSAGE_SIZE + fields_len;
+        let fields_data = data.slice(MIN_MESSAGE_SIZE - 4..header_len);
+        let (fields, _) =
+            fields_data.deserialize_for_signature::<_, Vec<(u8, Value<'_>)>>("a(yv)")?;
+        let fields = Fields::new(fields)?;
+        let body_offset = header_len + padding_for_8_bytes(header_len as u64) as usize;
+
+        Ok(Self {
+            bytes,
+            message_type,
+            flags: bytes[2],
+            serial,
+            fields,
+            body: data.slice(body_offset..),
+        })
+    }
+
+    /// The bytes of the whole frame.
+    pub fn bytes(&self) -> &'b [u8] {
+        self.bytes
+    }
+
+    /// The endianness of the message.
+    pub fn endian(&self) -> Endian {
+        self.body.context().endian()
+    }
+
+    /// The type of the message.
+    pub fn message_type(&self) -> MessageType {
+        self.message_type
+    }
+
+    /// The flags of the message, as a bitmask.
+    ///
+    /// `0x1` is set if no reply is expected, `0x2` if the destination should not be auto-started
+    /// and `0x4` if interactive authorization is allowed.
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// The serial number of the message.
+    pub fn serial(&self) -> NonZeroU32 {
+        self.serial
+    }
+
+    /// The object path the method call is sent to, or the signal is emitted from.
+    pub fn path(&self) -> Option<&ObjectPath<'static>> {
+        self.fields.path.as_deref()
+    }
+
+    /// The interface of the method call or signal.
+    pub fn interface(&self) -> Option<&InterfaceName<'static>> {
+        self.fields.interface.as_deref()
+    }
+
+    /// The member, method or signal, name.
+    pub fn member(&self) -> Option<&MemberName<'static>> {
+        self.fields.member.as_deref()
+    }
+
+    /// The name of the error, for error messages.
+    pub fn error_name(&self) -> Option<&ErrorName<'static>> {
+        self.fields.error_name.as_deref()
+    }
+
+    /// The serial number of the message this message is a reply to.
+    pub fn reply_serial(&self) -> Option<NonZeroU32> {
+        self.fields.reply_serial
+    }
+
+    /// The name of the peer the message is sent to.
+    pub fn destination(&self) -> Option<&BusName<'static>> {
+        self.fields.destination.as_deref()
+    }
+
+    /// The name of the peer that sent the message, set by the bus.
+    pub fn sender(&self) -> Option<&BusName<'static>> {
+        self.fields.sender.as_deref()
+    }
+
+    /// The signature of the body, if it's not empty.
+    pub fn signature(&self) -> Option<&Signature<'static>> {
+        self.fields.signature.as_deref()
+    }
+
+    /// The number of file descriptors accompanying the message.
+    pub fn unix_fds(&self) -> Option<u32> {
+        self.fields.unix_fds
+    }
+
+    /// The encoded body, to be deserialized for [`Frame::signature`].
+    ///
+    /// The file descriptors accompanying the message aren't part of the frame, so deserializing
+    /// those in the body fails.
+    pub fn body(&self) -> &Data<'b, 'static> {
+        &self.body
+    }
+
+    /// A preview of the arguments of the message.
+    pub fn args(&self) -> Option<ArgsPreview<'_>> {
+        let signature = self.signature()?;
+
+        Some(ArgsPreview::new(&self.body, signature.as_ref()))
+    }
+}
+
+impl fmt::Display for Frame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message_type {
+            MessageType::MethodCall => {
+                write!(f, "Method call")?;
+                if let Some(m) = self.member() {
+                    write!(f, " {m}")?;
+                }
+            }
+            MessageType::MethodReturn => {
+                write!(f, "Method return")?;
+            }
+            MessageType::Error => {
+                write!(f, "Error")?;
+                if let Some(e) = self.error_name() {
+                    write!(f, " {e}")?;
+                }
+
+                // The first argument of errors is their message, if it's a string.
+                if self.signature().is_some_and(|s| s.starts_with('s')) {
+                    if let Ok((msg, _)) = self.body.deserialize_for_signature::<_, &str>("s") {
+                        write!(f, ": {msg}")?;
+                    }
+                }
+            }
+            MessageType::Signal => {
+                write!(f, "Signal")?;
+                if let Some(m) = self.member() {
+                    write!(f, " {m}")?;
+                }
+            }
+        }
+
+        if let Some(s) = self.sender() {
+            write!(f, " from {s}")?;
+        }
+
+        if let Some(args) = self.args().filter(|_| f.alternate()) {
+            match f.precision() {
+                Some(max_len) => write!(f, " {args:.max_len$}")?,
+                None => write!(f, " {args}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The header fields known to the specification.
+#[derive(Debug, Default, Clone)]
+struct Fields {
+    path: Option<OwnedObjectPath>,
+    interface: Option<OwnedInterfaceName>,
+    member: Option<OwnedMemberName>,
+    error_name: Option<OwnedErrorName>,
+    reply_serial: Option<NonZeroU32>,
+    destination: Option<OwnedBusName>,
+    sender: Option<OwnedBusName>,
+    signature: Option<OwnedSignature>,
+    unix_fds: Option<u32>,
+}
+
+impl Fields {
+    fn new(fields: Vec<(u8, Value<'_>)>) -> Result<Self> {
+        let mut f = Fields::default();
+        for (code, value) in fields {
+            let invalid = || Error::InvalidField(code);
+            match (code, value) {
+                (1, Value::ObjectPath(path)) => f.path = Some(path.into()),
+                (2, Value::Str(s)) => {
+                    f.interface = Some(InterfaceName::try_from(s.as_str())?.into());
+                }
+                (3, Value::Str(s)) => f.member = Some(MemberName::try_from(s.as_str())?.into()),
+                (4, Value::Str(s)) => f.error_name = Some(ErrorName::try_from(s.as_str())?.into()),
+                (5, Value::U32(serial)) => {
+                    f.reply_serial = Some(NonZeroU32::new(serial).ok_or_else(invalid)?);
+                }
+                (6, Value::Str(s)) => f.destination = Some(BusName::try_from(s.as_str())?.into()),
+                (7, Value::Str(s)) => f.sender = Some(BusName::try_from(s.as_str())?.into()),
+                (8, Value::Signature(s)) => f.signature = Some(s.into()),
+                (9, Value::U32(n)) => f.unix_fds = Some(n),
+                (1..=9, _) => return Err(invalid()),
+                // Unknown fields must be ignored.
+                _ => (),
+            }
+        }
+
+        Ok(f)
+    }
+}
+
+fn endian(c: u8) -> Result<Endian> {
+    match c {
+        b'l' => Ok(Endian::Little),
+        b'B' => Ok(Endian::Big),
+        c => Err(Error::InvalidEndian(c)),
+    }
+}
+
+fn padding_for_8_bytes(len: u64) -> u64 {
+    (8 - len % 8) % 8
+}
+
+#[cfg(test)]
+mod tests {
+    use zvariant::{to_bytes, Str, BE, LE};
+
+    use super::*;
+
+    // Encode a message, with the given header fields and body.
+    fn encode<B>(
+        endian: Endian,
+        message_type: u8,
+        serial: u32,
+        fields: &[(u8, Value<'_>)],
+        body: &B,
+    ) -> Vec<u8>
+    where
+        B: serde::Serialize + zvariant::Type,
+    {
+        let ctxt = Context::new_dbus(endian, 0);
+        let body = to_bytes(ctxt, body).unwrap();
+        let endian_sig = match endian {
+            Endian::Little => b'l',
+            Endian::Big => b'B',
+        };
+        let header = (
+            endian_sig,
+            message_type,
+            0u8,
+            PROTOCOL_VERSION,
+            body.len() as u32,
+            serial,
+            fields,
+        );
+        let mut bytes = to_bytes(ctxt, &header).unwrap().to_vec();
+        bytes.resize(
+            bytes.len() + padding_for_8_bytes(bytes.len() as u64) as usize,
+            0,
+        );
+        bytes.extend_from_slice(&body);
+
+        bytes
+    }
+
+    fn signal_fields() -> Vec<(u8, Value<'static>)> {
+        vec![
+            (
+                1,
+                ObjectPath::from_static_str_unchecked("/org/zbus/Meter").into(),
+            ),
+            (2, Str::from_static("org.zbus.Meter").into()),
+            (3, Str::from_static("Changed").into()),
+            (7, Str::from_static(":1.7").into()),
+            (8, Signature::from_static_str_unchecked("sv").into()),
+            // Unknown to the specification.
+            (42, Value::from(1u32)),
+        ]
+    }
+
+    #[test]
+    fn parse() {
+        for endian in [LE, BE] {
+            let bytes = encode(endian, 4, 3, &signal_fields(), &("level", Value::from(7u8)));
+            assert_eq!(frame_len(&bytes[..16]).unwrap(), bytes.len());
+            let frame = Frame::parse(&bytes).unwrap();
+
+            assert_eq!(frame.endian(), endian);
+            assert_eq!(frame.message_type(), MessageType::Signal);
+            assert_eq!(frame.serial().get(), 3);
+            assert_eq!(frame.path().unwrap(), "/org/zbus/Meter");
+            assert_eq!(frame.interface().unwrap(), "org.zbus.Meter");
+            assert_eq!(frame.member().unwrap(), "Changed");
+            assert_eq!(frame.sender().unwrap(), ":1.7");
+            assert_eq!(frame.signature().unwrap(), "sv");
+            assert!(frame.destination().is_none());
+            let (level, _) = frame
+                .body()
+                .deserialize_for_signature::<_, (&str, Value<'_>)>("(sv)")
+                .unwrap();
+            assert_eq!(level, ("level", Value::from(7u8)));
+
+            assert_eq!(frame.to_string(), "Signal Changed from :1.7");
+            assert_eq!(
+                format!("{frame:#}"),
+                r#"Signal Changed from :1.7 ("level", <byte 0x07>)"#
+            );
+            assert_eq!(
+                format!("{frame:#.10}"),
+                r#"Signal Changed from :1.7 ("level", …"#
+            );
+        }
+
+        let fields = [
+            (4, Value::from(Str::from_static("org.zbus.Error.Failed"))),
+            (5, Value::from(3u32)),
+            (8, Signature::from_static_str_unchecked("s").into()),
+        ];
+        let bytes = encode(LE, 3, 4, &fields, &"kaboom");
+        let frame = Frame::parse(&bytes).unwrap();
+        assert_eq!(frame.reply_serial().unwrap().get(), 3);
+        assert_eq!(frame.to_string(), "Error org.zbus.Error.Failed: kaboom");
+    }
+
+    #[test]
+    fn invalid() {
+        let bytes = encode(LE, 4, 3, &signal_fields(), &("level", Value::from(7u8)));
+        assert_eq!(frame_len(&bytes[..15]), Err(Error::Incomplete(15)));
+        assert_eq!(
+            Frame::parse(&bytes[..bytes.len() - 1]).unwrap_err(),
+            Error::LengthMismatch(bytes.len() - 1, bytes.len())
+        );
+
+        let mut invalid = bytes.clone();
+        invalid[0] = b'x';
+        assert_eq!(
+            Frame::parse(&invalid).unwrap_err(),
+            Error::InvalidEndian(b'x')
+        );
+        let mut invalid = bytes.clone();
+        invalid[1] = 5;
+        assert_eq!(
+            Frame::parse(&invalid).unwrap_err(),
+            Error::InvalidMessageType(5)
+        );
+        let mut invalid = bytes.clone();
+        invalid[3] = 2;
+        assert_eq!(
+            Frame::parse(&invalid).unwrap_err(),
+            Error::UnsupportedProtocolVersion(2)
+        );
+
+        let bytes = encode(LE, 4, 0, &signal_fields(), &("level", Value::from(7u8)));
+        assert_eq!(Frame::parse(&bytes).unwrap_err(), Error::InvalidSerial);
+
+        let bytes = encode(LE, 4, 3, &[(3, Value::from(1u32))], &());
+        assert_eq!(Frame::parse(&bytes).unwrap_err(), Error::InvalidField(3));
+        let bytes = encode(
+            LE,
+            4,
+            3,
+            &[(3, Str::from_static("not.a.member").into())],
+            &(),
+        );
+        assert!(matches!(
+            Frame::parse(&bytes).unwrap_err(),
+            Error::Names(zbus_names::Error::InvalidMemberName(_))
+        ));
+
+        // Bodies are only decoded on demand.
+        let bytes = encode(LE, 4, 3, &signal_fields(), &"level");
+        let frame = Frame::parse(&bytes).unwrap();
+        assert_eq!(
+            format!("{frame:#}"),
+            r#"Signal Changed from :1.7 ("level", "#
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use static_assertions::assert_impl_all;
+use std::fmt::{self, Write};
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use zvariant::{serialized::Data, DynamicType, Signature};
+
+// The default length of the preview.
+const DEFAULT_LEN: usize = 256;
+
+// The nesting depth of containers beyond which the preview only shows an ellipsis.
+const MAX_DEPTH: u8 = 8;
+
+/// A preview of the arguments of a message body, for logging and debugging.
+///
+/// Its [`Display`](fmt::Display) implementation writes the arguments in the [GVariant text
+/// format], as a tuple, truncated to the precision of the format, if any, or to 256 characters.
+/// The arguments are written as they're decoded, so nothing past what fits in the preview is
+/// decoded, however large the body. Containers nested more than 8 levels deep are elided.
+///
+/// The preview is best-effort: it ends early if the body can't be decoded.
+///
+/// # Example
+///
+/// ```
+/// use zbus_inspect::ArgsPreview;
+/// use zvariant::{serialized::Context, to_bytes, Signature, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let samples = vec![0u8; 1024 * 1024];
+/// let body = to_bytes(ctxt, &("left", 42u32, &samples))?;
+/// let args = ArgsPreview::new(&body, Signature::from_static_str("suay")?);
+///
+/// assert_eq!(format!("{args:.24}"), r#"("left", uint32 42, [byt…"#);
+/// # Ok::<(), zvariant::Error>(())
+/// ```
+///
+/// [GVariant text format]: https://docs.gtk.org/glib/gvariant-text-format.html
+#[derive(Debug, Clone)]
+pub struct ArgsPreview<'a> {
+    body: &'a Data<'a, 'a>,
+    signature: Signature<'a>,
+}
+
+assert_impl_all!(ArgsPreview<'_>: Send, Sync, Unpin);
+
+impl<'a> ArgsPreview<'a> {
+    /// Create a preview of the arguments encoded in `body`, for the body signature `signature`.
+    pub fn new(body: &'a Data<'a, 'a>, signature: Signature<'a>) -> Self {
+        Self { body, signature }
+    }
+}
+
+impl fmt::Display for ArgsPreview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Ok(signatures) = self.signature.complete_types() else {
+            return Ok(());
+        };
+        let mut out = Truncated {
+            remaining: f.precision().unwrap_or(DEFAULT_LEN),
+            f,
+            failed: false,
+        };
+
+        match write_args(&mut out, self.body, signatures) {
+            // Stopping as soon as the limit is hit is what the error is for.
+            Err(_) if !out.failed => Ok(()),
+            res => res,
+        }
+    }
+}
+
+fn write_args(
+    out: &mut Truncated<'_, '_>,
+    body: &Data<'_, '_>,
+    signatures: Vec<Signature<'_>>,
+) -> fmt::Result {
+    let mut pos = 0;
+    for (i, signature) in signatures.iter().enumerate() {
+        let arg = Arg {
+            signature: signature.as_ref(),
+            out: &mut *out,
+            depth: 0,
+            annotate: true,
+            prefix: if i == 0 { "(" } else { ", " },
+        };
+        let ((), size) = body
+            .slice(pos..)
+            .deserialize_with_seed(arg)
+            .map_err(|_| fmt::Error)?;
+        pos += size;
+    }
+    match signatures.len() {
+        0 => out.write_str("()"),
+        1 => out.write_str(",)"),
+        _ => out.write_char(')'),
+    }
+}
+
+// A writer of at most `remaining` characters, followed by an ellipsis if there were more.
+//
+// Once the limit is hit, writing fails without `failed` being set.
+struct Truncated<'a, 'f> {
+    f: &'a mut fmt::Formatter<'f>,
+    remaining: usize,
+    // Whether writing to `f` failed.
+    failed: bool,
+}
+
+impl Truncated<'_, '_> {
+    fn write<E: de::Error>(&mut self, args: fmt::Arguments<'_>) -> Result<(), E> {
+        self.write_fmt(args)
+            .map_err(|_| E::custom("end of the arguments preview"))
+    }
+}
+
+impl fmt::Write for Truncated<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let (s, truncated) = match s.char_indices().nth(self.remaining) {
+            None => (s, false),
+            Some((end, _)) => (&s[..end], true),
+        };
+        self.remaining -= s.chars().count();
+        let mut res = self.f.write_str(s);
+        if truncated {
+            res = res.and_then(|_| self.f.write_char('…'));
+        }
+        self.failed |= res.is_err();
+
+        if truncated {
+            Err(fmt::Error)
+        } else {
+            res
+        }
+    }
+}
+
+// A value of the given signature, written to `out` as it's deserialized.
+struct Arg<'s, 'o, 'a, 'f> {
+    signature: Signature<'s>,
+    out: &'o mut Truncated<'a, 'f>,
+    depth: u8,
+    // Whether to annotate the type of values that would be ambiguous in the text format.
+    annotate: bool,
+    // Written before the value, so that separators are only written if there's a next value.
+    prefix: &'static str,
+}
+
+impl<'a, 'f> Arg<'_, '_, 'a, 'f> {
+    fn signature_char(&self) -> u8 {
+        self.signature
+            .as_bytes()
+            .first()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn child<'s>(
+        &mut self,
+        signature: Signature<'s>,
+        annotate: bool,
+        prefix: &'static str,
+    ) -> Arg<'s, '_, 'a, 'f> {
+        Arg {
+            signature,
+            out: &mut *self.out,
+            depth: self.depth + 1,
+            annotate,
+            prefix,
+        }
+    }
+
+    fn write<E: de::Error>(&mut self, args: fmt::Arguments<'_>) -> Result<(), E> {
+        self.out.write(args)
+    }
+
+    fn write_annotated<E: de::Error>(
+        &mut self,
+        type_name: &str,
+        args: fmt::Arguments<'_>,
+    ) -> Result<(), E> {
+        if self.annotate {
+            self.write(format_args!("{type_name} "))?;
+        }
+
+        self.write(args)
+    }
+
+    // Close a container of `count` elements, annotating the type of empty ones.
+    fn write_end<E: de::Error>(&mut self, count: usize, empty: &str, end: &str) -> Result<(), E> {
+        if count > 0 {
+            return self.write(format_args!("{end}"));
+        }
+        if self.annotate {
+            self.out.write(format_args!("@{} ", self.signature))?;
+        }
+
+        self.write(format_args!("{empty}"))
+    }
+}
+
+impl DynamicType for Arg<'_, '_, '_, '_> {
+    fn dynamic_signature(&self) -> Signature<'_> {
+        self.signature.as_ref()
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Arg<'_, '_, '_, '_> {
+    type Value = ();
+
+    fn deserialize<D>(mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.out.write(format_args!("{}", self.prefix))?;
+        let is_container = matches!(self.signature_char(), b'a' | b'(' | b'v');
+        if is_container && self.depth >= MAX_DEPTH {
+            deserializer.deserialize_ignored_any(IgnoredAny)?;
+
+            return self.write(format_args!("…"));
+        }
+
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Arg<'_, '_, '_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a value of signature `{}`", self.signature)
+    }
+
+    fn visit_bool<E: de::Error>(mut self, v: bool) -> Result<(), E> {
+        self.write(format_args!("{v}"))
+    }
+
+    fn visit_u8<E: de::Error>(mut self, v: u8) -> Result<(), E> {
+        self.write_annotated("byte", format_args!("0x{v:02x}"))
+    }
+
+    fn visit_i16<E: de::Error>(mut self, v: i16) -> Result<(), E> {
+        self.write_annotated("int16", format_args!("{v}"))
+    }
+
+    fn visit_u16<E: de::Error>(mut self, v: u16) -> Result<(), E> {
+        self.write_annotated("uint16", format_args!("{v}"))
+    }
+
+    fn visit_i32<E: de::Error>(mut self, v: i32) -> Result<(), E> {
+        if self.signature_char() == b'h' {
+            return self.write_annotated("handle", format_args!("{v}"));
+        }
+
+        // Never annotated, since it's the default for numbers.
+        self.write(format_args!("{v}"))
+    }
+
+    fn visit_u32<E: de::Error>(mut self, v: u32) -> Result<(), E> {
+        self.write_annotated("uint32", format_args!("{v}"))
+    }
+
+    fn visit_i64<E: de::Error>(mut self, v: i64) -> Result<(), E> {
+        self.write_annotated("int64", format_args!("{v}"))
+    }
+
+    fn visit_u64<E: de::Error>(mut self, v: u64) -> Result<(), E> {
+        self.write_annotated("uint64", format_args!("{v}"))
+    }
+
+    fn visit_f64<E: de::Error>(mut self, v: f64) -> Result<(), E> {
+        if v.fract() == 0. {
+            // Add a dot to make it clear that this is a float.
+            self.write(format_args!("{v}."))
+        } else {
+            self.write(format_args!("{v}"))
+        }
+    }
+
+    fn visit_str<E: de::Error>(mut self, v: &str) -> Result<(), E> {
+        match self.signature_char() {
+            b'o' => self.write_annotated("objectpath", format_args!("{v:?}")),
+            b'g' => self.write_annotated("signature", format_args!("{v:?}")),
+            _ => self.write(format_args!("{v:?}")),
+        }
+    }
+
+    fn visit_seq<V>(mut self, mut seq: V) -> Result<(), V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        match self.signature_char() {
+            b'v' => {
+                let signature = seq
+                    .next_element::<Signature<'_>>()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                // Always annotated, since variants are of variable type by nature.
+                seq.next_element_seed(self.child(signature, true, "<"))?;
+
+                self.write(format_args!(">"))
+            }
+            b'a' => {
+                let element_signature = self.signature.slice(1..);
+                let mut count = 0;
+                loop {
+                    // Only the first element is annotated, the others being of the same type.
+                    let (annotate, prefix) = match count {
+                        0 => (self.annotate, "["),
+                        _ => (false, ", "),
+                    };
+                    let element = self.child(element_signature.clone(), annotate, prefix);
+                    if seq.next_element_seed(element)?.is_none() {
+                        break;
+                    }
+                    count += 1;
+                }
+
+                self.write_end(count, "[]", "]")
+            }
+            _ => {
+                let end = self.signature.len() - 1;
+                let fields = self
+                    .signature
+                    .slice(1..end)
+                    .complete_types()
+                    .map_err(de::Error::custom)?;
+                let len = fields.len();
+                for (i, field) in fields.into_iter().enumerate() {
+                    let annotate = self.annotate;
+                    let prefix = if i == 0 { "(" } else { ", " };
+                    seq.next_element_seed(self.child(field, annotate, prefix))?;
+                }
+
+                match len {
+                    1 => self.write(format_args!(",)")),
+                    _ => self.write(format_args!(")")),
+                }
+            }
+        }
+    }
+
+    fn visit_map<V>(mut self, mut map: V) -> Result<(), V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let end = self.signature.len() - 1;
+        let key_signature = self.signature.slice(2..3);
+        let value_signature = self.signature.slice(3..end);
+        let mut count = 0;
+        loop {
+            // Only the first entry is annotated, the others being of the same type.
+            let (annotate, prefix) = match count {
+                0 => (self.annotate, "{"),
+                _ => (false, ", "),
+            };
+            let key = self.child(key_signature.clone(), annotate, prefix);
+            if map.next_key_seed(key)?.is_none() {
+                break;
+            }
+            map.next_value_seed(self.child(value_signature.clone(), annotate, ": "))?;
+            count += 1;
+        }
+
+        self.write_end(count, "{}", "}")
+    }
+}
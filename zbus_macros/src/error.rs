@@ -140,6 +140,7 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     }
 
     let from_zbus_error_impl = zbus_error_variant
+        .as_ref()
         .map(|ident| {
             quote! {
                 impl ::std::convert::From<#zbus::Error> for #name {
@@ -158,6 +159,20 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
         })
         .unwrap_or_default();
 
+    // The wrapped `zbus::Error` is the source of the `#[zbus(error)]` variant, so the chain of
+    // errors isn't cut short when converting between the two.
+    let source_impl = match &zbus_error_variant {
+        Some(ident) => quote! {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    Self::#ident(e) => ::std::option::Option::Some(e),
+                    _ => ::std::option::Option::None,
+                }
+            }
+        },
+        None => quote! {},
+    };
+
     let display_impl = if generate_display {
         quote! {
             impl ::std::fmt::Display for #name {
@@ -196,7 +211,9 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
 
         #display_impl
 
-        impl ::std::error::Error for #name {}
+        impl ::std::error::Error for #name {
+            #source_impl
+        }
 
         #from_zbus_error_impl
     })
@@ -19,7 +19,8 @@ pub mod old {
         pub TraitAttributes("trait") {
             interface str,
             name str,
-            spawn bool
+            spawn bool,
+            extends str
         };
 
         pub MethodAttributes("method") {
@@ -41,7 +42,8 @@ def_attrs! {
     pub TraitAttributes("trait") {
         interface str,
         name str,
-        spawn bool
+        spawn bool,
+        extends str
     };
 
     pub MethodAttributes("method") {
@@ -302,10 +304,10 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
         _ => return Err(Error::new_spanned(&input.self_ty, "Invalid type")),
     };
 
-    let (iface_name, with_spawn) = {
-        let (name, interface, spawn) = match T::parse_nested_metas(&args)?.into() {
-            TraitAttrs::New(new) => (new.name, new.interface, new.spawn),
-            TraitAttrs::Old(old) => (old.name, old.interface, old.spawn),
+    let (iface_name, with_spawn, extends) = {
+        let (name, interface, spawn, extends) = match T::parse_nested_metas(&args)?.into() {
+            TraitAttrs::New(new) => (new.name, new.interface, new.spawn, new.extends),
+            TraitAttrs::Old(old) => (old.name, old.interface, old.spawn, old.extends),
         };
 
         let name =
@@ -318,7 +320,11 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 )),
             };
 
-        (name, !spawn.unwrap_or(true))
+        let extends = extends
+            .map(|base| syn::parse_str::<Type>(&base))
+            .transpose()?;
+
+        (name, !spawn.unwrap_or(true), extends)
     };
 
     // Store parsed information about each method
@@ -411,7 +417,9 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                     #signal_context.connection().emit_signal(
                         #signal_context.destination(),
                         #signal_context.path(),
-                        <#self_ty as #zbus::object_server::Interface>::name(),
+                        #signal_context.interface_name(
+                            <#self_ty as #zbus::object_server::Interface>::name(),
+                        ),
                         #member_name,
                         &(#args_names),
                     )
@@ -630,7 +638,9 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                             changed.insert(#member_name, &value);
                             #zbus::fdo::Properties::properties_changed(
                                 signal_context,
-                                #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                                signal_context.interface_name(
+                                    #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                                ),
                                 &changed,
                                 &[],
                             ).await
@@ -646,7 +656,9 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                         ) -> #zbus::Result<()> {
                             #zbus::fdo::Properties::properties_changed(
                                 signal_context,
-                                #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                                signal_context.interface_name(
+                                    #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                                ),
                                 &::std::collections::HashMap::new(),
                                 &[#member_name],
                             ).await
@@ -692,6 +704,83 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
     let generics = &input.generics;
     let where_clause = &generics.where_clause;
 
+    // Members not found in this interface are looked up in the base interface, if any.
+    let (
+        get_fallback,
+        get_all_base,
+        set_fallback,
+        set_mut_fallback,
+        call_fallback,
+        call_mut_fallback,
+        introspect_base,
+        extend_signal_context,
+    ) = match &extends {
+        Some(base) => {
+            let base_iface = quote! { <#base as #zbus::object_server::Interface> };
+            let base_ref = quote! { <Self as ::std::convert::AsRef<#base>>::as_ref(self) };
+            let base_mut = quote! { <Self as ::std::convert::AsMut<#base>>::as_mut(self) };
+
+            (
+                quote! { #base_iface::get(#base_ref, property_name).await },
+                quote! { props.extend(#base_iface::get_all(#base_ref).await?); },
+                // The base is always set through `set_mut`, where its signals can be emitted
+                // under the name of this interface.
+                quote! {
+                    match #base_iface::set(#base_ref, property_name, value, signal_context) {
+                        #zbus::object_server::DispatchResult::NotFound => {
+                            #zbus::object_server::DispatchResult::NotFound
+                        }
+                        _ => #zbus::object_server::DispatchResult::RequiresMut,
+                    }
+                },
+                quote! {{
+                    let signal_context =
+                        <Self as #zbus::object_server::Interface>::extend_signal_context(
+                            ::std::clone::Clone::clone(signal_context),
+                        );
+                    let requires_mut =
+                        match #base_iface::set(#base_ref, property_name, value, &signal_context) {
+                            #zbus::object_server::DispatchResult::Async(f) => {
+                                return ::std::option::Option::Some(
+                                    f.await.map_err(::std::convert::Into::into),
+                                );
+                            }
+                            #zbus::object_server::DispatchResult::RequiresMut => true,
+                            #zbus::object_server::DispatchResult::NotFound => false,
+                        };
+                    if requires_mut {
+                        #base_iface::set_mut(#base_mut, property_name, value, &signal_context).await
+                    } else {
+                        ::std::option::Option::None
+                    }
+                }},
+                quote! { #base_iface::call(#base_ref, s, c, m, name) },
+                quote! { #base_iface::call_mut(#base_mut, s, c, m, name) },
+                quote! { #base_iface::introspect_members_to_writer(#base_ref, writer, level); },
+                quote! {
+                    fn extend_signal_context(
+                        ctxt: #zbus::object_server::SignalContext<'_>,
+                    ) -> #zbus::object_server::SignalContext<'_> {
+                        #base_iface::extend_signal_context(ctxt).set_extended_interface(
+                            #base_iface::name(),
+                            <Self as #zbus::object_server::Interface>::name(),
+                        )
+                    }
+                },
+            )
+        }
+        None => (
+            quote! { ::std::option::Option::None },
+            quote!(),
+            quote! { #zbus::object_server::DispatchResult::NotFound },
+            quote! { ::std::option::Option::None },
+            quote! { #zbus::object_server::DispatchResult::NotFound },
+            quote! { #zbus::object_server::DispatchResult::NotFound },
+            quote!(),
+            quote!(),
+        ),
+    };
+
     let generated_signals_impl = if generated_signals.is_empty() {
         quote!()
     } else {
@@ -717,6 +806,8 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name)
             }
 
+            #extend_signal_context
+
             fn spawn_tasks_for_methods(&self) -> bool {
                 #with_spawn
             }
@@ -727,7 +818,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
                 match property_name {
                     #get_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #get_fallback,
                 }
             }
 
@@ -741,6 +832,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                     ::std::string::String,
                     #zbus::zvariant::OwnedValue,
                 > = ::std::collections::HashMap::new();
+                #get_all_base
                 #get_all
                 Ok(props)
             }
@@ -753,7 +845,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             ) -> #zbus::object_server::DispatchResult<'call> {
                 match property_name {
                     #set_dispatch
-                    _ => #zbus::object_server::DispatchResult::NotFound,
+                    _ => #set_fallback,
                 }
             }
 
@@ -765,7 +857,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             ) -> ::std::option::Option<#zbus::fdo::Result<()>> {
                 match property_name {
                     #set_mut_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #set_mut_fallback,
                 }
             }
 
@@ -778,7 +870,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             ) -> #zbus::object_server::DispatchResult<'call> {
                 match name.as_str() {
                     #call_dispatch
-                    _ => #zbus::object_server::DispatchResult::NotFound,
+                    _ => #call_fallback,
                 }
             }
 
@@ -791,7 +883,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             ) -> #zbus::object_server::DispatchResult<'call> {
                 match name.as_str() {
                     #call_mut_dispatch
-                    _ => #zbus::object_server::DispatchResult::NotFound,
+                    _ => #call_mut_fallback,
                 }
             }
 
@@ -803,14 +895,24 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                     <Self as #zbus::object_server::Interface>::name(),
                     indent = level
                 ).unwrap();
-                {
-                    use #zbus::zvariant::Type;
-
-                    let level = level + 2;
-                    #introspect
-                }
+                <Self as #zbus::object_server::Interface>::introspect_members_to_writer(
+                    self,
+                    writer,
+                    level + 2,
+                );
                 ::std::writeln!(writer, r#"{:indent$}</interface>"#, "", indent = level).unwrap();
             }
+
+            fn introspect_members_to_writer(
+                &self,
+                writer: &mut dyn ::std::fmt::Write,
+                level: usize,
+            ) {
+                use #zbus::zvariant::Type;
+
+                #introspect_base
+                #introspect
+            }
         }
     })
 }
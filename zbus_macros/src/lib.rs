@@ -12,11 +12,12 @@
 )))]
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemImpl, ItemTrait};
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemFn, ItemImpl, ItemTrait};
 
 mod error;
 mod iface;
 mod proxy;
+mod test;
 mod utils;
 
 /// Attribute macro for defining D-Bus proxies (using [`zbus::Proxy`] and
@@ -51,11 +52,18 @@ mod utils;
 ///   attribute nor one of the default values are specified. Please make sure to explicitly set
 ///   either this attribute or the default values, according to your needs.
 ///
+/// * `allow_interactive_auth` - allow all the method calls to trigger an interactive prompt for
+///   authorization from the receiver, e.g for services guarded by polkit. This is the same as
+///   calling `allow_interactive_auth(true)` on the proxy builder.
+///
 /// Each trait method will be expanded to call to the associated D-Bus remote interface.
 ///
 /// Trait methods accept `proxy` attributes:
 ///
-/// * `name` - override the D-Bus name (pascal case form by default)
+/// * `name` - override the D-Bus name (pascal case form by default). The pascal case form only
+///   capitalizes the first letter of each word, so names containing acronyms, e.g `GetACLs` for a
+///   `get_acls` method, must be specified this way. `zbus-xmlgen` adds this attribute whenever
+///   needed.
 ///
 /// * `property` - expose the method as a property. If the method takes an argument, it must be a
 ///   setter, with a `set_` prefix. Otherwise, it's a getter. Additional sub-attributes exists to
@@ -73,11 +81,20 @@ mod utils;
 ///     * `"false"` - change signal is not (guaranteed to be) emitted if the property changes. This
 ///       disables property value caching, and does not generate a listener method for the change
 ///       signal.
+///   * `serde` - convert the property value through the `Serialize` and `Deserialize`
+///     implementations of its type, instead of `TryFrom<OwnedValue>` and `Into<Value>`. This
+///     allows any `T: Type + DeserializeOwned` to be used for the property, such as a struct that
+///     only derives `Deserialize` and `Type`, at the cost of encoding and decoding the value. The
+///     items of the change stream are then wrapped in [`DeserializeValue`].
+///
+///   Properties of dynamic types are best declared with `OwnedValue`, and converted by the caller.
 ///
 /// * `signal` - declare a signal just like a D-Bus method. Read the [Signals](#signals) section
 ///   below for details.
 ///
-/// * `no_reply` - declare a method call that does not wait for a reply.
+/// * `no_reply` - declare a method call that does not wait for a reply. The `NO_REPLY_EXPECTED`
+///   flag is set on the call, so the receiver doesn't send one, and the method returns as soon as
+///   the call is sent. Such methods must return `Result<()>` and can't be combined with `object`.
 ///
 /// * `no_autostart` - declare a method call that will not trigger the bus to automatically launch
 ///   the destination service if it is not already running.
@@ -96,6 +113,19 @@ mod utils;
 ///
 ///   NB: Any doc comments provided shall be appended to the ones added by the macro.
 ///
+/// Methods and properties can return a custom error type, rather than [`zbus::Error`], as long as it
+/// implements `From<zbus::Error>`. The [`DBusError`] derive macro provides such an implementation,
+/// mapping D-Bus error replies to the variants of the type according to their name.
+///
+/// The arguments of method calls support the following `zbus` attribute:
+///
+/// * `signature` - override the D-Bus signature of the argument, for types that don't implement
+///   [`Type`] or whose signature doesn't match the one the remote method expects. The argument is
+///   serialized as is under the given signature, except for `"v"`, for which it's first wrapped in
+///   a [`Value`], unless it already is a variant (e.g a [`Value`] or `OwnedValue`). As with the
+///   `zvariant` attribute of the same name, `"dict"` is an alias for `"a{sv}"`. An invalid
+///   signature is reported when the macro is expanded.
+///
 /// # Signals
 ///
 /// For each signal method declared, this macro will provide a method, named `receive_<method_name>`
@@ -105,6 +135,10 @@ mod utils;
 /// access to the signal arguments. It also implements `Deref<Target = Message>` to allow easy
 /// access to the underlying [`zbus::message::Message`].
 ///
+/// File descriptor (`h`) arguments are resolved from the file descriptors attached to the signal
+/// message. Declare them as `zvariant::OwnedFd` to take ownership of them, or as `zvariant::Fd<'_>`
+/// to borrow them from the message.
+///
 /// # Example
 ///
 /// ```no_run
@@ -190,6 +224,11 @@ mod utils;
 /// [`zbus::SignalStream`]: https://docs.rs/zbus/latest/zbus/proxy/struct.SignalStream.html
 /// [`zbus::blocking::SignalIterator`]: https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.SignalIterator.html
 /// [`ObjectPath`]: https://docs.rs/zvariant/latest/zvariant/struct.ObjectPath.html
+/// [`DeserializeValue`]: https://docs.rs/zvariant/latest/zvariant/struct.DeserializeValue.html
+/// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
+/// [`zbus::Error`]: https://docs.rs/zbus/latest/zbus/enum.Error.html
+/// [`DBusError`]: macro@DBusError
+/// [`Value`]: https://docs.rs/zvariant/latest/zvariant/enum.Value.html
 /// [dbus_emits_changed_signal]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 #[proc_macro_attribute]
 pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -216,6 +255,17 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// properties or signal depending on the item attributes. It will implement the [`Interface`] trait
 /// `for T` on your behalf, to handle the message dispatching and introspection support.
 ///
+/// Methods, including property getters and setters, can be `async`. They're awaited by the object
+/// server, which by default handles each method call in its own task (see `spawn` below), so a
+/// method awaiting some I/O doesn't hold up the dispatching of other calls. This is also the case
+/// for interfaces served through the blocking API, which is a wrapper of the asynchronous one.
+///
+/// The introspection data served by the [`ObjectServer`] through the
+/// `org.freedesktop.DBus.Introspectable` interface is generated from the declarations: methods,
+/// signals and properties are listed along with their signatures. Input and signal arguments are
+/// named after the Rust arguments, output arguments after `out_args` if given, and doc comments
+/// are included as XML comments.
+///
 /// The trait accepts the `interface` attributes:
 ///
 /// * `name` - the D-Bus interface name
@@ -235,6 +285,14 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   However, care must be taken to avoid making D-Bus method calls from within your interface
 ///   methods when this setting is false, as it may lead to deadlocks under certain conditions.
 ///
+/// * `extends` - The type of another interface whose members (methods and properties) are to be
+///   merged into this interface, e.g. `extends = "Describable"`. This is useful for sharing a
+///   common set of members between interfaces. The type must implement [`AsRef`] and [`AsMut`] for
+///   the base type, and any member not found in this interface is dispatched to the base. Members
+///   of this interface take precedence over the ones of the base. The signals of the base, including
+///   its `PropertiesChanged` signals, are emitted under the name of this interface when using the
+///   signal context of its [`InterfaceRef`], or the one passed to property setters.
+///
 /// The methods accepts the `interface` attributes:
 ///
 /// * `name` - override the D-Bus name (pascal case form of the method by default)
@@ -251,13 +309,19 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     signal.
 ///     * `"const"` - the property never changes, thus no signal is ever emitted for it.
 ///     * `"false"` - the change signal is not emitted if the property changes.
-
-/// * `signal` - the method is a "signal". It must be a method declaration (without body). Its code
-///   block will be expanded to emit the signal from the object path associated with the interface
-///   instance.
+///
+/// * `signal` - the method is a "signal". It must be a method declaration (without body), taking a
+///   [`SignalContext`] followed by the signal arguments, e.g
+///   `async fn device_added(ctxt: &SignalContext<'_>, path: ObjectPath<'_>) -> zbus::Result<()>;`.
+///   Its code block will be expanded to emit the signal from the object path associated with the
+///   given context, and the signal is included in the introspection data of the interface.
 ///
 ///   You can call a signal method from a an interface method, or from an [`ObjectServer::with`]
-///   function.
+///   function. Anywhere else, the context of the served object is available through
+///   `InterfaceRef::signal_context` (see [`InterfaceRef`]).
+///
+///   File descriptor (`h`) arguments, declared as `zvariant::Fd<'_>`, are attached to the signal
+///   message, so they're passed to the receivers along with it.
 ///
 /// * `out_args` - When returning multiple values from a method, naming the out arguments become
 ///   important. You can use `out_args` to specify their names.
@@ -274,12 +338,36 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// exists) will automatically call this method. For instance, a property setter named `set_foo`
 /// will be called to set the property "Foo", and will emit the "PropertiesChanged" signal with the
 /// new value for "Foo". Other changes to the "Foo" property can be signaled manually with the
-/// generated `foo_changed` method. In addition, a `<property_name_in_snake_case>_invalidated`
-/// method is also generated that much like `_changed` method, emits a "PropertyChanged" signal
+/// generated `foo_changed` method. In addition, a `<property_name_in_snake_case>_invalidate`
+/// method is also generated that much like `_changed` method, emits a "PropertiesChanged" signal
 /// but does not send over the new value of the property along with it. It is usually best to avoid
 /// using this since it will force all interested peers to fetch the new value and hence result in
 /// excess traffic on the bus.
 ///
+/// Both methods take the [`SignalContext`] of the object. Outside of the interface methods, e.g
+/// when the value changes in the background, it can be obtained from the [`InterfaceRef`] of the
+/// served object:
+///
+/// ```no_run
+/// # use zbus::{interface, Connection};
+/// # struct Thermometer { celsius: f64 }
+/// # #[interface(name = "org.example.Thermometer")]
+/// # impl Thermometer {
+/// #     #[zbus(property)]
+/// #     fn celsius(&self) -> f64 { self.celsius }
+/// # }
+/// # async fn update(connection: &Connection) -> zbus::Result<()> {
+/// let iface_ref = connection
+///     .object_server()
+///     .interface::<_, Thermometer>("/org/example/Thermometer")
+///     .await?;
+/// let mut iface = iface_ref.get_mut().await;
+/// iface.celsius = 21.5;
+/// iface.celsius_changed(iface_ref.signal_context()).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
 /// The method arguments support the following `zbus` attributes:
 ///
 /// * `object_server` - This marks the method argument to receive a reference to the
@@ -359,6 +447,7 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// [`Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
 /// [`Connection::emit_signal()`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html#method.emit_signal
 /// [`SignalContext`]: https://docs.rs/zbus/latest/zbus/object_server/struct.SignalContext.html
+/// [`InterfaceRef`]: https://docs.rs/zbus/latest/zbus/object_server/struct.InterfaceRef.html
 /// [`Interface`]: https://docs.rs/zbus/latest/zbus/object_server/trait.Interface.html
 /// [dbus_emits_changed_signal]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 #[proc_macro_attribute]
@@ -419,3 +508,65 @@ pub fn derive_dbus_error(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Attribute macro for tests that need a D-Bus connection.
+///
+/// The test function takes its connections as arguments, which are set up before it's run and
+/// dropped after it returns. One of the following attributes specifies which connections:
+///
+/// * `session_bus` - a single connection to a session bus of the test's own, which is launched
+///   before and shut down after it. This requires `dbus-daemon` to be in `PATH`.
+///
+/// * `p2p` - a pair of connections, server first and then client, connected to each other through
+///   an in-process channel. This is isolated from any other test and doesn't need a bus, but
+///   requires the `p2p` feature of zbus.
+///
+/// The arguments can be either [`zbus::Connection`] or [`zbus::blocking::Connection`]. The test
+/// function can be `async`, in which case it's run through a runtime matching the features zbus
+/// was built with. As with `#[test]` functions, it can also return a `Result`.
+///
+/// # Example
+///
+/// ```
+/// # // The `#[test]` function is only compiled by `cargo test`.
+/// # #![allow(dead_code, unused_imports)]
+/// use zbus::{interface, proxy, Connection, Result};
+///
+/// struct Greeter;
+///
+/// #[interface(name = "org.zbus.Greeter")]
+/// impl Greeter {
+///     fn say_hello(&self, name: &str) -> String {
+///         format!("Hello {name}!")
+///     }
+/// }
+///
+/// #[proxy(interface = "org.zbus.Greeter", default_path = "/org/zbus/Greeter")]
+/// trait Greeter {
+///     fn say_hello(&self, name: &str) -> Result<String>;
+/// }
+///
+/// #[zbus::test(p2p)]
+/// async fn greeting(server: Connection, client: Connection) -> Result<()> {
+///     server.object_server().at("/org/zbus/Greeter", Greeter).await?;
+///     // Peer-to-peer connections ignore the destination, but proxies need one.
+///     let proxy = GreeterProxy::builder(&client)
+///         .destination("org.zbus.Greeter")?
+///         .build()
+///         .await?;
+///     assert_eq!(proxy.say_hello("zbus").await?, "Hello zbus!");
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// [`zbus::Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
+/// [`zbus::blocking::Connection`]: https://docs.rs/zbus/latest/zbus/blocking/connection/struct.Connection.html
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as AttributeArgs);
+    let input = parse_macro_input!(item as ItemFn);
+    test::expand(args, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -21,7 +21,8 @@ pub mod old {
             async_name str,
             blocking_name str,
             gen_async bool,
-            gen_blocking bool
+            gen_blocking bool,
+            allow_interactive_auth none
         };
 
         pub MethodAttributes("method") {
@@ -54,14 +55,16 @@ def_attrs! {
         async_name str,
         blocking_name str,
         gen_async bool,
-        gen_blocking bool
+        gen_blocking bool,
+        allow_interactive_auth none
     };
 
     pub MethodAttributes("method") {
         name str,
         property {
             pub PropertyAttributes("property") {
-                emits_changed_signal str
+                emits_changed_signal str,
+                serde none
             }
         },
         signal none,
@@ -72,6 +75,10 @@ def_attrs! {
         no_autostart none,
         allow_interactive_auth none
     };
+
+    pub ArgAttributes("argument") {
+        signature str
+    };
 }
 
 old_new!(ImplAttrs, old::ImplAttributes, ImplAttributes);
@@ -112,6 +119,7 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
         blocking_name,
         gen_async,
         gen_blocking,
+        allow_interactive_auth,
     ) = match I::parse_nested_metas(&args)?.into() {
         ImplAttrs::Old(old) => (
             old.interface,
@@ -123,6 +131,7 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
             old.blocking_name,
             old.gen_async,
             old.gen_blocking,
+            old.allow_interactive_auth,
         ),
         ImplAttrs::New(new) => (
             new.interface,
@@ -134,6 +143,7 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
             new.blocking_name,
             new.gen_async,
             new.gen_blocking,
+            new.allow_interactive_auth,
         ),
     };
 
@@ -177,6 +187,7 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
             assume_defaults,
             default_path.as_deref(),
             default_service.as_deref(),
+            allow_interactive_auth,
             &proxy_name,
             true,
             // Signal args structs are shared between the two proxies so always generate it for
@@ -194,6 +205,7 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
             assume_defaults,
             default_path.as_deref(),
             default_service.as_deref(),
+            allow_interactive_auth,
             &proxy_name,
             false,
             true,
@@ -216,6 +228,7 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
     assume_defaults: Option<bool>,
     default_path: Option<&str>,
     default_service: Option<&str>,
+    allow_interactive_auth: bool,
     proxy_name: &str,
     blocking: bool,
     gen_sig_args: bool,
@@ -259,12 +272,14 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
                 MethodAttrs::Old(old) => (
                     old.name,
                     old.signal,
-                    old.property.map(|property| property.emits_changed_signal),
+                    old.property
+                        .map(|property| (property.emits_changed_signal, false)),
                 ),
                 MethodAttrs::New(new) => (
                     new.name,
                     new.signal,
-                    new.property.map(|property| property.emits_changed_signal),
+                    new.property
+                        .map(|property| (property.emits_changed_signal, property.serde)),
                 ),
             };
 
@@ -286,10 +301,10 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
                 )
             });
 
-            let m = if let Some(prop_attrs) = &property {
+            let m = if let Some((emits_changed_signal, serde)) = &property {
                 has_properties = true;
 
-                let emits_changed_signal = if let Some(s) = &prop_attrs {
+                let emits_changed_signal = if let Some(s) = emits_changed_signal {
                     PropertyEmitsChangedSignal::parse(s, m.span())?
                 } else {
                     PropertyEmitsChangedSignal::True
@@ -305,6 +320,7 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
                     m,
                     &async_opts,
                     emits_changed_signal,
+                    *serde,
                 )
             } else if is_signal {
                 let (method, types) = gen_proxy_signal(
@@ -349,6 +365,11 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
         (proxy, connection, builder, proxy_trait)
     };
 
+    let allow_interactive_auth = if allow_interactive_auth {
+        quote! { .allow_interactive_auth(true) }
+    } else {
+        quote! {}
+    };
     let proxy_method_new = match (&default_path, &default_service) {
         (None, None) => {
             quote! {
@@ -433,7 +454,7 @@ pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
 
             /// Returns a customizable builder for this proxy.
             pub fn builder(conn: &#connection) -> #builder<'p, Self> {
-                let mut builder = #builder::new(conn) ;
+                let mut builder = #builder::new(conn) #allow_interactive_auth;
                 if #has_properties {
                     let uncached = vec![#(#uncached_properties),*];
                     builder.cache_properties(#zbus::proxy::CacheProperties::default())
@@ -552,13 +573,102 @@ fn gen_proxy_method_call<M: AttrParse + Into<MethodAttrs>>(
         .iter()
         .filter(|a| !a.path.is_ident("zbus") && !a.path.is_ident("dbus_proxy"))
         .collect();
-    let args: Vec<_> = m
-        .sig
-        .inputs
-        .iter()
-        .filter_map(typed_arg)
-        .filter_map(pat_ident)
-        .collect();
+    let mut inputs = m.sig.inputs.clone();
+    let mut args = vec![];
+    for input in inputs.iter_mut() {
+        let FnArg::Typed(input) = input else {
+            continue;
+        };
+        let ArgAttributes { signature } = ArgAttributes::parse(&input.attrs)?;
+        input
+            .attrs
+            .retain(|attr| !attr.path.is_ident("zbus") && !attr.path.is_ident("dbus_proxy"));
+        let Some(arg) = pat_ident(input) else {
+            continue;
+        };
+
+        let signature = match signature.as_deref() {
+            Some("dict") => Some("a{sv}"),
+            signature => signature,
+        };
+        if let Some(signature) = signature {
+            zvariant_utils::signature::validate(signature)
+                .map_err(|e| Error::new_spanned(&input, e))?;
+        }
+
+        args.push(match signature {
+            None => quote! { #arg },
+            // Serializing as a variant requires wrapping the value in one, unless it's already a
+            // variant (e.g `Value`), which would otherwise end up nested in another one.
+            Some("v") => quote! {
+                {
+                    struct Arg<'a, T: ?Sized>(&'a T);
+
+                    impl<T> #zbus::export::serde::ser::Serialize for Arg<'_, T>
+                    where
+                        T: #zbus::export::serde::ser::Serialize + #zbus::zvariant::Type,
+                    {
+                        fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                        where
+                            S: #zbus::export::serde::ser::Serializer,
+                        {
+                            if <T as #zbus::zvariant::Type>::signature() == "v" {
+                                #zbus::export::serde::ser::Serialize::serialize(self.0, serializer)
+                            } else {
+                                #zbus::export::serde::ser::Serialize::serialize(
+                                    &#zbus::zvariant::SerializeValue(self.0),
+                                    serializer,
+                                )
+                            }
+                        }
+                    }
+
+                    impl<T: ?Sized> #zbus::zvariant::Type for Arg<'_, T> {
+                        fn signature() -> #zbus::zvariant::Signature<'static> {
+                            #zbus::zvariant::Signature::from_static_str_unchecked("v")
+                        }
+                    }
+
+                    Arg(&#arg)
+                }
+            },
+            Some(signature) => {
+                quote! {
+                    {
+                        struct Arg<'a, T: ?Sized>(&'a T);
+
+                        impl<T> #zbus::export::serde::ser::Serialize for Arg<'_, T>
+                        where
+                            T: #zbus::export::serde::ser::Serialize + ?Sized,
+                        {
+                            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                            where
+                                S: #zbus::export::serde::ser::Serializer,
+                            {
+                                #zbus::export::serde::ser::Serialize::serialize(self.0, serializer)
+                            }
+                        }
+
+                        impl<T: ?Sized> #zbus::zvariant::Type for Arg<'_, T> {
+                            fn signature() -> #zbus::zvariant::Signature<'static> {
+                                // Validated when the macro was expanded.
+                                #zbus::zvariant::Signature::from_static_str_unchecked(#signature)
+                            }
+                        }
+
+                        Arg(&#arg)
+                    }
+                }
+            }
+        });
+    }
+
+    if no_reply && object.is_some() {
+        return Err(Error::new_spanned(
+            &m.sig,
+            "`no_reply` methods can't return an object",
+        ));
+    }
 
     let proxy_object = object.as_ref().map(|o| {
         if *blocking {
@@ -607,7 +717,6 @@ fn gen_proxy_method_call<M: AttrParse + Into<MethodAttrs>>(
     };
 
     let method = Ident::new(snake_case_name, Span::call_site());
-    let inputs = &m.sig.inputs;
     let mut generics = m.sig.generics.clone();
     let where_clause = generics.where_clause.get_or_insert(parse_quote!(where));
     for param in generics
@@ -730,6 +839,7 @@ fn gen_proxy_property(
     m: &TraitItemMethod,
     async_opts: &AsyncOpts,
     emits_changed_signal: PropertyEmitsChangedSignal,
+    serde: bool,
 ) -> TokenStream {
     let AsyncOpts {
         usage,
@@ -745,6 +855,14 @@ fn gen_proxy_property(
     let signature = &m.sig;
     if signature.inputs.len() > 1 {
         let value = pat_ident(typed_arg(signature.inputs.last().unwrap()).unwrap()).unwrap();
+        let value = if serde {
+            quote! {
+                #zbus::zvariant::OwnedValue::try_from(#zbus::zvariant::SerializeValue(&#value))
+                    .map_err(#zbus::Error::from)?
+            }
+        } else {
+            quote! { #value }
+        };
         quote! {
             #(#other_attrs)*
             #[allow(clippy::needless_question_mark)]
@@ -760,14 +878,31 @@ fn gen_proxy_property(
         } else {
             signature.span()
         };
-        let body = quote_spanned! {body_span =>
-            ::std::result::Result::Ok(self.0.get_property(#property_name)#wait?)
-        };
         let ret_type = if let ReturnType::Type(_, ty) = &signature.output {
             Some(ty)
         } else {
             None
         };
+        // With `serde`, the value is converted through the `Deserialize` implementation of the
+        // property type, by wrapping it in `DeserializeValue`.
+        let value_type = if serde {
+            quote! {
+                #zbus::zvariant::DeserializeValue<'static, <#ret_type as #zbus::ResultAdapter>::Ok>
+            }
+        } else {
+            quote! { <#ret_type as #zbus::ResultAdapter>::Ok }
+        };
+        let body = if serde {
+            quote_spanned! {body_span =>
+                ::std::result::Result::Ok(
+                    self.0.get_property::<#value_type>(#property_name)#wait?.0
+                )
+            }
+        } else {
+            quote_spanned! {body_span =>
+                ::std::result::Result::Ok(self.0.get_property(#property_name)#wait?)
+            }
+        };
 
         let (proxy_name, prop_stream) = if *blocking {
             (
@@ -790,7 +925,7 @@ fn gen_proxy_property(
                     #[doc = #gen_doc]
                     pub #usage fn #receive #ty_generics(
                         &self
-                    ) -> #prop_stream<'p, <#ret_type as #zbus::ResultAdapter>::Ok>
+                    ) -> #prop_stream<'p, #value_type>
                     #where_clause
                     {
                         self.0.receive_property_changed(#property_name)#wait
@@ -807,6 +942,18 @@ fn gen_proxy_property(
             | PropertyEmitsChangedSignal::Invalidates
             | PropertyEmitsChangedSignal::Const => {
                 let cached_getter = format_ident!("cached_{}", method_name);
+                let cached_value = if serde {
+                    quote! {
+                        self.0
+                            .cached_property::<#value_type>(#property_name)
+                            .map(|value| value.map(|value| value.0))
+                            .map_err(::std::convert::Into::into)
+                    }
+                } else {
+                    quote! {
+                        self.0.cached_property(#property_name).map_err(::std::convert::Into::into)
+                    }
+                };
                 let cached_doc = format!(
                     " Get the cached value of the `{property_name}` property, or `None` if the property is not cached.",
                 );
@@ -816,7 +963,7 @@ fn gen_proxy_property(
                         ::std::option::Option<<#ret_type as #zbus::ResultAdapter>::Ok>,
                         <#ret_type as #zbus::ResultAdapter>::Err>
                     {
-                        self.0.cached_property(#property_name).map_err(::std::convert::Into::into)
+                        #cached_value
                     }
                 }
             }
@@ -1112,6 +1259,20 @@ fn gen_proxy_signal(
                         .map(|msg| #signal_name_ident(msg.body()))
                 }
             }
+
+            impl #stream_name<'_> {
+                /// Wait for the next signal, for at most `timeout`.
+                ///
+                /// See [`zbus::blocking::proxy::SignalIterator::next_timeout`] for details.
+                pub fn next_timeout(
+                    &mut self,
+                    timeout: ::std::time::Duration,
+                ) -> #zbus::Result<::std::option::Option<#signal_name_ident>> {
+                    self.0
+                        .next_timeout(timeout)
+                        .map(|msg| msg.map(|msg| #signal_name_ident(msg.body())))
+                }
+            }
         }
     } else {
         quote! {
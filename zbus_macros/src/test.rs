@@ -0,0 +1,137 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, AttributeArgs, Error, ItemFn};
+use zvariant_utils::def_attrs;
+
+use crate::utils::zbus_path;
+
+def_attrs! {
+    crate zbus;
+
+    pub TestAttributes("test") {
+        session_bus none,
+        p2p none
+    };
+}
+
+pub fn expand(args: AttributeArgs, input: ItemFn) -> Result<TokenStream, Error> {
+    let TestAttributes { session_bus, p2p } = TestAttributes::parse_nested_metas(&args)?;
+    let zbus = zbus_path();
+
+    let (setup, conns) = match (session_bus, p2p) {
+        (true, false) => {
+            // A bus of its own, so tests can't interfere with each other or the user's session.
+            let setup = quote! {
+                {
+                    struct TestBus(::std::process::Child);
+
+                    impl ::std::ops::Drop for TestBus {
+                        fn drop(&mut self) {
+                            let _ = self.0.kill();
+                            let _ = self.0.wait();
+                        }
+                    }
+
+                    let mut daemon = ::std::process::Command::new("dbus-daemon")
+                        .args(["--session", "--nofork", "--print-address"])
+                        .stdin(::std::process::Stdio::null())
+                        .stdout(::std::process::Stdio::piped())
+                        .spawn()
+                        .expect("failed to launch `dbus-daemon`");
+                    let stdout = daemon.stdout.take().expect("no stdout for `dbus-daemon`");
+                    let bus = TestBus(daemon);
+                    let mut address = ::std::string::String::new();
+                    ::std::io::BufRead::read_line(
+                        &mut ::std::io::BufReader::new(stdout),
+                        &mut address,
+                    )
+                    .expect("failed to read the address of the bus");
+                    let conn = #zbus::connection::Builder::address(address.trim())
+                        .expect("invalid bus address")
+                        .build()
+                        .await
+                        .expect("failed to connect to the bus");
+
+                    (bus, conn)
+                }
+            };
+
+            (setup, vec![format_ident!("conn")])
+        }
+        (false, true) => {
+            let setup = quote! {
+                {
+                    let guid = #zbus::Guid::generate();
+                    let (server, client) = #zbus::connection::socket::Channel::pair();
+                    let server = #zbus::connection::Builder::authenticated_socket(server, guid.clone())
+                        .map(|builder| builder.p2p())
+                        .expect("failed to set up a peer-to-peer connection");
+                    let client = #zbus::connection::Builder::authenticated_socket(client, guid)
+                        .map(|builder| builder.p2p())
+                        .expect("failed to set up a peer-to-peer connection");
+
+                    (
+                        server.build().await.expect("failed to set up a peer-to-peer connection"),
+                        client.build().await.expect("failed to set up a peer-to-peer connection"),
+                    )
+                }
+            };
+
+            (
+                setup,
+                vec![format_ident!("server"), format_ident!("client")],
+            )
+        }
+        _ => {
+            return Err(Error::new(
+                input.sig.span(),
+                "exactly one of `session_bus` or `p2p` must be specified",
+            ))
+        }
+    };
+
+    let inputs = &input.sig.inputs;
+    if inputs.len() != conns.len() {
+        let expected = if p2p {
+            "a server and a client connection"
+        } else {
+            "a single connection"
+        };
+
+        return Err(Error::new_spanned(
+            inputs,
+            format!("the test function must take {expected} as arguments"),
+        ));
+    }
+
+    let mut test_fn = input.clone();
+    let attrs = std::mem::take(&mut test_fn.attrs);
+    let vis = &input.vis;
+    let ident = &input.sig.ident;
+    let output = &input.sig.output;
+    // The bus, if any, is kept around until the test returns.
+    let conns_pat = if p2p {
+        quote! { (#(#conns),*) }
+    } else {
+        quote! { (_bus, #(#conns)*) }
+    };
+    let conn_args = conns
+        .iter()
+        .map(|c| quote! { ::std::convert::Into::into(#c) });
+    let call = if input.sig.asyncness.is_some() {
+        quote! { #zbus::block_on(#ident(#(#conn_args),*)) }
+    } else {
+        quote! { #ident(#(#conn_args),*) }
+    };
+
+    Ok(quote! {
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        #vis fn #ident() #output {
+            #test_fn
+
+            let #conns_pat = #zbus::block_on(async { #setup });
+            #call
+        }
+    })
+}
@@ -46,6 +46,13 @@ mod test {
         #[zbus(name = "CheckRENAMING")]
         fn check_renaming(&self) -> zbus::Result<Vec<u8>>;
 
+        /// A call with arguments whose D-Bus signature differs from the one of their Rust type.
+        fn check_signatures(
+            &self,
+            #[zbus(signature = "v")] value: u32,
+            #[zbus(signature = "(ii)")] geometry: &(i32, i32),
+        ) -> zbus::Result<()>;
+
         #[zbus(property)]
         fn property(&self) -> fdo::Result<Vec<String>>;
 
@@ -289,6 +296,76 @@ fn test_interface() {
     }
 }
 
+#[test]
+fn test_interface_extends() {
+    use zbus::object_server::Interface;
+
+    #[derive(Default)]
+    struct Describable {
+        description: String,
+    }
+
+    #[interface(name = "org.freedesktop.zbus.Describable")]
+    impl Describable {
+        #[zbus(property)]
+        fn description(&self) -> &str {
+            &self.description
+        }
+
+        #[zbus(property)]
+        fn set_description(&mut self, description: String) {
+            self.description = description;
+        }
+    }
+
+    #[derive(Default)]
+    struct Device {
+        base: Describable,
+    }
+
+    impl AsRef<Describable> for Device {
+        fn as_ref(&self) -> &Describable {
+            &self.base
+        }
+    }
+
+    impl AsMut<Describable> for Device {
+        fn as_mut(&mut self) -> &mut Describable {
+            &mut self.base
+        }
+    }
+
+    #[interface(name = "org.freedesktop.zbus.Device", extends = "Describable")]
+    impl Device {
+        fn reset(&self) {}
+    }
+
+    const EXPECTED_XML: &str = r#"<interface name="org.freedesktop.zbus.Device">
+  <property name="Description" type="s" access="readwrite"/>
+  <method name="Reset">
+  </method>
+</interface>
+"#;
+    let mut device = Device::default();
+    let mut xml = String::new();
+    device.introspect_to_writer(&mut xml, 0);
+    assert_eq!(xml, EXPECTED_XML);
+
+    block_on(async {
+        device.base.description = String::from("A device");
+        let value = device.get("Description").await.unwrap().unwrap();
+        assert_eq!(
+            value,
+            zbus::zvariant::Value::from("A device").try_into().unwrap()
+        );
+
+        let props = device.get_all().await.unwrap();
+        assert!(props.contains_key("Description"));
+
+        assert!(device.get("Unknown").await.is_none());
+    });
+}
+
 mod signal_from_message {
     use super::*;
     use zbus::message::Message;
@@ -365,3 +442,188 @@ mod signal_from_message {
             .expect_err("Message does not have correct data");
     }
 }
+
+#[zbus_macros::test(session_bus)]
+async fn test_session_bus_fixture(conn: zbus::Connection) -> zbus::Result<()> {
+    let proxy = fdo::DBusProxy::new(&conn).await?;
+    let name = conn.unique_name().unwrap();
+    assert!(proxy.name_has_owner(name.into()).await?);
+    // The bus is the test's own, so it's only the bus and us on it.
+    assert_eq!(proxy.list_names().await?.len(), 2);
+
+    Ok(())
+}
+
+#[zbus_macros::test(session_bus)]
+fn test_blocking_session_bus_fixture(conn: zbus::blocking::Connection) {
+    assert!(conn.unique_name().is_some());
+}
+
+mod p2p_fixture {
+    use zbus::{blocking, interface, proxy, Connection, Result};
+
+    struct Greeter;
+
+    #[interface(name = "org.zbus.Greeter")]
+    impl Greeter {
+        fn say_hello(&self, name: &str) -> String {
+            format!("Hello {name}!")
+        }
+    }
+
+    #[proxy(interface = "org.zbus.Greeter", default_path = "/org/zbus/Greeter")]
+    trait Greeter {
+        fn say_hello(&self, name: &str) -> Result<String>;
+    }
+
+    #[zbus::test(p2p)]
+    async fn test_p2p_fixture(server: Connection, client: Connection) -> Result<()> {
+        assert!(!server.is_bus() && !client.is_bus());
+        server
+            .object_server()
+            .at("/org/zbus/Greeter", Greeter)
+            .await?;
+        let proxy = GreeterProxy::builder(&client)
+            .destination("org.zbus.Greeter")?
+            .build()
+            .await?;
+        assert_eq!(proxy.say_hello("zbus").await?, "Hello zbus!");
+
+        Ok(())
+    }
+
+    #[zbus::test(p2p)]
+    fn test_blocking_p2p_fixture(server: blocking::Connection, client: blocking::Connection) {
+        server
+            .object_server()
+            .at("/org/zbus/Greeter", Greeter)
+            .unwrap();
+        let proxy = GreeterProxyBlocking::builder(&client)
+            .destination("org.zbus.Greeter")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(proxy.say_hello("zbus").unwrap(), "Hello zbus!");
+    }
+}
+
+mod signature_overrides {
+    use futures_util::StreamExt;
+    use zbus::{
+        message::{Message, Type},
+        proxy,
+        zvariant::{OwnedValue, Value},
+        Connection, MessageStream, Result,
+    };
+
+    #[proxy(
+        interface = "org.zbus.Signatures",
+        default_path = "/org/zbus/Signatures"
+    )]
+    trait Signatures {
+        #[zbus(no_reply)]
+        fn check(
+            &self,
+            #[zbus(signature = "v")] value: &Value<'_>,
+            #[zbus(signature = "v")] owned: OwnedValue,
+            #[zbus(signature = "v")] number: u32,
+            #[zbus(signature = "(ii)")] geometry: &(i32, i32),
+        ) -> Result<()>;
+    }
+
+    #[zbus::test(p2p)]
+    async fn test_signature_overrides(server: Connection, client: Connection) -> Result<()> {
+        let mut stream = MessageStream::from(&server);
+        let proxy = SignaturesProxy::builder(&client)
+            .destination("org.zbus.Signatures")?
+            .build()
+            .await?;
+        proxy
+            .check(&Value::from(7u32), OwnedValue::from(8u32), 9, &(1, 2))
+            .await?;
+
+        let call = loop {
+            let msg = stream.next().await.unwrap()?;
+            if msg.message_type() == Type::MethodCall {
+                break msg;
+            }
+        };
+        // Values that already are variants must not end up nested in another variant.
+        let expected = Message::method("/org/zbus/Signatures", "Check")?.build(&(
+            Value::from(7u32),
+            Value::from(8u32),
+            Value::from(9u32),
+            (1, 2),
+        ))?;
+        assert_eq!(call.body().signature().unwrap(), "vvv(ii)");
+        assert_eq!(call.body().data().bytes(), expected.body().data().bytes());
+
+        Ok(())
+    }
+}
+
+mod serde_properties {
+    use futures_util::StreamExt;
+    use serde::{Deserialize, Serialize};
+    use zbus::{interface, proxy, zvariant::Type, Connection, Result};
+
+    #[derive(Debug, Deserialize, Serialize, Type, PartialEq)]
+    pub struct Geometry {
+        x: i32,
+        y: i32,
+    }
+
+    struct Window {
+        geometry: (i32, i32),
+    }
+
+    #[interface(name = "org.zbus.Window")]
+    impl Window {
+        #[zbus(property)]
+        fn geometry(&self) -> (i32, i32) {
+            self.geometry
+        }
+
+        #[zbus(property)]
+        fn set_geometry(&mut self, geometry: (i32, i32)) {
+            self.geometry = geometry;
+        }
+    }
+
+    #[proxy(interface = "org.zbus.Window", default_path = "/org/zbus/Window")]
+    trait Window {
+        #[zbus(property(serde))]
+        fn geometry(&self) -> Result<Geometry>;
+
+        #[zbus(property(serde))]
+        fn set_geometry(&self, geometry: Geometry) -> Result<()>;
+    }
+
+    #[zbus::test(p2p)]
+    async fn test_serde_properties(server: Connection, client: Connection) -> Result<()> {
+        server
+            .object_server()
+            .at("/org/zbus/Window", Window { geometry: (1, 2) })
+            .await?;
+        let proxy = WindowProxy::builder(&client)
+            .destination("org.zbus.Window")?
+            .build()
+            .await?;
+
+        assert_eq!(proxy.geometry().await?, Geometry { x: 1, y: 2 });
+        assert_eq!(proxy.cached_geometry()?, Some(Geometry { x: 1, y: 2 }));
+
+        let mut changes = proxy.receive_geometry_changed().await;
+        proxy.set_geometry(Geometry { x: 3, y: 4 }).await?;
+        loop {
+            let change = changes.next().await.unwrap();
+            if change.get().await?.0 == (Geometry { x: 3, y: 4 }) {
+                break;
+            }
+        }
+        assert_eq!(proxy.cached_geometry()?, Some(Geometry { x: 3, y: 4 }));
+        assert_eq!(proxy.geometry().await?, Geometry { x: 3, y: 4 });
+
+        Ok(())
+    }
+}
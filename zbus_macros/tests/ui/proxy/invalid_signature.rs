@@ -0,0 +1,8 @@
+use zbus::proxy;
+
+#[proxy(interface = "org.zbus.Test", default_path = "/org/zbus/Test")]
+trait Test {
+    fn check(&self, #[zbus(signature = "a{s")] value: &str) -> zbus::Result<()>;
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use zbus::proxy;
+
+#[proxy(interface = "org.zbus.Test", default_path = "/org/zbus/Test")]
+trait Test {
+    #[zbus(no_reply, object = "Test")]
+    fn child(&self);
+}
+
+fn main() {}
@@ -0,0 +1,319 @@
+use quick_xml::{
+    de::DeError,
+    events::{BytesStart, Event},
+    name::ResolveResult,
+    NsReader,
+};
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use static_assertions::assert_impl_all;
+use std::str;
+
+use crate::Result;
+
+/// An XML element unknown to the D-Bus introspection format.
+///
+/// Introspection data in the wild often carries vendor extensions, such as the `tp:docstring`
+/// elements of Telepathy specifications. Rather than being dropped, these are kept as generic
+/// elements, available through the `extensions` method of the element they appear in, and
+/// written back out along with it.
+///
+/// Elements keep their qualified name (`tp:docstring`), their attributes as written in the
+/// document and their content, with text and child elements in document order. The namespace
+/// declarations they rely on are available through the `extension_attributes` method of the
+/// element declaring them, usually the root [`Node`](crate::Node).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    name: String,
+    namespace: Option<String>,
+    attributes: Vec<(String, String)>,
+    content: Vec<ElementContent>,
+}
+
+assert_impl_all!(Element: Send, Sync, Unpin);
+
+impl Element {
+    /// Return the qualified name of the element, including its namespace prefix if any.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the name of the element, without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        self.name
+            .split_once(':')
+            .map_or(self.name.as_str(), |(_, local)| local)
+    }
+
+    /// Return the URI of the namespace of the element, if it's in one.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Return the attributes of the element, as qualified name/value pairs.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Return the value of the attribute with the qualified name `name`, if any.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// Return the content of the element, text and child elements in document order.
+    pub fn content(&self) -> &[ElementContent] {
+        &self.content
+    }
+
+    /// Return the text content of the element, if it consists of text only.
+    ///
+    /// Use [`Element::content`] for elements with mixed content.
+    pub fn text(&self) -> Option<&str> {
+        match self.content.as_slice() {
+            [ElementContent::Text(text)] => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Return the child elements.
+    pub fn children(&self) -> impl Iterator<Item = &Element> {
+        self.content.iter().filter_map(|c| match c {
+            ElementContent::Element(element) => Some(element),
+            ElementContent::Text(_) => None,
+        })
+    }
+}
+
+/// A piece of the content of an [`Element`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementContent {
+    /// A run of text, with entities and character references resolved.
+    Text(String),
+    /// A child element.
+    Element(Element),
+}
+
+assert_impl_all!(ElementContent: Send, Sync, Unpin);
+
+/// The parts of an element of the introspection format that are unknown to it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Extensions {
+    /// Attributes, including namespace declarations.
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) elements: Vec<Element>,
+}
+
+pub(crate) fn find_attribute<'a>(
+    attributes: &'a [(String, String)],
+    name: &str,
+) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// The attributes and content of an [`Element`], as a map.
+struct Content<'e>(&'e Element);
+
+impl Serialize for Content<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, value) in &self.0.attributes {
+            map.serialize_entry(&format!("@{name}"), value)?;
+        }
+        for content in &self.0.content {
+            match content {
+                ElementContent::Text(text) => map.serialize_entry("$text", text)?,
+                ElementContent::Element(element) => {
+                    map.serialize_entry(&element.name, &Content(element))?
+                }
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// Add the `extensions` to the map of the element containing them.
+///
+/// Elements with extensions are serialized as maps: unlike struct field names, map keys are
+/// arbitrary strings, which the XML serializer uses as is for attribute and element names. The
+/// extension attributes have to be added right after the known ones, since the serializer doesn't
+/// accept attributes after child elements.
+pub(crate) fn serialize_extensions<M>(
+    map: &mut M,
+    extensions: &Extensions,
+) -> std::result::Result<(), M::Error>
+where
+    M: SerializeMap,
+{
+    for (name, value) in &extensions.attributes {
+        map.serialize_entry(&format!("@{name}"), value)?;
+    }
+    for element in &extensions.elements {
+        map.serialize_entry(&element.name, &Content(element))?;
+    }
+
+    Ok(())
+}
+
+/// The extensions of an element of the introspection format and of its known children.
+///
+/// The serde deserializer only sees local names and drops unknown attributes, so the extensions
+/// are collected in a separate pass over the document. The children are kept in document order,
+/// which matches the order of the lists they're deserialized into.
+#[derive(Debug)]
+pub(crate) struct Known {
+    pub(crate) name: String,
+    pub(crate) extensions: Extensions,
+    pub(crate) children: Vec<Known>,
+}
+
+/// Collect the extensions of the document `xml`, starting with its root `node` element.
+pub(crate) fn parse_extensions(xml: &[u8]) -> Result<Option<Known>> {
+    let mut reader = NsReader::from_reader(xml);
+    loop {
+        match reader.read_resolved_event().map_err(DeError::from)? {
+            (_, Event::Start(e)) => return known(&mut reader, &e, false).map(Some),
+            (_, Event::Empty(e)) => return known(&mut reader, &e, true).map(Some),
+            (_, Event::Eof) => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+// The attributes of the elements of the introspection format.
+fn known_attributes(element: &str) -> &'static [&'static str] {
+    match element {
+        "node" | "interface" | "method" | "signal" => &["name"],
+        "property" => &["name", "type", "access"],
+        "arg" => &["name", "type", "direction"],
+        "annotation" => &["name", "value"],
+        _ => &[],
+    }
+}
+
+// The child elements of the elements of the introspection format.
+fn known_children(element: &str) -> &'static [&'static str] {
+    match element {
+        "node" => &["interface", "node"],
+        "interface" => &["method", "signal", "property", "annotation"],
+        "method" | "signal" => &["arg", "annotation"],
+        "property" | "arg" => &["annotation"],
+        _ => &[],
+    }
+}
+
+fn known(reader: &mut NsReader<&[u8]>, start: &BytesStart<'_>, empty: bool) -> Result<Known> {
+    let name = str::from_utf8(start.local_name().into_inner())
+        .map_err(DeError::from)?
+        .to_owned();
+    let known_attributes = known_attributes(&name);
+    let attributes = attributes(start)?
+        .into_iter()
+        .filter(|(n, _)| !known_attributes.contains(&n.as_str()))
+        .collect();
+    let mut known = Known {
+        name,
+        extensions: Extensions {
+            attributes,
+            elements: vec![],
+        },
+        children: vec![],
+    };
+    if empty {
+        return Ok(known);
+    }
+
+    loop {
+        let (namespace, start, empty) = match reader.read_resolved_event().map_err(DeError::from)? {
+            (ns, Event::Start(e)) => (resolved_namespace(ns), e, false),
+            (ns, Event::Empty(e)) => (resolved_namespace(ns), e, true),
+            (_, Event::End(_)) => return Ok(known),
+            (_, Event::Eof) => return Err(DeError::UnexpectedEof.into()),
+            // Text and comments of the known elements aren't retained.
+            _ => continue,
+        };
+        let local_name = start.local_name();
+        if known_children(&known.name)
+            .iter()
+            .any(|c| c.as_bytes() == local_name.as_ref())
+        {
+            known.children.push(self::known(reader, &start, empty)?);
+        } else {
+            let element = element(reader, namespace, &start, empty)?;
+            known.extensions.elements.push(element);
+        }
+    }
+}
+
+fn element(
+    reader: &mut NsReader<&[u8]>,
+    namespace: Option<String>,
+    start: &BytesStart<'_>,
+    empty: bool,
+) -> Result<Element> {
+    let mut element = Element {
+        name: str::from_utf8(start.name().into_inner())
+            .map_err(DeError::from)?
+            .to_owned(),
+        namespace,
+        attributes: attributes(start)?,
+        content: vec![],
+    };
+    if empty {
+        return Ok(element);
+    }
+
+    loop {
+        let content = match reader.read_resolved_event().map_err(DeError::from)? {
+            (ns, Event::Start(e)) => {
+                let ns = resolved_namespace(ns);
+                ElementContent::Element(self::element(reader, ns, &e, false)?)
+            }
+            (ns, Event::Empty(e)) => {
+                let ns = resolved_namespace(ns);
+                ElementContent::Element(self::element(reader, ns, &e, true)?)
+            }
+            (_, Event::Text(e)) => {
+                ElementContent::Text(e.unescape().map_err(DeError::from)?.into_owned())
+            }
+            (_, Event::CData(e)) => ElementContent::Text(
+                String::from_utf8(e.into_inner().into_owned()).map_err(DeError::from)?,
+            ),
+            (_, Event::End(_)) => return Ok(element),
+            (_, Event::Eof) => return Err(DeError::UnexpectedEof.into()),
+            _ => continue,
+        };
+        // Comments between two runs of text would otherwise split them.
+        match (element.content.last_mut(), content) {
+            (Some(ElementContent::Text(last)), ElementContent::Text(text)) => last.push_str(&text),
+            (_, content) => element.content.push(content),
+        }
+    }
+}
+
+fn attributes(start: &BytesStart<'_>) -> Result<Vec<(String, String)>> {
+    start
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute.map_err(DeError::from)?;
+            let name = str::from_utf8(attribute.key.into_inner()).map_err(DeError::from)?;
+            let value = attribute.unescape_value().map_err(DeError::from)?;
+
+            Ok((name.to_owned(), value.into_owned()))
+        })
+        .collect()
+}
+
+fn resolved_namespace(ns: ResolveResult<'_>) -> Option<String> {
+    match ns {
+        ResolveResult::Bound(ns) => Some(String::from_utf8_lossy(ns.into_inner()).into_owned()),
+        // Unknown prefixes are kept as part of the qualified name.
+        ResolveResult::Unbound | ResolveResult::Unknown(_) => None,
+    }
+}
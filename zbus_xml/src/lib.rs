@@ -11,13 +11,19 @@
     allow(unused_extern_crates),
 )))]
 
+mod element;
+pub use element::{Element, ElementContent};
 mod error;
 pub use error::{Error, Result};
 
-use quick_xml::{de::Deserializer, se::to_writer};
-use serde::{Deserialize, Serialize};
+use element::{parse_extensions, serialize_extensions, Extensions, Known};
+use quick_xml::{
+    de::{DeError, Deserializer},
+    se::Serializer as XmlSerializer,
+};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 use static_assertions::assert_impl_all;
-use std::io::{BufReader, Read, Write};
+use std::io::{Read, Write};
 
 use zbus_names::{InterfaceName, MemberName, PropertyName};
 use zvariant::CompleteType;
@@ -55,7 +61,7 @@ pub enum ArgDirection {
 }
 
 /// An argument
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Arg<'a> {
     #[serde(rename = "@name")]
     name: Option<String>,
@@ -65,6 +71,8 @@ pub struct Arg<'a> {
     direction: Option<ArgDirection>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Arg<'_>: Send, Sync, Unpin);
@@ -89,10 +97,50 @@ impl<'a> Arg<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Return the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Return the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+    }
+}
+
+impl Serialize for Arg<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(name) = &self.name {
+            map.serialize_entry("@name", name)?;
+        }
+        map.serialize_entry("@type", &self.ty)?;
+        if let Some(direction) = &self.direction {
+            map.serialize_entry("@direction", direction)?;
+        }
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("annotation", &self.annotations)?;
+
+        map.end()
+    }
 }
 
 /// A method
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Method<'a> {
     #[serde(rename = "@name", borrow)]
     name: MemberName<'a>,
@@ -100,6 +148,8 @@ pub struct Method<'a> {
     args: Vec<Arg<'a>>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Method<'_>: Send, Sync, Unpin);
@@ -119,10 +169,53 @@ impl<'a> Method<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Return the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Return the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+        let mut args = self.args.iter_mut();
+        for child in known.children {
+            if child.name == "arg" {
+                if let Some(arg) = args.next() {
+                    arg.set_extensions(child);
+                }
+            }
+        }
+    }
+}
+
+impl Serialize for Method<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("@name", &self.name)?;
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("arg", &self.args)?;
+        map.serialize_entry("annotation", &self.annotations)?;
+
+        map.end()
+    }
 }
 
 /// A signal
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Signal<'a> {
     #[serde(rename = "@name", borrow)]
     name: MemberName<'a>,
@@ -131,6 +224,8 @@ pub struct Signal<'a> {
     args: Vec<Arg<'a>>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Signal<'_>: Send, Sync, Unpin);
@@ -150,6 +245,49 @@ impl<'a> Signal<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Return the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Return the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+        let mut args = self.args.iter_mut();
+        for child in known.children {
+            if child.name == "arg" {
+                if let Some(arg) = args.next() {
+                    arg.set_extensions(child);
+                }
+            }
+        }
+    }
+}
+
+impl Serialize for Signal<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("@name", &self.name)?;
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("arg", &self.args)?;
+        map.serialize_entry("annotation", &self.annotations)?;
+
+        map.end()
+    }
 }
 
 /// The possible property access types
@@ -174,7 +312,7 @@ impl PropertyAccess {
 }
 
 /// A property
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Property<'a> {
     #[serde(rename = "@name", borrow)]
     name: PropertyName<'a>,
@@ -186,6 +324,8 @@ pub struct Property<'a> {
 
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Property<'_>: Send, Sync, Unpin);
@@ -210,10 +350,46 @@ impl<'a> Property<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Returns the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Returns the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+    }
+}
+
+impl Serialize for Property<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("@name", &self.name)?;
+        map.serialize_entry("@type", &self.ty)?;
+        map.serialize_entry("@access", &self.access)?;
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("annotation", &self.annotations)?;
+
+        map.end()
+    }
 }
 
 /// An interface
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Interface<'a> {
     #[serde(rename = "@name", borrow)]
     name: InterfaceName<'a>,
@@ -226,6 +402,8 @@ pub struct Interface<'a> {
     signals: Vec<Signal<'a>>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Interface<'_>: Send, Sync, Unpin);
@@ -255,10 +433,58 @@ impl<'a> Interface<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Returns the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Returns the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+        let mut methods = self.methods.iter_mut();
+        let mut signals = self.signals.iter_mut();
+        let mut properties = self.properties.iter_mut();
+        for child in known.children {
+            match child.name.as_str() {
+                "method" => methods.next().map(|m| m.set_extensions(child)),
+                "signal" => signals.next().map(|s| s.set_extensions(child)),
+                "property" => properties.next().map(|p| p.set_extensions(child)),
+                _ => None,
+            };
+        }
+    }
+}
+
+impl Serialize for Interface<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("@name", &self.name)?;
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("method", &self.methods)?;
+        map.serialize_entry("property", &self.properties)?;
+        map.serialize_entry("signal", &self.signals)?;
+        map.serialize_entry("annotation", &self.annotations)?;
+
+        map.end()
+    }
 }
 
 /// An introspection tree node (typically the root of the XML document).
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Node<'a> {
     #[serde(rename = "@name")]
     name: Option<String>,
@@ -267,16 +493,34 @@ pub struct Node<'a> {
     interfaces: Vec<Interface<'a>>,
     #[serde(rename = "node", default, borrow)]
     nodes: Vec<Node<'a>>,
+    #[serde(skip)]
+    extensions: Extensions,
 }
 
 assert_impl_all!(Node<'_>: Send, Sync, Unpin);
 
 impl<'a> Node<'a> {
     /// Parse the introspection XML document from reader.
-    pub fn from_reader<R: Read>(reader: R) -> Result<Node<'a>> {
-        let mut deserializer = Deserializer::from_reader(BufReader::new(reader));
+    ///
+    /// The whole document is read into memory first, since it's parsed twice: once for the
+    /// introspection data and once for the elements and attributes unknown to the introspection
+    /// format, such as vendor extensions. Only this method and `Node::try_from` keep the latter.
+    /// The [`Deserialize`] implementations of `Node` and its children skip them, so they're empty
+    /// when deserializing through serde directly.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Node<'a>> {
+        // The document is read twice: once for the introspection data and once for extensions.
+        let mut xml = Vec::new();
+        reader
+            .read_to_end(&mut xml)
+            .map_err(|e| DeError::from(quick_xml::Error::from(e)))?;
+        let mut deserializer = Deserializer::from_reader(xml.as_slice());
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        let mut node = Node::deserialize(&mut deserializer)?;
+        if let Some(known) = parse_extensions(&xml)? {
+            node.set_extensions(known);
+        }
+
+        Ok(node)
     }
 
     /// Write the XML document to writer.
@@ -293,7 +537,8 @@ impl<'a> Node<'a> {
             }
         }
 
-        to_writer(Writer(writer), &self)?;
+        // The root element can't be named after the type, since it's serialized as a map.
+        self.serialize(XmlSerializer::with_root(&mut Writer(writer), Some("node"))?)?;
 
         Ok(())
     }
@@ -312,6 +557,52 @@ impl<'a> Node<'a> {
     pub fn interfaces(&self) -> &[Interface<'a>] {
         &self.interfaces
     }
+
+    /// Returns the elements unknown to the introspection format, such as vendor extensions.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extensions(&self) -> &[Element] {
+        &self.extensions.elements
+    }
+
+    /// Returns the attributes unknown to the introspection format, as qualified name/value pairs.
+    ///
+    /// These are only available if the document was parsed with [`Node::from_reader`] or
+    /// `Node::try_from`, see there.
+    pub fn extension_attributes(&self) -> &[(String, String)] {
+        &self.extensions.attributes
+    }
+
+    fn set_extensions(&mut self, known: Known) {
+        self.extensions = known.extensions;
+        let mut interfaces = self.interfaces.iter_mut();
+        let mut nodes = self.nodes.iter_mut();
+        for child in known.children {
+            match child.name.as_str() {
+                "interface" => interfaces.next().map(|i| i.set_extensions(child)),
+                "node" => nodes.next().map(|n| n.set_extensions(child)),
+                _ => None,
+            };
+        }
+    }
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(name) = &self.name {
+            map.serialize_entry("@name", name)?;
+        }
+        serialize_extensions(&mut map, &self.extensions)?;
+        map.serialize_entry("interface", &self.interfaces)?;
+        map.serialize_entry("node", &self.nodes)?;
+
+        map.end()
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Node<'a> {
@@ -321,6 +612,11 @@ impl<'a> TryFrom<&'a str> for Node<'a> {
     fn try_from(s: &'a str) -> Result<Node<'a>> {
         let mut deserializer = Deserializer::from_str(s);
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        let mut node = Node::deserialize(&mut deserializer)?;
+        if let Some(known) = parse_extensions(s.as_bytes())? {
+            node.set_extensions(known);
+        }
+
+        Ok(node)
     }
 }
@@ -1,7 +1,7 @@
 use quick_xml::de::DeError;
 use std::error::Error;
 
-use zbus_xml::{ArgDirection, Node};
+use zbus_xml::{ArgDirection, ElementContent, Node};
 
 #[test]
 fn serde() -> Result<(), Box<dyn Error>> {
@@ -36,3 +36,92 @@ fn invalid_arg_type() {
         Err(zbus_xml::Error::QuickXml(DeError::Custom(_)))
     ));
 }
+
+#[test]
+fn vendor_extensions() -> Result<(), Box<dyn Error>> {
+    let example = include_str!("data/vendor_extensions.xml");
+    let node = Node::try_from(example)?;
+    assert_eq!(node, Node::from_reader(example.as_bytes())?);
+    let tp = "http://telepathy.freedesktop.org/wiki/DbusSpec#extensions-v0";
+    assert_eq!(
+        node.extension_attributes(),
+        [("xmlns:tp".to_string(), tp.to_string())]
+    );
+    let copyright = &node.extensions()[0];
+    assert_eq!(copyright.name(), "tp:copyright");
+    assert_eq!(copyright.local_name(), "copyright");
+    assert_eq!(copyright.namespace(), Some(tp));
+    assert!(copyright.text().unwrap().contains("Collabora"));
+
+    let iface = &node.interfaces()[0];
+    let docstring = &iface.extensions()[0];
+    assert_eq!(docstring.name(), "tp:docstring");
+    let [ElementContent::Text(before), ElementContent::Element(b), ElementContent::Text(after)] =
+        docstring.content()
+    else {
+        panic!("unexpected content: {:?}", docstring.content());
+    };
+    assert_eq!(before.trim_start(), "The connection to a ");
+    assert_eq!(
+        (b.name(), b.namespace(), b.text()),
+        ("b", None, Some("server"))
+    );
+    assert_eq!(after.trim_end(), ".");
+
+    let method = &iface.methods()[0];
+    assert_eq!(method.name(), "Connect");
+    assert_eq!(
+        method.extension_attributes(),
+        [("tp:name-for-bindings".to_string(), "Connect".to_string())]
+    );
+    let [docstring, errors] = method.extensions() else {
+        panic!("unexpected extensions: {:?}", method.extensions());
+    };
+    assert_eq!(
+        docstring.text(),
+        Some("Request that the connection be established.")
+    );
+    assert_eq!(errors.name(), "tp:possible-errors");
+    assert_eq!(
+        errors.children().nth(1).unwrap().attribute("name"),
+        Some("org.freedesktop.Telepathy.Error.NetworkError")
+    );
+    assert_eq!(
+        iface.signals()[0].args()[0].extensions()[0].text(),
+        Some("The new status.")
+    );
+    assert_eq!(iface.properties()[0].extensions().len(), 1);
+
+    // Extensions are written back out as they were, only the indentation of the known elements
+    // and the doctype differ.
+    let mut writer = Vec::new();
+    node.to_writer(&mut writer)?;
+    let written = String::from_utf8(writer)?;
+    let doctype_end = example.find("<node").unwrap();
+    assert_eq!(
+        strip_indentation(&written),
+        strip_indentation(&example[doctype_end..])
+    );
+    assert_eq!(Node::try_from(written.as_str())?, node);
+
+    Ok(())
+}
+
+// Remove the whitespace-only text between tags.
+fn strip_indentation(xml: &str) -> String {
+    let mut stripped = String::new();
+    for (i, part) in xml.split('<').enumerate() {
+        if i > 0 {
+            stripped.push('<');
+        }
+        match part.split_once('>') {
+            Some((tag, text)) if text.trim().is_empty() => {
+                stripped.push_str(tag);
+                stripped.push('>');
+            }
+            _ => stripped.push_str(part),
+        }
+    }
+
+    stripped
+}
@@ -13,13 +13,40 @@ pub struct Args {
     /// be saved to that file. Use '-' to print the output to stdout.
     #[clap(short, long, allow_hyphen_values = true, global = true)]
     pub output: Option<String>,
+
+    /// Use the given Rust identifier for a D-Bus method, signal or property name, instead of its
+    /// snake case form, e.g. `--rename WiFiDevice=wifi_device`. The name can be prefixed with an
+    /// interface name to only rename the member of that interface, e.g.
+    /// `--rename org.example.Device.WiFiDevice=wifi_device`. Can be specified multiple times.
+    #[clap(long, value_name = "[INTERFACE.]NAME=IDENT", value_parser = parse_pair, global = true)]
+    pub rename: Vec<(String, String)>,
+
+    /// Don't generate code for the given D-Bus interface but refer to an existing proxy for it
+    /// instead, e.g. `--known-interface org.example.Common=common::CommonProxy`. The D-Bus standard
+    /// interfaces (`org.freedesktop.DBus.*`) are always referred to the zbus proxies. Can be
+    /// specified multiple times.
+    #[clap(long, value_name = "INTERFACE=PROXY", value_parser = parse_pair, global = true)]
+    pub known_interface: Vec<(String, String)>,
+}
+
+fn parse_pair(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected `KEY=VALUE`, got `{s}`")),
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
 pub enum Command {
-    /// Generate code for interfaces in the specified file.
+    /// Generate code for interfaces in the specified files. Interfaces found in several files are
+    /// only generated once.
     #[clap()]
-    File { path: PathBuf },
+    File {
+        #[clap(required = true)]
+        paths: Vec<PathBuf>,
+    },
 
     /// Generate code for interfaces from the specified system service.
     #[clap()]
@@ -1,16 +1,29 @@
-use snakecase::ascii::to_snakecase;
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
     fmt::{Display, Formatter, Write},
     process::{Command, Stdio},
 };
 
-use zbus::names::BusName;
-use zbus_xml::{Arg, ArgDirection, Interface};
+use zbus::names::{BusName, InterfaceName, MemberName};
+use zbus_xml::{Arg, ArgDirection, Interface, Node};
 use zvariant::{
     Basic, CompleteType, ObjectPath, Signature, ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_END_CHAR,
     DICT_ENTRY_SIG_START_CHAR, STRUCT_SIG_END_CHAR, STRUCT_SIG_START_CHAR, VARIANT_SIGNATURE_CHAR,
 };
+use zvariant_utils::case::acronym_aware_snake_case;
+
+/// The zbus proxy for `interface`, if it's one of the [D-Bus standard interfaces].
+///
+/// [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces
+pub fn fdo_proxy(interface: &str) -> Option<String> {
+    if !interface.starts_with("org.freedesktop.DBus") {
+        return None;
+    }
+    let idx = interface.rfind('.').unwrap() + 1;
+
+    Some(format!("zbus::fdo::{}Proxy", &interface[idx..]))
+}
 
 pub fn write_interfaces(
     interfaces: &[Interface<'_>],
@@ -20,13 +33,46 @@ pub fn write_interfaces(
     input_src: &str,
     cargo_bin_name: &str,
     cargo_bin_version: &str,
+) -> Result<String, Box<dyn Error>> {
+    let known_interfaces: Vec<_> = standard_interfaces
+        .iter()
+        .filter_map(|iface| {
+            let name = iface.name().to_string();
+            fdo_proxy(&name).map(|proxy| (name, proxy))
+        })
+        .collect();
+
+    write_interfaces_with(
+        interfaces,
+        &known_interfaces,
+        service,
+        path,
+        &Renames::default(),
+        input_src,
+        cargo_bin_name,
+        cargo_bin_version,
+    )
+}
+
+// `known_interfaces` lists the other interfaces of the object, for which code is not generated
+// since a proxy already exists, along with the path of that proxy type.
+#[allow(clippy::too_many_arguments)]
+fn write_interfaces_with(
+    interfaces: &[Interface<'_>],
+    known_interfaces: &[(String, String)],
+    service: Option<BusName<'_>>,
+    path: Option<ObjectPath<'_>>,
+    renames: &Renames,
+    input_src: &str,
+    cargo_bin_name: &str,
+    cargo_bin_version: &str,
 ) -> Result<String, Box<dyn Error>> {
     let mut unformatted = String::new();
 
     write_doc_header(
         &mut unformatted,
         interfaces,
-        standard_interfaces,
+        known_interfaces,
         input_src,
         cargo_bin_name,
         cargo_bin_version,
@@ -40,7 +86,7 @@ pub fn write_interfaces(
             format: false,
         };
 
-        write!(unformatted, "{}", gen)?;
+        write!(unformatted, "{}", gen.with_renames(renames))?;
     }
 
     let formatted = match format_generated_code(&unformatted) {
@@ -54,12 +100,133 @@ pub fn write_interfaces(
     Ok(formatted)
 }
 
+/// Generates code for the interfaces of one or more objects.
+///
+/// Interfaces found in several objects are only generated once. No code is generated for the
+/// interfaces that have a proxy already, i.e. the [D-Bus standard interfaces] and those given to
+/// [`Generator::known_interface`], but the generated code refers to that proxy instead.
+///
+/// [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces
+#[derive(Debug)]
+pub struct Generator<'a> {
+    cargo_bin_name: String,
+    cargo_bin_version: String,
+    known_interfaces: HashMap<String, String>,
+    renames: Renames,
+    // The interfaces generated so far, along with the source they were generated from.
+    generated: HashMap<String, (&'a Interface<'a>, String)>,
+    skipped: HashSet<String>,
+}
+
+/// The output of [`Generator::generate`].
+#[derive(Debug, Default)]
+pub struct Generated {
+    /// The code of each newly generated interface, along with the interface name.
+    pub interfaces: Vec<(String, String)>,
+    /// Messages about the interfaces that were skipped, to pass on to the user.
+    pub warnings: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    /// Create a generator, with the name and version of the program using it, for the doc header
+    /// of the generated code.
+    pub fn new(cargo_bin_name: &str, cargo_bin_version: &str) -> Self {
+        Self {
+            cargo_bin_name: cargo_bin_name.to_string(),
+            cargo_bin_version: cargo_bin_version.to_string(),
+            known_interfaces: HashMap::new(),
+            renames: Renames::default(),
+            generated: HashMap::new(),
+            skipped: HashSet::new(),
+        }
+    }
+
+    /// Don't generate code for `interface` but refer to the existing `proxy` type for it.
+    pub fn known_interface(&mut self, interface: String, proxy: String) -> &mut Self {
+        self.known_interfaces.insert(interface, proxy);
+
+        self
+    }
+
+    /// Use the given Rust identifiers for D-Bus member names.
+    pub fn renames(&mut self, renames: Renames) -> &mut Self {
+        self.renames = renames;
+
+        self
+    }
+
+    /// Generate the code for the interfaces of `node`, which are not generated or skipped already.
+    ///
+    /// `input_src` describes where `node` comes from, for the doc header of the generated code and
+    /// the warnings.
+    pub fn generate(
+        &mut self,
+        node: &'a Node<'a>,
+        service: Option<BusName<'_>>,
+        path: Option<ObjectPath<'_>>,
+        input_src: &str,
+    ) -> Result<Generated, Box<dyn Error>> {
+        let mut generated = Generated::default();
+
+        let mut known = vec![];
+        let mut needed = vec![];
+        for interface in node.interfaces() {
+            let name = interface.name().to_string();
+            match self
+                .known_interfaces
+                .get(&name)
+                .cloned()
+                .or_else(|| fdo_proxy(&name))
+            {
+                Some(proxy) => known.push((name, proxy)),
+                None => needed.push(interface),
+            }
+        }
+        for (name, proxy) in &known {
+            if self.skipped.insert(name.clone()) {
+                generated
+                    .warnings
+                    .push(format!("Skipping `{name}`, please use `{proxy}`"));
+            }
+        }
+
+        for interface in needed {
+            let name = interface.name().to_string();
+            if let Some((first, first_src)) = self.generated.get(&name) {
+                if *first != interface {
+                    generated.warnings.push(format!(
+                        "`{name}` from {input_src} differs from the one in {first_src}, only the \
+                         one from {first_src} was generated"
+                    ));
+                }
+                continue;
+            }
+
+            let code = write_interfaces_with(
+                std::slice::from_ref(interface),
+                &known,
+                service.clone(),
+                path.clone(),
+                &self.renames,
+                input_src,
+                &self.cargo_bin_name,
+                &self.cargo_bin_version,
+            )?;
+            self.generated
+                .insert(name.clone(), (interface, input_src.to_string()));
+            generated.interfaces.push((name, code));
+        }
+
+        Ok(generated)
+    }
+}
+
 /// Write a doc header, listing the included Interfaces and how the
 /// code was generated.
 fn write_doc_header<W: std::fmt::Write>(
     w: &mut W,
     interfaces: &[Interface<'_>],
-    standard_interfaces: &[Interface<'_>],
+    known_interfaces: &[(String, String)],
     input_src: &str,
     cargo_bin_name: &str,
     cargo_bin_version: &str,
@@ -99,16 +266,16 @@ fn write_doc_header<W: std::fmt::Write>(
         cargo_bin_name, cargo_bin_version, input_src,
     )?;
 
-    if !standard_interfaces.is_empty() {
-        write!(w,
-            "//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
-             //! following zbus API can be used:
+    if !known_interfaces.is_empty() {
+        write!(
+            w,
+            "//! This object also implements the following interfaces, for which existing proxies can be
+             //! used:
              //!
-            ")?;
-        for iface in standard_interfaces {
-            let idx = iface.name().rfind('.').unwrap() + 1;
-            let name = &iface.name()[idx..];
-            writeln!(w, "//! * [`zbus::fdo::{name}Proxy`]")?;
+            "
+        )?;
+        for (name, proxy) in known_interfaces {
+            writeln!(w, "//! * [`{proxy}`] for `{name}`")?;
         }
         write!(
             w,
@@ -140,7 +307,73 @@ pub struct GenTrait<'i> {
 
 impl<'i> Display for GenTrait<'i> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.format {
+        self.with_renames(&Renames::default()).fmt(f)
+    }
+}
+
+impl<'i> GenTrait<'i> {
+    /// The code, with `renames` applied to the member names.
+    pub fn with_renames<'r>(&'r self, renames: &'r Renames) -> impl Display + 'r {
+        RenamedGenTrait { gen: self, renames }
+    }
+}
+
+/// Rust identifiers to use for D-Bus member names, instead of their snake case form.
+///
+/// By default, acronyms are kept together, e.g. `GetACLs` yields `get_acls`. Names that don't map as
+/// desired, such as `WiFiDevice` yielding `wi_fi_device`, can be overridden here.
+#[derive(Debug, Default, Clone)]
+pub struct Renames {
+    // Keyed by interface and member name, with no interface for renames in all interfaces.
+    idents: HashMap<(Option<String>, String), String>,
+}
+
+impl Renames {
+    /// Use `ident` for `member`.
+    ///
+    /// `member` is either a member name, for renaming the member in all interfaces, or a member
+    /// name prefixed with an interface name, e.g. `org.example.Device.WiFiDevice`. Just like the
+    /// generated identifiers, Rust keywords get a trailing `_`. An error is returned if `member`
+    /// is not a valid (optionally prefixed) member name or `ident` is not a valid identifier.
+    pub fn insert(&mut self, member: &str, ident: &str) -> Result<(), Box<dyn Error>> {
+        let (interface, member) = match member.rsplit_once('.') {
+            Some((interface, member)) => (
+                Some(InterfaceName::try_from(interface)?.to_string()),
+                member,
+            ),
+            None => (None, member),
+        };
+        let member = MemberName::try_from(member)?.to_string();
+        let mut chars = ident.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || (c == '_' && ident.len() > 1))
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("`{ident}` is not a valid Rust identifier").into());
+        }
+        self.idents
+            .insert((interface, member), to_identifier(ident));
+
+        Ok(())
+    }
+
+    fn get(&self, interface: &str, member: &str) -> Option<&str> {
+        self.idents
+            .get(&(Some(interface.to_string()), member.to_string()))
+            .or_else(|| self.idents.get(&(None, member.to_string())))
+            .map(String::as_str)
+    }
+}
+
+struct RenamedGenTrait<'r, 'i> {
+    gen: &'r GenTrait<'i>,
+    renames: &'r Renames,
+}
+
+impl Display for RenamedGenTrait<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.gen.format {
             let mut unformatted = String::new();
             self.write_interface(&mut unformatted)?;
 
@@ -153,20 +386,27 @@ impl<'i> Display for GenTrait<'i> {
     }
 }
 
-impl<'i> GenTrait<'i> {
+impl RenamedGenTrait<'_, '_> {
+    fn member_identifier(&self, member: &str) -> String {
+        match self.renames.get(self.gen.interface.name().as_str(), member) {
+            Some(name) => name.to_string(),
+            None => to_identifier(&acronym_aware_snake_case(member)),
+        }
+    }
+
     fn write_interface<W: Write>(&self, w: &mut W) -> std::fmt::Result {
-        let iface = self.interface;
+        let iface = self.gen.interface;
         let idx = iface.name().rfind('.').unwrap() + 1;
         let name = &iface.name()[idx..];
 
         write!(w, "#[proxy(interface = \"{}\"", iface.name())?;
-        if let Some(service) = self.service {
+        if let Some(service) = self.gen.service {
             write!(w, ", default_service = \"{service}\"")?;
         }
-        if let Some(path) = self.path {
+        if let Some(path) = self.gen.path {
             write!(w, ", default_path = \"{path}\"")?;
         }
-        if self.path.is_none() || self.service.is_none() {
+        if self.gen.path.is_none() || self.gen.service.is_none() {
             write!(w, ", assume_defaults = true")?;
         }
         writeln!(w, ")]")?;
@@ -176,7 +416,7 @@ impl<'i> GenTrait<'i> {
         methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for m in &methods {
             let (inputs, output) = inputs_output_from_args(m.args());
-            let name = to_identifier(&to_snakecase(m.name().as_str()));
+            let name = self.member_identifier(m.name().as_str());
             writeln!(w)?;
             writeln!(w, "    /// {} method", m.name())?;
             if pascal_case(&name) != m.name().as_str() {
@@ -190,7 +430,7 @@ impl<'i> GenTrait<'i> {
         signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for signal in &signals {
             let args = parse_signal_args(signal.args());
-            let name = to_identifier(&to_snakecase(signal.name().as_str()));
+            let name = self.member_identifier(signal.name().as_str());
             writeln!(w)?;
             writeln!(w, "    /// {} signal", signal.name())?;
             if pascal_case(&name) != signal.name().as_str() {
@@ -204,7 +444,7 @@ impl<'i> GenTrait<'i> {
         let mut props = iface.properties().to_vec();
         props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for p in props {
-            let name = to_identifier(&to_snakecase(p.name().as_str()));
+            let name = self.member_identifier(p.name().as_str());
             let fn_attribute = if pascal_case(&name) != p.name().as_str() {
                 format!("    #[zbus(property, name = \"{}\")]", p.name())
             } else {
@@ -7,15 +7,15 @@ use std::{
 };
 
 use clap::Parser;
-use snakecase::ascii::to_snakecase;
 use zbus::{
     blocking::{connection, fdo::IntrospectableProxy, Connection},
     names::BusName,
 };
-use zbus_xml::{Interface, Node};
+use zbus_xml::Node;
 
-use zbus_xmlgen::write_interfaces;
+use zbus_xmlgen::{Generator, Renames};
 use zvariant::ObjectPath;
+use zvariant_utils::case::acronym_aware_snake_case;
 
 mod cli;
 
@@ -28,41 +28,43 @@ enum OutputTarget {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = cli::Args::parse();
 
-    let DBusInfo(node, service, path, input_src) = match args.command {
+    let inputs = match args.command {
         cli::Command::System {
             service,
             object_path,
-        } => DBusInfo::new(Connection::system()?, service, object_path)?,
+        } => vec![DBusInfo::new(Connection::system()?, service, object_path)?],
         cli::Command::Session {
             service,
             object_path,
-        } => DBusInfo::new(Connection::session()?, service, object_path)?,
+        } => vec![DBusInfo::new(Connection::session()?, service, object_path)?],
         cli::Command::Address {
             address,
             service,
             object_path,
-        } => DBusInfo::new(
+        } => vec![DBusInfo::new(
             connection::Builder::address(&*address)?.build()?,
             service,
             object_path,
-        )?,
-        cli::Command::File { path } => {
-            let input_src = path.file_name().unwrap().to_string_lossy().to_string();
-            let f = File::open(path)?;
-            DBusInfo(Node::from_reader(f)?, None, None, input_src)
-        }
+        )?],
+        cli::Command::File { paths } => paths
+            .into_iter()
+            .map(|path| {
+                let input_src = path.file_name().unwrap().to_string_lossy().to_string();
+                let f = File::open(path)?;
+                Ok(DBusInfo(Node::from_reader(f)?, None, None, input_src))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?,
     };
 
-    let fdo_iface_prefix = "org.freedesktop.DBus";
-    let (fdo_standard_ifaces, needed_ifaces): (Vec<Interface<'_>>, Vec<Interface<'_>>) = node
-        .interfaces()
-        .iter()
-        .cloned()
-        .partition(|i| i.name().starts_with(fdo_iface_prefix));
-
-    if !fdo_standard_ifaces.is_empty() {
-        eprintln!("Skipping `org.freedesktop.DBus` interfaces, please use https://docs.rs/zbus/latest/zbus/fdo/index.html")
+    let mut generator = Generator::new(env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+    for (interface, proxy) in args.known_interface {
+        generator.known_interface(interface, proxy);
+    }
+    let mut renames = Renames::default();
+    for (member, ident) in args.rename {
+        renames.insert(&member, &ident)?;
     }
+    generator.renames(renames);
 
     let mut output_target = match args.output.as_deref() {
         Some("-") => OutputTarget::Stdout,
@@ -77,34 +79,30 @@ fn main() -> Result<(), Box<dyn Error>> {
         _ => OutputTarget::MultipleFiles,
     };
 
-    for interface in needed_ifaces {
-        let output = write_interfaces(
-            &[interface.clone()],
-            &fdo_standard_ifaces,
-            service.clone(),
-            path.clone(),
-            &input_src,
-            env!("CARGO_BIN_NAME"),
-            env!("CARGO_PKG_VERSION"),
-        )?;
-
-        let interface_name = interface.name();
-        match output_target {
-            OutputTarget::Stdout => println!("{}", output),
-            OutputTarget::SingleFile(ref mut file) => {
-                file.write_all(output.as_bytes())?;
-                println!("Generated code for `{}`", interface_name);
-            }
-            OutputTarget::MultipleFiles => {
-                let filename = interface_name
-                    .split('.')
-                    .last()
-                    .expect("Failed to split name");
-                let filename = to_snakecase(filename);
-                std::fs::write(format!("{}.rs", &filename), output)?;
-                println!("Generated code for `{}` in {}.rs", interface_name, filename);
-            }
-        };
+    for DBusInfo(node, service, path, input_src) in &inputs {
+        let generated = generator.generate(node, service.clone(), path.clone(), input_src)?;
+        for warning in generated.warnings {
+            eprintln!("{warning}");
+        }
+
+        for (interface_name, output) in generated.interfaces {
+            match output_target {
+                OutputTarget::Stdout => println!("{}", output),
+                OutputTarget::SingleFile(ref mut file) => {
+                    file.write_all(output.as_bytes())?;
+                    println!("Generated code for `{}`", interface_name);
+                }
+                OutputTarget::MultipleFiles => {
+                    let filename = interface_name
+                        .split('.')
+                        .last()
+                        .expect("Failed to split name");
+                    let filename = acronym_aware_snake_case(filename);
+                    std::fs::write(format!("{}.rs", &filename), output)?;
+                    println!("Generated code for `{}` in {}.rs", interface_name, filename);
+                }
+            };
+        }
     }
 
     Ok(())
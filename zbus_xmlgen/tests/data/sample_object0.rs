@@ -29,6 +29,10 @@ trait SampleInterface0 {
         foo: i32,
     ) -> zbus::Result<(String, std::collections::HashMap<u32, String>)>;
 
+    /// GetACLs method
+    #[zbus(name = "GetACLs")]
+    fn get_acls(&self) -> zbus::Result<Vec<String>>;
+
     /// MogrifyMe method
     fn mogrify_me(&self, bar: &(i32, i32, &[&zbus::zvariant::Value<'_>])) -> zbus::Result<()>;
 
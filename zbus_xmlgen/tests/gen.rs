@@ -2,7 +2,7 @@ use pretty_assertions::assert_eq;
 use std::{env, error::Error, io::Write, path::Path};
 
 use zbus_xml::Node;
-use zbus_xmlgen::GenTrait;
+use zbus_xmlgen::{fdo_proxy, GenTrait, Generator, Renames};
 
 macro_rules! gen_diff {
     ($infile:literal, $outfile:literal) => {{
@@ -42,3 +42,112 @@ macro_rules! gen_diff {
 fn sample_object0() -> Result<(), Box<dyn Error>> {
     gen_diff!("sample_object0.xml", "sample_object0.rs")
 }
+
+#[test]
+fn renames() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("data/sample_object0.xml");
+    let node = Node::from_reader(input.as_bytes())?;
+    let gen = GenTrait {
+        interface: &node.interfaces()[0],
+        path: None,
+        service: None,
+        format: false,
+    };
+
+    let mut renames = Renames::default();
+    renames.insert("MogrifyMe", "mogrify")?;
+    let code = gen.with_renames(&renames).to_string();
+    assert!(code.contains("#[zbus(name = \"MogrifyMe\")]"));
+    assert!(code.contains("fn mogrify("));
+    assert!(!code.contains("fn mogrify_me("));
+
+    // Renames of another interface don't apply, and keywords are escaped.
+    let mut renames = Renames::default();
+    renames.insert("org.example.Other.MogrifyMe", "mogrify")?;
+    renames.insert("com.example.SampleInterface0.Odyssey", "type")?;
+    let code = gen.with_renames(&renames).to_string();
+    assert!(code.contains("fn mogrify_me("));
+    assert!(code.contains("fn type_("));
+
+    assert!(renames.insert("MogrifyMe", "mogrify-me").is_err());
+    assert!(renames.insert("MogrifyMe", "1mogrify").is_err());
+    assert!(renames.insert("MogrifyMe", "_").is_err());
+    assert!(renames.insert("org.example.Other.", "mogrify").is_err());
+    Ok(())
+}
+
+#[test]
+fn fdo_proxies() {
+    assert_eq!(
+        fdo_proxy("org.freedesktop.DBus.Properties").unwrap(),
+        "zbus::fdo::PropertiesProxy"
+    );
+    assert_eq!(
+        fdo_proxy("org.freedesktop.DBus").unwrap(),
+        "zbus::fdo::DBusProxy"
+    );
+    assert!(fdo_proxy("org.example.Foo").is_none());
+}
+
+#[test]
+fn generator() -> Result<(), Box<dyn Error>> {
+    let object1 = Node::from_reader(
+        r#"<node>
+             <interface name="org.freedesktop.DBus.Peer"><method name="Ping"/></interface>
+             <interface name="org.example.Common"><method name="Hello"/></interface>
+             <interface name="org.example.Foo"><method name="Foo"/></interface>
+           </node>"#
+            .as_bytes(),
+    )?;
+    let object2 = Node::from_reader(
+        r#"<node>
+             <interface name="org.freedesktop.DBus.Peer"><method name="Ping"/></interface>
+             <interface name="org.example.Common"><method name="Hello"/></interface>
+             <interface name="org.example.Foo"><method name="Bar"/></interface>
+             <interface name="org.example.Baz"><method name="Baz"/></interface>
+           </node>"#
+            .as_bytes(),
+    )?;
+
+    let mut generator = Generator::new("zbus-xmlgen", "0.0.0");
+    generator.known_interface(
+        "org.example.Common".to_string(),
+        "common::CommonProxy".to_string(),
+    );
+
+    let generated = generator.generate(&object1, None, None, "object1.xml")?;
+    let names: Vec<_> = generated
+        .interfaces
+        .iter()
+        .map(|(n, _)| n.as_str())
+        .collect();
+    assert_eq!(names, ["org.example.Foo"]);
+    let code = &generated.interfaces[0].1;
+    assert!(code.contains("[`zbus::fdo::PeerProxy`] for `org.freedesktop.DBus.Peer`"));
+    assert!(code.contains("[`common::CommonProxy`] for `org.example.Common`"));
+    assert_eq!(
+        generated.warnings,
+        [
+            "Skipping `org.freedesktop.DBus.Peer`, please use `zbus::fdo::PeerProxy`",
+            "Skipping `org.example.Common`, please use `common::CommonProxy`",
+        ]
+    );
+
+    // Interfaces are only generated and skipped once, and differing ones are reported.
+    let generated = generator.generate(&object2, None, None, "object2.xml")?;
+    let names: Vec<_> = generated
+        .interfaces
+        .iter()
+        .map(|(n, _)| n.as_str())
+        .collect();
+    assert_eq!(names, ["org.example.Baz"]);
+    assert_eq!(
+        generated.warnings,
+        [
+            "`org.example.Foo` from object2.xml differs from the one in object1.xml, only the one \
+             from object1.xml was generated"
+        ]
+    );
+
+    Ok(())
+}
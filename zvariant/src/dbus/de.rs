@@ -8,6 +8,7 @@ use std::os::fd::AsFd;
 
 use crate::{
     de::{DeserializerCommon, ValueParseStage},
+    fixed_array::{RawFixedArray, FIXED_ARRAY_NAME},
     serialized::{Context, Format},
     signature_parser::SignatureParser,
     utils::*,
@@ -161,7 +162,49 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     deserialize_as!(deserialize_tuple_struct(n: &'static str, _l: usize) => deserialize_struct(n, &[]));
     deserialize_as!(deserialize_struct(_n: &'static str, _f: &'static [&'static str]) => deserialize_seq());
     deserialize_as!(deserialize_map => deserialize_seq);
-    deserialize_as!(deserialize_ignored_any => deserialize_any);
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Variants and structures can't be visited generically, as the visitor doesn't know how
+        // many elements they contain. Arrays carry their length, so they're skipped as a whole.
+        match self.0.sig_parser.next_char()? {
+            VARIANT_SIGNATURE_CHAR => {
+                let mut value_de = ValueDeserializer::new(self);
+                value_de.next_element::<Signature<'_>>()?;
+                value_de.next_element::<de::IgnoredAny>()?;
+
+                visitor.visit_unit()
+            }
+            ARRAY_SIGNATURE_CHAR => {
+                self.0.sig_parser.skip_char()?;
+                let array_de = ArrayDeserializer::new(self)?;
+                let (len, element_signature_len) = (array_de.len, array_de.element_signature_len);
+                self.0.next_slice(len)?;
+                self.0.sig_parser.skip_chars(element_signature_len)?;
+                self.0.container_depths = self.0.container_depths.dec_array();
+
+                visitor.visit_unit()
+            }
+            STRUCT_SIG_START_CHAR => {
+                let signature = self.0.sig_parser.next_signature()?;
+                let alignment = alignment_for_signature(&signature, Format::DBus)?;
+                self.0.parse_padding(alignment)?;
+
+                self.0.sig_parser.skip_char()?;
+                self.0.container_depths = self.0.container_depths.inc_structure()?;
+                while self.0.sig_parser.next_char()? != STRUCT_SIG_END_CHAR {
+                    <de::IgnoredAny as de::Deserialize>::deserialize(&mut *self)?;
+                }
+                self.0.sig_parser.skip_char()?;
+                self.0.container_depths = self.0.container_depths.dec_structure();
+
+                visitor.visit_unit()
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -297,11 +340,34 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        if name != FIXED_ARRAY_NAME {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        // Hand the encoded elements of the array over as is.
+        if self.0.sig_parser.next_char()? != ARRAY_SIGNATURE_CHAR {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &visitor));
+        }
+        self.0.sig_parser.skip_char()?;
+        let array_de = ArrayDeserializer::new(self)?;
+        let (len, element_signature_len) = (array_de.len, array_de.element_signature_len);
+        let element_signature = self.0.sig_parser.next_char()?;
+        if element_signature_len != 1 || !"ynqiuxtd".contains(element_signature) {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &visitor));
+        }
+        let bytes = self.0.next_slice(len)?;
+        self.0.sig_parser.skip_char()?;
+        self.0.container_depths = self.0.container_depths.dec_array();
+
+        visitor.visit_seq(RawFixedArray {
+            element_signature: Some(element_signature),
+            endian: Some(self.0.ctxt.endian()),
+            bytes: Some(bytes),
+        })
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
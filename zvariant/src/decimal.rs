@@ -0,0 +1,105 @@
+//! Opt-in mappings for [`rust_decimal::Decimal`].
+//!
+//! The serde representation of `Decimal` depends on the features enabled on `rust_decimal` so
+//! it doesn't implement [`Type`](crate::Type). This module provides two mappings that you can
+//! choose from through serde's `with` attribute:
+//!
+//! * [`as_tuple`] encodes the value as a `(ttu)` structure: the 128-bit mantissa (as per
+//!   [`crate::int128::as_tuple`]), followed by the scale. This mapping is lossless and cheap.
+//! * [`as_string`] encodes the value as its string representation (`s`), which is the most
+//!   convenient for peers without a decimal type.
+//!
+//! When deriving [`Type`](crate::Type), use the `signature` field attribute to specify the
+//! signature of the chosen mapping:
+//!
+//! ```
+//! use rust_decimal::Decimal;
+//! use serde::{Deserialize, Serialize};
+//! use zvariant::{serialized::Context, to_bytes, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, Debug, PartialEq)]
+//! struct Price {
+//!     #[serde(with = "zvariant::decimal::as_tuple")]
+//!     #[zvariant(signature = "(ttu)")]
+//!     net: Decimal,
+//!     #[serde(with = "zvariant::decimal::as_string")]
+//!     #[zvariant(signature = "s")]
+//!     gross: Decimal,
+//! }
+//!
+//! assert_eq!(Price::signature(), "((ttu)s)");
+//!
+//! let price = Price {
+//!     net: Decimal::new(-1999, 2),
+//!     gross: Decimal::new(-2379, 2),
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &price).unwrap();
+//! let decoded: Price = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, price);
+//! ```
+//!
+//! This module is only available when the `rust_decimal` feature is enabled.
+
+/// Encode a `Decimal` as a `(ttu)` structure.
+///
+/// Use through `#[serde(with = "zvariant::decimal::as_tuple")]`.
+pub mod as_tuple {
+    use rust_decimal::Decimal;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize `value` as a `(ttu)` structure.
+    pub fn serialize<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let bytes = value.mantissa().to_be_bytes();
+        let (high, low) = bytes.split_at(8);
+        let high = u64::from_be_bytes(high.try_into().expect("8 bytes"));
+        let low = u64::from_be_bytes(low.try_into().expect("8 bytes"));
+
+        (high, low, value.scale()).serialize(serializer)
+    }
+
+    /// Deserialize a `(ttu)` structure into a `Decimal`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (high, low, scale) = <(u64, u64, u32)>::deserialize(deserializer)?;
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&high.to_be_bytes());
+        bytes[8..].copy_from_slice(&low.to_be_bytes());
+        let mantissa = i128::from_be_bytes(bytes);
+
+        Decimal::try_from_i128_with_scale(mantissa, scale).map_err(de::Error::custom)
+    }
+}
+
+/// Encode a `Decimal` as a string.
+///
+/// Use through `#[serde(with = "zvariant::decimal::as_string")]`.
+pub mod as_string {
+    use std::str::FromStr;
+
+    use rust_decimal::Decimal;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Serialize `value` as a string.
+    pub fn serialize<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    /// Deserialize a string into a `Decimal`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        Decimal::from_str(&s).map_err(de::Error::custom)
+    }
+}
@@ -1,10 +1,12 @@
 use core::str;
 use std::marker::PhantomData;
 
-use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, SeqAccess, Visitor};
 use static_assertions::assert_impl_all;
 
-use crate::{Signature, Type, Value};
+use crate::{
+    serialized::Context, to_bytes, Error, OwnedValue, Signature, Type, Value, NATIVE_ENDIAN,
+};
 
 /// A wrapper to deserialize a value to `T: Type + Deserialize`.
 ///
@@ -80,3 +82,22 @@ impl<'de, T: Type + Deserialize<'de>> Type for DeserializeValue<'de, T> {
         Value::signature()
     }
 }
+
+/// Convert a generic value to `T`, through its `Deserialize` implementation.
+///
+/// This allows types that implement `Deserialize` but not `TryFrom<OwnedValue>` to be used where
+/// the latter is required, at the cost of encoding and decoding the value.
+impl<T> TryFrom<OwnedValue> for DeserializeValue<'static, T>
+where
+    T: Type + DeserializeOwned,
+{
+    type Error = Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Error> {
+        let ctxt = Context::new_dbus(NATIVE_ENDIAN, 0);
+        let encoded = to_bytes(ctxt, &value)?;
+        let (DeserializeValue(value, _), _) = encoded.deserialize::<DeserializeValue<'_, T>>()?;
+
+        Ok(DeserializeValue(value, PhantomData))
+    }
+}
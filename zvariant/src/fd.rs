@@ -168,6 +168,32 @@ impl std::hash::Hash for Fd<'_> {
 /// A file-descriptor type wrapper.
 ///
 /// This is the same as [`Fd`] type, except it only keeps an owned file descriptor.
+///
+/// When deserialized, the file descriptor is resolved from the file descriptor table of the
+/// serialized data (e.g a D-Bus message) and duplicated once, so it remains valid after the data is
+/// dropped. It can then be converted into the standard type matching what it refers to, e.g
+/// [`std::fs::File`] for a file or pipe end, or [`std::os::unix::net::UnixStream`] for a socket:
+///
+/// ```
+/// use std::{
+///     io::{Read, Write},
+///     os::unix::net::UnixStream,
+/// };
+/// use zvariant::{serialized::Context, to_bytes, Fd, OwnedFd, LE};
+///
+/// let (mut ours, theirs) = UnixStream::pair()?;
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &Fd::from(&theirs))?;
+/// drop(theirs);
+///
+/// let (fd, _): (OwnedFd, _) = encoded.deserialize()?;
+/// let mut stream = UnixStream::from(fd);
+/// ours.write_all(b"hello")?;
+/// let mut buf = [0; 5];
+/// stream.read_exact(&mut buf)?;
+/// assert_eq!(&buf, b"hello");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct OwnedFd {
     inner: Fd<'static>,
@@ -229,6 +255,26 @@ impl From<OwnedFd> for fd::OwnedFd {
     }
 }
 
+macro_rules! owned_fd_into_impl {
+    ($($t:ty),+) => {
+        $(
+            impl From<OwnedFd> for $t {
+                fn from(value: OwnedFd) -> Self {
+                    fd::OwnedFd::from(value).into()
+                }
+            }
+        )+
+    };
+}
+
+owned_fd_into_impl!(
+    std::fs::File,
+    std::os::unix::net::UnixStream,
+    std::os::unix::net::UnixListener,
+    std::os::unix::net::UnixDatagram,
+    std::process::Stdio
+);
+
 impl From<Fd<'static>> for OwnedFd {
     fn from(value: Fd<'static>) -> Self {
         Self { inner: value }
@@ -0,0 +1,363 @@
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+    Deserialize,
+};
+use static_assertions::assert_impl_all;
+use std::{fmt, marker::PhantomData};
+
+use crate::{Basic, Signature, Type};
+use endi::Endian;
+
+/// The name `FixedArray` is deserialized as, recognized by the D-Bus deserializer.
+pub(crate) const FIXED_ARRAY_NAME: &str = "zvariant::FixedArray";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Basic types of a fixed size, that [`FixedArray`] can hold.
+///
+/// This trait is sealed and implemented for all integers and `f64`.
+pub trait FixedSize: Basic + Copy + PartialEq + sealed::Sealed {
+    #[doc(hidden)]
+    const SIZE: usize;
+
+    #[doc(hidden)]
+    fn read(endian: Endian, bytes: &[u8]) -> Self;
+}
+
+macro_rules! fixed_size {
+    ($($ty:ty => $read:ident),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl FixedSize for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                #[inline]
+                fn read(endian: Endian, bytes: &[u8]) -> Self {
+                    endian.$read(bytes)
+                }
+            }
+        )*
+    };
+}
+
+fixed_size!(
+    i16 => read_i16,
+    u16 => read_u16,
+    i32 => read_i32,
+    u32 => read_u32,
+    i64 => read_i64,
+    u64 => read_u64,
+    f64 => read_f64
+);
+
+impl sealed::Sealed for u8 {}
+
+impl FixedSize for u8 {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn read(_endian: Endian, bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+}
+
+/// An array of fixed-size elements, deserialized without copying.
+///
+/// Deserializing a `Vec<T>` decodes the elements one by one into a new allocation. With the D-Bus
+/// format, a `FixedArray` instead borrows the encoded elements from the input and only decodes
+/// them on access, which makes a difference for large arrays such as pixel or audio data. With
+/// other formats, the elements are copied.
+///
+/// If the input happens to be in the native byte order and suitably aligned in memory, the
+/// elements can even be accessed as a slice, through [`FixedArray::as_slice`].
+///
+/// # Example
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, FixedArray, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &vec![1u32, 2, 3])?;
+/// let (array, _): (FixedArray<u32>, _) = encoded.deserialize()?;
+///
+/// assert_eq!(array.len(), 3);
+/// assert_eq!(array.get(1), Some(2));
+/// assert_eq!(array.iter().sum::<u32>(), 6);
+/// assert_eq!(array.to_vec(), [1, 2, 3]);
+/// # Ok::<(), zvariant::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct FixedArray<'a, T> {
+    inner: Inner<'a, T>,
+}
+
+#[derive(Clone)]
+enum Inner<'a, T> {
+    Borrowed { bytes: &'a [u8], endian: Endian },
+    Owned(Vec<T>),
+}
+
+assert_impl_all!(FixedArray<'_, u32>: Send, Sync, Unpin);
+
+impl<'a, T: FixedSize> FixedArray<'a, T> {
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Borrowed { bytes, .. } => bytes.len() / T::SIZE,
+            Inner::Owned(elements) => elements.len(),
+        }
+    }
+
+    /// Whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The element at position `index`, if any.
+    pub fn get(&self, index: usize) -> Option<T> {
+        match &self.inner {
+            Inner::Borrowed { bytes, endian } => {
+                let start = index.checked_mul(T::SIZE)?;
+                let element = bytes.get(start..start.checked_add(T::SIZE)?)?;
+
+                Some(T::read(*endian, element))
+            }
+            Inner::Owned(elements) => elements.get(index).copied(),
+        }
+    }
+
+    /// Iterate over the elements.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = T> + '_ {
+        (0..self.len()).map(|i| self.get(i).expect("index is in bounds"))
+    }
+
+    /// The elements as a slice, if they can be accessed in place.
+    ///
+    /// This is the case if the elements were copied, or if they're encoded in the native byte
+    /// order and their location in memory is suitably aligned for `T`.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match &self.inner {
+            Inner::Borrowed { bytes, endian } => {
+                if *endian != Endian::native() {
+                    return None;
+                }
+                // SAFETY: `T` is a sealed primitive type, valid for any bit pattern.
+                let (prefix, elements, suffix) = unsafe { bytes.align_to::<T>() };
+
+                (prefix.is_empty() && suffix.is_empty()).then_some(elements)
+            }
+            Inner::Owned(elements) => Some(elements),
+        }
+    }
+
+    /// Copy the elements to a `Vec`.
+    pub fn to_vec(&self) -> Vec<T> {
+        match self.as_slice() {
+            Some(elements) => elements.to_vec(),
+            None => self.iter().collect(),
+        }
+    }
+}
+
+impl<T: FixedSize> From<Vec<T>> for FixedArray<'_, T> {
+    fn from(elements: Vec<T>) -> Self {
+        Self {
+            inner: Inner::Owned(elements),
+        }
+    }
+}
+
+impl<T: FixedSize> From<FixedArray<'_, T>> for Vec<T> {
+    fn from(array: FixedArray<'_, T>) -> Self {
+        match array.inner {
+            Inner::Owned(elements) => elements,
+            Inner::Borrowed { .. } => array.to_vec(),
+        }
+    }
+}
+
+impl<T: FixedSize + fmt::Debug> fmt::Debug for FixedArray<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: FixedSize> PartialEq for FixedArray<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: FixedSize> Type for FixedArray<'_, T> {
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
+impl<T> Serialize for FixedArray<'_, T>
+where
+    T: FixedSize + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.as_slice() {
+            Some(elements) => elements.serialize(serializer),
+            None => serializer.collect_seq(self.iter()),
+        }
+    }
+}
+
+impl<'de: 'a, 'a, T> Deserialize<'de> for FixedArray<'a, T>
+where
+    T: FixedSize + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(FIXED_ARRAY_NAME, FixedArrayVisitor(PhantomData))
+    }
+}
+
+struct FixedArrayVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for FixedArrayVisitor<T>
+where
+    T: FixedSize + Deserialize<'de>,
+{
+    type Value = FixedArray<'de, T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an array of `{}`", T::SIGNATURE_STR)
+    }
+
+    // Formats without support for borrowing the elements.
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(FixedArray::from)
+    }
+
+    // The D-Bus format, see `RawFixedArray`.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let element_signature: char = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if element_signature != T::SIGNATURE_CHAR {
+            let unexpected = format!("an array of `{element_signature}`");
+            return Err(de::Error::invalid_type(
+                de::Unexpected::Other(&unexpected),
+                &self,
+            ));
+        }
+        let big_endian: bool = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let bytes: &'de [u8] = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        if bytes.len() % T::SIZE != 0 {
+            return Err(de::Error::invalid_length(bytes.len(), &self));
+        }
+        let endian = if big_endian {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+
+        Ok(FixedArray {
+            inner: Inner::Borrowed { bytes, endian },
+        })
+    }
+}
+
+/// The encoded elements of a [`FixedArray`], passed by the D-Bus deserializer to its visitor.
+///
+/// They're visited as a sequence of the signature of the elements, the byte order (`true` for big
+/// endian) and the bytes.
+pub(crate) struct RawFixedArray<'de> {
+    pub(crate) element_signature: Option<char>,
+    pub(crate) endian: Option<Endian>,
+    pub(crate) bytes: Option<&'de [u8]>,
+}
+
+impl<'de> SeqAccess<'de> for RawFixedArray<'de> {
+    type Error = crate::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> crate::Result<Option<S::Value>>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        use de::value::{BoolDeserializer, BorrowedBytesDeserializer, CharDeserializer};
+
+        if let Some(signature) = self.element_signature.take() {
+            return seed.deserialize(CharDeserializer::new(signature)).map(Some);
+        }
+        if let Some(endian) = self.endian.take() {
+            return seed
+                .deserialize(BoolDeserializer::new(endian == Endian::Big))
+                .map(Some);
+        }
+
+        self.bytes
+            .take()
+            .map(|bytes| seed.deserialize(BorrowedBytesDeserializer::new(bytes)))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedArray;
+    use crate::{serialized::Context, to_bytes, BE, LE};
+
+    #[test]
+    fn fixed_array() {
+        for endian in [LE, BE] {
+            let ctxt = Context::new_dbus(endian, 0);
+            let encoded = to_bytes(ctxt, &(7u8, vec![5u16, 6], "hey", vec![1.5f64, -2.5])).unwrap();
+            let ((byte, shorts, s, doubles), _): (
+                (u8, FixedArray<'_, u16>, &str, FixedArray<'_, f64>),
+                _,
+            ) = encoded.deserialize().unwrap();
+            assert_eq!((byte, s), (7, "hey"));
+            assert_eq!(shorts.len(), 2);
+            assert_eq!(shorts.get(1), Some(6));
+            assert_eq!(shorts.get(2), None);
+            assert_eq!(doubles.to_vec(), [1.5, -2.5]);
+            if endian != crate::Endian::native() {
+                assert!(shorts.as_slice().is_none());
+            }
+
+            // Serializes back to the same bytes.
+            let reencoded = to_bytes(ctxt, &(byte, shorts, s, doubles)).unwrap();
+            assert_eq!(&*reencoded, &*encoded);
+        }
+    }
+
+    #[test]
+    fn fixed_array_signature_mismatch() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &vec![1u32, 2]).unwrap();
+        // The bytes of two `u32` would make four `u16`.
+        let res = encoded.deserialize_for_signature::<_, FixedArray<'_, u16>>("au");
+        assert!(res.is_err());
+        let res = encoded.deserialize_for_signature::<_, FixedArray<'_, i32>>("au");
+        assert!(res.is_err());
+        let (array, _) = encoded
+            .deserialize_for_signature::<_, FixedArray<'_, u32>>("au")
+            .unwrap();
+        assert_eq!(array.get(usize::MAX), None);
+    }
+}
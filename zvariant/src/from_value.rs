@@ -6,7 +6,7 @@ use crate::{
 };
 
 #[cfg(unix)]
-use crate::Fd;
+use crate::{Fd, OwnedFd};
 
 use std::{collections::HashMap, hash::BuildHasher};
 
@@ -124,6 +124,32 @@ value_try_from_ref!(Fd, Fd<'a>);
 #[cfg(unix)]
 value_try_from_ref_try_clone!(Fd, Fd<'a>);
 
+// A borrowed FD can only be turned into an owned one by duplicating it.
+#[cfg(unix)]
+impl TryFrom<Value<'_>> for OwnedFd {
+    type Error = Error;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Fd(Fd::Owned(fd)) => Ok(Fd::Owned(fd).into()),
+            Value::Fd(fd) => fd.try_to_owned().map(Into::into),
+            _ => Err(Error::IncorrectType),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl TryFrom<&Value<'_>> for OwnedFd {
+    type Error = Error;
+
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Fd(fd) => fd.try_to_owned().map(Into::into),
+            _ => Err(Error::IncorrectType),
+        }
+    }
+}
+
 impl TryFrom<&Value<'_>> for String {
     type Error = Error;
 
@@ -145,7 +145,55 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     deserialize_as!(deserialize_tuple_struct(n: &'static str, _l: usize) => deserialize_struct(n, &[]));
     deserialize_as!(deserialize_struct(_n: &'static str, _f: &'static [&'static str]) => deserialize_seq());
     deserialize_as!(deserialize_map => deserialize_seq);
-    deserialize_as!(deserialize_ignored_any => deserialize_any);
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Variants and structures can't be visited generically, as the visitor doesn't know how
+        // many elements they contain, nor that the signature of variants is a string.
+        match self.0.sig_parser.next_char()? {
+            VARIANT_SIGNATURE_CHAR => {
+                self.0.sig_parser.skip_char()?;
+                self.0.parse_padding(VARIANT_ALIGNMENT_GVARIANT)?;
+                let mut value_de = ValueDeserializer::new(self)?;
+                value_de.next_element::<Signature<'_>>()?;
+                value_de.next_element::<de::IgnoredAny>()?;
+
+                return visitor.visit_unit();
+            }
+            STRUCT_SIG_START_CHAR => (),
+            _ => return self.deserialize_any(visitor),
+        }
+
+        let signature = self.0.sig_parser.next_signature()?;
+        let num_fields = signature
+            .slice(1..signature.len() - 1)
+            .complete_types()?
+            .len();
+        let alignment = alignment_for_signature(&signature, self.0.ctxt.format())?;
+        self.0.parse_padding(alignment)?;
+
+        self.0.sig_parser.skip_char()?;
+
+        let start = self.0.pos;
+        let end = self.0.bytes.len();
+        let offset_size = FramingOffsetSize::for_encoded_container(end - start);
+        self.0.container_depths = self.0.container_depths.inc_structure()?;
+        let mut fields = StructureDeserializer {
+            de: &mut *self,
+            start,
+            end,
+            offsets_len: 0,
+            offset_size,
+        };
+        for _ in 0..num_fields {
+            fields.next_element::<de::IgnoredAny>()?;
+        }
+        self.0.container_depths = self.0.container_depths.dec_structure();
+
+        visitor.visit_unit()
+    }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -0,0 +1,177 @@
+//! Opt-in mappings for 128-bit integers.
+//!
+//! D-Bus and GVariant have no 128-bit integer types, so there is no canonical encoding for `i128`
+//! and `u128` and hence, they don't implement [`Type`](crate::Type). This module provides two
+//! mappings that you can choose from through serde's `with` attribute:
+//!
+//! * [`as_tuple`] encodes the value as a `(tt)` structure, the high 64 bits first.
+//! * [`as_bytes`] encodes the value as an `ay` of 16 bytes, in big-endian byte order.
+//!
+//! When deriving [`Type`](crate::Type), use the `signature` field attribute to specify the
+//! signature of the chosen mapping:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use zvariant::{serialized::Context, to_bytes, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, Debug, PartialEq)]
+//! struct Transfer {
+//!     #[serde(with = "zvariant::int128::as_tuple")]
+//!     #[zvariant(signature = "(tt)")]
+//!     id: u128,
+//!     #[serde(with = "zvariant::int128::as_bytes")]
+//!     #[zvariant(signature = "ay")]
+//!     amount: i128,
+//! }
+//!
+//! assert_eq!(Transfer::signature(), "((tt)ay)");
+//!
+//! let transfer = Transfer {
+//!     id: u128::MAX - 1,
+//!     amount: -42,
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &transfer).unwrap();
+//! let decoded: Transfer = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, transfer);
+//! ```
+
+/// A 128-bit integer type.
+///
+/// This trait is sealed and implemented for `i128` and `u128` only.
+pub trait Int128: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    fn to_be_bytes(self) -> [u8; 16];
+    #[doc(hidden)]
+    fn from_be_bytes(bytes: [u8; 16]) -> Self;
+}
+
+macro_rules! int128_impl {
+    ($ty:ty) => {
+        impl sealed::Sealed for $ty {}
+
+        impl Int128 for $ty {
+            fn to_be_bytes(self) -> [u8; 16] {
+                <$ty>::to_be_bytes(self)
+            }
+
+            fn from_be_bytes(bytes: [u8; 16]) -> Self {
+                <$ty>::from_be_bytes(bytes)
+            }
+        }
+    };
+}
+
+int128_impl!(i128);
+int128_impl!(u128);
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Encode a 128-bit integer as a `(tt)` structure, the high 64 bits first.
+///
+/// Use through `#[serde(with = "zvariant::int128::as_tuple")]`.
+pub mod as_tuple {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Int128;
+
+    /// Serialize `value` as a `(tt)` structure.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Int128,
+        S: Serializer,
+    {
+        let bytes = value.to_be_bytes();
+        let (high, low) = bytes.split_at(8);
+        let high = u64::from_be_bytes(high.try_into().expect("8 bytes"));
+        let low = u64::from_be_bytes(low.try_into().expect("8 bytes"));
+
+        (high, low).serialize(serializer)
+    }
+
+    /// Deserialize a `(tt)` structure into a 128-bit integer.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Int128,
+        D: Deserializer<'de>,
+    {
+        let (high, low) = <(u64, u64)>::deserialize(deserializer)?;
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&high.to_be_bytes());
+        bytes[8..].copy_from_slice(&low.to_be_bytes());
+
+        Ok(T::from_be_bytes(bytes))
+    }
+}
+
+/// Encode a 128-bit integer as an `ay` of 16 bytes, in big-endian byte order.
+///
+/// Use through `#[serde(with = "zvariant::int128::as_bytes")]`.
+pub mod as_bytes {
+    use std::{fmt, marker::PhantomData};
+
+    use serde::{
+        de::{self, SeqAccess, Visitor},
+        Deserializer, Serializer,
+    };
+
+    use super::Int128;
+
+    /// Serialize `value` as an `ay`.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Int128,
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&value.to_be_bytes())
+    }
+
+    /// Deserialize an `ay` of 16 bytes into a 128-bit integer.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Int128,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+    }
+
+    struct BytesVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Int128> Visitor<'de> for BytesVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an array of 16 bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            let bytes = v
+                .try_into()
+                .map_err(|_| E::invalid_length(v.len(), &self))?;
+
+            Ok(T::from_be_bytes(bytes))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = [0; 16];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(de::Error::invalid_length(17, &self));
+            }
+
+            Ok(T::from_be_bytes(bytes))
+        }
+    }
+}
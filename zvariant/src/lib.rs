@@ -26,6 +26,9 @@ pub use basic::*;
 mod dict;
 pub use dict::*;
 
+mod fixed_array;
+pub use fixed_array::*;
+
 #[deprecated(since = "4.0.0", note = "Use `serialized::Context` instead")]
 #[doc(hidden)]
 pub type EncodingContext = serialized::Context;
@@ -72,6 +75,7 @@ pub use crate::maybe::*;
 mod optional;
 pub use crate::optional::*;
 
+mod select;
 mod value;
 pub use value::*;
 
@@ -106,7 +110,14 @@ mod signature_parser;
 
 mod container_depths;
 
-pub use zvariant_derive::{DeserializeDict, OwnedValue, SerializeDict, Type, Value};
+pub mod int128;
+
+#[cfg(feature = "rust_decimal")]
+pub mod decimal;
+
+pub use zvariant_derive::{
+    DeserializeBits, DeserializeDict, OwnedValue, SerializeBits, SerializeDict, Type, Value,
+};
 
 // Required for the macros to function within this crate.
 extern crate self as zvariant;
@@ -400,6 +411,24 @@ mod tests {
         fd_value_test!(LE, GVariant, Fd::from(fd), 4, 4, 6);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn owned_fd_from_value() {
+        use crate::{OwnedFd, OwnedValue};
+        use std::os::fd::{AsFd, AsRawFd};
+
+        let stdout = std::io::stdout();
+        let value = Value::from(Fd::from(stdout.as_fd()));
+        // Borrowed FDs get duplicated.
+        let fd = OwnedFd::try_from(&value).unwrap();
+        assert_ne!(fd.as_raw_fd(), stdout.as_raw_fd());
+
+        let value = OwnedValue::try_from(value).unwrap();
+        let fd = OwnedFd::try_from(value).unwrap();
+        assert_ne!(fd.as_raw_fd(), stdout.as_raw_fd());
+        assert!(OwnedFd::try_from(Value::from(42u32)).is_err());
+    }
+
     #[test]
     fn u16_value() {
         let encoded = basic_type_test!(BE, DBus, 0xABBA_u16, 2, u16, 2, U16, 6);
@@ -1067,6 +1096,12 @@ mod tests {
         let decoded: DeserializeValue<'_, Foo> = encoded.deserialize().unwrap().0;
         assert_eq!(decoded.0, foo);
 
+        // Through a generic value.
+        let value = crate::OwnedValue::try_from(SerializeValue(&foo)).unwrap();
+        assert_eq!(value.value_signature(), "(u)");
+        let decoded = DeserializeValue::<'_, Foo>::try_from(value).unwrap();
+        assert_eq!(decoded.0, foo);
+
         // Unit struct should be treated as a 0-sized tuple (the same as unit type)
         #[derive(Serialize, Deserialize, Type, PartialEq, Debug)]
         struct Unit;
@@ -1951,6 +1986,80 @@ mod tests {
         assert_eq!(f, foo);
     }
 
+    #[test]
+    fn data_into_buffer() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let data = to_bytes(ctxt, &42u32).unwrap();
+        let slice = data.slice(2..);
+        let data = data.try_into_buffer().unwrap_err();
+        drop(slice);
+        assert_eq!(data.try_into_buffer().unwrap(), 42u32.to_le_bytes());
+
+        // Borrowed bytes can't be taken.
+        let bytes = [0u8; 4];
+        assert!(Data::new(&bytes[..], ctxt).try_into_buffer().is_err());
+    }
+
+    #[test]
+    fn ignored_any() {
+        // Values can be skipped, whatever their type.
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Full<'s> {
+            id: u32,
+            #[serde(borrow)]
+            value: Value<'s>,
+            pair: (u8, &'s str),
+            props: HashMap<&'s str, Value<'s>>,
+        }
+
+        let full = Full {
+            id: 42,
+            value: Value::Value(Box::new(Value::from((1u8, "nested")))),
+            pair: (2, "pair"),
+            props: HashMap::from([("key", Value::from(3u64))]),
+        };
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &HashMap::from([("full", &full)])).unwrap();
+        let decoded: HashMap<&str, serde::de::IgnoredAny> = encoded
+            .deserialize_for_signature("a{s(uv(ys)a{sv})}")
+            .unwrap()
+            .0;
+        assert!(decoded.contains_key("full"));
+
+        let encoded = to_bytes(ctxt, &(&full, 7u32)).unwrap();
+        let (_, last): (serde::de::IgnoredAny, u32) = encoded
+            .deserialize_for_signature("((uv(ys)a{sv})u)")
+            .unwrap()
+            .0;
+        assert_eq!(last, 7);
+
+        // Arrays are skipped as a whole, including the padding of their elements.
+        let ctxt = Context::new_dbus(LE, 3);
+        let encoded = to_bytes(ctxt, &(vec![vec![1u64]], Vec::<u64>::new(), 7u8)).unwrap();
+        let ((_, _, last), size): ((serde::de::IgnoredAny, serde::de::IgnoredAny, u8), _) =
+            encoded.deserialize_for_signature("(aataty)").unwrap();
+        assert_eq!(last, 7);
+        assert_eq!(size, encoded.len());
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let props = HashMap::from([("key", Value::from(3u64))]);
+            let encoded =
+                to_bytes(ctxt, &HashMap::from([("full", (2u8, "pair", &props))])).unwrap();
+            let decoded: HashMap<&str, serde::de::IgnoredAny> = encoded
+                .deserialize_for_signature("a{s(ysa{sv})}")
+                .unwrap()
+                .0;
+            assert!(decoded.contains_key("full"));
+
+            let encoded = to_bytes(ctxt, &((2u8, "pair", &props), 7u32)).unwrap();
+            let (_, last): (serde::de::IgnoredAny, u32) =
+                encoded.deserialize_for_signature("((ysa{sv})u)").unwrap().0;
+            assert_eq!(last, 7);
+        }
+    }
+
     #[test]
     fn issue_59() {
         // Ensure we don't panic on deserializing tuple of smaller than expected length.
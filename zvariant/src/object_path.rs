@@ -112,6 +112,153 @@ impl<'a> ObjectPath<'a> {
     pub fn into_owned(self) -> ObjectPath<'static> {
         ObjectPath(self.0.into_owned())
     }
+
+    /// The parent of this object path, or `None` if this is the root path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::ObjectPath;
+    ///
+    /// let path = ObjectPath::try_from("/org/example/Object").unwrap();
+    /// assert_eq!(path.parent().unwrap(), "/org/example");
+    /// assert_eq!(path.parent().unwrap().parent().unwrap().parent().unwrap(), "/");
+    /// assert!(ObjectPath::default().parent().is_none());
+    /// ```
+    pub fn parent(&self) -> Option<ObjectPath<'_>> {
+        let path = self.as_str();
+        match path.rfind('/')? {
+            0 if path.len() == 1 => None,
+            0 => Some(ObjectPath::from_str_unchecked("/")),
+            i => Some(ObjectPath::from_str_unchecked(&path[..i])),
+        }
+    }
+
+    /// Create the path of a child of this object path.
+    ///
+    /// `component` must be a single, valid path element: non-empty and only containing ASCII
+    /// alphanumeric characters or `_`. Use [`ObjectPath::escape_component`] to create one from an
+    /// arbitrary string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::ObjectPath;
+    ///
+    /// let path = ObjectPath::try_from("/org/example").unwrap();
+    /// assert_eq!(path.child("Object").unwrap(), "/org/example/Object");
+    /// assert_eq!(ObjectPath::default().child("org").unwrap(), "/org");
+    /// path.child("a/b").unwrap_err();
+    /// path.child("").unwrap_err();
+    /// ```
+    pub fn child(&self, component: &str) -> Result<ObjectPath<'static>> {
+        ensure_correct_component(component.as_bytes())?;
+
+        let parent = self.as_str().trim_end_matches('/');
+
+        Ok(ObjectPath::from_string_unchecked(format!(
+            "{parent}/{component}"
+        )))
+    }
+
+    /// Iterate over the elements of this object path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::ObjectPath;
+    ///
+    /// let path = ObjectPath::try_from("/org/example/Object").unwrap();
+    /// assert_eq!(path.components().collect::<Vec<_>>(), ["org", "example", "Object"]);
+    /// assert_eq!(ObjectPath::default().components().count(), 0);
+    /// ```
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.as_str().split('/').filter(|c| !c.is_empty())
+    }
+
+    /// Escape an arbitrary string into a valid object path element.
+    ///
+    /// All bytes, except ASCII letters and ASCII digits not in the first position, are escaped as
+    /// `_` followed by their value as two lowercase hexadecimal digits. The empty string is
+    /// escaped as a lone `_`. This is the same encoding as `sd_bus_path_encode` from systemd.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::ObjectPath;
+    ///
+    /// assert_eq!(ObjectPath::escape_component("foo-bar"), "foo_2dbar");
+    /// assert_eq!(ObjectPath::escape_component("1st"), "_31st");
+    /// assert_eq!(ObjectPath::escape_component(""), "_");
+    ///
+    /// let path = ObjectPath::try_from("/org/example/Users").unwrap();
+    /// let user = path.child(&ObjectPath::escape_component("jane.doe@example.com")).unwrap();
+    /// assert_eq!(user, "/org/example/Users/jane_2edoe_40example_2ecom");
+    /// ```
+    pub fn escape_component(s: &str) -> String {
+        if s.is_empty() {
+            return "_".to_owned();
+        }
+
+        let mut escaped = String::with_capacity(s.len());
+        for (i, b) in s.bytes().enumerate() {
+            if b.is_ascii_alphabetic() || (i > 0 && b.is_ascii_digit()) {
+                escaped.push(b as char);
+            } else {
+                escaped.push_str(&format!("_{b:02x}"));
+            }
+        }
+
+        escaped
+    }
+
+    /// Unescape an object path element escaped with [`ObjectPath::escape_component`].
+    ///
+    /// # Errors
+    ///
+    /// If `component` contains an invalid escape sequence or the unescaped bytes are not valid
+    /// UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::ObjectPath;
+    ///
+    /// assert_eq!(ObjectPath::unescape_component("foo_2dbar").unwrap(), "foo-bar");
+    /// assert_eq!(ObjectPath::unescape_component("_").unwrap(), "");
+    /// ObjectPath::unescape_component("foo_2").unwrap_err();
+    /// ```
+    pub fn unescape_component(component: &str) -> Result<String> {
+        if component == "_" {
+            return Ok(String::new());
+        }
+
+        let mut bytes = Vec::with_capacity(component.len());
+        let mut iter = component.bytes();
+        while let Some(b) = iter.next() {
+            if b != b'_' {
+                bytes.push(b);
+
+                continue;
+            }
+
+            let hex = [iter.next(), iter.next()];
+            let byte = match hex {
+                [Some(high), Some(low)] => str::from_utf8(&[high, low])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                _ => None,
+            };
+            bytes.push(byte.ok_or_else(|| {
+                <Error as de::Error>::invalid_value(
+                    serde::de::Unexpected::Str(component),
+                    &"`_` followed by 2 hexadecimal digits",
+                )
+            })?);
+        }
+
+        String::from_utf8(bytes).map_err(|e| Error::Utf8(e.utf8_error()))
+    }
 }
 
 impl std::default::Default for ObjectPath<'_> {
@@ -300,6 +447,23 @@ fn ensure_correct_object_path_str(path: &[u8]) -> Result<()> {
     Ok(())
 }
 
+fn ensure_correct_component(component: &[u8]) -> Result<()> {
+    if component.is_empty() {
+        return Err(serde::de::Error::invalid_length(0, &"> 0 character"));
+    }
+
+    match component
+        .iter()
+        .find(|c| !c.is_ascii_alphanumeric() && **c != b'_')
+    {
+        Some(c) => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Char(*c as char),
+            &"an alphanumeric character or `_`",
+        )),
+        None => Ok(()),
+    }
+}
+
 /// Owned [`ObjectPath`](struct.ObjectPath.html)
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, serde::Serialize, Type)]
 pub struct OwnedObjectPath(ObjectPath<'static>);
@@ -396,4 +560,27 @@ mod unit {
         let json_str = "\"/some/path\"";
         serde_json::de::from_reader::<_, OwnedObjectPath>(json_str.as_bytes()).unwrap();
     }
+
+    #[test]
+    fn escape_roundtrip() {
+        for s in ["", "_", "foo", "foo-bar", "0day", "ünïcödé", "a/b.c d"] {
+            let escaped = ObjectPath::escape_component(s);
+            ObjectPath::default().child(&escaped).unwrap();
+            assert_eq!(ObjectPath::unescape_component(&escaped).unwrap(), s);
+        }
+
+        ObjectPath::unescape_component("_zz").unwrap_err();
+        ObjectPath::unescape_component("_ff").unwrap_err();
+    }
+
+    #[test]
+    fn parent_and_child() {
+        let path = ObjectPath::try_from("/org/example").unwrap();
+        let child = path.child("Object").unwrap();
+        assert_eq!(child.parent().unwrap(), path);
+        assert_eq!(
+            child.components().rev().collect::<Vec<_>>(),
+            ["Object", "example", "org"]
+        );
+    }
 }
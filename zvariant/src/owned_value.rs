@@ -83,6 +83,8 @@ ov_try_from!(Str<'static>);
 ov_try_from!(Structure<'static>);
 #[cfg(unix)]
 ov_try_from!(Fd<'static>);
+#[cfg(unix)]
+ov_try_from!(crate::OwnedFd);
 
 ov_try_from_ref!(u8);
 ov_try_from_ref!(bool);
@@ -104,6 +106,8 @@ ov_try_from_ref!(&'a Structure<'a>);
 ov_try_from_ref!(&'a Maybe<'a>);
 #[cfg(unix)]
 ov_try_from_ref!(&'a Fd<'a>);
+#[cfg(unix)]
+ov_try_from_ref!(crate::OwnedFd);
 
 impl<'a, T> TryFrom<OwnedValue> for Vec<T>
 where
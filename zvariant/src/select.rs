@@ -0,0 +1,245 @@
+use std::ops::ControlFlow;
+
+use crate::Value;
+
+/// A step of a [`Value::select`] path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step<'p> {
+    /// The value of a dictionary entry, by key.
+    Key(&'p str),
+    /// An array element or a structure field, by position.
+    Index(usize),
+    /// All array elements, structure fields or dictionary values.
+    Wildcard,
+}
+
+/// Parse `path` into steps, or `None` if it's malformed.
+fn parse(path: &str) -> Option<Vec<Step<'_>>> {
+    let mut steps = vec![];
+    if path.is_empty() {
+        return Some(steps);
+    }
+
+    for segment in path.split('.') {
+        let (name, mut indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        match name {
+            // Only allowed for segments made of indices, e.g. `[0]` or `list.[0]`.
+            "" if indices.is_empty() => return None,
+            "" => (),
+            "*" => steps.push(Step::Wildcard),
+            name => steps.push(Step::Key(name)),
+        }
+
+        while !indices.is_empty() {
+            let end = indices.find(']')?;
+            let index = indices.strip_prefix('[')?.get(..end - 1)?;
+            steps.push(match index {
+                "*" => Step::Wildcard,
+                index => Step::Index(index.parse().ok()?),
+            });
+            indices = &indices[end + 1..];
+        }
+    }
+
+    Some(steps)
+}
+
+/// Whether the dictionary `key` is the one named `name`.
+fn key_matches(key: &Value<'_>, name: &str) -> bool {
+    match key.peel() {
+        Value::Str(key) => key.as_str() == name,
+        Value::ObjectPath(key) => key.as_str() == name,
+        Value::Signature(key) => key.as_str() == name,
+        Value::U8(key) => name.parse() == Ok(*key),
+        Value::I16(key) => name.parse() == Ok(*key),
+        Value::U16(key) => name.parse() == Ok(*key),
+        Value::I32(key) => name.parse() == Ok(*key),
+        Value::U32(key) => name.parse() == Ok(*key),
+        Value::I64(key) => name.parse() == Ok(*key),
+        Value::U64(key) => name.parse() == Ok(*key),
+        Value::Bool(key) => name.parse() == Ok(*key),
+        _ => false,
+    }
+}
+
+/// Call `f` on all the values under `value` matching `steps`, until it breaks.
+fn visit<'v, 'a, F>(value: &'v Value<'a>, steps: &[Step<'_>], f: &mut F) -> ControlFlow<()>
+where
+    F: FnMut(&'v Value<'a>) -> ControlFlow<()>,
+{
+    let value = value.peel();
+    let Some((step, steps)) = steps.split_first() else {
+        return f(value);
+    };
+
+    match (step, value) {
+        (Step::Key(name), Value::Dict(dict)) => {
+            for (key, value) in dict.iter() {
+                if key_matches(key, name) {
+                    visit(value, steps, f)?;
+                }
+            }
+        }
+        (Step::Index(i), Value::Array(array)) => {
+            if let Some(value) = array.inner().get(*i) {
+                visit(value, steps, f)?;
+            }
+        }
+        (Step::Index(i), Value::Structure(structure)) => {
+            if let Some(value) = structure.fields().get(*i) {
+                visit(value, steps, f)?;
+            }
+        }
+        (Step::Wildcard, Value::Array(array)) => {
+            for value in array.inner() {
+                visit(value, steps, f)?;
+            }
+        }
+        (Step::Wildcard, Value::Structure(structure)) => {
+            for value in structure.fields() {
+                visit(value, steps, f)?;
+            }
+        }
+        (Step::Wildcard, Value::Dict(dict)) => {
+            for (_, value) in dict.iter() {
+                visit(value, steps, f)?;
+            }
+        }
+        _ => (),
+    }
+
+    ControlFlow::Continue(())
+}
+
+impl<'a> Value<'a> {
+    /// The first value found at `path` under this value, if any.
+    ///
+    /// `path` is a list of steps separated by `.`, each of which is either:
+    ///
+    /// * a dictionary key, selecting the value of the matching entry. Keys are matched against
+    ///   string, object path, signature, integer and boolean keys.
+    /// * `[N]`, selecting the array element or the structure field at position `N`. It can follow
+    ///   a key directly, as in `list[2]`.
+    /// * `*` or `[*]`, selecting all array elements, structure fields or dictionary values.
+    ///
+    /// Variants are looked through, both along the path and for the returned value. An empty
+    /// path selects this value itself, while a malformed one selects nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let mut device = HashMap::new();
+    /// device.insert("Name", Value::new("Mouse"));
+    /// device.insert("Buttons", Value::new(vec![1u8, 2, 3]));
+    /// let mut props = HashMap::new();
+    /// props.insert("Device", Value::new(device));
+    /// let value = Value::from(props);
+    ///
+    /// assert_eq!(value.select("Device.Name"), Some(&Value::from("Mouse")));
+    /// assert_eq!(value.select("Device.Buttons[2]"), Some(&Value::U8(3)));
+    /// assert_eq!(value.select("Device.Buttons[3]"), None);
+    /// assert_eq!(value.select_all("Device.Buttons[*]").len(), 3);
+    /// ```
+    pub fn select(&self, path: &str) -> Option<&Value<'a>> {
+        let steps = parse(path)?;
+        let mut selected = None;
+        let _ = visit(self, &steps, &mut |value| {
+            selected = Some(value);
+
+            ControlFlow::Break(())
+        });
+
+        selected
+    }
+
+    /// All the values found at `path` under this value.
+    ///
+    /// See [`Value::select`] for the syntax of `path`.
+    pub fn select_all(&self, path: &str) -> Vec<&Value<'a>> {
+        let mut selected = vec![];
+        if let Some(steps) = parse(path) {
+            let _ = visit(self, &steps, &mut |value| {
+                selected.push(value);
+
+                ControlFlow::Continue(())
+            });
+        }
+
+        selected
+    }
+
+    /// This value, with any enclosing variants removed.
+    fn peel(&self) -> &Value<'a> {
+        let mut value = self;
+        while let Value::Value(inner) = value {
+            value = inner;
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{parse, Step};
+    use crate::{StructureBuilder, Value};
+
+    #[test]
+    fn parse_path() {
+        assert_eq!(parse(""), Some(vec![]));
+        assert_eq!(
+            parse("inner.list[2][*].name"),
+            Some(vec![
+                Step::Key("inner"),
+                Step::Key("list"),
+                Step::Index(2),
+                Step::Wildcard,
+                Step::Key("name"),
+            ])
+        );
+        assert_eq!(parse("[0].*"), Some(vec![Step::Index(0), Step::Wildcard]));
+        for malformed in ["a..b", "a.", "a[", "a[x]", "a[1]b", "a[]"] {
+            assert_eq!(parse(malformed), None, "{malformed}");
+        }
+    }
+
+    #[test]
+    fn select() {
+        let item = |name: &'static str, id: u32| {
+            Value::from(
+                StructureBuilder::new()
+                    .add_field(name)
+                    .add_field(Value::new(id))
+                    .build(),
+            )
+        };
+        let mut inner = HashMap::new();
+        inner.insert("list", Value::new(vec![item("a", 1), item("b", 2)]));
+        let mut by_id = HashMap::new();
+        by_id.insert(7u32, "seven");
+        inner.insert("by_id", Value::new(by_id));
+        let mut outer = HashMap::new();
+        outer.insert("inner", Value::new(inner));
+        let value = Value::new(Value::from(outer));
+
+        assert_eq!(value.select(""), Some(value.peel()));
+        assert_eq!(value.select("inner.list[1][0]"), Some(&Value::from("b")));
+        // Variants are looked through.
+        assert_eq!(value.select("inner.list[0][1]"), Some(&Value::U32(1)));
+        assert_eq!(value.select("inner.by_id.7"), Some(&Value::from("seven")));
+        assert_eq!(
+            value.select_all("inner.list[*][1]"),
+            [&Value::U32(1), &Value::U32(2)]
+        );
+        assert_eq!(value.select_all("*.list.*.*").len(), 4);
+        assert_eq!(value.select("inner.list[2]"), None);
+        assert_eq!(value.select("inner.nope"), None);
+        assert_eq!(value.select("inner.list.name"), None);
+        assert!(value.select_all("inner..list").is_empty());
+    }
+}
@@ -1,7 +1,9 @@
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 use static_assertions::assert_impl_all;
 
-use crate::{Signature, Type, Value};
+use crate::{
+    serialized::Context, to_bytes, Error, OwnedValue, Signature, Type, Value, NATIVE_ENDIAN,
+};
 
 /// A wrapper to serialize `T: Type + Serialize` as a value.
 ///
@@ -41,3 +43,18 @@ impl<'a, T: Type + Serialize> Type for SerializeValue<'a, T> {
         Value::signature()
     }
 }
+
+/// Convert `T` to a generic value, through its `Serialize` implementation.
+///
+/// This allows types that implement `Serialize` but can't be converted to a [`Value`] otherwise to
+/// be used where the latter is required, at the cost of encoding and decoding the value.
+impl<'a, T: Type + Serialize> TryFrom<SerializeValue<'a, T>> for OwnedValue {
+    type Error = Error;
+
+    fn try_from(value: SerializeValue<'a, T>) -> Result<Self, Error> {
+        let ctxt = Context::new_dbus(NATIVE_ENDIAN, 0);
+        let encoded = to_bytes(ctxt, &value)?;
+
+        encoded.deserialize().map(|(value, _)| value)
+    }
+}
@@ -121,6 +121,56 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
         }
     }
 
+    /// Take back the whole underlying buffer, so it can be reused.
+    ///
+    /// This only succeeds if the bytes are owned and `self` is the last reference to them (i-e
+    /// there are no clones or slices of `self` left), otherwise `self` is given back. Any file
+    /// descriptors are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::LE;
+    /// use zvariant::to_bytes;
+    /// use zvariant::serialized::Context;
+    ///
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let encoded = to_bytes(ctxt, "hello world").unwrap();
+    /// let slice = encoded.slice(4..);
+    ///
+    /// // The bytes are still referenced by `slice`.
+    /// let encoded = encoded.try_into_buffer().unwrap_err();
+    /// drop(slice);
+    /// let mut buffer = encoded.try_into_buffer().unwrap();
+    /// assert_eq!(&buffer[4..], b"hello world\0");
+    ///
+    /// // The buffer can now be reused, e.g. for encoding another value.
+    /// buffer.clear();
+    /// ```
+    pub fn try_into_buffer(self) -> std::result::Result<Vec<u8>, Self> {
+        let Data {
+            inner,
+            context,
+            range,
+        } = self;
+        match Arc::try_unwrap(inner) {
+            Ok(Inner {
+                bytes: Cow::Owned(buffer),
+                ..
+            }) => Ok(buffer),
+            Ok(inner) => Err(Data {
+                inner: Arc::new(inner),
+                context,
+                range,
+            }),
+            Err(inner) => Err(Data {
+                inner,
+                context,
+                range,
+            }),
+        }
+    }
+
     /// Deserialize `T` from `self`.
     ///
     /// # Examples
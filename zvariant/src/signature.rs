@@ -336,6 +336,44 @@ impl<'a> Signature<'a> {
         }
         Ok(count)
     }
+
+    /// The complete types of the signature, in order.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let s = Signature::try_from("sa{sv}(ii)").unwrap();
+    /// assert_eq!(s.complete_types().unwrap(), ["s", "a{sv}", "(ii)"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the signature is invalid, returns the first error.
+    pub fn complete_types(&self) -> Result<Vec<Signature<'a>>> {
+        SignatureParser::new(self.clone()).collect()
+    }
+
+    /// The first complete type of the signature, and the signature of the remaining ones.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let s = Signature::try_from("a{sv}(ii)s").unwrap();
+    /// let (first, rest) = s.split_first().unwrap();
+    /// assert_eq!(first, "a{sv}");
+    /// assert_eq!(rest, "(ii)s");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the signature is empty or doesn't start with a valid complete type.
+    pub fn split_first(&self) -> Result<(Signature<'a>, Signature<'a>)> {
+        let mut parser = SignatureParser::new(self.clone());
+        let first = parser.parse_next_signature()?;
+        let rest = self.slice(first.len()..);
+
+        Ok((first, rest))
+    }
 }
 
 impl<'a> Debug for Signature<'a> {
@@ -580,6 +618,7 @@ impl std::fmt::Display for OwnedSignature {
 #[cfg(test)]
 mod tests {
     use super::{Bytes, Signature};
+    use crate::{Error, MaxDepthExceeded};
     use std::sync::Arc;
 
     #[test]
@@ -636,4 +675,44 @@ mod tests {
         let sig_b = Signature::from_str_unchecked("(so)u");
         assert_ne!(sig_a, sig_b);
     }
+
+    #[test]
+    fn signature_limits() {
+        let long = "s".repeat(256);
+        assert!(Signature::try_from(&long[..255]).is_ok());
+        assert!(Signature::try_from(long.as_str()).is_err());
+
+        let arrays = format!("{}s", "a".repeat(32));
+        assert!(Signature::try_from(arrays.as_str()).is_ok());
+        assert_eq!(
+            Signature::try_from(format!("a{arrays}")),
+            Err(Error::MaxDepthExceeded(MaxDepthExceeded::Array))
+        );
+
+        let structs = format!("{}s{}", "(".repeat(32), ")".repeat(32));
+        assert!(Signature::try_from(structs.as_str()).is_ok());
+        assert_eq!(
+            Signature::try_from(format!("({structs})")),
+            Err(Error::MaxDepthExceeded(MaxDepthExceeded::Structure))
+        );
+        // Arrays of dict entries only count as arrays.
+        let dicts = format!("{}s{}", "a{s".repeat(32), "}".repeat(32));
+        assert!(Signature::try_from(dicts.as_str()).is_ok());
+    }
+
+    #[test]
+    fn signature_split_first() {
+        let sig = Signature::from_static_str("a{sv}(ii)s").unwrap();
+        assert_eq!(sig.n_complete_types().unwrap(), 3);
+
+        let (first, rest) = sig.split_first().unwrap();
+        assert_eq!(first.as_str(), "a{sv}");
+        assert_eq!(rest.as_str(), "(ii)s");
+        let (first, rest) = rest.split_first().unwrap();
+        assert_eq!(first.as_str(), "(ii)");
+        let (first, rest) = rest.split_first().unwrap();
+        assert_eq!(first.as_str(), "s");
+        assert!(rest.is_empty());
+        assert!(rest.split_first().is_err());
+    }
 }
@@ -1,6 +1,9 @@
 use std::ops::{Bound, RangeBounds};
 
-use crate::{subslice, Basic, ObjectPath, Result, Signature, STRUCT_SIG_END_CHAR};
+use crate::{
+    container_depths::ContainerDepths, subslice, Basic, ObjectPath, Result, Signature,
+    STRUCT_SIG_END_CHAR,
+};
 
 #[cfg(unix)]
 use crate::Fd;
@@ -17,6 +20,8 @@ pub(crate) struct SignatureParser<'s> {
     signature: Signature<'s>,
     pos: usize,
     end: usize,
+    // The depths of the containers enclosing the signature, to enforce the nesting limits.
+    depths: ContainerDepths,
 }
 
 impl<'s> SignatureParser<'s> {
@@ -27,6 +32,7 @@ impl<'s> SignatureParser<'s> {
             signature,
             pos: 0,
             end,
+            depths: ContainerDepths::default(),
         }
     }
 
@@ -179,6 +185,7 @@ impl<'s> SignatureParser<'s> {
     fn next_single_child_type_container_signature(
         &self,
         expected_sig_prefix: char,
+        child_depths: ContainerDepths,
     ) -> Result<Signature<'_>> {
         let signature = self.signature();
 
@@ -203,19 +210,28 @@ impl<'s> SignatureParser<'s> {
         }
 
         // There should be a valid complete signature after 'a' but not more than 1
-        let child_parser = self.slice(1..);
+        let mut child_parser = self.slice(1..);
+        child_parser.depths = child_depths;
         let child_len = child_parser.next_signature()?.len();
 
         Ok(self.signature_slice(0, child_len + 1))
     }
 
     fn next_array_signature(&self) -> Result<Signature<'_>> {
-        self.next_single_child_type_container_signature(ARRAY_SIGNATURE_CHAR)
+        self.next_single_child_type_container_signature(
+            ARRAY_SIGNATURE_CHAR,
+            self.depths.inc_array()?,
+        )
     }
 
     #[cfg(feature = "gvariant")]
     fn next_maybe_signature(&self) -> Result<Signature<'_>> {
-        self.next_single_child_type_container_signature(MAYBE_SIGNATURE_CHAR)
+        #[cfg(not(feature = "option-as-array"))]
+        let child_depths = self.depths.inc_maybe()?;
+        #[cfg(feature = "option-as-array")]
+        let child_depths = self.depths.inc_array()?;
+
+        self.next_single_child_type_container_signature(MAYBE_SIGNATURE_CHAR, child_depths)
     }
 
     fn next_structure_signature(&self) -> Result<Signature<'_>> {
@@ -246,6 +262,7 @@ impl<'s> SignatureParser<'s> {
 
         let mut fields_sig_len = 0;
         let mut fields_parser = self.slice(1..);
+        fields_parser.depths = self.depths.inc_structure()?;
         while !fields_parser.done() && fields_parser.next_char()? != STRUCT_SIG_END_CHAR {
             fields_sig_len += fields_parser.parse_next_signature()?.len();
         }
@@ -0,0 +1,58 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error};
+
+use crate::utils::*;
+
+pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let name = match input.data {
+        Data::Struct(_) => input.ident,
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
+
+    let zv = zvariant_path();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
+        #where_clause
+        {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: #zv::export::serde::ser::Serializer,
+            {
+                #zv::export::serde::ser::Serialize::serialize(&self.bits(), serializer)
+            }
+        }
+    })
+}
+
+pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let name = match input.data {
+        Data::Struct(_) => input.ident,
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
+
+    let zv = zvariant_path();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, syn::parse_quote!('de));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::de::Deserialize<'de> for #name #ty_generics
+        #where_clause
+        {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: #zv::export::serde::de::Deserializer<'de>,
+            {
+                let bits = #zv::export::serde::de::Deserialize::deserialize(deserializer)?;
+
+                ::std::result::Result::Ok(Self::from_bits_retain(bits))
+            }
+        }
+    })
+}
@@ -5,7 +5,7 @@ use zvariant_utils::{case, macros};
 
 use crate::utils::*;
 
-fn dict_name_for_field(
+pub(crate) fn dict_name_for_field(
     f: &Field,
     rename_attr: Option<String>,
     rename_all_attr: Option<&str>,
@@ -30,36 +30,77 @@ fn dict_name_for_field(
     }
 }
 
+/// Whether the values of the dictionary are variants, i.e. the signature is `a{sv}` or alike.
+///
+/// Otherwise all the values have the same type, e.g the `a{sa{sv}}` interfaces dictionary of
+/// `GetManagedObjects` reply, and the fields are (de)serialized as is.
+fn values_are_variants(signature: Option<&str>) -> bool {
+    match signature {
+        None | Some("dict") => true,
+        Some(signature) => dict_value_signature(signature) == Some("v"),
+    }
+}
+
+// The signature of the values of the dictionary signature `signature`, e.g `v` for `a{sv}`.
+fn dict_value_signature(signature: &str) -> Option<&str> {
+    signature.strip_prefix("a{")?.strip_suffix('}')?.get(1..)
+}
+
 pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let (name, data) = match input.data {
         Data::Struct(data) => (input.ident, data),
         _ => return Err(Error::new(input.span(), "only structs supported")),
     };
 
-    let StructAttributes { rename_all, .. } = StructAttributes::parse(&input.attrs)?;
+    let StructAttributes {
+        rename_all,
+        signature,
+        ..
+    } = StructAttributes::parse(&input.attrs)?;
+    let variants = values_are_variants(signature.as_deref());
 
     let zv = zvariant_path();
     let mut entries = quote! {};
     let mut num_entries: usize = 0;
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes { rename, .. } = FieldAttributes::parse(&f.attrs)?;
+        let serde_attrs = SerdeFieldAttributes::parse(&f.attrs)?;
+        if serde_attrs.skip_serializing {
+            continue;
+        }
 
         let name = &f.ident;
-        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
+        let dict_name =
+            dict_name_for_field(f, rename.or(serde_attrs.rename), rename_all.as_deref())?;
 
         let is_option = macros::ty_is_option(&f.ty);
 
-        let e = if is_option {
-            quote! {
+        let e = match (is_option, variants) {
+            (true, true) => quote! {
                 if self.#name.is_some() {
                     map.serialize_entry(#dict_name, &#zv::SerializeValue(self.#name.as_ref().unwrap()))?;
                 }
-            }
-        } else {
-            quote! {
+            },
+            (true, false) => quote! {
+                if self.#name.is_some() {
+                    map.serialize_entry(#dict_name, self.#name.as_ref().unwrap())?;
+                }
+            },
+            (false, true) => quote! {
                 map.serialize_entry(#dict_name, &#zv::SerializeValue(&self.#name))?;
-            }
+            },
+            (false, false) => quote! {
+                map.serialize_entry(#dict_name, &self.#name)?;
+            },
+        };
+        let e = match serde_attrs.skip_serializing_if {
+            Some(skip_if) => quote! {
+                if !#skip_if(&self.#name) {
+                    #e
+                }
+            },
+            None => e,
         };
 
         entries.extend(e);
@@ -99,36 +140,88 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
     let StructAttributes {
         rename_all,
         deny_unknown_fields,
-        ..
+        signature,
     } = StructAttributes::parse(&input.attrs)?;
+    let variants = values_are_variants(signature.as_deref());
+    // Like with serde, a `default` attribute on the structure provides the values of all the
+    // missing and skipped fields, from a default instance of the structure.
+    let struct_default = SerdeFieldAttributes::parse(&input.attrs)?
+        .default
+        .map(|default| match default {
+            Some(path) => quote! { #path() },
+            None => quote! { <Self::Value as ::std::default::Default>::default() },
+        });
 
     let visitor = format_ident!("{}Visitor", name);
     let zv = zvariant_path();
     let mut fields = Vec::new();
     let mut req_fields = Vec::new();
+    let mut skipped_fields = Vec::new();
     let mut dict_names = Vec::new();
     let mut entries = Vec::new();
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes { rename, .. } = FieldAttributes::parse(&f.attrs)?;
+        let serde_attrs = SerdeFieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
-        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
+        if serde_attrs.skip_deserializing {
+            let default = match (&serde_attrs.default, &struct_default) {
+                (None, Some(_)) => quote! { __default.#name },
+                _ => serde_attrs.default_value(),
+            };
+            skipped_fields.push(quote! { #name: #default });
+
+            continue;
+        }
+        let dict_name = dict_name_for_field(
+            f,
+            rename.or(serde_attrs.rename.clone()),
+            rename_all.as_deref(),
+        )?;
 
         let is_option = macros::ty_is_option(&f.ty);
 
-        entries.push(quote! {
-            #dict_name => {
-                // FIXME: add an option about strict parsing (instead of silently skipping the field)
-                #name = access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+        entries.push(if variants {
+            quote! {
+                #dict_name => {
+                    // FIXME: add an option about strict parsing (instead of silently skipping the field)
+                    #name = access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+                }
+            }
+        } else {
+            quote! {
+                #dict_name => {
+                    #name = ::std::option::Option::Some(access.next_value()?);
+                }
             }
         });
 
         dict_names.push(dict_name);
         fields.push(name);
 
-        if !is_option {
-            req_fields.push(name);
+        if is_option && serde_attrs.default.is_none() && struct_default.is_some() {
+            req_fields.push(quote! {
+                let #name = #name.or(__default.#name);
+            });
+        } else if !is_option {
+            let missing = match (&serde_attrs.default, &struct_default) {
+                (Some(_), _) => serde_attrs.default_value(),
+                (None, Some(_)) => quote! { __default.#name },
+                (None, None) => quote! {
+                    return ::std::result::Result::Err(
+                        <M::Error as #zv::export::serde::de::Error>::missing_field(
+                            ::std::stringify!(#name),
+                        ),
+                    )
+                },
+            };
+            req_fields.push(quote! {
+                let #name = match #name {
+                    ::std::option::Option::Some(val) => val,
+                    ::std::option::Option::None => #missing,
+                };
+            });
         }
     }
 
@@ -143,15 +236,27 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                 );
             }
         }
-    } else {
+    } else if variants {
         quote! {
             unknown => {
                 let _ = access.next_value::<#zv::Value>();
             }
         }
+    } else {
+        quote! {
+            unknown => {
+                access.next_value::<#zv::export::serde::de::IgnoredAny>()?;
+            }
+        }
     };
     entries.push(fallback);
 
+    let struct_default_value = struct_default.map(|default| {
+        quote! {
+            let __default: Self::Value = #default;
+        }
+    });
+
     let (_, ty_generics, _) = input.generics.split_for_impl();
     let mut generics = input.generics.clone();
     let def = syn::LifetimeDef {
@@ -201,17 +306,10 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             }
                         }
 
-                        #(let #req_fields = if let ::std::option::Option::Some(val) = #req_fields {
-                            val
-                        } else {
-                            return ::std::result::Result::Err(
-                                <M::Error as #zv::export::serde::de::Error>::missing_field(
-                                    ::std::stringify!(#req_fields),
-                                ),
-                            );
-                        };)*
-
-                        ::std::result::Result::Ok(#name { #(#fields),* })
+                        #struct_default_value
+                        #(#req_fields)*
+
+                        ::std::result::Result::Ok(#name { #(#fields,)* #(#skipped_fields,)* })
                     }
                 }
 
@@ -14,6 +14,7 @@
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
+mod bits;
 mod dict;
 mod r#type;
 mod utils;
@@ -155,6 +156,43 @@ mod value;
 /// assert_eq!(decoded, StrEnum::Variant2);
 /// ```
 ///
+/// The `signature` attribute can also be specified on individual fields, to override the
+/// signature of the field's type. This is useful when the field is (de)serialized through serde's
+/// `with` attribute, or its type doesn't implement [`Type`] at all:
+///
+/// ```
+/// use zvariant::Type;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize, Type)]
+/// struct Account {
+///     name: String,
+///     #[serde(with = "zvariant::int128::as_tuple")]
+///     #[zvariant(signature = "(tt)")]
+///     balance: i128,
+/// }
+///
+/// assert_eq!(Account::signature(), "(s(tt))");
+/// ```
+///
+/// Fields skipped with serde's `skip` attribute are not part of the signature. Since the fields of
+/// a structure are encoded by position, skipping a field only when serializing or deserializing,
+/// or based on a condition (`skip_serializing_if`), results in a compile error:
+///
+/// ```
+/// use zvariant::Type;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize, Type)]
+/// struct Session {
+///     id: u32,
+///     #[serde(skip)]
+///     cached_name: Option<String>,
+/// }
+///
+/// assert_eq!(Session::signature(), "(u)");
+/// ```
+///
 /// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
@@ -225,8 +263,28 @@ pub fn type_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Serde attributes
+///
+/// The serde `rename`, `skip`, `skip_serializing` and `skip_serializing_if` field attributes are
+/// respected as well. The `zvariant` `rename` attribute takes precedence over the serde one:
+///
+/// ```
+/// use zvariant::{SerializeDict, Type};
+///
+/// #[derive(SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// struct Struct {
+///     #[serde(rename = "Name")]
+///     name: String,
+///     #[serde(skip_serializing_if = "Vec::is_empty")]
+///     tags: Vec<String>,
+///     #[serde(skip)]
+///     cache: u32,
+/// }
+/// ```
+///
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
-#[proc_macro_derive(SerializeDict, attributes(zvariant))]
+#[proc_macro_derive(SerializeDict, attributes(zvariant, serde))]
 pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
     dict::expand_serialize_derive(input)
@@ -293,8 +351,67 @@ pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Serde attributes
+///
+/// The serde `rename`, `skip`, `skip_deserializing` and `default` field attributes are respected
+/// as well, and so is the `default` attribute on the structure, which provides the value of any
+/// field without one of its own. Skipped fields and missing fields with a default get their
+/// default value, instead of resulting in an error:
+///
+/// ```
+/// use zvariant::{DeserializeDict, Type};
+///
+/// #[derive(DeserializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// ##[allow(unused)]
+/// struct Struct {
+///     #[serde(rename = "Name")]
+///     name: String,
+///     #[serde(default)]
+///     tags: Vec<String>,
+///     #[serde(default = "default_priority")]
+///     priority: u8,
+///     #[serde(skip)]
+///     cache: u32,
+/// }
+///
+/// fn default_priority() -> u8 {
+///     5
+/// }
+/// ```
+///
+/// # Dictionaries of other values
+///
+/// If the signature of the struct is a dictionary whose values are not variants, such as
+/// `a{sa{sv}}`, the fields are deserialized as is and unknown entries are skipped. This allows
+/// deserializing the reply of `org.freedesktop.DBus.ObjectManager.GetManagedObjects` into typed
+/// data in one go:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{DeserializeDict, OwnedObjectPath, Type};
+///
+/// #[derive(DeserializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// ##[allow(unused)]
+/// struct DeviceProps {
+///     #[zvariant(rename = "Name")]
+///     name: String,
+/// }
+///
+/// #[derive(DeserializeDict, Type)]
+/// #[zvariant(signature = "a{sa{sv}}")]
+/// ##[allow(unused)]
+/// struct Interfaces {
+///     #[zvariant(rename = "org.example.Device")]
+///     device: Option<DeviceProps>,
+/// }
+///
+/// type Objects = HashMap<OwnedObjectPath, Interfaces>;
+/// ```
+///
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
-#[proc_macro_derive(DeserializeDict, attributes(zvariant))]
+#[proc_macro_derive(DeserializeDict, attributes(zvariant, serde))]
 pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
     dict::expand_deserialize_derive(input)
@@ -302,6 +419,76 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Adds [`Serialize`] implementation to bit flags types to be serialized as their integer bits.
+///
+/// D-Bus APIs commonly take flags as a bitmask in an integer argument. This macro allows you to use
+/// a type-safe flags type (e.g one created with the [`bitflags`] crate) in place of the raw integer.
+/// The deriving type must provide a `bits(&self)` method returning the underlying integer, which is
+/// what gets serialized. Combine it with [`Type`] derive and a `signature` attribute matching the
+/// integer type:
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, DeserializeBits, SerializeBits, Type, LE};
+///
+/// #[derive(SerializeBits, DeserializeBits, Type, Debug, PartialEq, Clone, Copy)]
+/// #[zvariant(signature = "u")]
+/// struct RequestNameFlags(u32);
+///
+/// impl RequestNameFlags {
+///     const ALLOW_REPLACEMENT: Self = Self(0x1);
+///     const REPLACE_EXISTING: Self = Self(0x2);
+///
+///     fn bits(&self) -> u32 {
+///         self.0
+///     }
+///
+///     fn from_bits_retain(bits: u32) -> Self {
+///         Self(bits)
+///     }
+/// }
+///
+/// assert_eq!(RequestNameFlags::signature(), "u");
+/// let flags = RequestNameFlags(
+///     RequestNameFlags::ALLOW_REPLACEMENT.bits() | RequestNameFlags::REPLACE_EXISTING.bits(),
+/// );
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &flags).unwrap();
+/// assert_eq!(encoded.len(), 4);
+/// let decoded: u32 = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, 0x3);
+/// let decoded: RequestNameFlags = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, flags);
+/// ```
+///
+/// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`Type`]: derive.Type.html
+/// [`bitflags`]: https://crates.io/crates/bitflags
+#[proc_macro_derive(SerializeBits, attributes(zvariant))]
+pub fn serialize_bits_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    bits::expand_serialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Adds [`Deserialize`] implementation to bit flags types to be deserialized from their integer
+/// bits.
+///
+/// This is the counterpart of [`SerializeBits`]. The deriving type must provide a
+/// `from_bits_retain` constructor, taking the underlying integer. Unknown bits are retained since
+/// services are free to define new flags in the future.
+///
+/// See [`SerializeBits`] documentation for an example.
+///
+/// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+#[proc_macro_derive(DeserializeBits, attributes(zvariant))]
+pub fn deserialize_bits_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    bits::expand_deserialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Implements conversions for your type to/from [`Value`].
 ///
 /// Implements `TryFrom<Value>` and `Into<Value>` for your type.
@@ -406,9 +593,38 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// [`Type`] for more details and an example use. Please note that this macro can only handle
 /// `dict` or `a{sv}` values. All other values will be ignored.
 ///
+/// The dictionary keys can be customized through the `rename` field attribute and the
+/// `rename_all` container attribute, the same way as for [`SerializeDict`] and
+/// [`DeserializeDict`]:
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use zvariant::{OwnedValue, Type, Value};
+/// #
+/// #[derive(Clone, Debug, PartialEq, Type, Value, OwnedValue)]
+/// #[zvariant(signature = "dict", rename_all = "PascalCase")]
+/// struct Properties {
+///     state: u32,
+///     #[zvariant(rename = "ID")]
+///     id: String,
+/// }
+///
+/// let props = Properties {
+///     state: 2,
+///     id: String::from("first"),
+/// };
+/// let value = Value::from(props.clone());
+/// let dict = HashMap::<String, Value>::try_from(value.try_clone().unwrap()).unwrap();
+/// assert_eq!(dict["State"], Value::from(2u32));
+/// assert_eq!(dict["ID"], Value::from("first"));
+/// assert_eq!(Properties::try_from(value).unwrap(), props);
+/// ```
+///
 /// [`Value`]: https://docs.rs/zvariant/latest/zvariant/enum.Value.html
 /// [`Type`]: derive.Type.html#custom-types
-#[proc_macro_derive(Value)]
+/// [`SerializeDict`]: derive.SerializeDict.html
+/// [`DeserializeDict`]: derive.DeserializeDict.html
+#[proc_macro_derive(Value, attributes(zvariant))]
 pub fn value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::Value)
@@ -423,7 +639,7 @@ pub fn value_macro_derive(input: TokenStream) -> TokenStream {
 /// See [`Value`] documentation for examples.
 ///
 /// [`OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
-#[proc_macro_derive(OwnedValue)]
+#[proc_macro_derive(OwnedValue, attributes(zvariant))]
 pub fn owned_value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::OwnedValue)
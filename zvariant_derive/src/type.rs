@@ -16,6 +16,9 @@ pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
             _ => signature,
         };
 
+        zvariant_utils::signature::validate(&signature)
+            .map_err(|e| Error::new(ast.ident.span(), e))?;
+
         // Signature already provided, easy then!
         let name = ast.ident;
         let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
@@ -23,12 +26,8 @@ pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
             impl #impl_generics #zv::Type for #name #ty_generics #where_clause {
                 #[inline]
                 fn signature() -> #zv::Signature<'static> {
-                    // FIXME: Would be nice if we had a parsed `Signature` in the macro code already so
-                    // it's checked at the build time but currently that's not easily possible w/o
-                    // zvariant_derive requiring zvaraint and we don't want it as it creates a cyclic
-                    // dep. Maybe we can find a way to share the `Signature` type between the two
-                    // crates?
-                    #zv::Signature::from_static_str(#signature).unwrap()
+                    // Validated when the macro was expanded.
+                    #zv::Signature::from_static_str_unchecked(#signature)
                 }
             }
         });
@@ -65,7 +64,7 @@ fn impl_struct(
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let signature = signature_for_struct(&fields, zv, false);
+    let signature = signature_for_struct(&fields, zv, false)?;
 
     Ok(quote! {
         impl #impl_generics #zv::Type for #name #ty_generics #where_clause {
@@ -81,25 +80,58 @@ fn signature_for_struct(
     fields: &Fields,
     zv: &TokenStream,
     insert_enum_variant: bool,
-) -> TokenStream {
-    let field_types = fields.iter().map(|field| field.ty.to_token_stream());
+) -> Result<TokenStream, Error> {
+    let mut field_signatures = vec![];
+    for field in fields {
+        let serde_attrs = SerdeFieldAttributes::parse(&field.attrs)?;
+        if serde_attrs.skip() {
+            // Skipped fields are not part of the encoding at all.
+            continue;
+        }
+        if serde_attrs.skip_serializing
+            || serde_attrs.skip_deserializing
+            || serde_attrs.skip_serializing_if.is_some()
+        {
+            return Err(Error::new(
+                field.span(),
+                "skipping a field in only one direction or conditionally would break the \
+                 structure signature, use `#[serde(skip)]` instead",
+            ));
+        }
+        let FieldAttributes { signature, .. } = FieldAttributes::parse(&field.attrs)?;
+
+        field_signatures.push(match signature {
+            Some(signature) => {
+                zvariant_utils::signature::validate(&signature)
+                    .map_err(|e| Error::new(field.span(), e))?;
+
+                quote! { #zv::Signature::from_static_str_unchecked(#signature) }
+            }
+            None => {
+                let ty = field.ty.to_token_stream();
+
+                quote! { <#ty as #zv::Type>::signature() }
+            }
+        });
+    }
     let new_type = match fields {
         Fields::Named(_) => false,
-        Fields::Unnamed(_) if field_types.len() == 1 => true,
+        // Like serde, only consider the declared fields here.
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => true,
         Fields::Unnamed(_) => false,
         Fields::Unit => panic!("signature_for_struct must not be called for unit fields"),
     };
     let inner_impl = if new_type {
         quote! {
             #(
-                <#field_types as #zv::Type>::signature()
+                #field_signatures
              )*
         }
     } else {
         quote! {
             let mut s = <::std::string::String as ::std::convert::From<_>>::from("(");
             #(
-                s.push_str(<#field_types as #zv::Type>::signature().as_str());
+                s.push_str(#field_signatures.as_str());
             )*
             s.push_str(")");
 
@@ -107,7 +139,7 @@ fn signature_for_struct(
         }
     };
 
-    if insert_enum_variant {
+    Ok(if insert_enum_variant {
         quote! {
             let inner_signature = {
                 #inner_impl
@@ -121,7 +153,7 @@ fn signature_for_struct(
         }
     } else {
         inner_impl
-    }
+    })
 }
 
 fn impl_unit_struct(
@@ -208,7 +240,6 @@ fn signature_for_variant(
 
             Ok(quote! { <#repr as #zv::Type>::signature() })
         }
-        Fields::Named(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
-        Fields::Unnamed(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
+        Fields::Named(_) | Fields::Unnamed(_) => signature_for_struct(&variant.fields, zv, true),
     }
 }
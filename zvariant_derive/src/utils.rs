@@ -21,5 +21,78 @@ def_attrs! {
     /// Attributes defined on structures.
     pub StructAttributes("struct") { signature str, rename_all str, deny_unknown_fields none };
     /// Attributes defined on fields.
-    pub FieldAttributes("field") { rename str };
+    pub FieldAttributes("field") { rename str, signature str };
+}
+
+/// The serde attributes of a field that affect its encoding.
+///
+/// Other serde attributes are ignored.
+#[derive(Default)]
+pub struct SerdeFieldAttributes {
+    pub rename: Option<String>,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    pub skip_serializing_if: Option<syn::ExprPath>,
+    /// `Some(None)` for `#[serde(default)]` and `Some(Some(path))` for `#[serde(default = "path")]`.
+    pub default: Option<Option<syn::ExprPath>>,
+}
+
+impl SerdeFieldAttributes {
+    pub fn parse(attrs: &[syn::Attribute]) -> Result<Self, syn::Error> {
+        use syn::{Lit, Meta, NestedMeta};
+
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|a| a.path.is_ident("serde")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                _ => continue,
+            };
+
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) => {
+                        if path.is_ident("skip") {
+                            parsed.skip_serializing = true;
+                            parsed.skip_deserializing = true;
+                        } else if path.is_ident("skip_serializing") {
+                            parsed.skip_serializing = true;
+                        } else if path.is_ident("skip_deserializing") {
+                            parsed.skip_deserializing = true;
+                        } else if path.is_ident("default") {
+                            parsed.default = Some(None);
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) => {
+                        let value = match &nv.lit {
+                            Lit::Str(value) => value,
+                            _ => continue,
+                        };
+                        if nv.path.is_ident("rename") {
+                            parsed.rename = Some(value.value());
+                        } else if nv.path.is_ident("skip_serializing_if") {
+                            parsed.skip_serializing_if = Some(value.parse()?);
+                        } else if nv.path.is_ident("default") {
+                            parsed.default = Some(Some(value.parse()?));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// If the field is skipped in both directions.
+    pub fn skip(&self) -> bool {
+        self.skip_serializing && self.skip_deserializing
+    }
+
+    /// The expression for the default value of the field.
+    pub fn default_value(&self) -> TokenStream {
+        match &self.default {
+            Some(Some(path)) => quote! { #path() },
+            _ => quote! { ::std::default::Default::default() },
+        }
+    }
 }
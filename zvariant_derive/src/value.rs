@@ -5,7 +5,7 @@ use syn::{
     Lifetime, LifetimeDef,
 };
 
-use crate::utils::*;
+use crate::{dict::dict_name_for_field, utils::*};
 
 pub enum ValueType {
     Value,
@@ -18,7 +18,11 @@ pub fn expand_derive(ast: DeriveInput, value_type: ValueType) -> Result<TokenStr
     match &ast.data {
         Data::Struct(ds) => match &ds.fields {
             Fields::Named(_) | Fields::Unnamed(_) => {
-                let StructAttributes { signature, .. } = StructAttributes::parse(&ast.attrs)?;
+                let StructAttributes {
+                    signature,
+                    rename_all,
+                    ..
+                } = StructAttributes::parse(&ast.attrs)?;
                 let signature = signature.map(|signature| match signature.as_str() {
                     "dict" => "a{sv}".to_string(),
                     _ => signature,
@@ -30,6 +34,7 @@ pub fn expand_derive(ast: DeriveInput, value_type: ValueType) -> Result<TokenStr
                     ast.generics,
                     &ds.fields,
                     signature,
+                    rename_all.as_deref(),
                     &zv,
                 )
             }
@@ -49,6 +54,7 @@ fn impl_struct(
     generics: Generics,
     fields: &Fields,
     signature: Option<String>,
+    rename_all: Option<&str>,
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let statc_lifetime = LifetimeDef::new(Lifetime::new("'static", Span::call_site()));
@@ -123,32 +129,44 @@ fn impl_struct(
                 .map(|field| field.ident.to_token_stream())
                 .collect();
             let (from_value_impl, into_value_impl) = match signature {
-                Some(signature) if signature == "a{sv}" => (
-                    // User wants the type to be encoded as a dict.
-                    // FIXME: Not the most efficient implementation.
-                    quote! {
-                        let mut fields = <::std::collections::HashMap::<::std::string::String, #zv::Value>>::try_from(value)?;
+                Some(signature) if signature == "a{sv}" => {
+                    let dict_names = fields
+                        .iter()
+                        .map(|field| {
+                            let FieldAttributes { rename, .. } =
+                                FieldAttributes::parse(&field.attrs)?;
 
-                        ::std::result::Result::Ok(Self {
-                            #(
-                                #field_names:
-                                    fields
-                                        .remove(stringify!(#field_names))
-                                        .ok_or_else(|| #zv::Error::IncorrectType)?
-                                        .downcast()?
-                            ),*
+                            dict_name_for_field(field, rename, rename_all)
                         })
-                    },
-                    quote! {
-                        let mut fields = ::std::collections::HashMap::new();
-                        #(
-                            fields.insert(stringify!(#field_names), #zv::Value::from(s.#field_names));
-                        )*
+                        .collect::<Result<Vec<_>, Error>>()?;
 
-                        <#value_type>::#into_value_method(#zv::Value::from(fields))
-                            #into_value_error_transform
-                    },
-                ),
+                    (
+                        // User wants the type to be encoded as a dict.
+                        // FIXME: Not the most efficient implementation.
+                        quote! {
+                            let mut fields = <::std::collections::HashMap::<::std::string::String, #zv::Value>>::try_from(value)?;
+
+                            ::std::result::Result::Ok(Self {
+                                #(
+                                    #field_names:
+                                        fields
+                                            .remove(#dict_names)
+                                            .ok_or_else(|| #zv::Error::IncorrectType)?
+                                            .downcast()?
+                                ),*
+                            })
+                        },
+                        quote! {
+                            let mut fields = ::std::collections::HashMap::new();
+                            #(
+                                fields.insert(#dict_names, #zv::Value::from(s.#field_names));
+                            )*
+
+                            <#value_type>::#into_value_method(#zv::Value::from(fields))
+                                #into_value_error_transform
+                        },
+                    )
+                }
                 Some(_) | None => (
                     quote! {
                         let mut fields = #zv::Structure::try_from(value)?.into_fields();
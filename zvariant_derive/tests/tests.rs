@@ -82,3 +82,305 @@ fn derive_dict() {
 
     assert_eq!(Test::signature(), "a{sv}")
 }
+
+#[test]
+fn derive_bits() {
+    use zvariant::{DeserializeBits, SerializeBits};
+
+    #[derive(SerializeBits, DeserializeBits, Type, Debug, PartialEq)]
+    #[zvariant(signature = "u")]
+    struct Flags(u32);
+
+    impl Flags {
+        fn bits(&self) -> u32 {
+            self.0
+        }
+
+        fn from_bits_retain(bits: u32) -> Self {
+            Self(bits)
+        }
+    }
+
+    assert_eq!(Flags::signature(), u32::signature());
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &Flags(0x5)).unwrap();
+    let bits: u32 = serialized.deserialize().unwrap().0;
+    assert_eq!(bits, 0x5);
+    let flags: Flags = serialized.deserialize().unwrap().0;
+    assert_eq!(flags, Flags(0x5));
+}
+
+#[test]
+fn derive_field_signature() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Type, Debug, PartialEq)]
+    struct Ids {
+        #[serde(with = "zvariant::int128::as_tuple")]
+        #[zvariant(signature = "(tt)")]
+        signed: i128,
+        #[serde(with = "zvariant::int128::as_bytes")]
+        #[zvariant(signature = "ay")]
+        unsigned: u128,
+    }
+
+    #[derive(Deserialize, Serialize, Type)]
+    struct NewType(
+        #[serde(with = "zvariant::int128::as_tuple")]
+        #[zvariant(signature = "(tt)")]
+        u128,
+    );
+
+    assert_eq!(Ids::signature(), "((tt)ay)");
+    assert_eq!(NewType::signature(), "(tt)");
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &NewType((1 << 64) | 2)).unwrap();
+    let halves: (u64, u64) = serialized.deserialize().unwrap().0;
+    assert_eq!(halves, (1, 2));
+
+    let ids = Ids {
+        signed: i128::MIN + 1,
+        unsigned: u128::MAX - 1,
+    };
+    let serialized = zvariant::to_bytes(ctxt, &ids).unwrap();
+    let deserialized: Ids = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized, ids);
+}
+
+#[test]
+fn derive_serde_field_attributes() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Type, Debug, PartialEq)]
+    struct Session {
+        id: u32,
+        #[serde(skip)]
+        cached_name: Option<String>,
+        #[serde(rename = "Active", default)]
+        active: bool,
+    }
+
+    #[derive(Deserialize, Serialize, Type)]
+    struct NotNewType(u32, #[serde(skip)] u8);
+
+    assert_eq!(Session::signature(), "(ub)");
+    assert_eq!(NotNewType::signature(), "(u)");
+
+    let session = Session {
+        id: 7,
+        cached_name: Some("ignored".to_string()),
+        active: true,
+    };
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &session).unwrap();
+    let deserialized: Session = serialized.deserialize().unwrap().0;
+    assert_eq!(
+        deserialized,
+        Session {
+            cached_name: None,
+            ..session
+        }
+    );
+
+    fn default_priority() -> u8 {
+        5
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type)]
+    #[zvariant(signature = "a{sv}")]
+    struct Task {
+        #[serde(rename = "Name")]
+        name: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default = "default_priority")]
+        priority: u8,
+        #[serde(skip)]
+        cache: u32,
+        #[serde(skip_deserializing)]
+        generation: u32,
+    }
+
+    let task = Task {
+        name: "build".to_string(),
+        tags: vec![],
+        priority: 1,
+        cache: 42,
+        generation: 3,
+    };
+    let serialized = zvariant::to_bytes(ctxt, &task).unwrap();
+    let dict: HashMap<String, OwnedValue> = serialized.deserialize().unwrap().0;
+    let mut keys: Vec<_> = dict.keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, ["Name", "generation", "priority"]);
+
+    let dict = HashMap::from([("Name", Value::from("test"))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let task: Task = serialized.deserialize().unwrap().0;
+    assert_eq!(task.name, "test");
+    assert!(task.tags.is_empty());
+    assert_eq!(task.priority, 5);
+    assert_eq!(task.cache, 0);
+    assert_eq!(task.generation, 0);
+
+    // Missing and skipped fields come from the default value of the structure.
+    #[derive(DeserializeDict, Type)]
+    #[zvariant(signature = "a{sv}")]
+    #[serde(default)]
+    struct Limits {
+        files: u32,
+        memory: Option<u64>,
+        #[serde(default)]
+        cpus: u8,
+        #[serde(skip)]
+        nice: i8,
+    }
+
+    impl Default for Limits {
+        fn default() -> Self {
+            Self {
+                files: 1024,
+                memory: Some(1 << 30),
+                cpus: 4,
+                nice: 10,
+            }
+        }
+    }
+
+    let dict = HashMap::from([("files", Value::from(16u32))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let limits: Limits = serialized.deserialize().unwrap().0;
+    assert_eq!(limits.files, 16);
+    assert_eq!(limits.memory, Some(1 << 30));
+    assert_eq!(limits.cpus, 0);
+    assert_eq!(limits.nice, 10);
+}
+
+#[test]
+fn derive_nested_dict() {
+    use zvariant::OwnedObjectPath;
+
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sv}")]
+    struct Device {
+        #[zvariant(rename = "Name")]
+        name: String,
+        #[zvariant(rename = "Powered")]
+        powered: Option<bool>,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sa{sv}}")]
+    struct Interfaces {
+        #[zvariant(rename = "org.zbus.Device")]
+        device: Option<Device>,
+        #[zvariant(rename = "org.zbus.Battery")]
+        battery: Option<HashMap<String, OwnedValue>>,
+    }
+
+    assert_eq!(Interfaces::signature(), "a{sa{sv}}");
+
+    // The typical reply of `org.freedesktop.DBus.ObjectManager.GetManagedObjects`.
+    let objects: HashMap<&str, HashMap<&str, HashMap<&str, Value<'_>>>> = HashMap::from([
+        (
+            "/org/zbus/device0",
+            HashMap::from([
+                (
+                    "org.zbus.Device",
+                    HashMap::from([
+                        ("Name", Value::from("first")),
+                        ("Powered", Value::from(true)),
+                        ("Unknown", Value::from(42u32)),
+                    ]),
+                ),
+                ("org.freedesktop.DBus.Properties", HashMap::new()),
+                (
+                    "org.zbus.Unknown",
+                    HashMap::from([("Level", Value::from(3u8))]),
+                ),
+            ]),
+        ),
+        ("/org/zbus/other", HashMap::new()),
+    ]);
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &objects).unwrap();
+    let objects: HashMap<OwnedObjectPath, Interfaces> = serialized.deserialize().unwrap().0;
+    assert_eq!(objects.len(), 2);
+    let device = &objects[&OwnedObjectPath::try_from("/org/zbus/device0").unwrap()];
+    assert_eq!(
+        device,
+        &Interfaces {
+            device: Some(Device {
+                name: "first".to_string(),
+                powered: Some(true),
+            }),
+            battery: None,
+        }
+    );
+    let other = &objects[&OwnedObjectPath::try_from("/org/zbus/other").unwrap()];
+    assert_eq!(
+        other,
+        &Interfaces {
+            device: None,
+            battery: None,
+        }
+    );
+
+    // And back.
+    let serialized = zvariant::to_bytes(ctxt, device).unwrap();
+    let decoded: Interfaces = serialized.deserialize().unwrap().0;
+    assert_eq!(&decoded, device);
+}
+
+#[test]
+fn derive_dict_of_variant_arrays() {
+    // Only `v` values are variants, not arrays of them.
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "a{sav}")]
+    struct Tags {
+        colors: Vec<OwnedValue>,
+        sizes: Option<Vec<OwnedValue>>,
+    }
+
+    assert_eq!(Tags::signature(), "a{sav}");
+
+    let tags = Tags {
+        colors: vec![
+            OwnedValue::from(1u8),
+            OwnedValue::try_from(Value::from("red")).unwrap(),
+        ],
+        sizes: None,
+    };
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &tags).unwrap();
+    let map: HashMap<String, Vec<OwnedValue>> = serialized.deserialize().unwrap().0;
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["colors"], tags.colors);
+
+    let decoded: Tags = serialized.deserialize().unwrap().0;
+    assert_eq!(decoded, tags);
+}
+
+#[test]
+fn derive_value_dict_renames() {
+    #[derive(Clone, Debug, PartialEq, Type, Value, OwnedValue)]
+    #[zvariant(signature = "dict", rename_all = "camelCase")]
+    struct Device {
+        device_name: String,
+        #[zvariant(rename = "UID")]
+        uid: u32,
+    }
+
+    let device = Device {
+        device_name: "sda".to_string(),
+        uid: 1000,
+    };
+    let value = OwnedValue::try_from(device.clone()).unwrap();
+    let dict = HashMap::<String, OwnedValue>::try_from(value.try_clone().unwrap()).unwrap();
+    let mut keys: Vec<_> = dict.keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, ["UID", "deviceName"]);
+    assert_eq!(Device::try_from(value).unwrap(), device);
+}
@@ -0,0 +1,10 @@
+use zvariant::Type;
+
+#[derive(Type)]
+struct Account {
+    name: String,
+    #[zvariant(signature = "(tt")]
+    balance: u128,
+}
+
+fn main() {}
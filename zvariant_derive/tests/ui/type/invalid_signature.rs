@@ -0,0 +1,7 @@
+use zvariant::Type;
+
+#[derive(Type)]
+#[zvariant(signature = "a{sv")]
+struct Opaque(Vec<u8>);
+
+fn main() {}
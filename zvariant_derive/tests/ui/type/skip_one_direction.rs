@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+#[derive(Deserialize, Serialize, Type)]
+struct Session {
+    id: u32,
+    #[serde(skip_serializing)]
+    cached_name: String,
+}
+
+fn main() {}
@@ -39,3 +39,58 @@ pub fn snake_case(s: &str) -> String {
     }
     snake
 }
+
+/// Convert to snake case, assuming pascal or camel case, keeping acronyms together.
+///
+/// Unlike [`snake_case`], a run of uppercase letters is considered a single word, so `GetACLs`
+/// yields `get_acls` rather than `get_a_c_ls`. A lowercase `s` ending such a run is taken as its
+/// plural form. A new word starts at an uppercase letter following a lowercase letter or a digit,
+/// or at the last uppercase letter of a run followed by lowercase letters, e.g. `WiFiDevice` and
+/// `DNSServer` yield `wi_fi_device` and `dns_server` respectively.
+///
+/// If `s` is already in snake case, should yield the same result.
+pub fn acronym_aware_snake_case(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut snake = String::with_capacity(s.len() + 4);
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+            let ends_acronym = prev.is_ascii_uppercase()
+                && next.is_some_and(|c| c.is_ascii_lowercase())
+                && !is_plural_suffix(&chars[i + 1..]);
+            if prev.is_ascii_lowercase() || prev.is_ascii_digit() || ends_acronym {
+                snake.push('_');
+            }
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    snake
+}
+
+// Whether `rest` starts with an `s` ending the current word.
+fn is_plural_suffix(rest: &[char]) -> bool {
+    rest.first() == Some(&'s') && !rest.get(1).is_some_and(|c| c.is_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acronyms() {
+        for (name, expected) in [
+            ("GetACLs", "get_acls"),
+            ("WiFiDevice", "wi_fi_device"),
+            ("DNSServer", "dns_server"),
+            ("URLsChanged", "urls_changed"),
+            ("Changed2", "changed2"),
+            ("ID", "id"),
+            ("volume", "volume"),
+            ("already_snake", "already_snake"),
+            ("camelCase", "camel_case"),
+        ] {
+            assert_eq!(acronym_aware_snake_case(name), expected, "{name}");
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Contains utilities to check signatures given to the macros at build time.
+//!
+//! The macros can't depend on `zvariant` so this mirrors the checks done by its `Signature` type.
+
+const MAX_LEN: usize = 255;
+const MAX_STRUCT_DEPTH: u8 = 32;
+const MAX_ARRAY_DEPTH: u8 = 32;
+const MAX_TOTAL_DEPTH: u8 = 64;
+
+/// Check that `signature` is a valid signature, consisting of any number of complete types.
+///
+/// The GVariant-specific `m` (maybe) type is accepted as well, since the macros don't know which
+/// format the signature is going to be used with.
+pub fn validate(signature: &str) -> Result<(), String> {
+    if signature.len() > MAX_LEN {
+        return Err(format!(
+            "signature is longer than {MAX_LEN} characters: `{signature}`"
+        ));
+    }
+
+    complete_types(signature).map_err(|e| {
+        // A signature that is only missing its closing brackets is a common typo, so point to the
+        // complete one, e.g `a{sv}` for `a{sv`.
+        let closers = missing_closers(signature);
+        let completed = format!("{signature}{closers}");
+        if !closers.is_empty() && completed.len() <= MAX_LEN && complete_types(&completed).is_ok() {
+            format!("invalid signature `{signature}`: missing `{closers}`, expected `{completed}`")
+        } else {
+            format!("invalid signature `{signature}`: {e}")
+        }
+    })
+}
+
+fn complete_types(signature: &str) -> Result<(), String> {
+    let bytes = signature.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        pos = complete_type(bytes, pos, Depths::default())?;
+    }
+
+    Ok(())
+}
+
+// The brackets that are opened but not closed in `signature`, in the order they'd be closed.
+fn missing_closers(signature: &str) -> String {
+    let mut closers = vec![];
+    for c in signature.chars() {
+        match c {
+            '(' => closers.push(')'),
+            '{' => closers.push('}'),
+            ')' | '}' => {
+                closers.pop();
+            }
+            _ => (),
+        }
+    }
+
+    closers.into_iter().rev().collect()
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Depths {
+    structure: u8,
+    array: u8,
+    maybe: u8,
+}
+
+impl Depths {
+    fn inc_structure(mut self) -> Result<Self, String> {
+        self.structure += 1;
+        self.check()
+    }
+
+    fn inc_array(mut self) -> Result<Self, String> {
+        self.array += 1;
+        self.check()
+    }
+
+    fn inc_maybe(mut self) -> Result<Self, String> {
+        self.maybe += 1;
+        self.check()
+    }
+
+    fn check(self) -> Result<Self, String> {
+        if self.structure > MAX_STRUCT_DEPTH {
+            return Err("structures are nested too deeply".to_string());
+        }
+        if self.array > MAX_ARRAY_DEPTH {
+            return Err("arrays are nested too deeply".to_string());
+        }
+        if self.structure + self.array + self.maybe > MAX_TOTAL_DEPTH {
+            return Err("containers are nested too deeply".to_string());
+        }
+
+        Ok(self)
+    }
+}
+
+// Parse the complete type starting at `pos` and return the position right after it.
+fn complete_type(bytes: &[u8], pos: usize, depths: Depths) -> Result<usize, String> {
+    let Some(&c) = bytes.get(pos) else {
+        return Err("expected a complete type".to_string());
+    };
+
+    match c {
+        b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b'h' | b's' | b'o'
+        | b'g' | b'v' => Ok(pos + 1),
+        b'a' => complete_type(bytes, pos + 1, depths.inc_array()?),
+        b'm' => complete_type(bytes, pos + 1, depths.inc_maybe()?),
+        b'(' => {
+            let depths = depths.inc_structure()?;
+            let mut pos = pos + 1;
+            if bytes.get(pos) == Some(&b')') {
+                return Err("structures must have at least one field".to_string());
+            }
+            while bytes.get(pos) != Some(&b')') {
+                pos = complete_type(bytes, pos, depths)?;
+            }
+
+            Ok(pos + 1)
+        }
+        b'{' => {
+            let key_end = complete_type(bytes, pos + 1, depths)?;
+            if key_end != pos + 2 {
+                return Err("dict-entry key's signature can only be a single character".to_string());
+            }
+            let end = complete_type(bytes, key_end, depths)?;
+            if bytes.get(end) != Some(&b'}') {
+                return Err("dict-entries must have exactly one key and one value".to_string());
+            }
+
+            Ok(end + 1)
+        }
+        c => Err(format!(
+            "`{}` is not a valid signature character",
+            c as char
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures() {
+        for valid in ["", "v", "a{sv}", "(ia(su))", "aay", "mas", "sa{s(iv)}u"] {
+            assert_eq!(validate(valid), Ok(()), "{valid}");
+        }
+
+        let nested_arrays = "a".repeat(33) + "y";
+        for invalid in [
+            "z",
+            "a",
+            "()",
+            "(ii",
+            "a{}",
+            "a{ass}",
+            "a{sss}",
+            "a{s",
+            "ii)",
+            &nested_arrays,
+        ] {
+            assert!(validate(invalid).is_err(), "{invalid}");
+        }
+
+        assert_eq!(
+            validate("a{sv"),
+            Err("invalid signature `a{sv`: missing `}`, expected `a{sv}`".to_string())
+        );
+        assert_eq!(
+            validate("(ta{s(ii"),
+            Err("invalid signature `(ta{s(ii`: missing `)})`, expected `(ta{s(ii)})`".to_string())
+        );
+        assert_eq!(
+            validate("a{s"),
+            Err("invalid signature `a{s`: expected a complete type".to_string())
+        );
+    }
+}